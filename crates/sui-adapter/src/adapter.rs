@@ -15,7 +15,10 @@ use move_binary_format::{
     access::ModuleAccess,
     binary_views::BinaryIndexedView,
     errors::VMResult,
-    file_format::{AbilitySet, CompiledModule, LocalIndex, SignatureToken, StructHandleIndex},
+    file_format::{
+        AbilitySet, CompiledModule, LocalIndex, SignatureToken, StructHandleIndex, Visibility,
+    },
+    normalized,
 };
 use move_bytecode_verifier::VerifierConfig;
 use move_core_types::{
@@ -38,11 +41,13 @@ use sui_json::primitive_type;
 use sui_types::storage::SingleTxContext;
 use sui_types::{
     base_types::*,
+    bytecode_rewriter::ModuleHandleRewriter,
     error::ExecutionError,
     error::{ExecutionErrorKind, SuiError},
     event::Event,
     messages::{CallArg, EntryArgumentErrorKind, InputObjectKind, ObjectArg},
-    object::{self, Data, MoveObject, Object, Owner, ID_END_INDEX},
+    move_package::{MovePackage, UpgradeCap, INITIAL_PACKAGE_VERSION},
+    object::{self, Data, MoveObject, Object, Owner, ID_END_INDEX, OBJECT_START_VERSION},
     storage::{ChildObjectResolver, DeleteKind, ObjectChange, ParentSync, Storage, WriteKind},
 };
 use sui_verifier::{
@@ -51,8 +56,6 @@ use sui_verifier::{
 };
 use tracing::instrument;
 
-use crate::bytecode_rewriter::ModuleHandleRewriter;
-
 macro_rules! assert_invariant {
     ($cond:expr, $msg:expr) => {
         if !$cond {
@@ -206,16 +209,19 @@ fn execute_internal<
         .map(|(id, (owner, _))| (*id, (by_value_objects.contains(id), *owner)))
         .collect();
     let mut session = new_session(vm, state_view, input_objects);
+    gas_status.start_profiled_call(module_id, function);
     // script visibility checked manually for entry points
+    let result = session
+        .execute_function_bypass_visibility(module_id, function, type_args, args, gas_status)
+        .and_then(|ret| Ok((ret, session.finish_with_extensions()?)));
+    gas_status.finish_profiled_call();
     let (
         SerializedReturnValues {
             mut mutable_reference_outputs,
             return_values,
         },
         (change_set, events, mut native_context_extensions),
-    ) = session
-        .execute_function_bypass_visibility(module_id, function, type_args, args, gas_status)
-        .and_then(|ret| Ok((ret, session.finish_with_extensions()?)))?;
+    ) = result?;
     assert_invariant!(return_values.is_empty(), "Return values must be empty");
     let object_runtime: ObjectRuntime = native_context_extensions.remove();
     std::mem::drop(native_context_extensions);
@@ -318,16 +324,11 @@ pub fn publish<
     state_view: &mut S,
     natives: NativeFunctionTable,
     module_bytes: Vec<Vec<u8>>,
+    upgradeable: bool,
     ctx: &mut TxContext,
     gas_status: &mut GasStatus,
 ) -> Result<(), ExecutionError> {
-    let mut modules = module_bytes
-        .iter()
-        .map(|b| {
-            CompiledModule::deserialize(b)
-                .map_err(|e| e.finish(move_binary_format::errors::Location::Undefined))
-        })
-        .collect::<move_binary_format::errors::VMResult<Vec<CompiledModule>>>()?;
+    let mut modules = deserialize_modules(&module_bytes)?;
 
     if modules.is_empty() {
         return Err(ExecutionErrorKind::PublishErrorEmptyPackage.into());
@@ -335,7 +336,173 @@ pub fn publish<
 
     let package_id = generate_package_id(&mut modules, ctx)?;
     let vm = verify_and_link(state_view, &modules, package_id, natives, gas_status)?;
-    store_package_and_init_modules(state_view, &vm, modules, ctx, gas_status)
+    store_package_and_init_modules(state_view, &vm, modules, ctx, gas_status)?;
+    if !upgradeable {
+        return Ok(());
+    }
+
+    // the publisher receives the capability to upgrade the package
+    let upgrade_cap = UpgradeCap::new(ctx.fresh_id(), package_id, INITIAL_PACKAGE_VERSION);
+    let upgrade_cap_object = Object::new_move(
+        MoveObject::new_upgrade_cap(OBJECT_START_VERSION, upgrade_cap.to_bcs_bytes()),
+        Owner::AddressOwner(ctx.sender()),
+        ctx.digest(),
+    );
+    state_view.apply_object_changes(BTreeMap::from([(
+        upgrade_cap_object.id(),
+        ObjectChange::Write(
+            SingleTxContext::publish(ctx.sender()),
+            upgrade_cap_object,
+            WriteKind::Create,
+        ),
+    )]));
+    Ok(())
+}
+
+/// Publish `module_bytes` as the next version of the package `package_id`, on behalf of the
+/// owner of the `upgrade_cap_id` capability.
+/// The upgraded package gets a fresh ID, the capability is updated to point to it, and the
+/// initializers of the modules that are new to the package are called.
+/// The modules are linked at the original ID of the package, like those of its previous versions,
+/// so the types of the upgrade are the types of the previous versions: their objects can be passed
+/// to the code of the upgrade.
+#[allow(clippy::too_many_arguments)]
+#[instrument(name = "adapter_upgrade", level = "trace", skip_all)]
+pub fn upgrade<
+    E: Debug,
+    S: ResourceResolver<Error = E>
+        + ModuleResolver<Error = E>
+        + Storage
+        + ParentSync
+        + ChildObjectResolver,
+>(
+    state_view: &mut S,
+    natives: NativeFunctionTable,
+    module_bytes: Vec<Vec<u8>>,
+    package_id: ObjectID,
+    upgrade_cap_id: ObjectID,
+    ctx: &mut TxContext,
+    gas_status: &mut GasStatus,
+) -> Result<(), ExecutionError> {
+    let mut modules = deserialize_modules(&module_bytes)?;
+
+    if modules.is_empty() {
+        return Err(ExecutionErrorKind::PublishErrorEmptyPackage.into());
+    }
+
+    let previous = match state_view.read_object(&package_id).map(|o| &o.data) {
+        Some(Data::Package(package)) => package.clone(),
+        _ => {
+            return Err(ExecutionError::new_with_source(
+                ExecutionErrorKind::InvariantViolation,
+                format!("Package {package_id} to upgrade is not an input of the transaction"),
+            ))
+        }
+    };
+    let mut upgrade_cap_object = match state_view.read_object(&upgrade_cap_id) {
+        Some(object) => object.clone(),
+        None => {
+            return Err(ExecutionError::new_with_source(
+                ExecutionErrorKind::InvariantViolation,
+                format!("Upgrade capability {upgrade_cap_id} is not an input of the transaction"),
+            ))
+        }
+    };
+    // the capability can be frozen or shared like any object with `store`, only its owner can use
+    // it to upgrade the package
+    if upgrade_cap_object.owner != Owner::AddressOwner(ctx.sender()) {
+        return Err(ExecutionError::new_with_source(
+            ExecutionErrorKind::PackageUpgradeErrorInvalidCapability,
+            format!(
+                "Upgrade capability {upgrade_cap_id} is not owned by the sender {}, but {}",
+                ctx.sender(),
+                upgrade_cap_object.owner,
+            ),
+        ));
+    }
+    let mut upgrade_cap = match &upgrade_cap_object.data {
+        Data::Move(move_obj) if UpgradeCap::is_upgrade_cap(&move_obj.type_) => {
+            UpgradeCap::from_bcs_bytes(move_obj.contents())?
+        }
+        _ => {
+            return Err(ExecutionError::new_with_source(
+                ExecutionErrorKind::PackageUpgradeErrorInvalidCapability,
+                format!("Object {upgrade_cap_id} is not an upgrade capability"),
+            ))
+        }
+    };
+    // only the latest version of a package can be upgraded
+    if upgrade_cap.package.bytes != package_id || upgrade_cap.version != previous.version() {
+        return Err(ExecutionError::new_with_source(
+            ExecutionErrorKind::PackageUpgradeErrorInvalidCapability,
+            format!(
+                "Upgrade capability {upgrade_cap_id} grants the upgrade of package {} at version {}, \
+                not of package {package_id} at version {}",
+                upgrade_cap.package.bytes,
+                upgrade_cap.version,
+                previous.version(),
+            ),
+        ));
+    }
+
+    check_upgrade_compatibility(&previous, &modules)?;
+    let original_id = previous.original_id();
+    substitute_package_id(&mut modules, original_id)?;
+    verify_and_link(
+        state_view,
+        &modules,
+        original_id,
+        natives.clone(),
+        gas_status,
+    )?;
+
+    // modules that were already part of the package were initialized by its first version
+    let modules_to_init = modules_to_init(&modules)
+        .into_iter()
+        .filter(|(module_id, _)| {
+            !previous
+                .serialized_module_map()
+                .contains_key(module_id.name().as_str())
+        })
+        .collect();
+    let package_object =
+        Object::new_upgraded_package(ctx.fresh_id(), modules, &previous, ctx.digest());
+    // Safe to unwrap, the object was just created as a package
+    upgrade_cap.upgrade_to(package_object.data.try_as_package().unwrap());
+    // Safe to unwrap, we checked that the capability is a Move object above
+    upgrade_cap_object
+        .data
+        .try_as_move_mut()
+        .unwrap()
+        .update_contents_and_increment_version(upgrade_cap.to_bcs_bytes());
+
+    let ctx_publish = SingleTxContext::publish(ctx.sender());
+    state_view.apply_object_changes(BTreeMap::from([
+        (
+            package_object.id(),
+            ObjectChange::Write(ctx_publish.clone(), package_object, WriteKind::Create),
+        ),
+        (
+            upgrade_cap_id,
+            ObjectChange::Write(ctx_publish, upgrade_cap_object, WriteKind::Mutate),
+        ),
+    ]));
+
+    // the VM linking the upgrade may have cached the previous versions of its modules, which share
+    // their IDs, so the initializers run in a VM of their own
+    let vm = MoveVM::new(natives)
+        .expect("VM creation only fails if natives are invalid, and we created the natives");
+    init_modules(state_view, &vm, modules_to_init, ctx, gas_status)
+}
+
+fn deserialize_modules(module_bytes: &[Vec<u8>]) -> VMResult<Vec<CompiledModule>> {
+    module_bytes
+        .iter()
+        .map(|b| {
+            CompiledModule::deserialize(b)
+                .map_err(|e| e.finish(move_binary_format::errors::Location::Undefined))
+        })
+        .collect()
 }
 
 /// Store package in state_view and call module initializers
@@ -353,22 +520,7 @@ pub fn store_package_and_init_modules<
     ctx: &mut TxContext,
     gas_status: &mut GasStatus,
 ) -> Result<(), ExecutionError> {
-    let modules_to_init = modules
-        .iter()
-        .filter_map(|module| {
-            for fdef in &module.function_defs {
-                let fhandle = module.function_handle_at(fdef.function);
-                let fname = module.identifier_at(fhandle.name);
-                if fname == INIT_FN_NAME {
-                    return Some((
-                        module.self_id(),
-                        module.signature_at(fhandle.parameters).len(),
-                    ));
-                }
-            }
-            None
-        })
-        .collect();
+    let modules_to_init = modules_to_init(&modules);
 
     // wrap the modules in an object, write it to the store
     // The call to unwrap() will go away once we remove address owner from Immutable objects.
@@ -387,6 +539,26 @@ pub fn store_package_and_init_modules<
     init_modules(state_view, vm, modules_to_init, ctx, gas_status)
 }
 
+/// Returns the modules defining an init method, along with the number of arguments of the method
+fn modules_to_init(modules: &[CompiledModule]) -> Vec<(ModuleId, usize)> {
+    modules
+        .iter()
+        .filter_map(|module| {
+            for fdef in &module.function_defs {
+                let fhandle = module.function_handle_at(fdef.function);
+                let fname = module.identifier_at(fhandle.name);
+                if fname == INIT_FN_NAME {
+                    return Some((
+                        module.self_id(),
+                        module.signature_at(fhandle.parameters).len(),
+                    ));
+                }
+            }
+            None
+        })
+        .collect()
+}
+
 /// Modules in module_ids_to_init must have the init method defined
 fn init_modules<
     E: Debug,
@@ -479,9 +651,65 @@ pub fn verify_and_link<
     Ok(vm)
 }
 
+/// Checks that `modules` are a compatible upgrade of the `previous` package: every module of
+/// `previous` is kept, with the same struct layouts and abilities, and the same public function
+/// signatures. New modules, structs and functions can be added.
+fn check_upgrade_compatibility(
+    previous: &MovePackage,
+    modules: &[CompiledModule],
+) -> Result<(), ExecutionError> {
+    let incompatible = |msg: String| {
+        ExecutionError::new_with_source(ExecutionErrorKind::PackageUpgradeErrorIncompatible, msg)
+    };
+    let previous_modules = previous.normalize().map_err(|e| {
+        ExecutionError::new_with_source(ExecutionErrorKind::InvariantViolation, e.to_string())
+    })?;
+    // Compare against the new modules as if they were already linked at the original ID of the
+    // package, so that the types they define and use are comparable with the previous ones.
+    let mut modules = modules.to_vec();
+    substitute_package_id(&mut modules, previous.original_id())?;
+    let new_modules: BTreeMap<_, _> = modules
+        .iter()
+        .map(|module| {
+            let module = normalized::Module::new(module);
+            (module.name.to_string(), module)
+        })
+        .collect();
+
+    for (name, old_module) in &previous_modules {
+        let new_module = new_modules
+            .get(name)
+            .ok_or_else(|| incompatible(format!("Module {name} was removed")))?;
+        for (struct_name, old_struct) in &old_module.structs {
+            if new_module.structs.get(struct_name) != Some(old_struct) {
+                return Err(incompatible(format!(
+                    "Struct {name}::{struct_name} was removed or its layout changed"
+                )));
+            }
+        }
+        for (fun_name, old_fun) in &old_module.exposed_functions {
+            if old_fun.visibility != Visibility::Public {
+                continue;
+            }
+            let compatible = matches!(
+                new_module.exposed_functions.get(fun_name),
+                Some(new_fun) if new_fun.visibility == Visibility::Public
+                    && new_fun.type_parameters == old_fun.type_parameters
+                    && new_fun.parameters == old_fun.parameters
+                    && new_fun.return_ == old_fun.return_
+            );
+            if !compatible {
+                return Err(incompatible(format!(
+                    "Public function {name}::{fun_name} was removed or its signature changed"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Given a list of `modules`, use `ctx` to generate a fresh ID for the new packages.
-/// If `is_framework` is true, then the modules can have arbitrary user-defined address,
-/// otherwise their addresses must be 0.
+/// The modules' addresses must be 0.
 /// Mutate each module's self ID to the appropriate fresh ID and update its module handle tables
 /// to reflect the new ID's of its dependencies.
 /// Returns the newly created package ID.
@@ -489,8 +717,18 @@ pub fn generate_package_id(
     modules: &mut [CompiledModule],
     ctx: &mut TxContext,
 ) -> Result<ObjectID, ExecutionError> {
-    let mut sub_map = BTreeMap::new();
     let package_id = ctx.fresh_id();
+    substitute_package_id(modules, package_id)?;
+    Ok(package_id)
+}
+
+/// Mutate the self ID of each of `modules`, whose addresses must be 0, to `package_id`, and
+/// update their module handle tables accordingly.
+pub fn substitute_package_id(
+    modules: &mut [CompiledModule],
+    package_id: ObjectID,
+) -> Result<(), ExecutionError> {
+    let mut sub_map = BTreeMap::new();
     for module in modules.iter() {
         let old_module_id = module.self_id();
         let old_address = *old_module_id.address();
@@ -520,7 +758,7 @@ pub fn generate_package_id(
         // rewrite module handles to reflect freshly generated ID's
         rewriter.sub_module_ids(module);
     }
-    Ok(package_id)
+    Ok(())
 }
 
 /// Update `state_view` with the effects of successfully executing a transaction:
@@ -2,5 +2,4 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod adapter;
-pub mod bytecode_rewriter;
 pub mod genesis;
@@ -22,6 +22,8 @@ pub struct P2pConfig {
     pub seed_peers: Vec<SeedPeer>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub anemo_config: Option<anemo::Config>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_sync: Option<StateSyncConfig>,
 }
 
 fn default_listen_address() -> SocketAddr {
@@ -35,6 +37,7 @@ impl Default for P2pConfig {
             external_address: Default::default(),
             seed_peers: Default::default(),
             anemo_config: Default::default(),
+            state_sync: None,
         }
     }
 }
@@ -74,6 +77,13 @@ pub struct StateSyncConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub checkpoint_header_download_concurrency: Option<usize>,
 
+    /// Set the upper bound on the number of checkpoints whose contents are downloaded
+    /// concurrently.
+    ///
+    /// If unspecified, this will default to `10`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint_content_download_concurrency: Option<usize>,
+
     /// Set the upper bound on the number of transactions to be downloaded concurrently from a
     /// single checkpoint.
     ///
@@ -109,6 +119,13 @@ impl StateSyncConfig {
             .unwrap_or(CHECKPOINT_HEADER_DOWNLOAD_CONCURRENCY)
     }
 
+    pub fn checkpoint_content_download_concurrency(&self) -> usize {
+        const CHECKPOINT_CONTENT_DOWNLOAD_CONCURRENCY: usize = 10;
+
+        self.checkpoint_content_download_concurrency
+            .unwrap_or(CHECKPOINT_CONTENT_DOWNLOAD_CONCURRENCY)
+    }
+
     pub fn transaction_download_concurrency(&self) -> usize {
         const TRANSACTION_DOWNLOAD_CONCURRENCY: usize = 100;
 
@@ -1,8 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::p2p::P2pConfig;
+use crate::p2p::{P2pConfig, SeedPeer};
 use crate::{builder, genesis, utils, Config, NodeConfig, ValidatorInfo, FULL_NODE_DB_PATH};
+use fastcrypto::traits::KeyPair;
+use multiaddr::{Multiaddr, Protocol};
 use rand::rngs::OsRng;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
@@ -98,8 +100,20 @@ impl NetworkConfig {
         };
 
         let network_address = utils::new_network_address();
+        // Fullnodes sync checkpoints from the validators over the p2p network
+        let seed_peers = self
+            .validator_configs
+            .iter()
+            .map(|config| SeedPeer {
+                peer_id: Some(anemo::PeerId(config.network_key_pair.public().0.to_bytes())),
+                address: Multiaddr::empty()
+                    .with(Protocol::from(config.p2p_config.listen_address.ip()))
+                    .with(Protocol::Udp(config.p2p_config.listen_address.port())),
+            })
+            .collect();
         let p2p_config = P2pConfig {
             listen_address: utils::available_local_socket_address(),
+            seed_peers,
             ..Default::default()
         };
 
@@ -126,6 +140,8 @@ impl NetworkConfig {
             grpc_load_shed: None,
             grpc_concurrency_limit: None,
             p2p_config,
+            event_store_config: None,
+            event_retention_config: None,
         }
     }
 }
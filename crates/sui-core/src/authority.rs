@@ -44,7 +44,7 @@ use narwhal_config::{
     Committee as ConsensusCommittee, WorkerCache as ConsensusWorkerCache,
     WorkerId as ConsensusWorkerId,
 };
-use narwhal_types::CommittedSubDag;
+use narwhal_types::{CommittedSubDag, Round as ConsensusRound};
 use sui_adapter::adapter;
 use sui_config::genesis::Genesis;
 use sui_json_rpc_types::{
@@ -60,7 +60,12 @@ use sui_storage::{
 use sui_types::committee::EpochId;
 use sui_types::crypto::{AuthorityKeyPair, NetworkKeyPair};
 use sui_types::event::{Event, EventID};
-use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
+use sui_types::gas::GasProfiler;
+use sui_types::light_client::TransactionInclusionProof;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointRequest, CheckpointResponse,
+    CheckpointSequenceNumber, CheckpointSummary,
+};
 use sui_types::object::{Owner, PastObjectRead};
 use sui_types::query::{EventQuery, TransactionQuery};
 use sui_types::sui_system_state::SuiSystemState;
@@ -129,12 +134,19 @@ const BROADCAST_CAPACITY: usize = 10_000;
 pub(crate) const MAX_TX_RECOVERY_RETRY: u32 = 3;
 type CertTxGuard<'a> = DBTxGuard<'a, TrustedCertificate>;
 
+/// The keys, committee and workers of the next epoch of narwhal, and the round at which the node
+/// moves to them in place when its keys and workers do not change.
+///
+/// Sui does not send these messages yet: its epoch change (see `epoch::reconfiguration`) is
+/// disabled, and has no round agreed by the committee to move at. Until it does, Sui epoch changes
+/// do not reach narwhal, which only moves between committees in place in its own tests.
 pub type ReconfigConsensusMessage = (
     AuthorityKeyPair,
     NetworkKeyPair,
     ConsensusCommittee,
     Vec<(ConsensusWorkerId, NetworkKeyPair)>,
     ConsensusWorkerCache,
+    ConsensusRound,
 );
 
 /// Prometheus metrics which can be displayed in Grafana, queried and alerted on
@@ -555,7 +567,7 @@ pub struct AuthorityState {
 
     pub metrics: Arc<AuthorityMetrics>,
 
-    /// A channel to tell consensus to reconfigure.
+    /// A channel to tell consensus to reconfigure. Unused until Sui's epoch change is enabled.
     _tx_reconfigure_consensus: mpsc::Sender<ReconfigConsensusMessage>,
 }
 
@@ -1081,9 +1093,35 @@ impl AuthorityState {
         transaction: TransactionData,
         transaction_digest: TransactionDigest,
     ) -> Result<SuiTransactionEffects, anyhow::Error> {
-        let (gas_status, input_objects) =
+        self.dry_exec_transaction_impl(transaction, transaction_digest, None)
+            .await
+    }
+
+    /// Dry run `transaction`, recording the gas charged by each call stack of its Move calls.
+    pub async fn dry_exec_transaction_with_gas_profile(
+        &self,
+        transaction: TransactionData,
+        transaction_digest: TransactionDigest,
+    ) -> Result<(SuiTransactionEffects, GasProfiler), anyhow::Error> {
+        let profiler = GasProfiler::new();
+        let effects = self
+            .dry_exec_transaction_impl(transaction, transaction_digest, Some(profiler.clone()))
+            .await?;
+        Ok((effects, profiler))
+    }
+
+    async fn dry_exec_transaction_impl(
+        &self,
+        transaction: TransactionData,
+        transaction_digest: TransactionDigest,
+        profiler: Option<GasProfiler>,
+    ) -> Result<SuiTransactionEffects, anyhow::Error> {
+        let (mut gas_status, input_objects) =
             transaction_input_checker::check_transaction_input(&self.database, &transaction)
                 .await?;
+        if let Some(profiler) = profiler {
+            gas_status.set_profiler(profiler);
+        }
         let shared_object_refs = input_objects.filter_shared_objects();
 
         let transaction_dependencies = input_objects.transaction_dependencies();
@@ -1872,6 +1910,71 @@ impl AuthorityState {
         Ok(self.get_indexes()?.get_timestamp_ms(digest)?)
     }
 
+    pub fn get_latest_checkpoint_sequence_number(
+        &self,
+    ) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        self.checkpoint_service
+            .get_latest_checkpoint_sequence_number()
+    }
+
+    pub fn get_checkpoint_summary(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CheckpointSummary>> {
+        self.checkpoint_service
+            .get_checkpoint_summary(sequence_number)
+    }
+
+    pub fn get_checkpoint_contents(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CheckpointContents>> {
+        self.checkpoint_service
+            .get_checkpoint_contents(sequence_number)
+    }
+
+    pub fn get_checkpoint_sequence_number_by_transaction(
+        &self,
+        digest: &TransactionDigest,
+    ) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        self.checkpoint_service
+            .get_checkpoint_sequence_number_by_transaction(digest)
+    }
+
+    pub fn get_certified_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CertifiedCheckpointSummary>> {
+        self.checkpoint_service
+            .get_certified_checkpoint(sequence_number)
+    }
+
+    /// Returns a proof that the effects of the given transaction were included in a certified
+    /// checkpoint, or None if the transaction is not part of a certified checkpoint yet.
+    pub fn get_transaction_inclusion_proof(
+        &self,
+        digest: &TransactionDigest,
+    ) -> SuiResult<Option<TransactionInclusionProof>> {
+        let Some(sequence_number) = self
+            .checkpoint_service
+            .get_checkpoint_sequence_number_by_transaction(digest)? else { return Ok(None); };
+        let Some(checkpoint) = self
+            .checkpoint_service
+            .get_certified_checkpoint(sequence_number)? else { return Ok(None); };
+        let contents = self
+            .checkpoint_service
+            .get_checkpoint_contents(sequence_number)?
+            .ok_or_else(|| SuiError::GenericAuthorityError {
+                error: format!("Contents of checkpoint {sequence_number} not found"),
+            })?;
+        let effects = self.database.get_effects(digest)?;
+        Ok(Some(TransactionInclusionProof {
+            checkpoint,
+            contents,
+            effects,
+        }))
+    }
+
     /// Returns a full handle to the event store, including inserts... so be careful!
     fn get_event_store(&self) -> Option<Arc<EventStoreType>> {
         self.event_handler
@@ -1953,6 +2056,13 @@ impl AuthorityState {
         Ok(events)
     }
 
+    /// Returns the ID of the earliest event available in the event store, events before it may
+    /// have been pruned.
+    pub async fn get_earliest_available_event(&self) -> Result<Option<EventID>, anyhow::Error> {
+        let es = self.get_event_store().ok_or(SuiError::NoEventStore)?;
+        Ok(es.earliest_available_event().await?)
+    }
+
     pub async fn insert_genesis_object(&self, object: Object) {
         self.database
             .insert_genesis_object(object)
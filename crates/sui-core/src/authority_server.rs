@@ -4,7 +4,7 @@
 
 use crate::{
     authority::{AuthorityState, ReconfigConsensusMessage},
-    consensus_adapter::{ConsensusAdapter, ConsensusAdapterMetrics},
+    consensus_adapter::{ConsensusAdapter, ConsensusAdapterMetrics, ConsensusTransactionPolicy},
     metrics::start_timer,
 };
 use anyhow::anyhow;
@@ -289,6 +289,7 @@ impl ValidatorService {
             consensus_storage_base_path,
             consensus_execution_state,
             consensus_parameters,
+            Arc::new(ConsensusTransactionPolicy),
             rx_reconfigure_consensus,
             &registry,
         ));
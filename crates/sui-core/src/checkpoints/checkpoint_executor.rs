@@ -0,0 +1,144 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Execution of the transactions included in checkpoints synced by state sync.
+//!
+//! Fullnodes do not take part in consensus, instead they follow the chain of certified
+//! checkpoints. Once state sync has downloaded the contents of a checkpoint, along with the
+//! certificates and effects of all of its transactions, the CheckpointExecutor executes these
+//! transactions in the causal order given by the checkpoint contents, and ratchets up the
+//! highest executed checkpoint watermark.
+
+use crate::authority::AuthorityState;
+use crate::checkpoints::{CheckpointService, CheckpointWatermark};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_metrics::spawn_monitored_task;
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::messages::UnsignedTransactionEffects;
+use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, VerifiedCheckpoint};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+pub struct CheckpointExecutor {
+    state: Arc<AuthorityState>,
+    checkpoint_service: Arc<CheckpointService>,
+}
+
+impl CheckpointExecutor {
+    pub fn new(state: Arc<AuthorityState>, checkpoint_service: Arc<CheckpointService>) -> Self {
+        Self {
+            state,
+            checkpoint_service,
+        }
+    }
+
+    /// Start executing synced checkpoints. The receiver is only used as a notification that new
+    /// checkpoints were synced, checkpoints are always read back from the store in order.
+    pub fn start(self, receiver: broadcast::Receiver<VerifiedCheckpoint>) -> JoinHandle<()> {
+        spawn_monitored_task!(self.run(receiver))
+    }
+
+    async fn run(self, mut receiver: broadcast::Receiver<VerifiedCheckpoint>) {
+        info!("Checkpoint executor started");
+        loop {
+            if let Err(e) = self.execute_synced_checkpoints().await {
+                error!(
+                    "Error while executing checkpoints, will retry in 1s: {:?}",
+                    e
+                );
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+
+            match receiver.recv().await {
+                // Missed notifications are fine, as every synced checkpoint is executed above
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+        info!("Checkpoint executor ended");
+    }
+
+    async fn execute_synced_checkpoints(&self) -> SuiResult {
+        let Some(highest_synced) = self
+            .checkpoint_service
+            .get_checkpoint_watermark(CheckpointWatermark::HighestSynced)? else {
+            return Ok(());
+        };
+
+        let mut next = self
+            .checkpoint_service
+            .get_checkpoint_watermark(CheckpointWatermark::HighestExecuted)?
+            .map(|checkpoint| checkpoint.sequence_number() + 1)
+            .unwrap_or_default();
+
+        while next <= highest_synced.sequence_number() {
+            let checkpoint = self
+                .checkpoint_service
+                .get_certified_checkpoint(next)?
+                .ok_or_else(|| {
+                    SuiError::GenericStorageError(format!("Synced checkpoint {next} is missing"))
+                })?;
+            self.execute_checkpoint(&checkpoint).await?;
+            self.checkpoint_service
+                .update_checkpoint_watermark(CheckpointWatermark::HighestExecuted, &checkpoint)?;
+            next += 1;
+        }
+
+        Ok(())
+    }
+
+    async fn execute_checkpoint(&self, checkpoint: &CertifiedCheckpointSummary) -> SuiResult {
+        let sequence_number = checkpoint.sequence_number();
+        let contents = self
+            .checkpoint_service
+            .get_checkpoint_contents(sequence_number)?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError(format!(
+                    "Contents of synced checkpoint {sequence_number} are missing"
+                ))
+            })?;
+        debug!(
+            "Executing checkpoint {} with {} transactions",
+            sequence_number,
+            contents.size()
+        );
+
+        // Contents are causally ordered, so the dependencies of each transaction were executed
+        // before it, either in this checkpoint or in an earlier one.
+        for digests in contents.iter() {
+            if self.state.is_tx_already_executed(&digests.transaction)? {
+                continue;
+            }
+            let certificate = self
+                .checkpoint_service
+                .get_synced_transaction(&digests.transaction)?
+                .ok_or_else(|| {
+                    SuiError::GenericStorageError(format!(
+                        "Certificate {:?} of synced checkpoint {sequence_number} is missing",
+                        digests.transaction
+                    ))
+                })?;
+            let effects = self
+                .checkpoint_service
+                .get_checkpoint_effects(&digests.effects)?
+                .ok_or_else(|| {
+                    SuiError::GenericStorageError(format!(
+                        "Effects of {:?} in synced checkpoint {sequence_number} are missing",
+                        digests.transaction
+                    ))
+                })?;
+            // The effects are certified by the checkpoint contents
+            self.state
+                .handle_certificate_with_effects(
+                    &certificate,
+                    &UnsignedTransactionEffects::new(effects),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -6,12 +6,13 @@ use crate::consensus_adapter::SubmitToConsensus;
 use async_trait::async_trait;
 use fastcrypto::encoding::{Encoding, Hex};
 use sui_types::base_types::AuthorityName;
-use sui_types::error::SuiResult;
+use sui_types::error::{SuiError, SuiResult};
 use sui_types::messages::ConsensusTransaction;
 use sui_types::messages_checkpoint::{
     CertifiedCheckpointSummary, CheckpointContents, CheckpointSignatureMessage, CheckpointSummary,
-    SignedCheckpointSummary,
+    SignedCheckpointSummary, VerifiedCheckpoint,
 };
+use tokio::sync::mpsc;
 use tracing::{debug, info};
 
 #[async_trait]
@@ -35,6 +36,11 @@ pub struct SubmitCheckpointToConsensus<T> {
     pub authority: AuthorityName,
 }
 
+/// Forwards certified checkpoints to state sync, so that they can be disseminated to peers.
+pub struct SendCheckpointToStateSync {
+    pub sender: mpsc::Sender<VerifiedCheckpoint>,
+}
+
 pub struct LogCheckpointOutput;
 
 impl LogCheckpointOutput {
@@ -106,3 +112,21 @@ impl CertifiedCheckpointOutput for LogCheckpointOutput {
         Ok(())
     }
 }
+
+#[async_trait]
+impl CertifiedCheckpointOutput for SendCheckpointToStateSync {
+    async fn certified_checkpoint_created(
+        &self,
+        summary: &CertifiedCheckpointSummary,
+    ) -> SuiResult {
+        LogCheckpointOutput
+            .certified_checkpoint_created(summary)
+            .await?;
+        // The aggregator only certifies checkpoints with a quorum of valid signatures
+        let checkpoint = VerifiedCheckpoint::new_unchecked(summary.clone());
+        self.sender
+            .send(checkpoint)
+            .await
+            .map_err(|_| SuiError::from("State sync is not running"))
+    }
+}
@@ -2,34 +2,41 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod casual_order;
+mod checkpoint_executor;
 mod checkpoint_output;
 mod metrics;
+mod state_sync_store;
 
 use crate::authority::EffectsNotifyRead;
 use crate::checkpoints::casual_order::CasualOrder;
+pub use crate::checkpoints::checkpoint_executor::CheckpointExecutor;
 use crate::checkpoints::checkpoint_output::{CertifiedCheckpointOutput, CheckpointOutput};
-pub use crate::checkpoints::checkpoint_output::{LogCheckpointOutput, SubmitCheckpointToConsensus};
+pub use crate::checkpoints::checkpoint_output::{
+    LogCheckpointOutput, SendCheckpointToStateSync, SubmitCheckpointToConsensus,
+};
 pub use crate::checkpoints::metrics::CheckpointMetrics;
+pub use crate::checkpoints::state_sync_store::StateSyncStore;
 use crate::metrics::TaskUtilizationExt;
 use fastcrypto::encoding::{Encoding, Hex};
 use futures::future::{select, Either};
 use futures::FutureExt;
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use sui_metrics::spawn_monitored_task;
-use sui_types::base_types::{AuthorityName, EpochId, TransactionDigest};
+use sui_types::base_types::{AuthorityName, EpochId, TransactionDigest, TransactionEffectsDigest};
 use sui_types::committee::{Committee, StakeUnit};
 use sui_types::crypto::{AuthoritySignInfo, AuthorityWeakQuorumSignInfo};
 use sui_types::error::{SuiError, SuiResult};
 use sui_types::gas::GasCostSummary;
-use sui_types::messages::TransactionEffects;
+use sui_types::messages::{TransactionEffects, TrustedCertificate, VerifiedCertificate};
 use sui_types::messages_checkpoint::{
-    CertifiedCheckpointSummary, CheckpointContents, CheckpointDigest, CheckpointSequenceNumber,
-    CheckpointSignatureMessage, CheckpointSummary,
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointContentsDigest, CheckpointDigest,
+    CheckpointSequenceNumber, CheckpointSignatureMessage, CheckpointSummary,
 };
 use tokio::sync::{mpsc, watch, Notify};
 use tracing::{debug, error, info, warn};
@@ -59,7 +66,7 @@ struct CheckpointStoreTables {
     checkpoint_summary: DBMap<CheckpointSequenceNumber, CheckpointSummary>,
 
     /// Lists all transaction digests included in checkpoints
-    /// This can be cleaned up on epoch boundary
+    /// This is also used to serve "which checkpoint includes this transaction" queries
     digest_to_checkpoint: DBMap<TransactionDigest, CheckpointSequenceNumber>,
 
     /// Stores pending signatures
@@ -69,6 +76,33 @@ struct CheckpointStoreTables {
 
     /// Stores certified checkpoints
     certified_checkpoints: DBMap<CheckpointSequenceNumber, CertifiedCheckpointSummary>,
+
+    /// Maps the digest of a certified checkpoint to its sequence number
+    checkpoint_by_digest: DBMap<CheckpointDigest, CheckpointSequenceNumber>,
+
+    /// Maps the digest of checkpoint contents to the sequence number of their checkpoint.
+    /// This is populated as soon as the checkpoint summary is known, so that contents synced
+    /// from peers can be matched with their checkpoint.
+    content_digest_to_checkpoint: DBMap<CheckpointContentsDigest, CheckpointSequenceNumber>,
+
+    /// Certificates of the transactions included in checkpoints synced from peers
+    synced_transactions: DBMap<TransactionDigest, TrustedCertificate>,
+
+    /// Effects of the transactions included in checkpoints, keyed by effects digest
+    checkpoint_effects: DBMap<TransactionEffectsDigest, TransactionEffects>,
+
+    /// Watermarks of the checkpoints processed by state sync and checkpoint execution
+    watermarks: DBMap<CheckpointWatermark, (CheckpointSequenceNumber, CheckpointDigest)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckpointWatermark {
+    /// Highest checkpoint whose summary was verified and stored
+    HighestVerified,
+    /// Highest checkpoint whose contents, transactions and effects were all stored
+    HighestSynced,
+    /// Highest checkpoint whose transactions were all executed locally
+    HighestExecuted,
 }
 
 pub struct CheckpointBuilder {
@@ -208,6 +242,10 @@ impl CheckpointBuilder {
             .last_constructed_checkpoint
             .set(sequence_number as i64);
 
+        batch = batch.insert_batch(
+            &self.tables.content_digest_to_checkpoint,
+            [(contents.digest(), sequence_number)],
+        )?;
         batch = batch.insert_batch(
             &self.tables.checkpoint_content,
             [(sequence_number, contents)],
@@ -222,6 +260,11 @@ impl CheckpointBuilder {
                 [(txn.transaction_digest, sequence_number)],
             )?;
         }
+        // Effects are kept so that they can be served to peers syncing this checkpoint
+        batch = batch.insert_batch(
+            &self.tables.checkpoint_effects,
+            l.into_iter().map(|txn| (txn.digest(), txn)),
+        )?;
 
         Ok(batch)
     }
@@ -565,6 +608,186 @@ impl CheckpointService {
         Ok(())
     }
 
+    pub fn get_latest_checkpoint_sequence_number(
+        &self,
+    ) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        Ok(self
+            .tables
+            .checkpoint_summary
+            .iter()
+            .skip_to_last()
+            .next()
+            .map(|(seq, _)| seq))
+    }
+
+    pub fn get_checkpoint_summary(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CheckpointSummary>> {
+        Ok(self.tables.checkpoint_summary.get(&sequence_number)?)
+    }
+
+    pub fn get_checkpoint_contents(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CheckpointContents>> {
+        Ok(self.tables.checkpoint_content.get(&sequence_number)?)
+    }
+
+    pub fn get_certified_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CertifiedCheckpointSummary>> {
+        Ok(self.tables.certified_checkpoints.get(&sequence_number)?)
+    }
+
+    /// Returns the sequence number of the checkpoint that includes the given transaction,
+    /// or None if the transaction has not been included in a checkpoint yet
+    pub fn get_checkpoint_sequence_number_by_transaction(
+        &self,
+        digest: &TransactionDigest,
+    ) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        Ok(self.tables.digest_to_checkpoint.get(digest)?)
+    }
+
+    pub fn get_certified_checkpoint_by_digest(
+        &self,
+        digest: &CheckpointDigest,
+    ) -> SuiResult<Option<CertifiedCheckpointSummary>> {
+        let Some(sequence_number) = self.tables.checkpoint_by_digest.get(digest)? else {
+            return Ok(None);
+        };
+        self.get_certified_checkpoint(sequence_number)
+    }
+
+    pub fn get_checkpoint_contents_by_digest(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> SuiResult<Option<CheckpointContents>> {
+        let Some(sequence_number) = self.tables.content_digest_to_checkpoint.get(digest)? else {
+            return Ok(None);
+        };
+        self.get_checkpoint_contents(sequence_number)
+    }
+
+    pub fn get_checkpoint_watermark(
+        &self,
+        watermark: CheckpointWatermark,
+    ) -> SuiResult<Option<CertifiedCheckpointSummary>> {
+        let Some((sequence_number, _digest)) = self.tables.watermarks.get(&watermark)? else {
+            return Ok(None);
+        };
+        self.get_certified_checkpoint(sequence_number)
+    }
+
+    pub fn update_checkpoint_watermark(
+        &self,
+        watermark: CheckpointWatermark,
+        checkpoint: &CertifiedCheckpointSummary,
+    ) -> SuiResult {
+        Ok(self.tables.watermarks.insert(
+            &watermark,
+            &(checkpoint.sequence_number(), checkpoint.digest()),
+        )?)
+    }
+
+    /// Stores a certified checkpoint received from the aggregator or synced from peers, and bumps
+    /// the highest verified checkpoint watermark. Callers must have verified the checkpoint.
+    pub fn insert_certified_checkpoint(
+        &self,
+        checkpoint: &CertifiedCheckpointSummary,
+    ) -> SuiResult {
+        let sequence_number = checkpoint.sequence_number();
+        let mut batch = self.tables.certified_checkpoints.batch();
+        batch = batch.insert_batch(
+            &self.tables.certified_checkpoints,
+            [(sequence_number, checkpoint)],
+        )?;
+        // Locally built summaries are left untouched, on fullnodes this is the only writer
+        if !self
+            .tables
+            .checkpoint_summary
+            .contains_key(&sequence_number)?
+        {
+            batch = batch.insert_batch(
+                &self.tables.checkpoint_summary,
+                [(sequence_number, &checkpoint.summary)],
+            )?;
+        }
+        batch = batch.insert_batch(
+            &self.tables.checkpoint_by_digest,
+            [(checkpoint.digest(), sequence_number)],
+        )?;
+        batch = batch.insert_batch(
+            &self.tables.content_digest_to_checkpoint,
+            [(checkpoint.content_digest(), sequence_number)],
+        )?;
+        let highest_verified = self
+            .tables
+            .watermarks
+            .get(&CheckpointWatermark::HighestVerified)?;
+        if highest_verified.map(|(seq, _)| seq) < Some(sequence_number) {
+            batch = batch.insert_batch(
+                &self.tables.watermarks,
+                [(
+                    CheckpointWatermark::HighestVerified,
+                    (sequence_number, checkpoint.digest()),
+                )],
+            )?;
+        }
+        batch.write()?;
+        Ok(())
+    }
+
+    /// Stores the contents of a checkpoint whose summary is already stored.
+    pub fn insert_checkpoint_contents(&self, contents: CheckpointContents) -> SuiResult {
+        let digest = contents.digest();
+        let Some(sequence_number) = self.tables.content_digest_to_checkpoint.get(&digest)? else {
+            return Err(SuiError::from("Checkpoint contents do not belong to any known checkpoint"));
+        };
+        let mut batch = self.tables.checkpoint_content.batch();
+        batch = batch.insert_batch(
+            &self.tables.digest_to_checkpoint,
+            contents
+                .iter()
+                .map(|digests| (digests.transaction, sequence_number)),
+        )?;
+        batch = batch.insert_batch(
+            &self.tables.checkpoint_content,
+            [(sequence_number, contents)],
+        )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    pub fn get_synced_transaction(
+        &self,
+        digest: &TransactionDigest,
+    ) -> SuiResult<Option<VerifiedCertificate>> {
+        Ok(self.tables.synced_transactions.get(digest)?.map(Into::into))
+    }
+
+    pub fn insert_synced_transaction(&self, certificate: VerifiedCertificate) -> SuiResult {
+        Ok(self
+            .tables
+            .synced_transactions
+            .insert(certificate.digest(), certificate.serializable_ref())?)
+    }
+
+    pub fn get_checkpoint_effects(
+        &self,
+        digest: &TransactionEffectsDigest,
+    ) -> SuiResult<Option<TransactionEffects>> {
+        Ok(self.tables.checkpoint_effects.get(digest)?)
+    }
+
+    pub fn insert_checkpoint_effects(&self, effects: &TransactionEffects) -> SuiResult {
+        Ok(self
+            .tables
+            .checkpoint_effects
+            .insert(&effects.digest(), effects)?)
+    }
+
     /// Used by internal systems that want to subscribe to checkpoints.
     /// Returned sender will contain all checkpoints starting from(inclusive) given sequence number
     /// CheckpointSequenceNumber::default() can be used to start from the beginning
@@ -703,6 +926,136 @@ mod tests {
         let (t2s, _content) = tailer.recv().await.unwrap();
         assert_eq!(t1s.sequence_number, 0);
         assert_eq!(t2s.sequence_number, 1);
+
+        assert_eq!(
+            checkpoint_service
+                .get_latest_checkpoint_sequence_number()
+                .unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            checkpoint_service.get_checkpoint_summary(1).unwrap(),
+            Some(t2s)
+        );
+        let c2t = checkpoint_service
+            .get_checkpoint_contents(1)
+            .unwrap()
+            .unwrap()
+            .iter()
+            .map(|d| d.transaction)
+            .collect::<Vec<_>>();
+        assert_eq!(c2t, vec![d(3), d(2), d(1)]);
+        assert_eq!(
+            checkpoint_service
+                .get_checkpoint_sequence_number_by_transaction(&d(4))
+                .unwrap(),
+            Some(0)
+        );
+        assert_eq!(
+            checkpoint_service
+                .get_checkpoint_sequence_number_by_transaction(&d(2))
+                .unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            checkpoint_service
+                .get_checkpoint_sequence_number_by_transaction(&d(5))
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    pub async fn synced_checkpoint_test() {
+        let tempdir = tempdir().unwrap();
+        let (keypair, committee) = committee();
+        let (certified_output, _certified_result) = mpsc::channel::<CertifiedCheckpointSummary>(10);
+        let checkpoint_service = CheckpointService::spawn(
+            tempdir.path(),
+            Box::new(HashMap::<TransactionDigest, TransactionEffects>::new()),
+            LogCheckpointOutput::boxed(),
+            Box::new(certified_output),
+            committee.clone(),
+            CheckpointMetrics::new_for_tests(),
+        );
+
+        let effects = e(d(1), vec![]);
+        let contents = CheckpointContents::new_with_causally_ordered_transactions(
+            [effects.execution_digests()].into_iter(),
+        );
+        let summary =
+            CheckpointSummary::new(0, 0, &contents, None, GasCostSummary::default(), None);
+        let signed =
+            SignedCheckpointSummary::new_from_summary(summary, keypair.public().into(), &keypair);
+        let certified = CertifiedCheckpointSummary::aggregate(vec![signed], &committee).unwrap();
+
+        // Contents can only be stored once their checkpoint is known
+        assert!(checkpoint_service
+            .insert_checkpoint_contents(contents.clone())
+            .is_err());
+
+        checkpoint_service
+            .insert_certified_checkpoint(&certified)
+            .unwrap();
+        assert_eq!(
+            checkpoint_service
+                .get_certified_checkpoint_by_digest(&certified.digest())
+                .unwrap()
+                .map(|c| c.sequence_number()),
+            Some(0)
+        );
+        assert_eq!(
+            checkpoint_service
+                .get_checkpoint_watermark(CheckpointWatermark::HighestVerified)
+                .unwrap()
+                .map(|c| c.digest()),
+            Some(certified.digest())
+        );
+        assert!(checkpoint_service
+            .get_checkpoint_watermark(CheckpointWatermark::HighestSynced)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            checkpoint_service
+                .get_latest_checkpoint_sequence_number()
+                .unwrap(),
+            Some(0)
+        );
+
+        checkpoint_service
+            .insert_checkpoint_contents(contents.clone())
+            .unwrap();
+        checkpoint_service
+            .insert_checkpoint_effects(&effects)
+            .unwrap();
+        assert_eq!(
+            checkpoint_service
+                .get_checkpoint_contents_by_digest(&contents.digest())
+                .unwrap()
+                .map(|c| c.digest()),
+            Some(contents.digest())
+        );
+        assert_eq!(
+            checkpoint_service
+                .get_checkpoint_sequence_number_by_transaction(&d(1))
+                .unwrap(),
+            Some(0)
+        );
+        assert!(checkpoint_service
+            .get_checkpoint_effects(&effects.digest())
+            .unwrap()
+            .is_some());
+
+        checkpoint_service
+            .update_checkpoint_watermark(CheckpointWatermark::HighestSynced, &certified)
+            .unwrap();
+        assert_eq!(
+            checkpoint_service
+                .get_checkpoint_watermark(CheckpointWatermark::HighestSynced)
+                .unwrap()
+                .map(|c| c.sequence_number()),
+            Some(0)
+        );
     }
 
     #[async_trait]
@@ -0,0 +1,148 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::authority::AuthorityStore;
+use crate::checkpoints::{CheckpointService, CheckpointWatermark};
+use crate::epoch::committee_store::CommitteeStore;
+use std::sync::Arc;
+use sui_types::base_types::{TransactionDigest, TransactionEffectsDigest};
+use sui_types::committee::{Committee, EpochId};
+use sui_types::error::SuiResult;
+use sui_types::messages::{TransactionEffects, VerifiedCertificate};
+use sui_types::messages_checkpoint::{
+    CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
+    VerifiedCheckpoint,
+};
+use sui_types::storage::{ReadStore, WriteStore};
+
+/// Storage backing the state sync subsystem, on top of the checkpoint and authority stores.
+///
+/// Certificates are read from the authority store when this node executed them itself, which is
+/// how validators serve the transactions of the checkpoints they build.
+#[derive(Clone)]
+pub struct StateSyncStore {
+    checkpoint_service: Arc<CheckpointService>,
+    authority_store: Arc<AuthorityStore>,
+    committee_store: Arc<CommitteeStore>,
+}
+
+impl StateSyncStore {
+    pub fn new(
+        checkpoint_service: Arc<CheckpointService>,
+        authority_store: Arc<AuthorityStore>,
+        committee_store: Arc<CommitteeStore>,
+    ) -> Self {
+        Self {
+            checkpoint_service,
+            authority_store,
+            committee_store,
+        }
+    }
+
+    fn get_checkpoint_watermark(
+        &self,
+        watermark: CheckpointWatermark,
+    ) -> SuiResult<Option<VerifiedCheckpoint>> {
+        self.checkpoint_service
+            .get_checkpoint_watermark(watermark)
+            .map(|checkpoint| checkpoint.map(VerifiedCheckpoint::new_unchecked))
+    }
+}
+
+impl ReadStore for StateSyncStore {
+    fn get_checkpoint_by_digest(
+        &self,
+        digest: &CheckpointDigest,
+    ) -> SuiResult<Option<VerifiedCheckpoint>> {
+        self.checkpoint_service
+            .get_certified_checkpoint_by_digest(digest)
+            .map(|checkpoint| checkpoint.map(VerifiedCheckpoint::new_unchecked))
+    }
+
+    fn get_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<VerifiedCheckpoint>> {
+        self.checkpoint_service
+            .get_certified_checkpoint(sequence_number)
+            .map(|checkpoint| checkpoint.map(VerifiedCheckpoint::new_unchecked))
+    }
+
+    fn get_highest_verified_checkpoint(&self) -> SuiResult<Option<VerifiedCheckpoint>> {
+        self.get_checkpoint_watermark(CheckpointWatermark::HighestVerified)
+    }
+
+    fn get_highest_synced_checkpoint(&self) -> SuiResult<Option<VerifiedCheckpoint>> {
+        self.get_checkpoint_watermark(CheckpointWatermark::HighestSynced)
+    }
+
+    fn get_checkpoint_contents(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> SuiResult<Option<CheckpointContents>> {
+        self.checkpoint_service
+            .get_checkpoint_contents_by_digest(digest)
+    }
+
+    fn get_committee(&self, epoch: EpochId) -> SuiResult<Option<Committee>> {
+        self.committee_store.get_committee(&epoch)
+    }
+
+    fn get_transaction(
+        &self,
+        digest: &TransactionDigest,
+    ) -> SuiResult<Option<VerifiedCertificate>> {
+        if let Some(certificate) = self.checkpoint_service.get_synced_transaction(digest)? {
+            return Ok(Some(certificate));
+        }
+        self.authority_store.get_certified_transaction(digest)
+    }
+
+    fn get_transaction_effects(
+        &self,
+        digest: &TransactionEffectsDigest,
+    ) -> SuiResult<Option<TransactionEffects>> {
+        self.checkpoint_service.get_checkpoint_effects(digest)
+    }
+}
+
+impl WriteStore for StateSyncStore {
+    fn insert_checkpoint(&self, checkpoint: VerifiedCheckpoint) -> SuiResult {
+        if let Some(next_committee) = checkpoint.next_epoch_committee() {
+            let next_committee = next_committee.iter().cloned().collect();
+            let committee = Committee::new(checkpoint.epoch().saturating_add(1), next_committee)
+                .expect("new committee from a certified checkpoint should be constructable");
+            self.insert_committee(committee)?;
+        }
+
+        self.checkpoint_service
+            .insert_certified_checkpoint(checkpoint.inner())
+    }
+
+    fn update_highest_synced_checkpoint(&self, checkpoint: &VerifiedCheckpoint) -> SuiResult {
+        self.checkpoint_service
+            .update_checkpoint_watermark(CheckpointWatermark::HighestSynced, checkpoint.inner())
+    }
+
+    fn insert_checkpoint_contents(&self, contents: CheckpointContents) -> SuiResult {
+        self.checkpoint_service.insert_checkpoint_contents(contents)
+    }
+
+    fn insert_committee(&self, new_committee: Committee) -> SuiResult {
+        // The committee may already be known through reconfiguration
+        if self.get_committee(new_committee.epoch)?.is_some() {
+            return Ok(());
+        }
+        self.committee_store.insert_new_committee(&new_committee)
+    }
+
+    fn insert_transaction(&self, transaction: VerifiedCertificate) -> SuiResult {
+        self.checkpoint_service
+            .insert_synced_transaction(transaction)
+    }
+
+    fn insert_transaction_effects(&self, transaction_effects: TransactionEffects) -> SuiResult {
+        self.checkpoint_service
+            .insert_checkpoint_effects(&transaction_effects)
+    }
+}
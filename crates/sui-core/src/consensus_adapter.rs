@@ -4,6 +4,7 @@
 use bytes::Bytes;
 use narwhal_types::TransactionProto;
 use narwhal_types::TransactionsClient;
+use narwhal_worker::{BatchPolicy, TransactionPriority};
 use prometheus::register_int_gauge_with_registry;
 use prometheus::IntCounter;
 use prometheus::IntGauge;
@@ -20,7 +21,7 @@ use sui_types::base_types::TransactionDigest;
 use sui_types::committee::Committee;
 use sui_types::{
     error::{SuiError, SuiResult},
-    messages::{ConsensusTransaction, VerifiedCertificate},
+    messages::{ConsensusTransaction, ConsensusTransactionKind, VerifiedCertificate},
 };
 
 use tap::prelude::*;
@@ -148,6 +149,42 @@ pub trait SubmitToConsensus: Sync + Send + 'static {
     async fn submit_to_consensus(&self, transaction: &ConsensusTransaction) -> SuiResult;
 }
 
+/// Orders the transactions waiting in the consensus worker to be sealed into batches, so that
+/// under congestion certificates paying a higher gas price are sequenced first. Checkpoint
+/// signatures are sequenced before any certificate, and the worker limits the pending
+/// certificates of each sender.
+pub struct ConsensusTransactionPolicy;
+
+impl BatchPolicy for ConsensusTransactionPolicy {
+    fn prioritize(&self, transaction: &narwhal_types::Transaction) -> TransactionPriority {
+        match bincode::deserialize::<ConsensusTransaction>(transaction) {
+            Ok(ConsensusTransaction {
+                kind: ConsensusTransactionKind::UserTransaction(certificate),
+                ..
+            }) => {
+                let data = &certificate.data().data;
+                TransactionPriority {
+                    priority: data.gas_price,
+                    sender: Some(data.sender_address().to_vec()),
+                }
+            }
+            Ok(ConsensusTransaction {
+                kind: ConsensusTransactionKind::CheckpointSignature(_),
+                ..
+            }) => TransactionPriority {
+                priority: u64::MAX,
+                sender: None,
+            },
+            // Transactions that are not consensus transactions come last, and share the limits
+            // of a single sender.
+            Err(_) => TransactionPriority {
+                priority: 0,
+                sender: Some(Vec::new()),
+            },
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl SubmitToConsensus for TransactionsClient<sui_network::tonic::transport::Channel> {
     async fn submit_to_consensus(&self, transaction: &ConsensusTransaction) -> SuiResult {
@@ -30,8 +30,8 @@ use sui_types::{
     base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest, TxContext},
     gas::SuiGasStatus,
     messages::{
-        CallArg, ChangeEpoch, ExecutionStatus, MoveCall, MoveModulePublish, SingleTransactionKind,
-        TransactionData, TransactionEffects, TransferObject, TransferSui,
+        CallArg, ChangeEpoch, ExecutionStatus, MoveCall, MoveModulePublish, MoveModuleUpgrade,
+        SingleTransactionKind, TransactionData, TransactionEffects, TransferObject, TransferSui,
     },
     object::Object,
     storage::BackingPackageStore,
@@ -135,6 +135,7 @@ fn execute_transaction<S: BackingPackageStore + ParentSync + ChildObjectResolver
         // TODO: Since we require all mutable objects to not show up more than
         // once across single tx, we should be able to run them in parallel.
         for single_tx in transaction_data.kind.into_single_transactions() {
+            let upgradeable = matches!(single_tx, SingleTransactionKind::PublishUpgradeable(_));
             result = match single_tx {
                 SingleTransactionKind::TransferObject(TransferObject {
                     recipient,
@@ -206,9 +207,26 @@ fn execute_transaction<S: BackingPackageStore + ParentSync + ChildObjectResolver
                         break;
                     }
 
-                    let module_id = ModuleId::new(package.0.into(), module);
+                    // the modules of every version of a package are linked at its original ID
+                    let package_id = match temporary_store.objects().get(&package.0) {
+                        Some(Object {
+                            data: Data::Package(package),
+                            ..
+                        }) => package.original_id(),
+                        _ => package.0,
+                    };
+                    let module_id = ModuleId::new(package_id.into(), module);
+                    // the shared VM caches modules by ID, which the versions of a package share
+                    let linked_vm;
+                    let vm = if temporary_store.links_upgraded_packages() {
+                        linked_vm = adapter::new_move_vm(native_functions.clone())
+                            .expect("We defined natives to not fail here");
+                        &linked_vm
+                    } else {
+                        move_vm.as_ref()
+                    };
                     adapter::execute(
-                        move_vm,
+                        vm,
                         temporary_store,
                         module_id,
                         &function,
@@ -218,7 +236,8 @@ fn execute_transaction<S: BackingPackageStore + ParentSync + ChildObjectResolver
                         tx_ctx,
                     )
                 }
-                SingleTransactionKind::Publish(MoveModulePublish { modules }) => {
+                SingleTransactionKind::Publish(MoveModulePublish { modules })
+                | SingleTransactionKind::PublishUpgradeable(MoveModulePublish { modules }) => {
                     // Charge gas for this VM execution
                     if let Err(e) = gas_status.charge_vm_gas() {
                         result = Err(e);
@@ -235,6 +254,34 @@ fn execute_transaction<S: BackingPackageStore + ParentSync + ChildObjectResolver
                         temporary_store,
                         native_functions.clone(),
                         modules,
+                        upgradeable,
+                        tx_ctx,
+                        gas_status.create_move_gas_status(),
+                    )
+                }
+                SingleTransactionKind::Upgrade(MoveModuleUpgrade {
+                    modules,
+                    package,
+                    upgrade_cap,
+                }) => {
+                    // Charge gas for this VM execution
+                    if let Err(e) = gas_status.charge_vm_gas() {
+                        result = Err(e);
+                        break;
+                    }
+                    // An upgrade is charged like the publish of a new package
+                    if let Err(e) =
+                        gas_status.charge_publish_package(modules.iter().map(|v| v.len()).sum())
+                    {
+                        result = Err(e);
+                        break;
+                    }
+                    adapter::upgrade(
+                        temporary_store,
+                        native_functions.clone(),
+                        modules,
+                        package,
+                        upgrade_cap.0,
                         tx_ctx,
                         gas_status.create_move_gas_status(),
                     )
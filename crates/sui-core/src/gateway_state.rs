@@ -895,7 +895,8 @@ where
     ) -> Result<Option<SuiParsedTransactionResponse>, anyhow::Error> {
         if let TransactionKind::Single(tx_kind) = tx_kind {
             match tx_kind {
-                SingleTransactionKind::Publish(_) => {
+                SingleTransactionKind::Publish(_)
+                | SingleTransactionKind::PublishUpgradeable(_) => {
                     self.metrics.num_tx_publish.inc();
                     return Ok(Some(
                         self.create_publish_response(certificate, effects).await?,
@@ -3,6 +3,3 @@
 
 mod node_state;
 pub use node_state::*;
-
-mod node_follower;
-pub use node_follower::*;
@@ -205,6 +205,55 @@ async fn test_dry_run_transaction() {
     assert_eq!(shared_object_version, SequenceNumber::MIN);
 }
 
+#[tokio::test]
+async fn test_dry_run_transaction_with_gas_profile() {
+    let (sender, keypair): (_, AccountKeyPair) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let (authority, pkg_ref) =
+        init_state_with_ids_and_object_basics(vec![(sender, gas_object_id)]).await;
+    let gas_object_ref = authority
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .compute_object_reference();
+
+    let data = TransactionData::new_move_call(
+        sender,
+        pkg_ref,
+        ident_str!("object_basics").to_owned(),
+        ident_str!("create").to_owned(),
+        /* type_args */ vec![],
+        gas_object_ref,
+        /* args */
+        vec![
+            CallArg::Pure(16u64.to_le_bytes().to_vec()),
+            CallArg::Pure(bcs::to_bytes(&AccountAddress::from(sender)).unwrap()),
+        ],
+        MAX_GAS,
+    );
+    let transaction = to_sender_signed_transaction(data, &keypair);
+    let (effects, profiler) = authority
+        .dry_exec_transaction_with_gas_profile(
+            transaction.data().data.clone(),
+            *transaction.digest(),
+        )
+        .await
+        .unwrap();
+    assert!(effects.status.is_ok());
+
+    // every stack starts at the entry function, and the framework natives it calls are recorded
+    let stacks = profiler.stacks();
+    assert!(stacks
+        .iter()
+        .all(|(frames, _)| frames[0].ends_with("::object_basics::create")));
+    assert!(stacks.iter().any(|(frames, _)| {
+        let last = frames.last().unwrap();
+        last.starts_with("0x2::") && last.ends_with("[native]")
+    }));
+    assert!(stacks.iter().all(|(_, gas)| *gas > 0));
+}
+
 #[tokio::test]
 async fn test_handle_transfer_transaction_bad_signature() {
     let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
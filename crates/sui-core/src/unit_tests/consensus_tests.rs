@@ -187,3 +187,27 @@ impl Transactions for ConsensusMockServer {
         unimplemented!()
     }
 }
+
+#[tokio::test]
+async fn consensus_transaction_policy_prioritizes_gas_price() {
+    let mut objects = test_gas_objects();
+    objects.push(test_shared_object());
+    let state = init_state_with_objects(objects).await;
+    let certificate = test_certificates(&state).await.pop().unwrap();
+    let data = &certificate.data().data;
+
+    let transaction =
+        ConsensusTransaction::new_certificate_message(&state.name, certificate.clone());
+    let priority =
+        ConsensusTransactionPolicy.prioritize(&bincode::serialize(&transaction).unwrap());
+    assert_eq!(priority.priority, data.gas_price);
+    assert_eq!(priority.sender, Some(data.sender_address().to_vec()));
+
+    // bytes that are not a consensus transaction come last
+    assert_eq!(
+        ConsensusTransactionPolicy
+            .prioritize(&vec![1, 2, 3])
+            .priority,
+        0
+    );
+}
@@ -0,0 +1,25 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// First version of an upgradeable package
+module examples::versioned {
+    use sui::object::{Self, UID};
+    use sui::tx_context::{Self, TxContext};
+    use sui::transfer;
+
+    struct Item has key, store {
+        id: UID,
+        value: u64,
+    }
+
+    public entry fun create(value: u64, ctx: &mut TxContext) {
+        transfer::transfer(
+            Item { id: object::new(ctx), value },
+            tx_context::sender(ctx)
+        )
+    }
+
+    public fun value(item: &Item): u64 {
+        item.value
+    }
+}
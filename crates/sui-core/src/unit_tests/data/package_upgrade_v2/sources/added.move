@@ -0,0 +1,17 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Module added by the upgrade, whose initializer runs on upgrade
+module examples::added {
+    use sui::object::{Self, UID};
+    use sui::tx_context::{Self, TxContext};
+    use sui::transfer;
+
+    struct Registry has key {
+        id: UID,
+    }
+
+    fun init(ctx: &mut TxContext) {
+        transfer::transfer(Registry { id: object::new(ctx) }, tx_context::sender(ctx))
+    }
+}
@@ -0,0 +1,16 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Moves upgrade capabilities out of the publisher's hands
+module examples::holder {
+    use sui::package::UpgradeCap;
+    use sui::transfer;
+
+    public entry fun freeze_cap(cap: UpgradeCap) {
+        transfer::freeze_object(cap)
+    }
+
+    public entry fun give_cap(cap: UpgradeCap, recipient: address) {
+        transfer::transfer(cap, recipient)
+    }
+}
@@ -20,8 +20,9 @@ use sui_types::{
     crypto::{get_key_pair, AccountKeyPair},
     error::SuiError,
     event::{Event, EventType},
-    messages::ExecutionStatus,
-    object::OBJECT_START_VERSION,
+    messages::{ExecutionFailureStatus, ExecutionStatus},
+    move_package::UpgradeCap,
+    object::{Owner, OBJECT_START_VERSION},
 };
 
 use std::path::PathBuf;
@@ -1846,27 +1847,398 @@ fn test_object_no_id_error() {
         path.push("src/unit_tests/data/object_no_id/");
         let res = sui_framework::build_move_package(&path, build_config);
 
-        matches!(res.err(), Some(SuiError::ExecutionError(err_str)) if
-                 err_str.contains("SuiMoveVerificationError")
-                 && err_str.contains("First field of struct NotObject must be 'id'"));
+        assert!(
+            matches!(res.err(), Some(SuiError::ModuleVerificationFailure { error }) if
+                 error.contains("First field of struct NotObject must be 'id'"))
+        );
     })
 }
 
-pub async fn build_and_try_publish_test_package(
+#[test]
+#[cfg_attr(msim, ignore)]
+fn test_package_upgrade() {
+    run_tokio_test_with_big_stack(async move {
+        let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+        let gas = ObjectID::random();
+        let authority = init_state_with_ids(vec![(sender, gas)]).await;
+
+        let (v1, upgrade_cap) =
+            publish_upgradeable_test_package(&authority, &sender, &sender_key, &gas).await;
+
+        // changing the layout of a struct is not allowed
+        let effects = upgrade_test_package(
+            &authority,
+            &sender,
+            &sender_key,
+            &gas,
+            "package_upgrade_incompatible",
+            v1,
+            &upgrade_cap,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            effects.status,
+            ExecutionStatus::Failure {
+                error: ExecutionFailureStatus::PackageUpgradeErrorIncompatible
+            }
+        );
+
+        // adding functions and modules is
+        let effects = upgrade_test_package(
+            &authority,
+            &sender,
+            &sender_key,
+            &gas,
+            "package_upgrade_v2",
+            v1,
+            &upgrade_cap,
+        )
+        .await
+        .unwrap();
+        assert!(effects.status.is_ok(), "{:?}", effects.status);
+        let (v2, _) = *effects
+            .created
+            .iter()
+            .find(|(_, owner)| owner == &Owner::Immutable)
+            .unwrap();
+        // the initializer of the added module created an object
+        assert_eq!(effects.created.len(), 2);
+
+        let v2_package = authority.get_object(&v2.0).await.unwrap().unwrap();
+        let v2_package = v2_package.data.try_as_package().unwrap();
+        assert_eq!(v2_package.version(), 2);
+        assert_eq!(v2_package.original_id(), v1);
+        assert_eq!(v2_package.previous_id(), Some(v1));
+
+        let cap_object = authority.get_object(&upgrade_cap).await.unwrap().unwrap();
+        let cap =
+            UpgradeCap::from_bcs_bytes(cap_object.data.try_as_move().unwrap().contents()).unwrap();
+        assert_eq!(cap.package.bytes, v2.0);
+        assert_eq!(cap.version, 2);
+
+        // only the latest version of the package can be upgraded
+        let effects = upgrade_test_package(
+            &authority,
+            &sender,
+            &sender_key,
+            &gas,
+            "package_upgrade_v2",
+            v1,
+            &upgrade_cap,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            effects.status,
+            ExecutionStatus::Failure {
+                error: ExecutionFailureStatus::PackageUpgradeErrorInvalidCapability
+            }
+        );
+    })
+}
+
+#[test]
+#[cfg_attr(msim, ignore)]
+fn test_package_upgrade_preserves_types() {
+    run_tokio_test_with_big_stack(async move {
+        let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+        let gas = ObjectID::random();
+        let authority = init_state_with_ids(vec![(sender, gas)]).await;
+
+        let (v1, upgrade_cap) =
+            publish_upgradeable_test_package(&authority, &sender, &sender_key, &gas).await;
+        let v1_ref = authority
+            .get_object(&v1)
+            .await
+            .unwrap()
+            .unwrap()
+            .compute_object_reference();
+        let effects = call_move(
+            &authority,
+            &gas,
+            &sender,
+            &sender_key,
+            &v1_ref,
+            "versioned",
+            "create",
+            vec![],
+            vec![TestCallArg::Pure(bcs::to_bytes(&(21_u64)).unwrap())],
+        )
+        .await
+        .unwrap();
+        assert!(effects.status.is_ok(), "{:?}", effects.status);
+        let ((item, _, _), _) = effects.created[0];
+
+        let effects = upgrade_test_package(
+            &authority,
+            &sender,
+            &sender_key,
+            &gas,
+            "package_upgrade_v2",
+            v1,
+            &upgrade_cap,
+        )
+        .await
+        .unwrap();
+        assert!(effects.status.is_ok(), "{:?}", effects.status);
+        let (v2, _) = *effects
+            .created
+            .iter()
+            .find(|(_, owner)| owner == &Owner::Immutable)
+            .unwrap();
+
+        // the code of the upgrade accepts the objects created by the first version
+        let effects = call_move(
+            &authority,
+            &gas,
+            &sender,
+            &sender_key,
+            &v2,
+            "versioned",
+            "double_value",
+            vec![],
+            vec![TestCallArg::Object(item)],
+        )
+        .await
+        .unwrap();
+        assert!(effects.status.is_ok(), "{:?}", effects.status);
+        let item = authority.get_object(&item).await.unwrap().unwrap();
+        let item = item.data.try_as_move().unwrap();
+        assert_eq!(item.type_.address, v1.into());
+        let value = u64::from_le_bytes(item.contents()[ObjectID::LENGTH..].try_into().unwrap());
+        assert_eq!(value, 42);
+
+        // and creates objects of the same type
+        let effects = call_move(
+            &authority,
+            &gas,
+            &sender,
+            &sender_key,
+            &v2,
+            "versioned",
+            "create",
+            vec![],
+            vec![TestCallArg::Pure(bcs::to_bytes(&(1_u64)).unwrap())],
+        )
+        .await
+        .unwrap();
+        assert!(effects.status.is_ok(), "{:?}", effects.status);
+        let ((new_item, _, _), _) = effects.created[0];
+        let new_item = authority.get_object(&new_item).await.unwrap().unwrap();
+        assert_eq!(new_item.data.try_as_move().unwrap().type_, item.type_);
+    })
+}
+
+#[test]
+#[cfg_attr(msim, ignore)]
+fn test_package_upgrade_frozen_cap() {
+    run_tokio_test_with_big_stack(async move {
+        let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+        let gas = ObjectID::random();
+        let authority = init_state_with_ids(vec![(sender, gas)]).await;
+
+        let (v1, upgrade_cap) =
+            publish_upgradeable_test_package(&authority, &sender, &sender_key, &gas).await;
+        let holder = build_and_publish_test_package(
+            &authority,
+            &sender,
+            &sender_key,
+            &gas,
+            "upgrade_cap_holder",
+        )
+        .await;
+        let effects = call_move(
+            &authority,
+            &gas,
+            &sender,
+            &sender_key,
+            &holder,
+            "holder",
+            "freeze_cap",
+            vec![],
+            vec![TestCallArg::Object(upgrade_cap)],
+        )
+        .await
+        .unwrap();
+        assert!(effects.status.is_ok(), "{:?}", effects.status);
+
+        // a frozen capability is an input anyone can pass, but it no longer grants the upgrade
+        let effects = upgrade_test_package(
+            &authority,
+            &sender,
+            &sender_key,
+            &gas,
+            "package_upgrade_v2",
+            v1,
+            &upgrade_cap,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            effects.status,
+            ExecutionStatus::Failure {
+                error: ExecutionFailureStatus::PackageUpgradeErrorInvalidCapability
+            }
+        );
+        let cap_object = authority.get_object(&upgrade_cap).await.unwrap().unwrap();
+        assert_eq!(cap_object.owner, Owner::Immutable);
+        let cap =
+            UpgradeCap::from_bcs_bytes(cap_object.data.try_as_move().unwrap().contents()).unwrap();
+        assert_eq!(cap.package.bytes, v1);
+        assert_eq!(cap.version, 1);
+    })
+}
+
+#[test]
+#[cfg_attr(msim, ignore)]
+fn test_package_upgrade_cap_owned_by_another_address() {
+    run_tokio_test_with_big_stack(async move {
+        let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+        let (recipient, _): (_, AccountKeyPair) = get_key_pair();
+        let gas = ObjectID::random();
+        let authority = init_state_with_ids(vec![(sender, gas)]).await;
+
+        let (v1, upgrade_cap) =
+            publish_upgradeable_test_package(&authority, &sender, &sender_key, &gas).await;
+        let holder = build_and_publish_test_package(
+            &authority,
+            &sender,
+            &sender_key,
+            &gas,
+            "upgrade_cap_holder",
+        )
+        .await;
+        let effects = call_move(
+            &authority,
+            &gas,
+            &sender,
+            &sender_key,
+            &holder,
+            "holder",
+            "give_cap",
+            vec![],
+            vec![
+                TestCallArg::Object(upgrade_cap),
+                TestCallArg::Pure(bcs::to_bytes(&recipient).unwrap()),
+            ],
+        )
+        .await
+        .unwrap();
+        assert!(effects.status.is_ok(), "{:?}", effects.status);
+
+        // the publisher gave the capability away and can no longer upgrade the package
+        let result = upgrade_test_package(
+            &authority,
+            &sender,
+            &sender_key,
+            &gas,
+            "package_upgrade_v2",
+            v1,
+            &upgrade_cap,
+        )
+        .await;
+        assert!(
+            matches!(
+                &result,
+                Err(SuiError::TransactionInputObjectsErrors { errors })
+                    if matches!(errors[..], [SuiError::IncorrectSigner { .. }])
+            ),
+            "{:?}",
+            result
+        );
+        let cap_object = authority.get_object(&upgrade_cap).await.unwrap().unwrap();
+        assert_eq!(cap_object.owner, Owner::AddressOwner(recipient));
+        let cap =
+            UpgradeCap::from_bcs_bytes(cap_object.data.try_as_move().unwrap().contents()).unwrap();
+        assert_eq!(cap.package.bytes, v1);
+        assert_eq!(cap.version, 1);
+    })
+}
+
+/// Publish the first version of the upgradeable test package, and return its ID and the ID of its
+/// upgrade capability
+async fn publish_upgradeable_test_package(
     authority: &AuthorityState,
     sender: &SuiAddress,
     sender_key: &AccountKeyPair,
     gas_object_id: &ObjectID,
-    test_dir: &str,
-    gas_budget: u64,
-) -> VerifiedTransactionInfoResponse {
+) -> (ObjectID, ObjectID) {
+    let gas_object = authority.get_object(gas_object_id).await.unwrap().unwrap();
+    let data = TransactionData::new_upgradeable_module(
+        *sender,
+        gas_object.compute_object_reference(),
+        build_test_package("package_upgrade_v1"),
+        MAX_GAS,
+    );
+    let effects =
+        send_and_confirm_transaction(authority, to_sender_signed_transaction(data, sender_key))
+            .await
+            .unwrap()
+            .signed_effects
+            .unwrap()
+            .into_data();
+    assert!(effects.status.is_ok(), "{:?}", effects.status);
+    let (package, _) = *effects
+        .created
+        .iter()
+        .find(|(_, owner)| owner == &Owner::Immutable)
+        .unwrap();
+    let (upgrade_cap, _) = *effects
+        .created
+        .iter()
+        .find(|(_, owner)| owner == &Owner::AddressOwner(*sender))
+        .unwrap();
+    (package.0, upgrade_cap.0)
+}
+
+fn build_test_package(test_dir: &str) -> Vec<Vec<u8>> {
     let build_config = BuildConfig::default();
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("src/unit_tests/data/");
     path.push(test_dir);
-    let all_module_bytes = sui_framework::build_move_package(&path, build_config)
+    sui_framework::build_move_package(&path, build_config)
         .unwrap()
-        .get_package_bytes();
+        .get_package_bytes()
+}
+
+async fn upgrade_test_package(
+    authority: &AuthorityState,
+    sender: &SuiAddress,
+    sender_key: &AccountKeyPair,
+    gas_object_id: &ObjectID,
+    test_dir: &str,
+    package: ObjectID,
+    upgrade_cap: &ObjectID,
+) -> SuiResult<TransactionEffects> {
+    let gas_object = authority.get_object(gas_object_id).await.unwrap().unwrap();
+    let upgrade_cap = authority.get_object(upgrade_cap).await.unwrap().unwrap();
+    let data = TransactionData::new_upgrade(
+        *sender,
+        gas_object.compute_object_reference(),
+        build_test_package(test_dir),
+        package,
+        upgrade_cap.compute_object_reference(),
+        MAX_GAS,
+    );
+    Ok(
+        send_and_confirm_transaction(authority, to_sender_signed_transaction(data, sender_key))
+            .await?
+            .signed_effects
+            .unwrap()
+            .into_data(),
+    )
+}
+
+pub async fn build_and_try_publish_test_package(
+    authority: &AuthorityState,
+    sender: &SuiAddress,
+    sender_key: &AccountKeyPair,
+    gas_object_id: &ObjectID,
+    test_dir: &str,
+    gas_budget: u64,
+) -> VerifiedTransactionInfoResponse {
+    let all_module_bytes = build_test_package(test_dir);
 
     let gas_object = authority.get_object(gas_object_id).await.unwrap();
     let gas_object_ref = gas_object.unwrap().compute_object_reference();
@@ -8,6 +8,7 @@ use move_binary_format::file_format_common::Opcodes::{self};
 use move_core_types::gas_algebra::{
     AbstractMemorySize, InternalGas, InternalGasPerAbstractMemoryUnit, NumArgs, NumBytes,
 };
+use move_core_types::identifier::IdentStr;
 use move_core_types::language_storage::ModuleId;
 use move_core_types::u256::U256;
 use move_core_types::vm_status::StatusCode;
@@ -15,6 +16,7 @@ use move_vm_types::gas::{GasMeter, SimpleInstruction};
 use move_vm_types::views::{TypeView, ValueView};
 use once_cell::sync::Lazy;
 
+use crate::gas_profiler::GasProfiler;
 use crate::units_types::{CostTable, Gas, GasCost};
 use move_binary_format::{
     file_format::{
@@ -52,6 +54,7 @@ pub struct GasStatus<'a> {
     cost_table: &'a CostTable,
     gas_left: InternalGas,
     charge: bool,
+    profiler: Option<GasProfiler>,
 }
 
 impl<'a> GasStatus<'a> {
@@ -64,6 +67,7 @@ impl<'a> GasStatus<'a> {
             gas_left: gas_left.to_unit(),
             cost_table,
             charge: true,
+            profiler: None,
         }
     }
 
@@ -76,6 +80,7 @@ impl<'a> GasStatus<'a> {
             gas_left: InternalGas::new(0),
             cost_table: &ZERO_COST_SCHEDULE,
             charge: false,
+            profiler: None,
         }
     }
 
@@ -89,6 +94,25 @@ impl<'a> GasStatus<'a> {
         self.gas_left.to_unit_round_down()
     }
 
+    /// Record the gas charged by Move calls in `profiler`.
+    pub fn set_profiler(&mut self, profiler: GasProfiler) {
+        self.profiler = Some(profiler);
+    }
+
+    /// Start profiling a call made by the adapter to `function`, if profiling is enabled.
+    pub fn start_profiled_call(&mut self, module_id: &ModuleId, function: &IdentStr) {
+        if let Some(profiler) = &self.profiler {
+            profiler.start_call(module_id, function);
+        }
+    }
+
+    /// Stop profiling the call made by the adapter, whether it returned or aborted.
+    pub fn finish_profiled_call(&mut self) {
+        if let Some(profiler) = &self.profiler {
+            profiler.finish_call();
+        }
+    }
+
     /// Charge a given amount of gas and fail if not enough gas units are left.
     pub fn deduct_gas(&mut self, amount: InternalGas) -> PartialVMResult<()> {
         if !self.charge {
@@ -97,10 +121,16 @@ impl<'a> GasStatus<'a> {
 
         match self.gas_left.checked_sub(amount) {
             Some(gas_left) => {
+                if let Some(profiler) = &self.profiler {
+                    profiler.charge(amount.into());
+                }
                 self.gas_left = gas_left;
                 Ok(())
             }
             None => {
+                if let Some(profiler) = &self.profiler {
+                    profiler.charge(self.gas_left.into());
+                }
                 self.gas_left = InternalGas::new(0);
                 Err(PartialVMError::new(StatusCode::OUT_OF_GAS))
             }
@@ -202,24 +232,33 @@ fn get_simple_instruction_opcode(instr: SimpleInstruction) -> Opcodes {
 impl<'b> GasMeter for GasStatus<'b> {
     /// Charge an instruction and fail if not enough gas units are left.
     fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
-        self.charge_instr(get_simple_instruction_opcode(instr))
+        let is_ret = matches!(instr, SimpleInstruction::Ret);
+        self.charge_instr(get_simple_instruction_opcode(instr))?;
+        if let (true, Some(profiler)) = (is_ret, &self.profiler) {
+            profiler.exit();
+        }
+        Ok(())
     }
 
     fn charge_call(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
     ) -> PartialVMResult<()> {
         // TODO (Gas Maintainance)
-        self.charge_instr_with_size(Opcodes::CALL, (args.len() as u64 + 1).into())
+        self.charge_instr_with_size(Opcodes::CALL, (args.len() as u64 + 1).into())?;
+        if let Some(profiler) = &self.profiler {
+            profiler.enter(module_id, func_name);
+        }
+        Ok(())
     }
 
     fn charge_call_generic(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         ty_args: impl ExactSizeIterator<Item = impl TypeView>,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
@@ -228,7 +267,11 @@ impl<'b> GasMeter for GasStatus<'b> {
         self.charge_instr_with_size(
             Opcodes::CALL_GENERIC,
             ((ty_args.len() + args.len() + 1) as u64).into(),
-        )
+        )?;
+        if let Some(profiler) = &self.profiler {
+            profiler.enter(module_id, func_name);
+        }
+        Ok(())
     }
 
     fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
@@ -436,7 +479,11 @@ impl<'b> GasMeter for GasStatus<'b> {
         _ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
     ) -> PartialVMResult<()> {
         // TODO (Gas Maintainance)
-        self.deduct_gas(amount)
+        self.deduct_gas(amount)?;
+        if let Some(profiler) = &self.profiler {
+            profiler.exit_native();
+        }
+        Ok(())
     }
 
     fn charge_pop(&mut self, _popped_val: impl ValueView) -> PartialVMResult<()> {
@@ -458,6 +505,9 @@ impl<'b> GasMeter for GasStatus<'b> {
         _args: impl ExactSizeIterator<Item = impl ValueView>,
     ) -> PartialVMResult<()> {
         // TODO (Gas Maintainance)
+        if let Some(profiler) = &self.profiler {
+            profiler.enter_native();
+        }
         Ok(())
     }
 
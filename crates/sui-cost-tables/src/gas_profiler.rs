@@ -0,0 +1,167 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Attribution of the gas charged by Move execution to the call stacks charging it.
+//!
+//! A `GasProfiler` attached to a `GasStatus` follows the calls the VM makes, and adds every
+//! amount the `GasStatus` deducts to the stack of functions being executed at the time. Native
+//! functions appear as the innermost frame of their caller's stack, with a `[native]` suffix.
+//! Profiles are reported as folded stacks (`0x2::coin::split;0x2::balance::split 1400`), the
+//! input format of flamegraph tools. Amounts are in internal gas units, a thousandth of a gas
+//! unit, as most instructions cost less than a gas unit.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
+
+const NATIVE_SUFFIX: &str = "[native]";
+
+/// A handle on a gas profile. Clones share the same profile, so that the profile can be read
+/// once the `GasStatus` recording it is consumed by execution.
+#[derive(Clone, Debug, Default)]
+pub struct GasProfiler {
+    inner: Arc<Mutex<Profile>>,
+}
+
+#[derive(Debug, Default)]
+struct Profile {
+    /// The functions being executed, innermost last
+    stack: Vec<String>,
+    /// Whether the innermost function is a native function
+    in_native: bool,
+    /// The gas charged by each call stack, excluding the gas charged by the calls it makes
+    stacks: BTreeMap<Vec<String>, u64>,
+}
+
+impl GasProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start recording a call to `function` made by the adapter, at the root of its call stack.
+    pub fn start_call(&self, module_id: &ModuleId, function: &IdentStr) {
+        let mut profile = self.inner.lock().unwrap();
+        profile.stack = vec![frame_name(module_id, function.as_str())];
+        profile.in_native = false;
+    }
+
+    /// Stop recording the call made by the adapter. A call that aborted leaves its frames behind,
+    /// and charges made after it must not be attributed to them.
+    pub fn finish_call(&self) {
+        let mut profile = self.inner.lock().unwrap();
+        profile.stack.clear();
+        profile.in_native = false;
+    }
+
+    pub(crate) fn enter(&self, module_id: &ModuleId, function: &str) {
+        let mut profile = self.inner.lock().unwrap();
+        profile.stack.push(frame_name(module_id, function));
+    }
+
+    /// Mark the function just entered as a native function. Native functions do not return
+    /// through `Ret`, so they are exited when their cost is charged.
+    pub(crate) fn enter_native(&self) {
+        let mut profile = self.inner.lock().unwrap();
+        if let Some(frame) = profile.stack.last_mut() {
+            frame.push_str(NATIVE_SUFFIX);
+            profile.in_native = true;
+        }
+    }
+
+    pub(crate) fn exit(&self) {
+        let mut profile = self.inner.lock().unwrap();
+        profile.stack.pop();
+        profile.in_native = false;
+    }
+
+    pub(crate) fn exit_native(&self) {
+        let mut profile = self.inner.lock().unwrap();
+        if profile.in_native {
+            profile.stack.pop();
+            profile.in_native = false;
+        }
+    }
+
+    /// Add `amount` to the call stack being executed. Charges made outside of Move calls, such
+    /// as storage charges, are not recorded.
+    pub(crate) fn charge(&self, amount: u64) {
+        let profile = &mut *self.inner.lock().unwrap();
+        if profile.stack.is_empty() || amount == 0 {
+            return;
+        }
+        match profile.stacks.get_mut(&profile.stack) {
+            Some(total) => *total += amount,
+            None => {
+                profile.stacks.insert(profile.stack.clone(), amount);
+            }
+        }
+    }
+
+    /// The gas charged by each call stack recorded, outermost frame first.
+    pub fn stacks(&self) -> Vec<(Vec<String>, u64)> {
+        let profile = self.inner.lock().unwrap();
+        profile
+            .stacks
+            .iter()
+            .map(|(stack, amount)| (stack.clone(), *amount))
+            .collect()
+    }
+
+    /// The profile in the folded stack format, one call stack per line.
+    pub fn to_folded(&self) -> String {
+        let mut folded = String::new();
+        for (stack, amount) in self.stacks() {
+            writeln!(folded, "{} {amount}", stack.join(";")).unwrap();
+        }
+        folded
+    }
+}
+
+fn frame_name(module_id: &ModuleId, function: &str) -> String {
+    format!(
+        "0x{}::{}::{function}",
+        module_id.address().short_str_lossless(),
+        module_id.name()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+
+    use super::*;
+
+    #[test]
+    fn charges_are_attributed_to_the_executing_stack() {
+        let coin = ModuleId::new(AccountAddress::TWO, Identifier::new("coin").unwrap());
+        let balance = ModuleId::new(AccountAddress::TWO, Identifier::new("balance").unwrap());
+        let profiler = GasProfiler::new();
+
+        profiler.charge(5); // outside of any call
+        profiler.start_call(&coin, &Identifier::new("split").unwrap());
+        profiler.charge(10);
+        profiler.enter(&balance, "split");
+        profiler.charge(20);
+        profiler.enter(&balance, "value");
+        profiler.enter_native();
+        profiler.charge(30);
+        profiler.exit_native();
+        profiler.charge(1);
+        profiler.exit();
+        profiler.charge(40);
+        profiler.exit();
+        profiler.finish_call();
+        profiler.charge(5);
+
+        assert_eq!(
+            profiler.to_folded(),
+            "0x2::coin::split 50\n\
+             0x2::coin::split;0x2::balance::split 21\n\
+             0x2::coin::split;0x2::balance::split;0x2::balance::value[native] 30\n"
+        );
+    }
+}
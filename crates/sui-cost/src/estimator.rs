@@ -99,7 +99,8 @@ fn estimate_transaction_inner<S>(
     // Steps 4 & 5
     for single_tx in tx.single_transactions() {
         match single_tx {
-            SingleTransactionKind::Publish(module) => {
+            SingleTransactionKind::Publish(module)
+            | SingleTransactionKind::PublishUpgradeable(module) => {
                 gas_status.charge_publish_package(module.modules.iter().map(|v| v.len()).sum())?
             }
             SingleTransactionKind::Call(_) => (),
@@ -0,0 +1,466 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation of Rust code for clients of a Move package:
+//! - structs with serde derives for the types of the package, whose BCS encoding is the encoding
+//!   of the Move values, to read the package's objects and events
+//! - typed wrappers around `sui-sdk` Move calls, for each entry function of the package
+//!
+//! Types are generated from the layouts of `CompiledPackage::generate_struct_layouts`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use move_binary_format::{access::ModuleAccess, file_format::FunctionDefinitionIndex, normalized};
+use move_compiler::compiled_unit::CompiledUnitEnum;
+use move_core_types::account_address::AccountAddress;
+use serde_reflection::{ContainerFormat, Format, Named};
+use sui_types::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
+
+use crate::compiled_package::CompiledPackage;
+
+const GENERATED_HEADER: &str =
+    "// @generated by `sui move codegen --lang rust`, do not edit by hand\n";
+
+/// Name of the Move `address` type in serde layouts
+const ADDRESS_LAYOUT_NAME: &str = "AccountAddress";
+
+/// Parameters of the generated entry function wrappers, that Move parameters cannot shadow
+const WRAPPER_PARAMS: &[&str] = &[
+    "client",
+    "package",
+    "signer",
+    "type_args",
+    "gas",
+    "gas_budget",
+];
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+/// Generate the Rust types and entry function wrappers of the modules of `package` (not
+/// including its dependencies), as the source of a Rust module depending on `serde`, `anyhow`
+/// and `sui-sdk`.
+pub fn generate_rust(package: &CompiledPackage) -> String {
+    let registry = package.generate_struct_layouts();
+    let names = rust_type_names(registry.keys());
+
+    let mut out = String::from(GENERATED_HEADER);
+    writeln!(out, "\nuse serde::{{Deserialize, Serialize}};").unwrap();
+    for (layout_name, container) in &registry {
+        let Some(name) = names.get(layout_name) else {
+            // mapped to an existing Rust type
+            continue;
+        };
+        writeln!(out, "\n/// Move type `{layout_name}`").unwrap();
+        if name.contains('_') {
+            writeln!(out, "#[allow(non_camel_case_types)]").unwrap();
+        }
+        writeln!(
+            out,
+            "#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]"
+        )
+        .unwrap();
+        write_container(&mut out, name, container, &names);
+    }
+
+    for unit in &package.package.root_compiled_units {
+        let CompiledUnitEnum::Module(m) = &unit.unit else {
+            continue;
+        };
+        let module = &m.module;
+        let entry_functions: Vec<_> = module
+            .function_defs
+            .iter()
+            .enumerate()
+            .filter(|(_, fdef)| fdef.is_entry)
+            .collect();
+        if entry_functions.is_empty() {
+            continue;
+        }
+        let module_name = module.name().as_str();
+        writeln!(
+            out,
+            "\n/// Entry functions of module `{}`",
+            module.self_id().name()
+        )
+        .unwrap();
+        writeln!(out, "pub mod {} {{", rust_ident(module_name)).unwrap();
+        for (idx, fdef) in entry_functions {
+            let handle = module.function_handle_at(fdef.function);
+            let (_, function) = normalized::Function::new(module, fdef);
+            let source_names = m
+                .source_map
+                .get_function_source_map(FunctionDefinitionIndex(idx as u16))
+                .map(|f| f.parameters.iter().map(|(n, _)| n.clone()).collect())
+                .unwrap_or_default();
+            write_entry_function(
+                &mut out,
+                module_name,
+                module.identifier_at(handle.name).as_str(),
+                &function,
+                &source_names,
+            );
+        }
+        writeln!(out, "}}").unwrap();
+    }
+    out
+}
+
+/// Name the Rust types generated for the layouts named `layout_names`. Layouts of Move types that
+/// already have a Rust equivalent are left out.
+fn rust_type_names<'a>(layout_names: impl Iterator<Item = &'a String>) -> BTreeMap<String, String> {
+    let candidates: Vec<_> = layout_names
+        .filter(|name| builtin_type(name).is_none())
+        .map(|name| (name, parse_layout_name(name)))
+        .collect();
+    let mut counts = BTreeMap::new();
+    for (_, parsed) in &candidates {
+        *counts.entry(parsed.rust_name()).or_insert(0) += 1;
+    }
+    candidates
+        .into_iter()
+        .map(|(layout_name, parsed)| {
+            let rust_name = parsed.rust_name();
+            // disambiguate types with the same name in different modules by their module
+            let rust_name = match parsed.module {
+                Some(module) if counts[&rust_name] > 1 => {
+                    format!("{}{}", camel_case(module), rust_name)
+                }
+                _ => rust_name,
+            };
+            (layout_name.clone(), rust_name)
+        })
+        .collect()
+}
+
+/// The layout name of a Move struct, `address::module::Name<TypeArgs>`
+struct LayoutName<'a> {
+    address: Option<AccountAddress>,
+    module: Option<&'a str>,
+    name: &'a str,
+    type_args: Vec<&'a str>,
+}
+
+fn parse_layout_name(layout_name: &str) -> LayoutName<'_> {
+    let (base, type_args) = match layout_name.split_once('<') {
+        Some((base, args)) => (
+            base,
+            split_type_args(args.strip_suffix('>').unwrap_or(args)),
+        ),
+        None => (layout_name, vec![]),
+    };
+    let mut parts = base.rsplit("::");
+    let name = parts.next().unwrap_or(base);
+    let module = parts.next();
+    let address = parts.next().and_then(|a| {
+        AccountAddress::from_hex_literal(a)
+            .or_else(|_| AccountAddress::from_hex(a))
+            .ok()
+    });
+    LayoutName {
+        address,
+        module,
+        name,
+        type_args,
+    }
+}
+
+impl LayoutName<'_> {
+    /// The name of the Rust type for this instantiation of the struct. Type arguments show up in
+    /// the name, except for the placeholders of phantom type parameters.
+    fn rust_name(&self) -> String {
+        let mut name = sanitize(self.name);
+        for arg in &self.type_args {
+            if *arg == "signer" {
+                continue;
+            }
+            let arg = parse_layout_name(arg);
+            name.push_str(&camel_case(&arg.rust_name()));
+        }
+        name
+    }
+}
+
+/// Split the type arguments of a layout name at the commas that are not nested in other type
+/// arguments
+fn split_type_args(args: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in args.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    if !args[start..].trim().is_empty() {
+        result.push(args[start..].trim());
+    }
+    result
+}
+
+/// The Rust type that a Move type with layout `layout_name` maps to, if the Sui SDK or Rust
+/// already have one with the same BCS encoding
+fn builtin_type(layout_name: &str) -> Option<&'static str> {
+    if layout_name == ADDRESS_LAYOUT_NAME {
+        return Some("sui_sdk::types::base_types::SuiAddress");
+    }
+    let parsed = parse_layout_name(layout_name);
+    let framework = |address| parsed.address.map_or(true, |a| a == address);
+    match (parsed.module?, parsed.name) {
+        ("object", "UID") if framework(SUI_FRAMEWORK_ADDRESS) => Some("sui_sdk::types::id::UID"),
+        ("object", "ID") if framework(SUI_FRAMEWORK_ADDRESS) => Some("sui_sdk::types::id::ID"),
+        ("string" | "ascii", "String") if framework(MOVE_STDLIB_ADDRESS) => Some("String"),
+        _ => None,
+    }
+}
+
+fn rust_type(format: &Format, names: &BTreeMap<String, String>) -> String {
+    match format {
+        Format::Variable(_) => unreachable!("layouts are fully resolved"),
+        Format::TypeName(name) => match builtin_type(name) {
+            Some(builtin) => builtin.to_string(),
+            None => names.get(name).cloned().unwrap_or_else(|| sanitize(name)),
+        },
+        Format::Unit => "()".to_string(),
+        Format::Bool => "bool".to_string(),
+        Format::I8 => "i8".to_string(),
+        Format::I16 => "i16".to_string(),
+        Format::I32 => "i32".to_string(),
+        Format::I64 => "i64".to_string(),
+        Format::I128 => "i128".to_string(),
+        Format::U8 => "u8".to_string(),
+        Format::U16 => "u16".to_string(),
+        Format::U32 => "u32".to_string(),
+        Format::U64 => "u64".to_string(),
+        Format::U128 => "u128".to_string(),
+        Format::F32 => "f32".to_string(),
+        Format::F64 => "f64".to_string(),
+        Format::Char => "char".to_string(),
+        Format::Str => "String".to_string(),
+        Format::Bytes => "Vec<u8>".to_string(),
+        Format::Option(inner) => format!("Option<{}>", rust_type(inner, names)),
+        Format::Seq(inner) => format!("Vec<{}>", rust_type(inner, names)),
+        Format::Map { key, value } => format!(
+            "std::collections::BTreeMap<{}, {}>",
+            rust_type(key, names),
+            rust_type(value, names)
+        ),
+        Format::Tuple(formats) => format!(
+            "({})",
+            formats
+                .iter()
+                .map(|f| format!("{}, ", rust_type(f, names)))
+                .collect::<String>()
+        ),
+        Format::TupleArray { content, size } => {
+            format!("[{}; {}]", rust_type(content, names), size)
+        }
+    }
+}
+
+fn write_container(
+    out: &mut String,
+    name: &str,
+    container: &ContainerFormat,
+    names: &BTreeMap<String, String>,
+) {
+    match container {
+        ContainerFormat::UnitStruct => writeln!(out, "pub struct {name};").unwrap(),
+        ContainerFormat::NewTypeStruct(format) => {
+            writeln!(out, "pub struct {name}(pub {});", rust_type(format, names)).unwrap()
+        }
+        ContainerFormat::TupleStruct(formats) => writeln!(
+            out,
+            "pub struct {name}({});",
+            formats
+                .iter()
+                .map(|f| format!("pub {}", rust_type(f, names)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+        .unwrap(),
+        ContainerFormat::Struct(fields) => {
+            writeln!(out, "pub struct {name} {{").unwrap();
+            for Named { name: field, value } in fields {
+                writeln!(
+                    out,
+                    "    pub {}: {},",
+                    rust_ident(field),
+                    rust_type(value, names)
+                )
+                .unwrap();
+            }
+            writeln!(out, "}}").unwrap();
+        }
+        ContainerFormat::Enum(_) => unreachable!("Move has no enums"),
+    }
+}
+
+fn write_entry_function(
+    out: &mut String,
+    module: &str,
+    function: &str,
+    normalized: &normalized::Function,
+    source_names: &[String],
+) {
+    let mut params = vec![];
+    let mut used_names: BTreeSet<String> = WRAPPER_PARAMS.iter().map(|p| p.to_string()).collect();
+    for (i, ty) in normalized.parameters.iter().enumerate() {
+        let Some(rust_ty) = arg_type(ty) else {
+            continue;
+        };
+        let mut param = source_names
+            .get(i)
+            .map(|n| rust_ident(n.trim_start_matches('_')))
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| format!("arg{i}"));
+        if !used_names.insert(param.clone()) {
+            param = format!("{param}_{i}");
+            used_names.insert(param.clone());
+        }
+        params.push((param, rust_ty));
+    }
+    let has_type_params = !normalized.type_parameters.is_empty();
+
+    writeln!(
+        out,
+        "\n    /// Build a transaction calling entry function `{module}::{function}`"
+    )
+    .unwrap();
+    writeln!(out, "    #[allow(clippy::too_many_arguments)]").unwrap();
+    writeln!(out, "    pub async fn {}(", rust_ident(function)).unwrap();
+    writeln!(out, "        client: &sui_sdk::SuiClient,").unwrap();
+    writeln!(
+        out,
+        "        package: sui_sdk::types::base_types::ObjectID,"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        signer: sui_sdk::types::base_types::SuiAddress,"
+    )
+    .unwrap();
+    if has_type_params {
+        writeln!(
+            out,
+            "        type_args: Vec<sui_sdk::rpc_types::SuiTypeTag>,"
+        )
+        .unwrap();
+    }
+    for (param, ty) in &params {
+        writeln!(out, "        {param}: {ty},").unwrap();
+    }
+    writeln!(
+        out,
+        "        gas: Option<sui_sdk::types::base_types::ObjectID>,"
+    )
+    .unwrap();
+    writeln!(out, "        gas_budget: u64,").unwrap();
+    writeln!(
+        out,
+        "    ) -> anyhow::Result<sui_sdk::types::messages::TransactionData> {{"
+    )
+    .unwrap();
+    writeln!(out, "        client").unwrap();
+    writeln!(out, "            .transaction_builder()").unwrap();
+    writeln!(out, "            .move_call(").unwrap();
+    writeln!(out, "                signer,").unwrap();
+    writeln!(out, "                package,").unwrap();
+    writeln!(out, "                {module:?},").unwrap();
+    writeln!(out, "                {function:?},").unwrap();
+    if has_type_params {
+        writeln!(out, "                type_args,").unwrap();
+    } else {
+        writeln!(out, "                vec![],").unwrap();
+    }
+    writeln!(out, "                vec![").unwrap();
+    for (param, _) in &params {
+        writeln!(
+            out,
+            "                    sui_sdk::move_call::MoveCallArg::to_sui_json(&{param})?,"
+        )
+        .unwrap();
+    }
+    writeln!(out, "                ],").unwrap();
+    writeln!(out, "                gas,").unwrap();
+    writeln!(out, "                gas_budget,").unwrap();
+    writeln!(out, "            )").unwrap();
+    writeln!(out, "            .await").unwrap();
+    writeln!(out, "    }}").unwrap();
+}
+
+/// The Rust type of the argument for an entry function parameter of type `ty`, or `None` for the
+/// transaction context, that the transaction provides
+fn arg_type(ty: &normalized::Type) -> Option<String> {
+    use normalized::Type;
+    Some(match ty {
+        Type::Bool => "bool".to_string(),
+        Type::U8 => "u8".to_string(),
+        Type::U16 => "u16".to_string(),
+        Type::U32 => "u32".to_string(),
+        Type::U64 => "u64".to_string(),
+        Type::U128 => "u128".to_string(),
+        Type::U256 => "sui_sdk::move_call::U256".to_string(),
+        Type::Address | Type::Signer => "sui_sdk::types::base_types::SuiAddress".to_string(),
+        Type::Vector(inner) => format!("Vec<{}>", arg_type(inner)?),
+        Type::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => match (*address, module.as_str(), name.as_str()) {
+            (MOVE_STDLIB_ADDRESS, "string" | "ascii", "String") => "String".to_string(),
+            (MOVE_STDLIB_ADDRESS, "option", "Option") => {
+                format!("Option<{}>", arg_type(type_arguments.first()?)?)
+            }
+            (SUI_FRAMEWORK_ADDRESS, "tx_context", "TxContext") => return None,
+            // IDs, and objects passed by value, are passed by their ID
+            _ => "sui_sdk::types::base_types::ObjectID".to_string(),
+        },
+        Type::Reference(inner) | Type::MutableReference(inner) => {
+            arg_type(inner)?;
+            "sui_sdk::types::base_types::ObjectID".to_string()
+        }
+        Type::TypeParameter(_) => "sui_sdk::types::base_types::ObjectID".to_string(),
+    })
+}
+
+/// `name` as a Rust identifier, escaping keywords
+fn rust_ident(name: &str) -> String {
+    let name = sanitize(name);
+    match name.as_str() {
+        "self" | "super" | "crate" | "Self" => format!("{name}_"),
+        n if RUST_KEYWORDS.contains(&n) => format!("r#{name}"),
+        _ => name,
+    }
+}
+
+/// Replace the characters of `name` that cannot be in a Rust identifier
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().unwrap().to_ascii_uppercase().to_string() + chars.as_str()
+        })
+        .collect()
+}
@@ -2,40 +2,73 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-    collections::{BTreeSet, HashSet},
-    path::PathBuf,
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt::{self, Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
 };
 
 use fastcrypto::encoding::Base64;
 use move_binary_format::{
     access::ModuleAccess,
+    errors::VMError,
+    file_format::{FunctionDefinitionIndex, StructDefinitionIndex},
     normalized::{self, Type},
-    CompiledModule,
+    CompiledModule, IndexKind,
 };
 use move_bytecode_utils::{layout::SerdeLayoutBuilder, module_cache::GetModule, Modules};
-use move_compiler::compiled_unit::CompiledUnitEnum;
+use move_compiler::compiled_unit::{CompiledUnitEnum, NamedCompiledModule};
 use move_core_types::{
     account_address::AccountAddress,
     language_storage::{ModuleId, StructTag, TypeTag},
 };
 use move_package::{
-    compilation::compiled_package::CompiledPackage as MoveCompiledPackage,
+    compilation::compiled_package::{
+        CompiledPackage as MoveCompiledPackage, CompiledUnitWithSource,
+    },
     BuildConfig as MoveBuildConfig,
 };
 use serde_reflection::Registry;
 use sui_types::{
+    base_types::ObjectID,
+    bytecode_rewriter::ModuleHandleRewriter,
     error::{SuiError, SuiResult},
     MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS,
 };
-use sui_verifier::verifier as sui_bytecode_verifier;
+use sui_verifier::{
+    linter::{self, LintWarning},
+    verifier as sui_bytecode_verifier, BytecodeLocation, VerificationFailure,
+};
 
-use crate::{MOVE_STDLIB_PACKAGE_NAME, SUI_PACKAGE_NAME};
+use crate::{published::PublishedAddresses, MOVE_STDLIB_PACKAGE_NAME, SUI_PACKAGE_NAME};
 
 /// Wrapper around the core Move `CompiledPackage` with some Sui-specific traits and info
 pub struct CompiledPackage {
     pub package: MoveCompiledPackage,
     /// Path to the Move package (i.e., where the Move.toml file is)
     pub path: PathBuf,
+    /// Addresses that dependencies built with address 0x0 are published at, by package name
+    pub published_dependencies: BTreeMap<String, ObjectID>,
+}
+
+/// A lint warning on a module of a package, located in the package's sources
+#[derive(Debug, Clone)]
+pub struct SourceLintWarning {
+    pub module: ModuleId,
+    pub warning: LintWarning,
+    /// The file, line and column the warning is about, if the source map locates it
+    pub location: Option<(PathBuf, usize, usize)>,
+}
+
+/// A module of a package that fails verification, located in the package's sources
+#[derive(Debug, Clone)]
+pub struct SourceVerificationFailure {
+    pub module: ModuleId,
+    /// The function that fails verification, if the failure is in a function
+    pub function: Option<String>,
+    pub message: String,
+    /// The file, line and column of the failure, if the source map locates it
+    pub location: Option<(PathBuf, usize, usize)>,
 }
 
 /// Wrapper around the core Move `BuildConfig` with some Sui-specific info
@@ -87,18 +120,29 @@ impl BuildConfig {
                 });
             }
         }
+        let compiled_package = CompiledPackage {
+            package,
+            path,
+            published_dependencies: BTreeMap::new(),
+        };
         if self.run_bytecode_verifier {
-            for m in compiled_modules.iter_modules() {
-                move_bytecode_verifier::verify_module(m).map_err(|err| {
-                    SuiError::ModuleVerificationFailure {
-                        error: err.to_string(),
-                    }
-                })?;
-                sui_bytecode_verifier::verify_module(m)?;
+            let failures = compiled_package.verify();
+            if self.print_diags_to_stderr {
+                for failure in &failures {
+                    eprintln!("{}\n", failure);
+                }
+            }
+            if !failures.is_empty() {
+                return Err(SuiError::ModuleVerificationFailure {
+                    error: failures
+                        .iter()
+                        .map(|f| f.to_string())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                });
             }
-            // TODO(https://github.com/MystenLabs/sui/issues/69): Run Move linker
         }
-        Ok(CompiledPackage { package, path })
+        Ok(compiled_package)
     }
 }
 
@@ -150,6 +194,8 @@ impl CompiledPackage {
 
     /// Return the bytecode modules in this package, topologically sorted in dependency order
     /// This is the function to call if you would like to publish or statically analyze the modules
+    /// References to dependencies resolved by `resolve_published_dependencies` point at the
+    /// addresses the dependencies are published at.
     pub fn get_dependency_sorted_modules(&self) -> Vec<CompiledModule> {
         let compiled_modules = self.package.root_modules_map();
         // Collect all module IDs from the current package to be
@@ -164,16 +210,237 @@ impl CompiledPackage {
             .iter()
             .map(|m| m.self_id())
             .collect();
+        let rewriter = self.published_dependencies_rewriter();
         self.package
             .all_modules_map()
             .compute_dependency_graph()
             .compute_topological_order()
             .unwrap() // safe because package built successfully
             .filter(|m| self_modules.contains(&m.self_id()))
-            .cloned()
+            .map(|m| {
+                let mut m = m.clone();
+                rewriter.sub_module_ids(&mut m);
+                m
+            })
+            .collect()
+    }
+
+    /// Return the names of the dependencies of this package, with the modules built for them
+    pub fn get_dependencies_by_package(&self) -> BTreeMap<String, Vec<&CompiledModule>> {
+        let mut dependencies: BTreeMap<String, Vec<&CompiledModule>> = BTreeMap::new();
+        for (name, unit) in &self.package.deps_compiled_units {
+            if let CompiledUnitEnum::Module(m) = &unit.unit {
+                dependencies
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(&m.module);
+            }
+        }
+        dependencies
+    }
+
+    /// Resolve the dependencies of this package that are built with address 0x0 to the addresses
+    /// they are published at in `env`, as recorded in the `Move.published` file next to their
+    /// `Move.toml`. Returns an error naming the first dependency that is not published in `env`.
+    pub fn resolve_published_dependencies(&mut self, env: &str) -> SuiResult<()> {
+        match self.resolve_available_dependencies(env)?.first() {
+            Some(name) => Err(SuiError::ModuleBuildFailure {
+                error: format!(
+                    "Dependency {name} is not published in environment {env}. Publish it \
+                    first, or assign the address it is published at in its Move.toml"
+                ),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Like `resolve_published_dependencies`, but the dependencies that are not published in
+    /// `env` are left at address 0x0. Returns their names.
+    pub fn resolve_available_dependencies(&mut self, env: &str) -> SuiResult<Vec<String>> {
+        let mut published_dependencies = BTreeMap::new();
+        let mut unpublished = vec![];
+        for (name, unit) in &self.package.deps_compiled_units {
+            let CompiledUnitEnum::Module(m) = &unit.unit else {
+                continue;
+            };
+            if *m.module.address() != AccountAddress::ZERO
+                || published_dependencies.contains_key(name.as_str())
+                || unpublished.contains(&name.to_string())
+            {
+                continue;
+            }
+            let published = match package_root(&unit.source_path) {
+                Some(root) => {
+                    PublishedAddresses::read(root).map_err(|e| SuiError::ModuleBuildFailure {
+                        error: format!("{:?}", e),
+                    })?
+                }
+                None => PublishedAddresses::default(),
+            };
+            match published.get(env) {
+                Some(published_at) => {
+                    published_dependencies.insert(name.to_string(), published_at);
+                }
+                None => unpublished.push(name.to_string()),
+            }
+        }
+        self.published_dependencies = published_dependencies;
+        Ok(unpublished)
+    }
+
+    /// Return the bytecode modules of each dependency resolved by
+    /// `resolve_published_dependencies`, bound to the address the dependency is published at.
+    pub fn get_published_dependency_modules(&self) -> BTreeMap<ObjectID, Vec<CompiledModule>> {
+        let rewriter = self.published_dependencies_rewriter();
+        self.get_dependencies_by_package()
+            .into_iter()
+            .filter_map(|(name, modules)| {
+                let published_at = *self.published_dependencies.get(&name)?;
+                let modules = modules
+                    .into_iter()
+                    .map(|m| {
+                        let mut m = m.clone();
+                        rewriter.sub_module_ids(&mut m);
+                        m
+                    })
+                    .collect();
+                Some((published_at, modules))
+            })
             .collect()
     }
 
+    /// Check that the modules of the dependency published at `package_id` are the modules built
+    /// locally for it. `on_chain_modules` are the serialized modules of the on-chain package, by
+    /// name.
+    pub fn verify_published_dependency(
+        &self,
+        package_id: ObjectID,
+        on_chain_modules: &BTreeMap<String, Vec<u8>>,
+    ) -> SuiResult<()> {
+        let local_modules = self
+            .get_published_dependency_modules()
+            .remove(&package_id)
+            .unwrap_or_default();
+        let mut local_names = BTreeSet::new();
+        let mut mismatches = vec![];
+        for module in &local_modules {
+            let name = module.self_id().name().to_string();
+            let mut bytes = Vec::new();
+            module.serialize(&mut bytes).unwrap(); // safe because package built successfully
+            match on_chain_modules.get(&name) {
+                None => mismatches.push(format!("{name} is not published")),
+                Some(on_chain) if on_chain != &bytes => {
+                    mismatches.push(format!("{name} does not match the local build"))
+                }
+                Some(_) => (),
+            }
+            local_names.insert(name);
+        }
+        mismatches.extend(
+            on_chain_modules
+                .keys()
+                .filter(|name| !local_names.contains(*name))
+                .map(|name| format!("{name} is not built locally")),
+        );
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(SuiError::ModuleVerificationFailure {
+                error: format!(
+                    "The dependency published at {package_id} does not match its local sources: \
+                    module {}",
+                    mismatches.join(", module ")
+                ),
+            })
+        }
+    }
+
+    /// Return a rewriter binding the modules of the dependencies resolved by
+    /// `resolve_published_dependencies`, and references to them, to the addresses the
+    /// dependencies are published at.
+    fn published_dependencies_rewriter(&self) -> ModuleHandleRewriter {
+        let sub_map = self
+            .get_dependencies_by_package()
+            .into_iter()
+            .filter_map(|(name, modules)| {
+                let published_at = *self.published_dependencies.get(&name)?;
+                Some(modules.into_iter().map(move |m| {
+                    let old_id = m.self_id();
+                    let new_id = ModuleId::new(published_at.into(), old_id.name().to_owned());
+                    (old_id, new_id)
+                }))
+            })
+            .flatten()
+            .collect();
+        // safe because the domain only has modules at 0x0, and the range none
+        ModuleHandleRewriter::new(sub_map).unwrap()
+    }
+
+    /// Run the Move and Sui bytecode verifiers over the modules of this package (not including
+    /// deps), linking them against their dependencies the way publishing does, and locate the
+    /// failures in the package sources. Verification stops at the first failure of each module.
+    pub fn verify(&self) -> Vec<SourceVerificationFailure> {
+        let all_modules = self.package.all_modules_map();
+        let mut failures = vec![];
+        for unit in &self.package.root_compiled_units {
+            let CompiledUnitEnum::Module(m) = &unit.unit else {
+                continue;
+            };
+            let module = &m.module;
+            let self_id = module.self_id();
+            let deps = all_modules
+                .iter_modules()
+                .into_iter()
+                .filter(|dep| dep.self_id() != self_id);
+            // the Sui verifier assumes that the Move verifier passed
+            let move_result = move_bytecode_verifier::verify_module(module)
+                .and_then(|()| move_bytecode_verifier::dependencies::verify_module(module, deps));
+            let (location, message) = match move_result {
+                Err(e) => (vm_error_location(&e), vm_error_message(&e)),
+                Ok(()) => match sui_bytecode_verifier::verify_module(module) {
+                    Ok(()) => continue,
+                    Err(e) => match VerificationFailure::from_error(&e) {
+                        Some(failure) => (Some(failure.location), failure.message()),
+                        None => (None, e.to_string()),
+                    },
+                },
+            };
+            let function = location.and_then(|location| match location {
+                BytecodeLocation::Function(idx) | BytecodeLocation::Code(idx, _) => {
+                    let fdef = module.function_defs.get(idx.0 as usize)?;
+                    Some(module.identifier_at(module.function_handle_at(fdef.function).name))
+                }
+                BytecodeLocation::Struct(_) => None,
+            });
+            failures.push(SourceVerificationFailure {
+                module: self_id,
+                function: function.map(|f| f.to_string()),
+                message,
+                location: location.and_then(|location| locate(unit, m, location)),
+            });
+        }
+        failures
+    }
+
+    /// Run the Sui lints over the modules of this package (not including deps), and locate the
+    /// warnings in the package sources
+    pub fn lint(&self) -> Vec<SourceLintWarning> {
+        let mut warnings = vec![];
+        for unit in &self.package.root_compiled_units {
+            let CompiledUnitEnum::Module(m) = &unit.unit else {
+                continue;
+            };
+            for warning in linter::lint_module(&m.module) {
+                warnings.push(SourceLintWarning {
+                    module: m.module.self_id(),
+                    location: locate(unit, m, warning.location),
+                    warning,
+                });
+            }
+        }
+        warnings
+    }
+
     /// Return a serialized representation of the bytecode modules in this package, topologically sorted in dependency order
     pub fn get_package_bytes(&self) -> Vec<Vec<u8>> {
         self.get_dependency_sorted_modules()
@@ -328,6 +595,68 @@ impl CompiledPackage {
     }
 }
 
+/// Return the file, line and column of the source `location` in `module` is compiled from
+fn locate(
+    unit: &CompiledUnitWithSource,
+    module: &NamedCompiledModule,
+    location: BytecodeLocation,
+) -> Option<(PathBuf, usize, usize)> {
+    let loc = match location {
+        BytecodeLocation::Struct(idx) => module
+            .source_map
+            .get_struct_source_map(idx)
+            .map(|s| s.definition_location),
+        BytecodeLocation::Function(idx) => module
+            .source_map
+            .get_function_source_map(idx)
+            .map(|f| f.definition_location),
+        BytecodeLocation::Code(idx, offset) => module.source_map.get_code_location(idx, offset),
+    }
+    .ok()?;
+    let source = fs::read_to_string(&unit.source_path).ok()?;
+    let (line, column) = line_and_column(&source, loc.start() as usize);
+    Some((unit.source_path.clone(), line, column))
+}
+
+/// Return the bytecode a Move verifier error is about, if it has an index into the module
+fn vm_error_location(error: &VMError) -> Option<BytecodeLocation> {
+    if let Some((idx, offset)) = error.offsets().first() {
+        return Some(BytecodeLocation::Code(*idx, *offset));
+    }
+    error.indices().iter().find_map(|(kind, idx)| match kind {
+        IndexKind::FunctionDefinition => {
+            Some(BytecodeLocation::Function(FunctionDefinitionIndex(*idx)))
+        }
+        IndexKind::StructDefinition => Some(BytecodeLocation::Struct(StructDefinitionIndex(*idx))),
+        _ => None,
+    })
+}
+
+fn vm_error_message(error: &VMError) -> String {
+    match error.message() {
+        Some(message) => format!("{:?}: {}", error.major_status(), message),
+        None => format!("{:?}", error.major_status()),
+    }
+}
+
+/// Return the 1-based line and column of the byte at `offset` in `source`
+pub(crate) fn line_and_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = source.get(..offset).unwrap_or(source);
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Return the root of the package that the source file at `source_path` belongs to, i.e., the
+/// closest ancestor directory with a Move.toml
+fn package_root(source_path: &Path) -> Option<&Path> {
+    source_path
+        .ancestors()
+        .find(|dir| dir.join("Move.toml").exists())
+}
+
 impl Default for BuildConfig {
     fn default() -> Self {
         BuildConfig {
@@ -338,6 +667,36 @@ impl Default for BuildConfig {
     }
 }
 
+impl Display for SourceLintWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "warning[{}]: {}",
+            self.warning.lint, self.warning.message
+        )?;
+        match &self.location {
+            Some((file, line, column)) => write!(f, "  --> {}:{}:{}", file.display(), line, column),
+            None => write!(f, "  --> module {}", self.module),
+        }
+    }
+}
+
+impl Display for SourceVerificationFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "error[verifier]: {}", self.message)?;
+        match &self.location {
+            Some((file, line, column)) => {
+                write!(f, "  --> {}:{}:{}", file.display(), line, column)?
+            }
+            None => write!(f, "  --> module {}", self.module)?,
+        }
+        if let Some(function) = &self.function {
+            write!(f, "\n   = in function {}::{}", self.module, function)?;
+        }
+        Ok(())
+    }
+}
+
 impl GetModule for CompiledPackage {
     type Error = anyhow::Error;
     // TODO: return ref here for better efficiency? Borrow checker + all_modules_map() make it hard to do this
@@ -0,0 +1,190 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Export of the coverage recorded by `sui move test --coverage` in the lcov format, which
+//! coverage services and tools such as `genhtml` read.
+
+use std::{collections::BTreeMap, fmt::Write, fs, path::Path};
+
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CodeOffset, FunctionDefinitionIndex},
+};
+use move_compiler::compiled_unit::CompiledUnitEnum;
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use move_coverage::coverage_map::ExecCoverageMap;
+
+use crate::compiled_package::{line_and_column, CompiledPackage};
+
+#[derive(Default)]
+struct FileCoverage {
+    /// Line, name and number of calls of each function defined in the file
+    functions: Vec<(usize, String, u64)>,
+    /// Number of executions of each line bytecode is compiled from
+    lines: BTreeMap<usize, u64>,
+}
+
+/// Return the coverage of the root modules of `package` in the lcov format. A line is executed
+/// as many times as the most executed instruction compiled from it, and a function is called as
+/// many times as its first instruction is executed. Native functions, such as the Sui natives,
+/// have no bytecode: they are called as many times as the instructions calling them are executed,
+/// and are part of the coverage if they are defined in a root module or were called.
+pub fn lcov(package: &CompiledPackage, coverage: &ExecCoverageMap) -> String {
+    let mut files: BTreeMap<&Path, FileCoverage> = BTreeMap::new();
+    for unit in &package.package.root_compiled_units {
+        let CompiledUnitEnum::Module(m) = &unit.unit else {
+            continue;
+        };
+        let Ok(source) = fs::read_to_string(&unit.source_path) else {
+            continue;
+        };
+        let module = &m.module;
+        let module_coverage = coverage
+            .module_maps
+            .get(&(*module.address(), module.name().to_owned()));
+        let file = files.entry(unit.source_path.as_path()).or_default();
+
+        for (idx, fdef) in module.function_defs().iter().enumerate() {
+            let Some(code) = &fdef.code else {
+                continue;
+            };
+            let idx = FunctionDefinitionIndex(idx as u16);
+            let name = module.identifier_at(module.function_handle_at(fdef.function).name);
+            let Ok(function_map) = m.source_map.get_function_source_map(idx) else {
+                continue;
+            };
+            let hits = module_coverage.and_then(|c| c.function_maps.get(name));
+            let executions = |offset: CodeOffset| {
+                hits.and_then(|h| h.get(&(offset as u64)))
+                    .copied()
+                    .unwrap_or(0)
+            };
+
+            let (line, _) =
+                line_and_column(&source, function_map.definition_location.start() as usize);
+            file.functions
+                .push((line, format!("{}::{name}", module.name()), executions(0)));
+            for offset in 0..code.code.len() as CodeOffset {
+                let Ok(loc) = m.source_map.get_code_location(idx, offset) else {
+                    continue;
+                };
+                let (line, _) = line_and_column(&source, loc.start() as usize);
+                let line_executions = file.lines.entry(line).or_default();
+                *line_executions = (*line_executions).max(executions(offset));
+            }
+        }
+    }
+
+    let calls = function_calls(package, coverage);
+    let units = package
+        .package
+        .root_compiled_units
+        .iter()
+        .map(|unit| (unit, true));
+    let dep_units = package.package.deps_compiled_units.iter();
+    for (unit, is_root) in units.chain(dep_units.map(|(_, unit)| (unit, false))) {
+        let CompiledUnitEnum::Module(m) = &unit.unit else {
+            continue;
+        };
+        let module = &m.module;
+        for (idx, fdef) in module.function_defs().iter().enumerate() {
+            if !fdef.is_native() {
+                continue;
+            }
+            let name = module.identifier_at(module.function_handle_at(fdef.function).name);
+            let native_calls = calls
+                .get(&(module.self_id(), name.to_owned()))
+                .copied()
+                .unwrap_or(0);
+            if !is_root && native_calls == 0 {
+                continue;
+            }
+            let idx = FunctionDefinitionIndex(idx as u16);
+            let Ok(function_map) = m.source_map.get_function_source_map(idx) else {
+                continue;
+            };
+            let Ok(source) = fs::read_to_string(&unit.source_path) else {
+                continue;
+            };
+            let (line, _) =
+                line_and_column(&source, function_map.definition_location.start() as usize);
+            files
+                .entry(unit.source_path.as_path())
+                .or_default()
+                .functions
+                .push((line, format!("{}::{name}", module.name()), native_calls));
+        }
+    }
+
+    let mut lcov = String::new();
+    for (path, file) in files {
+        write_record(&mut lcov, path, &file).unwrap();
+    }
+    lcov
+}
+
+/// Return the number of times each function was called by the instructions that were executed,
+/// in any module of `package`
+fn function_calls(
+    package: &CompiledPackage,
+    coverage: &ExecCoverageMap,
+) -> BTreeMap<(ModuleId, Identifier), u64> {
+    let mut calls = BTreeMap::new();
+    for unit in package.package.all_modules() {
+        let CompiledUnitEnum::Module(m) = &unit.unit else {
+            continue;
+        };
+        let module = &m.module;
+        let Some(module_coverage) = coverage
+            .module_maps
+            .get(&(*module.address(), module.name().to_owned()))
+        else {
+            continue;
+        };
+        for fdef in module.function_defs() {
+            let Some(code) = &fdef.code else {
+                continue;
+            };
+            let name = module.identifier_at(module.function_handle_at(fdef.function).name);
+            let Some(hits) = module_coverage.function_maps.get(name) else {
+                continue;
+            };
+            for (offset, instruction) in code.code.iter().enumerate() {
+                let handle = match instruction {
+                    Bytecode::Call(handle) => *handle,
+                    Bytecode::CallGeneric(idx) => module.function_instantiation_at(*idx).handle,
+                    _ => continue,
+                };
+                let Some(executions) = hits.get(&(offset as u64)) else {
+                    continue;
+                };
+                let handle = module.function_handle_at(handle);
+                let callee = module.module_id_for_handle(module.module_handle_at(handle.module));
+                let callee_name = module.identifier_at(handle.name).to_owned();
+                *calls.entry((callee, callee_name)).or_default() += executions;
+            }
+        }
+    }
+    calls
+}
+
+fn write_record(lcov: &mut String, path: &Path, file: &FileCoverage) -> std::fmt::Result {
+    writeln!(lcov, "TN:")?;
+    writeln!(lcov, "SF:{}", path.display())?;
+    for (line, name, _) in &file.functions {
+        writeln!(lcov, "FN:{line},{name}")?;
+    }
+    for (_, name, calls) in &file.functions {
+        writeln!(lcov, "FNDA:{calls},{name}")?;
+    }
+    writeln!(lcov, "FNF:{}", file.functions.len())?;
+    let called = file.functions.iter().filter(|(_, _, calls)| *calls > 0);
+    writeln!(lcov, "FNH:{}", called.count())?;
+    for (line, executions) in &file.lines {
+        writeln!(lcov, "DA:{line},{executions}")?;
+    }
+    writeln!(lcov, "LF:{}", file.lines.len())?;
+    let executed = file.lines.values().filter(|executions| **executions > 0);
+    writeln!(lcov, "LH:{}", executed.count())?;
+    writeln!(lcov, "end_of_record")
+}
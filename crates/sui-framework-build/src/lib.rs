@@ -1,7 +1,11 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod codegen;
 pub mod compiled_package;
+pub mod lcov;
+pub mod listing;
+pub mod published;
 
 #[cfg(test)]
 #[path = "unit_tests/build_tests.rs"]
@@ -0,0 +1,166 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Listings of the bytecode of a package's modules, annotated with the sources it is compiled
+//! from, to follow the Move VM's step debugger (`MOVE_VM_STEP`) in the sources. The debugger
+//! names functions, instructions, program counters and locals the way the VM sees them, and
+//! listings name them the same way, so that what the debugger shows can be looked up in them.
+//! `SourceLines` adds the source line of each program counter to the output of the debugger.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+    io::{self, Read, Write},
+};
+
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{CodeOffset, FunctionDefinitionIndex},
+};
+use move_compiler::compiled_unit::{CompiledUnitEnum, NamedCompiledModule};
+
+use crate::compiled_package::{line_and_column, CompiledPackage};
+
+/// Return the listing of each root module of `package`, by module name. Each function is listed
+/// under the name the debugger gives it, with the names of its locals by index, then its
+/// instructions by program counter, each followed by the source line it is compiled from.
+pub fn listings(package: &CompiledPackage) -> BTreeMap<String, String> {
+    let mut listings = BTreeMap::new();
+    for unit in &package.package.root_compiled_units {
+        let CompiledUnitEnum::Module(m) = &unit.unit else {
+            continue;
+        };
+        let source = fs::read_to_string(&unit.source_path).unwrap_or_default();
+        let file = unit.source_path.display().to_string();
+        let module = &m.module;
+        let mut listing = String::new();
+
+        for (idx, fdef) in module.function_defs().iter().enumerate() {
+            let Some(code) = &fdef.code else {
+                continue;
+            };
+            let idx = FunctionDefinitionIndex(idx as u16);
+            let name = module.identifier_at(module.function_handle_at(fdef.function).name);
+            let function_map = m.source_map.get_function_source_map(idx).ok();
+            let parameters =
+                module.signature_at(module.function_handle_at(fdef.function).parameters);
+            let num_locals = parameters.len() + module.signature_at(code.locals).len();
+
+            writeln!(listing, "0x{}::{}::{name}", module.address(), module.name()).unwrap();
+            writeln!(listing, "    Locals:").unwrap();
+            if num_locals == 0 {
+                writeln!(listing, "        (none)").unwrap();
+            }
+            for local in 0..num_locals {
+                let local_name = function_map
+                    .and_then(|f| f.get_parameter_or_local_name(local as u64))
+                    .map_or_else(|| "?".to_string(), |(name, _)| name);
+                writeln!(listing, "        [{local}] {local_name}").unwrap();
+            }
+            writeln!(listing, "    Code:").unwrap();
+            for (offset, instr) in code.code.iter().enumerate() {
+                write!(listing, "        [{offset}] {instr:?}").unwrap();
+                if let Some(source_line) = source_line(m, idx, offset, &source, &file) {
+                    write!(listing, "\n            {source_line}").unwrap();
+                }
+                writeln!(listing).unwrap();
+            }
+            writeln!(listing).unwrap();
+        }
+        listings.insert(module.name().to_string(), listing);
+    }
+    listings
+}
+
+/// The source line each instruction of the root modules of a package is compiled from, by the
+/// name the debugger gives its function and by program counter.
+pub struct SourceLines(BTreeMap<String, Vec<Option<String>>>);
+
+impl SourceLines {
+    pub fn new(package: &CompiledPackage) -> Self {
+        let mut functions = BTreeMap::new();
+        for unit in &package.package.root_compiled_units {
+            let CompiledUnitEnum::Module(m) = &unit.unit else {
+                continue;
+            };
+            let source = fs::read_to_string(&unit.source_path).unwrap_or_default();
+            let file = unit.source_path.display().to_string();
+            let module = &m.module;
+            for (idx, fdef) in module.function_defs().iter().enumerate() {
+                let Some(code) = &fdef.code else {
+                    continue;
+                };
+                let idx = FunctionDefinitionIndex(idx as u16);
+                let name = module.identifier_at(module.function_handle_at(fdef.function).name);
+                let lines = (0..code.code.len())
+                    .map(|offset| source_line(m, idx, offset, &source, &file))
+                    .collect();
+                functions.insert(
+                    format!("0x{}::{}::{name}", module.address(), module.name()),
+                    lines,
+                );
+            }
+        }
+        Self(functions)
+    }
+
+    /// Copy the `output` of the debugger to `out` as it comes, following each program counter
+    /// it prints with the source line of the instruction.
+    pub fn annotate(&self, mut output: impl Read, mut out: impl Write) -> io::Result<()> {
+        let mut buf = [0; 4096];
+        let mut line = Vec::new();
+        let mut function = None;
+        loop {
+            let n = match output.read(&mut buf) {
+                Ok(0) => return Ok(()),
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            // the debugger prompts for commands without ending the line, which is passed on
+            // right away
+            for chunk in buf[..n].split_inclusive(|b| *b == b'\n') {
+                out.write_all(chunk)?;
+                line.extend_from_slice(chunk);
+                if !line.ends_with(b"\n") {
+                    continue;
+                }
+                let text = String::from_utf8_lossy(&line);
+                let text = text.trim_end();
+                if let Some(name) = text.strip_prefix("function >> ") {
+                    function = Some(name.to_string());
+                } else if let Some(pc) = text.strip_prefix("program counter >> ") {
+                    let source_line = function
+                        .as_ref()
+                        .and_then(|function| self.0.get(function))
+                        .zip(pc.parse::<usize>().ok())
+                        .and_then(|(lines, pc)| lines.get(pc)?.as_ref());
+                    if let Some(source_line) = source_line {
+                        writeln!(out, "source >> {source_line}")?;
+                    }
+                }
+                line.clear();
+            }
+            out.flush()?;
+        }
+    }
+}
+
+/// The source line the instruction at `offset` of function `idx` of `m` is compiled from, as
+/// `<file>:<line>: <text>`
+fn source_line(
+    m: &NamedCompiledModule,
+    idx: FunctionDefinitionIndex,
+    offset: usize,
+    source: &str,
+    file: &str,
+) -> Option<String> {
+    let loc = m
+        .source_map
+        .get_code_location(idx, offset as CodeOffset)
+        .ok()?;
+    let (line, _) = line_and_column(source, loc.start() as usize);
+    let text = source.lines().nth(line - 1).unwrap_or_default().trim();
+    Some(format!("{file}:{line}: {text}"))
+}
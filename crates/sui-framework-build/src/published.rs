@@ -0,0 +1,65 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::ObjectID;
+
+/// Name of the file, next to a package's `Move.toml`, recording where the package is published
+pub const PUBLISHED_FILE_NAME: &str = "Move.published";
+
+const PUBLISHED_FILE_HEADER: &str =
+    "# @generated by `sui client publish` and `sui client upgrade`, do not edit by hand\n\n";
+
+/// The addresses a package is published at, keyed by the alias of the `sui client` environment
+/// it was published to.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PublishedAddresses {
+    envs: BTreeMap<String, PublishedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct PublishedPackage {
+    published_at: ObjectID,
+}
+
+impl PublishedAddresses {
+    /// Read the published addresses of the package at `package_path`. A package without a
+    /// lockfile has not been published anywhere.
+    pub fn read(package_path: &Path) -> anyhow::Result<Self> {
+        let path = package_path.join(PUBLISHED_FILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Cannot read {path:?}"))?;
+        toml::from_str(&contents).with_context(|| format!("Cannot parse {path:?}"))
+    }
+
+    /// Write the published addresses next to the `Move.toml` of the package at `package_path`.
+    pub fn write(&self, package_path: &Path) -> anyhow::Result<()> {
+        let path = package_path.join(PUBLISHED_FILE_NAME);
+        let contents = format!("{PUBLISHED_FILE_HEADER}{}", toml::to_string(self)?);
+        fs::write(&path, contents).with_context(|| format!("Cannot write {path:?}"))
+    }
+
+    /// The address the package is published at in `env`, if it is published there
+    pub fn get(&self, env: &str) -> Option<ObjectID> {
+        self.envs.get(env).map(|p| p.published_at)
+    }
+
+    /// Record that the package is published at `package_id` in `env`, replacing the address of
+    /// any previous version of the package.
+    pub fn insert(&mut self, env: &str, package_id: ObjectID) {
+        self.envs.insert(
+            env.to_string(),
+            PublishedPackage {
+                published_at: package_id,
+            },
+        );
+    }
+}
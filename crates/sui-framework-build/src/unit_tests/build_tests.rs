@@ -1,9 +1,15 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::path::Path;
+use std::{collections::BTreeMap, path::Path};
 
-use crate::compiled_package::BuildConfig;
+use move_binary_format::access::ModuleAccess;
+use move_core_types::{ident_str, language_storage::ModuleId};
+use move_coverage::coverage_map::ExecCoverageMap;
+use sui_types::{base_types::ObjectID, error::SuiError};
+use sui_verifier::linter::Lint;
+
+use crate::{codegen, compiled_package::BuildConfig, lcov, listing, published::PublishedAddresses};
 
 #[test]
 fn generate_struct_layouts() {
@@ -22,3 +28,281 @@ fn generate_struct_layouts() {
         registry.contains_key("0000000000000000000000000000000000000002::tx_context::TxContext")
     );
 }
+
+#[test]
+fn published_addresses_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    // a package without a lockfile is not published anywhere
+    let mut published = PublishedAddresses::read(dir.path()).unwrap();
+    assert_eq!(published.get("localnet"), None);
+
+    let first = ObjectID::random();
+    let second = ObjectID::random();
+    published.insert("localnet", first);
+    published.insert("devnet", second);
+    published.write(dir.path()).unwrap();
+
+    let mut published = PublishedAddresses::read(dir.path()).unwrap();
+    assert_eq!(published.get("localnet"), Some(first));
+    assert_eq!(published.get("devnet"), Some(second));
+
+    // upgrading the package replaces its address in the environment
+    let upgraded = ObjectID::random();
+    published.insert("localnet", upgraded);
+    published.write(dir.path()).unwrap();
+    let published = PublishedAddresses::read(dir.path()).unwrap();
+    assert_eq!(published.get("localnet"), Some(upgraded));
+    assert_eq!(published.get("devnet"), Some(second));
+}
+
+#[test]
+fn resolve_published_dependencies() {
+    let mut path = Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    path.push("src/unit_tests/data/depends_on_published");
+    let mut pkg = BuildConfig::default().build(path).unwrap();
+
+    // the dependency is only published in localnet
+    assert!(pkg.resolve_published_dependencies("devnet").is_err());
+    assert_eq!(
+        pkg.resolve_available_dependencies("devnet").unwrap(),
+        vec!["PublishedDependency".to_string()]
+    );
+    assert!(pkg.published_dependencies.is_empty());
+    pkg.resolve_published_dependencies("localnet").unwrap();
+    let published_at =
+        ObjectID::from_hex_literal("0x5e7a4bd6a4e1c5a0e8d1e1b0a3b58a58e0c1f0a4").unwrap();
+    assert_eq!(
+        pkg.published_dependencies,
+        BTreeMap::from([("PublishedDependency".to_string(), published_at)])
+    );
+
+    // the package refers to the dependency at the address it is published at
+    let counter = ModuleId::new(published_at.into(), ident_str!("counter").to_owned());
+    let modules = pkg.get_dependency_sorted_modules();
+    assert_eq!(modules.len(), 1);
+    assert!(modules[0]
+        .module_handles()
+        .iter()
+        .any(|h| modules[0].module_id_for_handle(h) == counter));
+
+    // the on-chain dependency must match the local build
+    let mut on_chain_modules: BTreeMap<String, Vec<u8>> = pkg
+        .get_published_dependency_modules()
+        .remove(&published_at)
+        .unwrap()
+        .into_iter()
+        .map(|m| {
+            assert_eq!(m.self_id(), counter);
+            let mut bytes = vec![];
+            m.serialize(&mut bytes).unwrap();
+            (m.self_id().name().to_string(), bytes)
+        })
+        .collect();
+    pkg.verify_published_dependency(published_at, &on_chain_modules)
+        .unwrap();
+
+    on_chain_modules.get_mut("counter").unwrap().push(0);
+    assert!(pkg
+        .verify_published_dependency(published_at, &on_chain_modules)
+        .is_err());
+    on_chain_modules.remove("counter");
+    assert!(pkg
+        .verify_published_dependency(published_at, &on_chain_modules)
+        .is_err());
+}
+
+#[test]
+fn lint_package() {
+    let mut path = Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    path.push("src/unit_tests/data/lints");
+    let pkg = BuildConfig::default().build(path).unwrap();
+
+    let mut warnings: Vec<(Lint, usize)> = pkg
+        .lint()
+        .into_iter()
+        .map(|w| {
+            let (file, line, _) = w.location.unwrap();
+            assert!(file.ends_with("sources/lints.move"));
+            (w.warning.lint, line)
+        })
+        .collect();
+    warnings.sort();
+    assert_eq!(
+        warnings,
+        vec![
+            (Lint::SelfTransfer, 23),
+            (Lint::ShareOwned, 27),
+            (Lint::MissingStore, 11),
+            (Lint::UnusedOneTimeWitness, 19),
+            (Lint::PublicNotEntry, 21),
+        ]
+    );
+}
+
+#[test]
+fn verifier_failure_location() {
+    let mut path = Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    path.push("src/unit_tests/data/verifier_failure");
+    let pkg = BuildConfig {
+        run_bytecode_verifier: false,
+        ..Default::default()
+    }
+    .build(path.clone())
+    .unwrap();
+
+    let failures = pkg.verify();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].function.as_deref(), Some("init"));
+    assert!(failures[0]
+        .message
+        .contains("'init' function must be private"));
+    let (file, line, _) = failures[0].location.clone().unwrap();
+    assert!(file.ends_with("sources/verifier_failure.move"));
+    assert_eq!(line, 8);
+
+    // building with the verifier reports the failure at its source location
+    let Err(SuiError::ModuleVerificationFailure { error }) = BuildConfig::default().build(path)
+    else {
+        panic!("expected the package to fail verification");
+    };
+    assert!(
+        error.contains("sources/verifier_failure.move:8:"),
+        "{error}"
+    );
+}
+
+#[test]
+fn generate_rust_client() {
+    let mut path = Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    path.push("src/unit_tests/data/codegen");
+    let pkg = BuildConfig::default().build(path).unwrap();
+    let code = codegen::generate_rust(&pkg);
+
+    // objects and events, with framework types mapped to their Rust equivalents
+    assert!(code.contains(
+        "pub struct Counter {\n    pub id: sui_sdk::types::id::UID,\n    pub name: String,\n    \
+         pub value: u64,\n}"
+    ));
+    assert!(code.contains(
+        "pub struct Incremented {\n    pub counter: sui_sdk::types::base_types::SuiAddress,\n    \
+         pub by: u64,\n}"
+    ));
+    assert!(!code.contains("pub struct UID"));
+
+    // entry function wrappers, without the transaction context
+    assert!(code.contains("pub mod counter {"));
+    assert!(code.contains("pub async fn create(") && code.contains("        name: String,\n"));
+    assert!(code.contains(
+        "        counter: sui_sdk::types::base_types::ObjectID,\n        by: u64,\n        gas:"
+    ));
+    assert!(!code.contains("ctx"));
+}
+
+#[test]
+fn lcov_reports_executed_lines_and_functions() {
+    let mut path = Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    path.push("src/unit_tests/data/codegen");
+    let pkg = BuildConfig::default().build(path).unwrap();
+    let module = pkg
+        .get_modules()
+        .find(|m| m.name().as_str() == "counter")
+        .unwrap();
+    let create = module
+        .function_defs()
+        .iter()
+        .find(|f| {
+            module
+                .identifier_at(module.function_handle_at(f.function).name)
+                .as_str()
+                == "create"
+        })
+        .unwrap();
+
+    // run every instruction of create once, and never call increment
+    let mut coverage = ExecCoverageMap::new("unit_tests".to_string());
+    for pc in 0..create.code.as_ref().unwrap().code.len() {
+        coverage.insert(
+            *module.address(),
+            module.name().to_owned(),
+            ident_str!("create").to_owned(),
+            pc as u64,
+        );
+    }
+    // ... and transfer::transfer, which calls the transfer_internal native once
+    let transfer = pkg
+        .get_dependent_modules()
+        .find(|m| m.name().as_str() == "transfer")
+        .unwrap();
+    let transfer_fn = transfer
+        .function_defs()
+        .iter()
+        .find(|f| {
+            transfer
+                .identifier_at(transfer.function_handle_at(f.function).name)
+                .as_str()
+                == "transfer"
+        })
+        .unwrap();
+    for pc in 0..transfer_fn.code.as_ref().unwrap().code.len() {
+        coverage.insert(
+            *transfer.address(),
+            transfer.name().to_owned(),
+            ident_str!("transfer").to_owned(),
+            pc as u64,
+        );
+    }
+    let lcov = lcov::lcov(&pkg, &coverage);
+
+    assert!(lcov.contains("/sources/counter.move\n"));
+    assert!(lcov.contains("FN:22,counter::create\n"));
+    assert!(lcov.contains("FNDA:1,counter::create\nFNDA:0,counter::increment\n"));
+    assert!(lcov.contains("FNF:2\nFNH:1\n"));
+    assert!(lcov.contains("DA:23,1\n"));
+    assert!(lcov.contains("DA:28,0\n"));
+    // natives that were called are reported in the files declaring them, the others are not
+    assert!(lcov.contains("/sources/transfer.move\n"));
+    assert!(lcov.contains("FN:35,transfer::transfer_internal\n"));
+    assert!(lcov.contains("FNDA:1,transfer::transfer_internal\n"));
+    assert!(!lcov.contains("transfer::share_object"));
+    assert!(lcov.ends_with("end_of_record\n"));
+}
+
+#[test]
+fn listings_map_bytecode_to_sources() {
+    let mut path = Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    path.push("src/unit_tests/data/codegen");
+    let pkg = BuildConfig::default().build(path).unwrap();
+    let listings = listing::listings(&pkg);
+    let counter = &listings["counter"];
+
+    assert!(counter.contains("::counter::create\n    Locals:\n        [0] name\n        [1] ctx\n"));
+    assert!(counter.contains("::counter::increment\n"));
+    assert!(counter.contains("    Code:\n        [0] "));
+    assert!(counter.contains("/sources/counter.move:28: counter.value = counter.value + by;\n"));
+}
+
+#[test]
+fn debugger_output_is_annotated_with_sources() {
+    let mut path = Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    path.push("src/unit_tests/data/codegen");
+    let pkg = BuildConfig::default().build(path).unwrap();
+    let listings = listing::listings(&pkg);
+    let create = listings["counter"]
+        .lines()
+        .find(|line| line.ends_with("::counter::create"))
+        .unwrap();
+
+    let output =
+        format!("function >> {create}\ninstruction >> CopyLoc(1)\nprogram counter >> 0\n> ");
+    let mut annotated = Vec::new();
+    listing::SourceLines::new(&pkg)
+        .annotate(output.as_bytes(), &mut annotated)
+        .unwrap();
+    let annotated = String::from_utf8(annotated).unwrap();
+
+    let (debugger, source) = annotated.split_once("source >> ").unwrap();
+    assert_eq!(debugger, &output[..output.len() - 2]);
+    assert!(source.contains("/sources/counter.move:23: let counter = Counter"));
+    // the prompt is passed on after the source line
+    assert!(source.ends_with("\n> "));
+}
@@ -0,0 +1,31 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module codegen::counter {
+    use std::string::String;
+    use sui::event;
+    use sui::object::{Self, UID};
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+
+    struct Counter has key {
+        id: UID,
+        name: String,
+        value: u64,
+    }
+
+    struct Incremented has copy, drop {
+        counter: address,
+        by: u64,
+    }
+
+    public entry fun create(name: String, ctx: &mut TxContext) {
+        let counter = Counter { id: object::new(ctx), name, value: 0 };
+        transfer::transfer(counter, tx_context::sender(ctx))
+    }
+
+    public entry fun increment(counter: &mut Counter, by: u64, _ctx: &mut TxContext) {
+        counter.value = counter.value + by;
+        event::emit(Incremented { counter: object::uid_to_address(&counter.id), by })
+    }
+}
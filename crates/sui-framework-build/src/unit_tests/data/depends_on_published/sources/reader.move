@@ -0,0 +1,10 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module depends_on_published::reader {
+    use published_dependency::counter::{Self, Counter};
+
+    public fun is_zero(counter: &Counter): bool {
+        counter::value(counter) == 0
+    }
+}
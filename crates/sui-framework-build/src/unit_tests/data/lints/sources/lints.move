@@ -0,0 +1,37 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module lints::lints {
+    use sui::object::{Self, UID};
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+
+    struct LINTS has drop {}
+
+    struct Soulbound has key {
+        id: UID,
+    }
+
+    struct Shareable has key, store {
+        id: UID,
+    }
+
+    fun init(_witness: LINTS, _ctx: &mut TxContext) {}
+
+    public fun mint(ctx: &mut TxContext) {
+        let sender = tx_context::sender(ctx);
+        transfer::transfer(Soulbound { id: object::new(ctx) }, sender)
+    }
+
+    public fun share(shareable: Shareable) {
+        transfer::share_object(shareable)
+    }
+
+    public entry fun create(ctx: &mut TxContext) {
+        transfer::share_object(Shareable { id: object::new(ctx) })
+    }
+
+    public fun new(ctx: &mut TxContext): Shareable {
+        Shareable { id: object::new(ctx) }
+    }
+}
@@ -0,0 +1,15 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module published_dependency::counter {
+    use sui::object::UID;
+
+    struct Counter has key {
+        id: UID,
+        value: u64,
+    }
+
+    public fun value(counter: &Counter): u64 {
+        counter.value
+    }
+}
@@ -0,0 +1,10 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module verifier_failure::verifier_failure {
+    use sui::tx_context::TxContext;
+
+    // initializers must be private
+    public fun init(_ctx: &mut TxContext) {
+    }
+}
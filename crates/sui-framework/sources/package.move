@@ -0,0 +1,43 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Capabilities over published Move packages.
+/// Publishing an upgradeable package sends an `UpgradeCap` to the publisher. Its owner can publish
+/// upgraded versions of the package with an upgrade transaction, as long as the upgrade
+/// preserves the layout of every struct and the signature of every public function of
+/// the package it upgrades.
+///
+/// An upgraded package is published under a new ID, but its modules keep the address of the
+/// first version of the package, so the types it defines are the types of every other version:
+/// objects created with one version of a package can be used with the functions of the others.
+module sui::package {
+    use sui::object::{Self, ID, UID};
+
+    /// Capability to upgrade the package `package`.
+    /// Created by the runtime when the package is published, and updated to point at the
+    /// latest version of the package by every upgrade.
+    struct UpgradeCap has key, store {
+        id: UID,
+        /// The latest version of the package that can be upgraded with this capability
+        package: ID,
+        /// The version of `package` in its upgrade lineage, starting at 1 for the original
+        /// publish
+        version: u64,
+    }
+
+    /// The ID of the latest version of the package `cap` can upgrade
+    public fun upgrade_package(cap: &UpgradeCap): ID {
+        cap.package
+    }
+
+    /// The version of the latest package `cap` can upgrade
+    public fun version(cap: &UpgradeCap): u64 {
+        cap.version
+    }
+
+    /// Discard the capability, making the package permanently immutable
+    public entry fun make_immutable(cap: UpgradeCap) {
+        let UpgradeCap { id, package: _, version: _ } = cap;
+        object::delete(id);
+    }
+}
@@ -10,7 +10,7 @@ use move_vm_runtime::native_extensions::NativeContextExtensions;
 use move_vm_test_utils::gas_schedule::INITIAL_COST_SCHEDULE;
 use natives::object_runtime::ObjectRuntime;
 use once_cell::sync::Lazy;
-use std::{collections::BTreeMap, path::Path};
+use std::{collections::BTreeMap, io::Write, path::Path};
 use sui_framework_build::compiled_package::{BuildConfig, CompiledPackage};
 use sui_types::{
     base_types::TransactionDigest, error::SuiResult, in_memory_storage::InMemoryStorage,
@@ -129,11 +129,58 @@ pub fn run_move_unit_tests(
     config: Option<UnitTestingConfig>,
     compute_coverage: bool,
 ) -> anyhow::Result<UnitTestResult> {
-    // bind the extension hook if it has not yet been done
-    Lazy::force(&SET_EXTENSION_HOOK);
+    let config = config
+        .unwrap_or_else(|| UnitTestingConfig::default_with_bound(Some(MAX_UNIT_TEST_INSTRUCTIONS)));
+    run_move_unit_tests_with_writer(
+        path,
+        build_config,
+        config,
+        compute_coverage,
+        &mut std::io::stdout(),
+    )
+}
 
+/// The gas used by a Move unit test
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitTestGas {
+    /// Fully qualified name of the test, e.g. `0x2::coin::test_split`
+    pub name: String,
+    pub gas_used: u64,
+}
+
+/// Like `run_move_unit_tests`, but also return the gas used by each test that ran, passed or
+/// failed. The statistics the gas is read from are only printed if `config` asks for them.
+pub fn run_move_unit_tests_with_gas(
+    path: &Path,
+    build_config: MoveBuildConfig,
+    config: Option<UnitTestingConfig>,
+    compute_coverage: bool,
+) -> anyhow::Result<(UnitTestResult, Vec<UnitTestGas>)> {
     let config = config
         .unwrap_or_else(|| UnitTestingConfig::default_with_bound(Some(MAX_UNIT_TEST_INSTRUCTIONS)));
+    let mut writer = StatisticsWriter::new(std::io::stdout(), config.report_statistics);
+    let result = run_move_unit_tests_with_writer(
+        path,
+        build_config,
+        UnitTestingConfig {
+            report_statistics: true,
+            ..config
+        },
+        compute_coverage,
+        &mut writer,
+    )?;
+    Ok((result, writer.finish()?))
+}
+
+fn run_move_unit_tests_with_writer<W: Write + Send>(
+    path: &Path,
+    build_config: MoveBuildConfig,
+    config: UnitTestingConfig,
+    compute_coverage: bool,
+    writer: &mut W,
+) -> anyhow::Result<UnitTestResult> {
+    // bind the extension hook if it has not yet been done
+    Lazy::force(&SET_EXTENSION_HOOK);
 
     move_cli::base::test::run_move_unit_tests(
         path,
@@ -145,10 +192,106 @@ pub fn run_move_unit_tests(
         natives::all_natives(MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS),
         Some(INITIAL_COST_SCHEDULE.clone()),
         compute_coverage,
-        &mut std::io::stdout(),
+        writer,
     )
 }
 
+// Header of the statistics the unit test runner prints after the test results
+const STATISTICS_HEADER: &str = "Test Statistics:";
+// The runner meters tests with the cost table it is given, so with Sui's cost table the units it
+// reports as executed instructions are the gas charged for the test
+const TEST_NAME_COLUMN: &str = "Test Name";
+const GAS_USED_COLUMN: &str = "Instructions Executed";
+
+/// Forwards the output of the unit test runner line by line, recording the gas used by each test
+/// from the statistics as they go through. The statistics are only forwarded if `show_statistics`.
+struct StatisticsWriter<W> {
+    inner: W,
+    show_statistics: bool,
+    // Bytes of the line that has not been terminated yet
+    line: Vec<u8>,
+    in_statistics: bool,
+    // Positions of the test name and gas in the rows of the statistics table
+    columns: Option<(usize, usize)>,
+    gas: Vec<UnitTestGas>,
+}
+
+impl<W: Write> StatisticsWriter<W> {
+    fn new(inner: W, show_statistics: bool) -> Self {
+        Self {
+            inner,
+            show_statistics,
+            line: Vec::new(),
+            in_statistics: false,
+            columns: None,
+            gas: Vec::new(),
+        }
+    }
+
+    /// Forward the unterminated output, if any, and return the gas used by each test
+    fn finish(mut self) -> std::io::Result<Vec<UnitTestGas>> {
+        let line = std::mem::take(&mut self.line);
+        self.process_line(&line)?;
+        self.inner.flush()?;
+        Ok(self.gas)
+    }
+
+    fn process_line(&mut self, line: &[u8]) -> std::io::Result<()> {
+        let text = String::from_utf8_lossy(line);
+        let text = text.trim_end_matches(['\n', '\r']);
+        if text.trim() == STATISTICS_HEADER {
+            self.in_statistics = true;
+            self.columns = None;
+        } else if self.in_statistics
+            && !text.trim().is_empty()
+            && !text.starts_with(['┌', '├', '│', '└'])
+        {
+            self.in_statistics = false;
+        } else if self.in_statistics {
+            self.record_row(text);
+        }
+        if !self.in_statistics || self.show_statistics {
+            self.inner.write_all(line)?;
+        }
+        Ok(())
+    }
+
+    fn record_row(&mut self, row: &str) {
+        let cells: Vec<_> = row.split('│').map(str::trim).collect();
+        let Some((name, gas)) = self.columns else {
+            let name = cells.iter().position(|cell| *cell == TEST_NAME_COLUMN);
+            let gas = cells.iter().position(|cell| *cell == GAS_USED_COLUMN);
+            self.columns = name.zip(gas);
+            return;
+        };
+        if let (Some(name), Some(Ok(gas_used))) =
+            (cells.get(name), cells.get(gas).map(|gas| gas.parse()))
+        {
+            self.gas.push(UnitTestGas {
+                name: name.to_string(),
+                gas_used,
+            });
+        }
+    }
+}
+
+impl<W: Write> Write for StatisticsWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for byte in buf {
+            self.line.push(*byte);
+            if *byte == b'\n' {
+                let line = std::mem::take(&mut self.line);
+                self.process_line(&line)?;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// Wrapper of the build command that verifies the framework version. Should eventually be removed once we can
 /// do this in the obvious way (via version checks)
 pub fn build_move_package(path: &Path, config: BuildConfig) -> SuiResult<CompiledPackage> {
@@ -208,6 +351,60 @@ mod tests {
         check_move_unit_tests(&path);
     }
 
+    #[test]
+    fn extract_gas_from_statistics() {
+        let output = "\
+Running Move unit tests
+[ PASS    ] 0x2::coin::test_split
+[ FAIL    ] 0x2::coin::test_join
+
+Test Statistics:
+
+┌───────────────────────┬────────────┬───────────────────────────┐
+│       Test Name       │    Time    │   Instructions Executed   │
+├───────────────────────┼────────────┼───────────────────────────┤
+│ 0x2::coin::test_split │   0.003    │           1234            │
+├───────────────────────┼────────────┼───────────────────────────┤
+│ 0x2::coin::test_join  │   0.001    │            56             │
+└───────────────────────┴────────────┴───────────────────────────┘
+
+Test failures:
+";
+        let check = |show_statistics, expected_output: &str| {
+            let mut forwarded = Vec::new();
+            let mut writer = StatisticsWriter::new(&mut forwarded, show_statistics);
+            // The runner may split its output anywhere
+            for chunk in output.as_bytes().chunks(7) {
+                writer.write_all(chunk).unwrap();
+            }
+            assert_eq!(
+                writer.finish().unwrap(),
+                vec![
+                    UnitTestGas {
+                        name: "0x2::coin::test_split".to_string(),
+                        gas_used: 1234
+                    },
+                    UnitTestGas {
+                        name: "0x2::coin::test_join".to_string(),
+                        gas_used: 56
+                    },
+                ]
+            );
+            assert_eq!(String::from_utf8(forwarded).unwrap(), expected_output);
+        };
+        check(true, output);
+        check(
+            false,
+            "\
+Running Move unit tests
+[ PASS    ] 0x2::coin::test_split
+[ FAIL    ] 0x2::coin::test_join
+
+Test failures:
+",
+        );
+    }
+
     fn check_move_unit_tests(path: &Path) {
         assert_eq!(
             run_move_unit_tests(path, MoveBuildConfig::default(), None, false).unwrap(),
@@ -41,15 +41,16 @@ use sui_types::error::SuiError;
 use sui_types::event::{BalanceChangeType, Event, EventID};
 use sui_types::event::{EventEnvelope, EventType};
 use sui_types::filter::{EventFilter, TransactionFilter};
-use sui_types::gas::GasCostSummary;
+use sui_types::gas::{GasCostSummary, GasProfiler};
 use sui_types::gas_coin::GasCoin;
+use sui_types::light_client::TransactionInclusionProof;
 use sui_types::messages::{
     CallArg, CertifiedTransaction, CertifiedTransactionEffects, ExecuteTransactionResponse,
-    ExecutionStatus, InputObjectKind, MoveModulePublish, ObjectArg, Pay, PayAllSui, PaySui,
-    SingleTransactionKind, TransactionData, TransactionEffects, TransactionKind,
+    ExecutionStatus, InputObjectKind, MoveModulePublish, MoveModuleUpgrade, ObjectArg, Pay,
+    PayAllSui, PaySui, SingleTransactionKind, TransactionData, TransactionEffects, TransactionKind,
     VerifiedCertificate,
 };
-use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
 use sui_types::move_package::{disassemble_modules, MovePackage};
 use sui_types::object::{
     Data, MoveObject, Object, ObjectFormatOptions, ObjectRead, Owner, PastObjectRead,
@@ -63,8 +64,6 @@ mod rpc_types_tests;
 pub type SuiMoveTypeParameterIndex = u16;
 pub type TransactionsPage = Page<TransactionDigest, TransactionDigest>;
 
-pub type EventPage = Page<SuiEventEnvelope, EventID>;
-
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub enum SuiMoveAbility {
     Copy,
@@ -1377,6 +1376,28 @@ impl TryFrom<MoveModulePublish> for SuiMovePackage {
     }
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Eq, PartialEq)]
+#[serde(rename = "MovePackageUpgrade", rename_all = "camelCase")]
+pub struct SuiMovePackageUpgrade {
+    /// The latest version of the package being upgraded
+    pub package: ObjectID,
+    /// The capability granting the upgrade of the package
+    pub upgrade_cap: SuiObjectRef,
+    pub disassembled: BTreeMap<String, Value>,
+}
+
+impl TryFrom<MoveModuleUpgrade> for SuiMovePackageUpgrade {
+    type Error = anyhow::Error;
+
+    fn try_from(m: MoveModuleUpgrade) -> Result<Self, Self::Error> {
+        Ok(Self {
+            package: m.package,
+            upgrade_cap: m.upgrade_cap.into(),
+            disassembled: disassemble_modules(m.modules.iter())?,
+        })
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Eq, PartialEq)]
 #[serde(rename = "Pay")]
 pub struct SuiPay {
@@ -1527,6 +1548,8 @@ pub enum SuiTransactionKind {
     TransferSui(SuiTransferSui),
     /// A system transaction that will update epoch information on-chain.
     ChangeEpoch(SuiChangeEpoch),
+    /// Upgrade a published Move package
+    Upgrade(SuiMovePackageUpgrade),
     // .. more transaction types go here
 }
 
@@ -1596,6 +1619,11 @@ impl Display for SuiTransactionKind {
             Self::Publish(_p) => {
                 write!(writer, "Transaction Kind : Publish")?;
             }
+            Self::Upgrade(u) => {
+                writeln!(writer, "Transaction Kind : Upgrade")?;
+                writeln!(writer, "Package ID : {}", u.package.to_hex_literal())?;
+                write!(writer, "Upgrade Capability : {}", u.upgrade_cap.object_id)?;
+            }
             Self::Call(c) => {
                 writeln!(writer, "Transaction Kind : Call")?;
                 writeln!(
@@ -1635,7 +1663,10 @@ impl TryFrom<SingleTransactionKind> for SuiTransactionKind {
             SingleTransactionKind::Pay(p) => Self::Pay(p.into()),
             SingleTransactionKind::PaySui(p) => Self::PaySui(p.into()),
             SingleTransactionKind::PayAllSui(p) => Self::PayAllSui(p.into()),
-            SingleTransactionKind::Publish(p) => Self::Publish(p.try_into()?),
+            SingleTransactionKind::Publish(p) | SingleTransactionKind::PublishUpgradeable(p) => {
+                Self::Publish(p.try_into()?)
+            }
+            SingleTransactionKind::Upgrade(u) => Self::Upgrade(u.try_into()?),
             SingleTransactionKind::Call(c) => Self::Call(SuiMoveCall {
                 package: c.package.into(),
                 module: c.module.to_string(),
@@ -1969,6 +2000,42 @@ impl From<GasCostSummary> for SuiGasCostSummary {
     }
 }
 
+/// The effects of a dry run transaction, with the gas charged by each call stack of its Move
+/// calls, in internal gas units (a thousandth of a gas unit)
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "GasProfile", rename_all = "camelCase")]
+pub struct SuiGasProfile {
+    pub effects: SuiTransactionEffects,
+    pub stacks: Vec<SuiGasProfileStack>,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "GasProfileStack", rename_all = "camelCase")]
+pub struct SuiGasProfileStack {
+    /// The functions of the call stack, outermost first
+    pub frames: Vec<String>,
+    pub gas: u64,
+}
+
+impl SuiGasProfile {
+    pub fn new(effects: SuiTransactionEffects, profiler: &GasProfiler) -> Self {
+        let stacks = profiler
+            .stacks()
+            .into_iter()
+            .map(|(frames, gas)| SuiGasProfileStack { frames, gas })
+            .collect();
+        Self { effects, stacks }
+    }
+
+    /// The profile in the folded stack format read by flamegraph tools
+    pub fn to_folded(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|stack| format!("{} {}\n", stack.frames.join(";"), stack.gas))
+            .collect()
+    }
+}
+
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "ObjectRef")]
 pub struct OwnedObjectRef {
@@ -2749,9 +2816,71 @@ impl TransactionBytes {
     }
 }
 
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CertifiedCheckpointBytes {
+    /// the sequence number of the checkpoint
+    pub sequence_number: CheckpointSequenceNumber,
+    /// BCS bytes of the certified checkpoint summary, as base-64 encoded string
+    pub summary_bytes: Base64,
+}
+
+impl CertifiedCheckpointBytes {
+    pub fn from_data(checkpoint: &CertifiedCheckpointSummary) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            sequence_number: checkpoint.sequence_number(),
+            summary_bytes: Base64::from_bytes(&bcs::to_bytes(checkpoint)?),
+        })
+    }
+
+    pub fn to_data(self) -> Result<CertifiedCheckpointSummary, anyhow::Error> {
+        Ok(bcs::from_bytes(
+            &self
+                .summary_bytes
+                .to_vec()
+                .map_err(|e| anyhow::anyhow!(e))?,
+        )?)
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionInclusionProofBytes {
+    /// the sequence number of the certified checkpoint that includes the transaction
+    pub checkpoint_sequence_number: CheckpointSequenceNumber,
+    /// BCS bytes of the inclusion proof, as base-64 encoded string
+    pub proof_bytes: Base64,
+}
+
+impl TransactionInclusionProofBytes {
+    pub fn from_data(proof: &TransactionInclusionProof) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            checkpoint_sequence_number: proof.checkpoint_sequence_number(),
+            proof_bytes: Base64::from_bytes(&bcs::to_bytes(proof)?),
+        })
+    }
+
+    pub fn to_data(self) -> Result<TransactionInclusionProof, anyhow::Error> {
+        Ok(bcs::from_bytes(
+            &self.proof_bytes.to_vec().map_err(|e| anyhow::anyhow!(e))?,
+        )?)
+    }
+}
+
 #[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Page<T, C> {
     pub data: Vec<T>,
     pub next_cursor: Option<C>,
 }
+
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventPage {
+    pub data: Vec<SuiEventEnvelope>,
+    pub next_cursor: Option<EventID>,
+    /// The earliest event available in the event store, events before it may have been pruned
+    /// by the node's event retention policy.
+    #[serde(default)]
+    pub earliest_available: Option<EventID>,
+}
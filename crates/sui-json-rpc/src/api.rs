@@ -10,11 +10,12 @@ use jsonrpsee_proc_macros::rpc;
 use fastcrypto::encoding::Base64;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    EventPage, GetObjectDataResponse, GetPastObjectDataResponse, GetRawObjectDataResponse,
-    MoveFunctionArgType, RPCTransactionRequestParams, SuiEventEnvelope, SuiEventFilter,
-    SuiExecuteTransactionResponse, SuiGasCostSummary, SuiMoveNormalizedFunction,
-    SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiObjectInfo, SuiTransactionEffects,
-    SuiTransactionFilter, SuiTransactionResponse, SuiTypeTag, TransactionBytes, TransactionsPage,
+    CertifiedCheckpointBytes, EventPage, GetObjectDataResponse, GetPastObjectDataResponse,
+    GetRawObjectDataResponse, MoveFunctionArgType, RPCTransactionRequestParams, SuiEventEnvelope,
+    SuiEventFilter, SuiExecuteTransactionResponse, SuiGasCostSummary, SuiGasProfile,
+    SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiObjectInfo,
+    SuiTransactionEffects, SuiTransactionFilter, SuiTransactionResponse, SuiTypeTag,
+    TransactionBytes, TransactionInclusionProofBytes, TransactionsPage,
 };
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
@@ -24,6 +25,9 @@ use sui_types::crypto::SignatureScheme;
 use sui_types::event::EventID;
 use sui_types::messages::CommitteeInfoResponse;
 use sui_types::messages::ExecuteTransactionRequestType;
+use sui_types::messages_checkpoint::{
+    CheckpointContents, CheckpointSequenceNumber, CheckpointSummary,
+};
 use sui_types::query::{EventQuery, TransactionQuery};
 
 /// Maximum number of events returned in an event query.
@@ -118,6 +122,14 @@ pub trait RpcFullNodeReadApi {
     #[method(name = "dryRunTransaction")]
     async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<SuiTransactionEffects>;
 
+    /// Dry run a transaction, and return its effects with the gas charged by each call stack of
+    /// its Move calls, in internal gas units
+    #[method(name = "dryRunTransactionWithGasProfile")]
+    async fn dry_run_transaction_with_gas_profile(
+        &self,
+        tx_bytes: Base64,
+    ) -> RpcResult<SuiGasProfile>;
+
     /// Return the argument types of a Move function,
     /// based on normalized Type.
     #[method(name = "getMoveFunctionArgTypes")]
@@ -195,6 +207,55 @@ pub trait RpcFullNodeReadApi {
         /// The epoch of interest. If None, default to the latest epoch
         epoch: Option<EpochId>,
     ) -> RpcResult<CommitteeInfoResponse>;
+
+    /// Return the sequence number of the latest checkpoint known to the server
+    #[method(name = "getLatestCheckpointSequenceNumber")]
+    async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<CheckpointSequenceNumber>;
+
+    /// Return a checkpoint summary based on a checkpoint sequence number
+    #[method(name = "getCheckpointSummary")]
+    async fn get_checkpoint_summary(
+        &self,
+        /// the sequence number of the queried checkpoint
+        seq_number: CheckpointSequenceNumber,
+    ) -> RpcResult<CheckpointSummary>;
+
+    /// Return contents of a checkpoint, namely a list of execution digests
+    #[method(name = "getCheckpointContents")]
+    async fn get_checkpoint_contents(
+        &self,
+        /// the sequence number of the queried checkpoint
+        seq_number: CheckpointSequenceNumber,
+    ) -> RpcResult<CheckpointContents>;
+
+    /// Return the sequence number of the checkpoint that includes the given transaction.
+    /// Returns None if the transaction has not been included in a checkpoint yet.
+    #[method(name = "getCheckpointSequenceNumberByTransaction")]
+    async fn get_checkpoint_sequence_number_by_transaction(
+        &self,
+        /// the digest of the queried transaction
+        digest: TransactionDigest,
+    ) -> RpcResult<Option<CheckpointSequenceNumber>>;
+
+    /// Return the BCS bytes of a certified checkpoint summary, which can be verified against
+    /// the committee of the checkpoint's epoch.
+    /// Returns None if the checkpoint has not been certified yet.
+    #[method(name = "getCertifiedCheckpoint")]
+    async fn get_certified_checkpoint(
+        &self,
+        /// the sequence number of the queried checkpoint
+        seq_number: CheckpointSequenceNumber,
+    ) -> RpcResult<Option<CertifiedCheckpointBytes>>;
+
+    /// Return a proof that the effects of a transaction were included in a certified checkpoint,
+    /// for clients that verify responses without trusting the server.
+    /// Returns None if the transaction has not been included in a certified checkpoint yet.
+    #[method(name = "getTransactionInclusionProof")]
+    async fn get_transaction_inclusion_proof(
+        &self,
+        /// the digest of the queried transaction
+        digest: TransactionDigest,
+    ) -> RpcResult<Option<TransactionInclusionProofBytes>>;
 }
 
 #[open_rpc(namespace = "sui", tag = "Transaction Builder API")]
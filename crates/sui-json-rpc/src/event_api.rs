@@ -113,7 +113,12 @@ impl EventReadApiServer for EventReadApiImpl {
         let next_cursor = data.get(limit).map(|(id, _)| id.clone());
         data.truncate(limit);
         let data = data.into_iter().map(|(_, event)| event).collect();
-        Ok(EventPage { data, next_cursor })
+        let earliest_available = self.state.get_earliest_available_event().await?;
+        Ok(EventPage {
+            data,
+            next_cursor,
+            earliest_available,
+        })
     }
 }
 
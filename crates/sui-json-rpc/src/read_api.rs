@@ -14,9 +14,10 @@ use tap::TapFallible;
 use fastcrypto::encoding::Base64;
 use sui_core::authority::AuthorityState;
 use sui_json_rpc_types::{
-    GetObjectDataResponse, GetPastObjectDataResponse, MoveFunctionArgType, ObjectValueKind, Page,
-    SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiObjectInfo,
-    SuiTransactionEffects, SuiTransactionResponse, TransactionsPage,
+    CertifiedCheckpointBytes, GetObjectDataResponse, GetPastObjectDataResponse,
+    MoveFunctionArgType, ObjectValueKind, Page, SuiGasProfile, SuiMoveNormalizedFunction,
+    SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiObjectInfo, SuiTransactionEffects,
+    SuiTransactionResponse, TransactionInclusionProofBytes, TransactionsPage,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::SequenceNumber;
@@ -25,6 +26,9 @@ use sui_types::batch::TxSequenceNumber;
 use sui_types::committee::EpochId;
 use sui_types::crypto::{sha3_hash, SignableBytes};
 use sui_types::messages::{CommitteeInfoRequest, CommitteeInfoResponse, TransactionData};
+use sui_types::messages_checkpoint::{
+    CheckpointContents, CheckpointSequenceNumber, CheckpointSummary,
+};
 use sui_types::move_package::normalize_modules;
 use sui_types::object::{Data, ObjectRead, Owner};
 use sui_types::query::TransactionQuery;
@@ -151,6 +155,20 @@ impl RpcFullNodeReadApiServer for FullNodeApi {
         Ok(self.state.dry_exec_transaction(tx_data, txn_digest).await?)
     }
 
+    async fn dry_run_transaction_with_gas_profile(
+        &self,
+        tx_bytes: Base64,
+    ) -> RpcResult<SuiGasProfile> {
+        let tx_data =
+            TransactionData::from_signable_bytes(&tx_bytes.to_vec().map_err(|e| anyhow!(e))?)?;
+        let txn_digest = TransactionDigest::new(sha3_hash(&tx_data));
+        let (effects, profiler) = self
+            .state
+            .dry_exec_transaction_with_gas_profile(tx_data, txn_digest)
+            .await?;
+        Ok(SuiGasProfile::new(effects, &profiler))
+    }
+
     async fn get_normalized_move_modules_by_package(
         &self,
         package: ObjectID,
@@ -298,6 +316,70 @@ impl RpcFullNodeReadApiServer for FullNodeApi {
             .handle_committee_info_request(&CommitteeInfoRequest { epoch })
             .map_err(|e| anyhow!("{e}"))?)
     }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<CheckpointSequenceNumber> {
+        Ok(self
+            .state
+            .get_latest_checkpoint_sequence_number()
+            .map_err(|e| anyhow!("{e}"))?
+            .ok_or_else(|| anyhow!("No checkpoint is available on this server yet"))?)
+    }
+
+    async fn get_checkpoint_summary(
+        &self,
+        seq_number: CheckpointSequenceNumber,
+    ) -> RpcResult<CheckpointSummary> {
+        Ok(self
+            .state
+            .get_checkpoint_summary(seq_number)
+            .map_err(|e| anyhow!("{e}"))?
+            .ok_or_else(|| anyhow!("Checkpoint {seq_number} not found"))?)
+    }
+
+    async fn get_checkpoint_contents(
+        &self,
+        seq_number: CheckpointSequenceNumber,
+    ) -> RpcResult<CheckpointContents> {
+        Ok(self
+            .state
+            .get_checkpoint_contents(seq_number)
+            .map_err(|e| anyhow!("{e}"))?
+            .ok_or_else(|| anyhow!("Contents of checkpoint {seq_number} not found"))?)
+    }
+
+    async fn get_checkpoint_sequence_number_by_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<Option<CheckpointSequenceNumber>> {
+        Ok(self
+            .state
+            .get_checkpoint_sequence_number_by_transaction(&digest)
+            .map_err(|e| anyhow!("{e}"))?)
+    }
+
+    async fn get_certified_checkpoint(
+        &self,
+        seq_number: CheckpointSequenceNumber,
+    ) -> RpcResult<Option<CertifiedCheckpointBytes>> {
+        Ok(self
+            .state
+            .get_certified_checkpoint(seq_number)
+            .map_err(|e| anyhow!("{e}"))?
+            .map(|checkpoint| CertifiedCheckpointBytes::from_data(&checkpoint))
+            .transpose()?)
+    }
+
+    async fn get_transaction_inclusion_proof(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<Option<TransactionInclusionProofBytes>> {
+        Ok(self
+            .state
+            .get_transaction_inclusion_proof(&digest)
+            .map_err(|e| anyhow!("{e}"))?
+            .map(|proof| TransactionInclusionProofBytes::from_data(&proof))
+            .transpose()?)
+    }
 }
 
 impl SuiRpcModule for FullNodeApi {
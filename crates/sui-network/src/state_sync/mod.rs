@@ -74,7 +74,7 @@ use tokio::{
     sync::{broadcast, mpsc},
     task::{AbortHandle, JoinSet},
 };
-use tracing::{debug, info, trace, warn};
+use tracing::{debug, error, info, trace, warn};
 
 const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
 
@@ -277,36 +277,48 @@ where
                 },
             }
 
-            self.maybe_start_checkpoint_summary_sync_task();
-            self.maybe_start_checkpoint_contents_sync_task();
+            if let Err(e) = self.maybe_start_checkpoint_summary_sync_task() {
+                error!("unable to start checkpoint summary sync: {e}");
+            }
+            if let Err(e) = self.maybe_start_checkpoint_contents_sync_task() {
+                error!("unable to start checkpoint contents sync: {e}");
+            }
         }
 
         info!("State-Synchronizer ended");
     }
 
     fn handle_message(&mut self, message: StateSyncMessage) {
-        match message {
+        let result = match message {
             StateSyncMessage::StartSyncJob => self.maybe_start_checkpoint_summary_sync_task(),
             StateSyncMessage::VerifiedCheckpoint(checkpoint) => {
                 self.handle_checkpoint_from_consensus(checkpoint)
             }
             // After we've successfully synced a checkpoint we can notify our peers
             StateSyncMessage::SyncedCheckpoint(checkpoint) => {
-                self.spawn_notify_peers_of_checkpoint(*checkpoint)
+                self.spawn_notify_peers_of_checkpoint(*checkpoint);
+                Ok(())
             }
+        };
+
+        if let Err(e) = result {
+            error!("unable to handle state sync message: {e}");
         }
     }
 
     // Handle a checkpoint that we received from consensus
-    fn handle_checkpoint_from_consensus(&mut self, checkpoint: Box<VerifiedCheckpoint>) {
+    fn handle_checkpoint_from_consensus(
+        &mut self,
+        checkpoint: Box<VerifiedCheckpoint>,
+    ) -> Result<()> {
         let (next_sequence_number, previous_digest) = {
-            let latest_checkpoint = self.store.get_highest_verified_checkpoint();
+            let latest_checkpoint = self.store.get_highest_verified_checkpoint()?;
 
             // If this is an older checkpoint, just ignore it
             if latest_checkpoint.as_ref().map(|x| x.sequence_number())
                 >= Some(checkpoint.sequence_number())
             {
-                return;
+                return Ok(());
             }
 
             let next_sequence_number = latest_checkpoint
@@ -328,19 +340,19 @@ where
             {
                 let contents = self
                     .store
-                    .get_checkpoint_contents(&checkpoint.content_digest())
+                    .get_checkpoint_contents(&checkpoint.content_digest())?
                     .unwrap();
                 for digests in contents.into_inner() {
-                    debug_assert!(self.store.get_transaction(&digests.transaction).is_some());
+                    debug_assert!(self.store.get_transaction(&digests.transaction)?.is_some());
                     debug_assert!(self
                         .store
-                        .get_transaction_effects(&digests.effects)
+                        .get_transaction_effects(&digests.effects)?
                         .is_some());
                 }
             }
 
-            self.store.insert_checkpoint(checkpoint.clone());
-            self.store.update_highest_synced_checkpoint(&checkpoint);
+            self.store.insert_checkpoint(checkpoint.clone())?;
+            self.store.update_highest_synced_checkpoint(&checkpoint)?;
 
             // We don't care if no one is listening as this is a broadcast channel
             let _ = self.checkpoint_event_sender.send(checkpoint.clone());
@@ -355,6 +367,8 @@ where
                 .insert_checkpoint(checkpoint.into_inner());
             warn!("Consensus gave us too new of a checkpoint");
         }
+
+        Ok(())
     }
 
     fn handle_peer_event(
@@ -397,13 +411,13 @@ where
         self.tasks.spawn(task);
     }
 
-    fn maybe_start_checkpoint_summary_sync_task(&mut self) {
+    fn maybe_start_checkpoint_summary_sync_task(&mut self) -> Result<()> {
         // Only run one sync task at a time
         if self.sync_checkpoint_summaries_task.is_some() {
-            return;
+            return Ok(());
         }
 
-        let highest_processed_checkpoint = self.store.get_highest_verified_checkpoint();
+        let highest_processed_checkpoint = self.store.get_highest_verified_checkpoint()?;
 
         let highest_known_checkpoint = self
             .peer_heights
@@ -435,16 +449,18 @@ where
             let task_handle = self.tasks.spawn(task);
             self.sync_checkpoint_summaries_task = Some(task_handle);
         }
+
+        Ok(())
     }
 
-    fn maybe_start_checkpoint_contents_sync_task(&mut self) {
+    fn maybe_start_checkpoint_contents_sync_task(&mut self) -> Result<()> {
         // Only run one sync task at a time
         if self.sync_checkpoint_contents_task.is_some() {
-            return;
+            return Ok(());
         }
 
-        let highest_verified_checkpoint = self.store.get_highest_verified_checkpoint();
-        let highest_synced_checkpoint = self.store.get_highest_synced_checkpoint();
+        let highest_verified_checkpoint = self.store.get_highest_verified_checkpoint()?;
+        let highest_synced_checkpoint = self.store.get_highest_synced_checkpoint()?;
 
         if highest_verified_checkpoint
             .as_ref()
@@ -459,6 +475,7 @@ where
                 self.peer_heights.clone(),
                 self.weak_sender.clone(),
                 self.checkpoint_event_sender.clone(),
+                self.config.checkpoint_content_download_concurrency(),
                 self.config.transaction_download_concurrency(),
                 // The if condition should ensure that this is Some
                 highest_verified_checkpoint.unwrap(),
@@ -467,6 +484,8 @@ where
             let task_handle = self.tasks.spawn(task);
             self.sync_checkpoint_contents_task = Some(task_handle);
         }
+
+        Ok(())
     }
 
     fn spawn_notify_peers_of_checkpoint(&mut self, checkpoint: VerifiedCheckpoint) {
@@ -591,7 +610,7 @@ async fn sync_to_checkpoint<S: WriteStore>(
     checkpoint_header_download_concurrency: usize,
     checkpoint: Checkpoint,
 ) -> Result<()> {
-    let mut current = store.get_highest_verified_checkpoint();
+    let mut current = store.get_highest_verified_checkpoint()?;
     if current.as_ref().map(|x| x.sequence_number()) >= Some(checkpoint.sequence_number()) {
         return Err(anyhow::anyhow!(
             "target checkpoint {} is older than highest verified checkpoint {}",
@@ -706,7 +725,7 @@ async fn sync_to_checkpoint<S: WriteStore>(
             }
 
             let committee = store
-                .get_committee(checkpoint.epoch())
+                .get_committee(checkpoint.epoch())?
                 .expect("BUG: should have a committee for an epoch before we try to verify checkpoints from an epoch");
             VerifiedCheckpoint::new(checkpoint, &committee).map_err(|(_, e)| e)?
         };
@@ -714,7 +733,7 @@ async fn sync_to_checkpoint<S: WriteStore>(
         current = Some(checkpoint.clone());
         // Insert the newly verified checkpoint into our store, which will bump our highest
         // verified checkpoint watermark as well.
-        store.insert_checkpoint(checkpoint.clone());
+        store.insert_checkpoint(checkpoint.clone())?;
     }
 
     peer_heights
@@ -731,32 +750,45 @@ async fn sync_checkpoint_contents<S: WriteStore + Clone>(
     peer_heights: Arc<RwLock<PeerHeights>>,
     sender: mpsc::WeakSender<StateSyncMessage>,
     checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
+    checkpoint_content_download_concurrency: usize,
     transaction_download_concurrency: usize,
     target_checkpoint: VerifiedCheckpoint,
 ) {
     let mut highest_synced = None;
 
-    for checkpoint in (store
-        .get_highest_synced_checkpoint()
+    let highest_synced_checkpoint = match store.get_highest_synced_checkpoint() {
+        Ok(checkpoint) => checkpoint,
+        Err(err) => {
+            error!("unable to read highest synced checkpoint: {err}");
+            return;
+        }
+    };
+
+    // The contents of several checkpoints are downloaded concurrently, but the results are
+    // yielded in order so that the highest_synced_checkpoint watermark is only ever ratcheted up
+    // one checkpoint at a time.
+    let mut stream = (highest_synced_checkpoint
         .map(|x| x.sequence_number().saturating_add(1))
         .unwrap_or(0)..=target_checkpoint.sequence_number())
         .map(|next| {
-            store.get_checkpoint_by_sequence_number(next).expect(
-                "BUG: store should have all checkpoints older than highest_verified_checkpoint",
+            sync_one_checkpoint_contents(
+                network.clone(),
+                store.clone(),
+                peer_heights.clone(),
+                transaction_download_concurrency,
+                next,
             )
         })
-    {
-        match sync_one_checkpoint_contents(
-            network.clone(),
-            &store,
-            peer_heights.clone(),
-            transaction_download_concurrency,
-            checkpoint,
-        )
-        .await
-        {
+        .pipe(futures::stream::iter)
+        .buffered(checkpoint_content_download_concurrency);
+
+    while let Some(result) = stream.next().await {
+        match result {
             Ok(checkpoint) => {
-                store.update_highest_synced_checkpoint(&checkpoint);
+                if let Err(err) = store.update_highest_synced_checkpoint(&checkpoint) {
+                    error!("unable to update highest synced checkpoint: {err}");
+                    break;
+                }
                 // We don't care if no one is listening as this is a broadcast channel
                 let _ = checkpoint_event_sender.send(checkpoint.clone());
                 highest_synced = Some(checkpoint);
@@ -782,8 +814,12 @@ async fn sync_one_checkpoint_contents<S: WriteStore + Clone>(
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
     transaction_download_concurrency: usize,
-    checkpoint: VerifiedCheckpoint,
+    sequence_number: CheckpointSequenceNumber,
 ) -> Result<VerifiedCheckpoint> {
+    let checkpoint = store
+        .get_checkpoint_by_sequence_number(sequence_number)?
+        .expect("BUG: store should have all checkpoints older than highest_verified_checkpoint");
+
     let mut rng = <rand::rngs::StdRng as rand::SeedableRng>::from_entropy();
     // get a list of peers that can help
     let mut peers = peer_heights
@@ -799,7 +835,7 @@ async fn sync_one_checkpoint_contents<S: WriteStore + Clone>(
         .collect::<Vec<_>>();
     rand::seq::SliceRandom::shuffle(peers.as_mut_slice(), &mut rng);
 
-    let Some(contents) = get_checkpoint_contents(&mut peers, &store, checkpoint.content_digest()).await else {
+    let Some(contents) = get_checkpoint_contents(&mut peers, &store, checkpoint.content_digest()).await? else {
         return Err(anyhow!("unable to sync checkpoint contents for checkpoint {}", checkpoint.sequence_number()));
     };
 
@@ -822,9 +858,9 @@ async fn get_checkpoint_contents<S: WriteStore>(
     peers: &mut [StateSyncClient<anemo::Peer>],
     store: S,
     digest: CheckpointContentsDigest,
-) -> Option<CheckpointContents> {
-    if let Some(contents) = store.get_checkpoint_contents(&digest) {
-        return Some(contents);
+) -> Result<Option<CheckpointContents>> {
+    if let Some(contents) = store.get_checkpoint_contents(&digest)? {
+        return Ok(Some(contents));
     }
 
     // Iterate through our selected peers trying each one in turn until we're able to
@@ -840,13 +876,13 @@ async fn get_checkpoint_contents<S: WriteStore>(
             .tap_none(|| trace!("peer unable to help sync"))
         {
             if digest == contents.digest() {
-                store.insert_checkpoint_contents(contents.clone());
-                return Some(contents);
+                store.insert_checkpoint_contents(contents.clone())?;
+                return Ok(Some(contents));
             }
         }
     }
 
-    None
+    Ok(None)
 }
 
 async fn get_transaction_and_effects<S: WriteStore>(
@@ -855,8 +891,8 @@ async fn get_transaction_and_effects<S: WriteStore>(
     digests: ExecutionDigests,
 ) -> Result<()> {
     if let (Some(_transaction), Some(_effects)) = (
-        store.get_transaction(&digests.transaction),
-        store.get_transaction_effects(&digests.effects),
+        store.get_transaction(&digests.transaction)?,
+        store.get_transaction_effects(&digests.effects)?,
     ) {
         return Ok(());
     }
@@ -873,12 +909,25 @@ async fn get_transaction_and_effects<S: WriteStore>(
             .and_then(Response::into_inner)
             .tap_none(|| trace!("peer unable to help sync"))
         {
-            if transaction.digest() == &digests.transaction
-                && effects.digest() == digests.effects
-                && effects.transaction_digest == digests.transaction
+            if transaction.digest() != &digests.transaction
+                || effects.digest() != digests.effects
+                || effects.transaction_digest != digests.transaction
+            {
+                continue;
+            }
+
+            // The certificate is executed by the node once its checkpoint is synced, so its
+            // signatures need to be checked against the committee of the epoch it was certified in
+            let Some(committee) = store.get_committee(transaction.epoch())? else {
+                trace!("unknown committee for epoch {}", transaction.epoch());
+                continue;
+            };
+            if let Ok(transaction) = transaction
+                .verify(&committee)
+                .tap_err(|e| trace!("invalid certificate from peer: {e:?}"))
             {
-                store.insert_transaction(transaction);
-                store.insert_transaction_effects(effects);
+                store.insert_transaction(transaction)?;
+                store.insert_transaction_effects(effects)?;
                 return Ok(());
             }
         }
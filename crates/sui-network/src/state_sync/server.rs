@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 use sui_types::{
     base_types::ExecutionDigests,
-    messages::{Transaction, TransactionEffects},
+    messages::{CertifiedTransaction, TransactionEffects},
     messages_checkpoint::{
         CertifiedCheckpointSummary as Checkpoint, CheckpointContents, CheckpointContentsDigest,
         CheckpointDigest, CheckpointSequenceNumber, VerifiedCheckpoint,
@@ -53,6 +53,7 @@ where
         let highest_verified_checkpoint = self
             .store
             .get_highest_verified_checkpoint()
+            .map_err(|e| Status::internal(e.to_string()))?
             .map(|x| x.sequence_number());
 
         // If this checkpoint is higher than our highest verified checkpoint notify the
@@ -79,6 +80,7 @@ where
                 .store
                 .get_checkpoint_by_sequence_number(*sequence_number),
         }
+        .map_err(|e| Status::internal(e.to_string()))?
         .map(VerifiedCheckpoint::into_inner);
 
         Ok(Response::new(checkpoint))
@@ -88,7 +90,10 @@ where
         &self,
         request: Request<CheckpointContentsDigest>,
     ) -> Result<Response<Option<CheckpointContents>>, Status> {
-        let contents = self.store.get_checkpoint_contents(request.inner());
+        let contents = self
+            .store
+            .get_checkpoint_contents(request.inner())
+            .map_err(|e| Status::internal(e.to_string()))?;
 
         Ok(Response::new(contents))
     }
@@ -96,20 +101,26 @@ where
     async fn get_transaction_and_effects(
         &self,
         request: Request<ExecutionDigests>,
-    ) -> Result<Response<Option<(Transaction, TransactionEffects)>>, Status> {
+    ) -> Result<Response<Option<(CertifiedTransaction, TransactionEffects)>>, Status> {
         let ExecutionDigests {
             transaction,
             effects,
         } = request.into_inner();
 
-        let Some(transaction) = self.store.get_transaction(&transaction) else {
+        let Some(transaction) = self
+            .store
+            .get_transaction(&transaction)
+            .map_err(|e| Status::internal(e.to_string()))? else {
             return Ok(Response::new(None));
         };
 
-        let Some(effects) = self.store.get_transaction_effects(&effects) else {
+        let Some(effects) = self
+            .store
+            .get_transaction_effects(&effects)
+            .map_err(|e| Status::internal(e.to_string()))? else {
             return Ok(Response::new(None));
         };
 
-        Ok(Response::new(Some((transaction, effects))))
+        Ok(Response::new(Some((transaction.into_inner(), effects))))
     }
 }
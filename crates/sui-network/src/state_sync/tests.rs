@@ -190,13 +190,16 @@ async fn isolated_sync_job() {
         );
 
     // Sync the data
-    event_loop_1.maybe_start_checkpoint_summary_sync_task();
+    event_loop_1
+        .maybe_start_checkpoint_summary_sync_task()
+        .unwrap();
     event_loop_1.tasks.join_next().await.unwrap().unwrap();
     assert_eq!(
         ordered_checkpoints.last().map(|x| &x.summary),
         event_loop_1
             .store
             .get_highest_verified_checkpoint()
+            .unwrap()
             .as_ref()
             .map(|x| &x.summary)
     );
@@ -267,7 +270,9 @@ async fn sync_with_checkpoints_being_inserted() {
 
     // Inject one checkpoint and verify that it was shared with the other node
     let mut checkpoint_iter = ordered_checkpoints.clone().into_iter();
-    store_1.insert_checkpoint_contents(empty_contents());
+    store_1
+        .insert_checkpoint_contents(empty_contents())
+        .unwrap();
     handle_1
         .send_checkpoint(checkpoint_iter.next().unwrap())
         .await;
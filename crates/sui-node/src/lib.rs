@@ -13,6 +13,7 @@ use narwhal_network::metrics::{NetworkConnectionMetrics, NetworkMetrics};
 use prometheus::Registry;
 use std::option::Option::None;
 use std::{sync::Arc, time::Duration};
+use sui_config::node::EventStoreConfig;
 use sui_config::NodeConfig;
 use sui_core::authority_aggregator::{AuthAggMetrics, AuthorityAggregator};
 use sui_core::authority_server::ValidatorService;
@@ -33,8 +34,11 @@ use sui_json_rpc::transaction_builder_api::FullNodeTransactionBuilderApi;
 use sui_network::api::ValidatorServer;
 use sui_network::default_mysten_network_config;
 use sui_network::discovery;
+use sui_network::state_sync;
 use sui_storage::{
-    event_store::{EventStoreType, SqlEventStore},
+    event_store::{
+        EventRetentionPolicy, EventStorePruner, EventStoreType, PgEventStore, SqlEventStore,
+    },
     node_sync_store::NodeSyncStore,
     IndexStore,
 };
@@ -65,7 +69,8 @@ mod handle;
 pub use handle::SuiNodeHandle;
 use narwhal_types::TransactionsClient;
 use sui_core::checkpoints::{
-    CheckpointMetrics, CheckpointService, LogCheckpointOutput, SubmitCheckpointToConsensus,
+    CheckpointExecutor, CheckpointMetrics, CheckpointService, LogCheckpointOutput,
+    SendCheckpointToStateSync, StateSyncStore, SubmitCheckpointToConsensus,
 };
 
 pub struct SuiNode {
@@ -74,7 +79,8 @@ pub struct SuiNode {
     _ws_subscription_service: Option<WsServerHandle>,
     _batch_subsystem_handle: tokio::task::JoinHandle<()>,
     _post_processing_subsystem_handle: Option<tokio::task::JoinHandle<Result<()>>>,
-    _gossip_handle: Option<tokio::task::JoinHandle<()>>,
+    checkpoint_executor_handle: tokio::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+    _event_store_pruner: Option<EventStorePruner>,
     _execute_driver_handle: tokio::task::JoinHandle<()>,
     state: Arc<AuthorityState>,
     active: Arc<ActiveAuthority<NetworkAuthorityClient>>,
@@ -83,6 +89,7 @@ pub struct SuiNode {
 
     _p2p_network: anemo::Network,
     _discovery: discovery::Handle,
+    _state_sync: state_sync::Handle,
 
     #[cfg(msim)]
     sim_node: sui_simulator::runtime::NodeHandle,
@@ -124,14 +131,39 @@ impl SuiNode {
         };
 
         let event_store = if config.enable_event_processing {
-            let path = config.db_path().join("events.db");
-            let db = SqlEventStore::new_from_file(&path).await?;
-            db.initialize().await?;
-            Some(Arc::new(EventStoreType::SqlEventStore(db)))
+            let event_store = match &config.event_store_config {
+                Some(EventStoreConfig::Postgres { url }) => {
+                    let db = PgEventStore::new(url).await?;
+                    db.initialize().await?;
+                    EventStoreType::PgEventStore(db)
+                }
+                Some(EventStoreConfig::Sqlite) | None => {
+                    let path = config.db_path().join("events.db");
+                    let db = SqlEventStore::new_from_file(&path).await?;
+                    db.initialize().await?;
+                    EventStoreType::SqlEventStore(db)
+                }
+            };
+            Some(Arc::new(event_store))
         } else {
             None
         };
 
+        let event_store_pruner = event_store.as_ref().and_then(|event_store| {
+            let retention_config = config.event_retention_config.as_ref()?;
+            let policy = EventRetentionPolicy {
+                max_age: retention_config.max_age(),
+                max_events: retention_config.max_events,
+                packages: retention_config.packages.clone(),
+                excluded_event_types: retention_config.excluded_event_types.clone(),
+            };
+            Some(EventStorePruner::new(
+                event_store.clone(),
+                policy,
+                retention_config.pruning_interval(),
+            ))
+        });
+
         let (tx_reconfigure_consensus, rx_reconfigure_consensus) = channel(100);
 
         let transaction_streamer = config
@@ -163,14 +195,23 @@ impl SuiNode {
             LogCheckpointOutput::boxed()
         };
 
+        // Checkpoints certified by this validator are disseminated to peers by state sync
+        let (certified_checkpoint_sender, mut certified_checkpoint_receiver) = channel(100);
         let checkpoint_service = CheckpointService::spawn(
             &config.db_path().join("checkpoints"),
             Box::new(store.clone()),
             checkpoint_output,
-            LogCheckpointOutput::boxed_certified(),
+            Box::new(SendCheckpointToStateSync {
+                sender: certified_checkpoint_sender,
+            }),
             committee.clone(),
             CheckpointMetrics::new(&prometheus_registry),
         );
+        let state_sync_store = StateSyncStore::new(
+            checkpoint_service.clone(),
+            store.clone(),
+            committee_store.clone(),
+        );
 
         let state = Arc::new(
             AuthorityState::new(
@@ -185,7 +226,7 @@ impl SuiNode {
                 genesis,
                 &prometheus_registry,
                 tx_reconfigure_consensus,
-                checkpoint_service,
+                checkpoint_service.clone(),
             )
             .await,
         );
@@ -260,12 +301,6 @@ impl SuiNode {
                 None
             };
 
-        let gossip_handle = if is_full_node {
-            active_authority.clone().spawn_node_sync_process().await;
-            None
-        } else {
-            None
-        };
         let execute_driver_handle = active_authority.clone().spawn_execute_process().await;
 
         let registry = prometheus_registry.clone();
@@ -309,8 +344,15 @@ impl SuiNode {
             .config(config.p2p_config.clone())
             .build();
 
+        let (state_sync, state_sync_server) = state_sync::Builder::new()
+            .config(config.p2p_config.state_sync.clone().unwrap_or_default())
+            .store(state_sync_store)
+            .build();
+
         let p2p_network = {
-            let routes = anemo::Router::new().add_rpc_service(discovery_server);
+            let routes = anemo::Router::new()
+                .add_rpc_service(discovery_server)
+                .add_rpc_service(state_sync_server);
 
             let inbound_network_metrics =
                 NetworkMetrics::new("sui", "inbound", &prometheus_registry);
@@ -351,6 +393,26 @@ impl SuiNode {
         };
 
         let discovery_handle = discovery.start(p2p_network.clone());
+        let state_sync_handle = state_sync.start(p2p_network.clone());
+
+        {
+            let state_sync_handle = state_sync_handle.clone();
+            spawn_monitored_task!(async move {
+                while let Some(checkpoint) = certified_checkpoint_receiver.recv().await {
+                    state_sync_handle.send_checkpoint(checkpoint).await;
+                }
+            });
+        }
+
+        // Fullnodes execute the transactions of the checkpoints synced from their peers
+        let checkpoint_executor_handle = if is_full_node {
+            Some(
+                CheckpointExecutor::new(state.clone(), checkpoint_service)
+                    .start(state_sync_handle.subscribe_to_synced_checkpoints()),
+            )
+        } else {
+            None
+        };
 
         let (json_rpc_service, ws_subscription_service) = build_http_servers(
             state.clone(),
@@ -364,7 +426,8 @@ impl SuiNode {
             grpc_server,
             _json_rpc_service: json_rpc_service,
             _ws_subscription_service: ws_subscription_service,
-            _gossip_handle: gossip_handle,
+            checkpoint_executor_handle: tokio::sync::Mutex::new(checkpoint_executor_handle),
+            _event_store_pruner: event_store_pruner,
             _execute_driver_handle: execute_driver_handle,
             _batch_subsystem_handle: batch_subsystem_handle,
             _post_processing_subsystem_handle: post_processing_subsystem_handle,
@@ -374,6 +437,7 @@ impl SuiNode {
             _prometheus_registry: prometheus_registry,
             _p2p_network: p2p_network,
             _discovery: discovery_handle,
+            _state_sync: state_sync_handle,
 
             #[cfg(msim)]
             sim_node: sui_simulator::runtime::NodeHandle::current(),
@@ -408,6 +472,16 @@ impl SuiNode {
             .ok_or_else(|| anyhow::anyhow!("Transaction Orchestrator is not enabled in this node."))
     }
 
+    /// Stop executing the transactions of synced checkpoints, so that tests can observe which
+    /// transactions were executed locally by other means.
+    pub async fn cancel_checkpoint_executor_for_tests(&self) {
+        if let Some(handle) = self.checkpoint_executor_handle.lock().await.take() {
+            info!("cancelling checkpoint executor");
+            handle.abort();
+            let _ = handle.await;
+        }
+    }
+
     //TODO watch/wait on all the components
     pub async fn wait(self) -> Result<()> {
         self.grpc_server.await??;
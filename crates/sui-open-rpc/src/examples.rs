@@ -485,6 +485,7 @@ impl RpcExampleProvider {
         let page = EventPage {
             data: events.clone(),
             next_cursor: Some((1000, 5).into()),
+            earliest_available: Some((0, 0).into()),
         };
         Examples::new(
             "sui_getEvents",
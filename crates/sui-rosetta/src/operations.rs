@@ -217,10 +217,17 @@ fn parse_operations(
 ) -> Result<Vec<Operation>, anyhow::Error> {
     let (type_, metadata) = match tx {
         SingleTransactionKind::TransferObject(tx) => (OperationType::TransferObject, json!(tx)),
-        SingleTransactionKind::Publish(tx) => {
+        SingleTransactionKind::Publish(tx) | SingleTransactionKind::PublishUpgradeable(tx) => {
             let disassembled = disassemble_modules(tx.modules.iter())?;
             (OperationType::Publish, json!(disassembled))
         }
+        SingleTransactionKind::Upgrade(tx) => {
+            let disassembled = disassemble_modules(tx.modules.iter())?;
+            (
+                OperationType::Upgrade,
+                json!({ "package": tx.package, "modules": disassembled }),
+            )
+        }
         SingleTransactionKind::Call(tx) => (OperationType::MoveCall, json!(tx)),
         SingleTransactionKind::TransferSui(tx) => (OperationType::TransferSUI, json!(tx)),
         SingleTransactionKind::Pay(tx) => (OperationType::Pay, json!(tx)),
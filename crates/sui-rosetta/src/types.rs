@@ -418,6 +418,7 @@ pub enum OperationType {
     PayAllSui,
     TransferObject,
     Publish,
+    Upgrade,
     MoveCall,
     EpochChange,
     // Rosetta only transaction type, used for fabricating genesis transactions.
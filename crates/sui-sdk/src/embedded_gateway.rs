@@ -11,13 +11,13 @@ use sui_config::gateway::GatewayConfig;
 use sui_config::{NetworkConfig, PersistedConfig, SUI_NETWORK_CONFIG};
 use sui_core::gateway_state::{GatewayClient, GatewayState, TxSeqNumber};
 use sui_json_rpc_types::{
-    EventPage, GetObjectDataResponse, GetRawObjectDataResponse, SuiObjectInfo,
+    EventPage, GetObjectDataResponse, GetRawObjectDataResponse, SuiGasProfile, SuiObjectInfo,
     SuiTransactionResponse,
 };
 use sui_transaction_builder::{DataReader, TransactionBuilder};
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use sui_types::event::EventID;
-use sui_types::messages::{ExecuteTransactionRequestType, VerifiedTransaction};
+use sui_types::messages::{ExecuteTransactionRequestType, TransactionData, VerifiedTransaction};
 use sui_types::query::EventQuery;
 
 use crate::TransactionExecutionResult;
@@ -143,6 +143,13 @@ impl ReadApi {
     ) -> anyhow::Result<SuiTransactionResponse> {
         Ok(self.api.get_transaction(digest).await?)
     }
+
+    pub async fn dry_run_transaction_with_gas_profile(
+        &self,
+        _tx: TransactionData,
+    ) -> anyhow::Result<SuiGasProfile> {
+        anyhow::bail!("Gas profiling is not supported by the embedded gateway")
+    }
 }
 
 #[derive(Clone)]
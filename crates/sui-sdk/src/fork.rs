@@ -0,0 +1,297 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Local execution of transactions against a fork of a network's state.
+//!
+//! A `ForkExecutor` starts with no objects, and fetches the objects and packages transactions
+//! read from an `ObjectSource` the first time they are read: a fullnode, or a fixture recorded
+//! from an earlier fork so that tests can replay transactions offline. Transactions only change
+//! the fork, and objects can be overridden to try out states the network never had.
+//!
+//! Sources serve the latest version of objects, and transactions execute against whatever
+//! version of their inputs the fork has, whatever the versions their data refers to.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::{anyhow, bail};
+use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
+use sui_adapter::adapter::new_move_vm;
+use sui_core::execution_engine;
+use sui_types::{
+    base_types::{ObjectID, ObjectRef, TransactionDigest},
+    error::{ExecutionError, SuiError, SuiResult},
+    gas,
+    in_memory_storage::InMemoryStorage,
+    messages::{InputObjects, TransactionData, TransactionEffects},
+    object::{Object, Owner},
+    storage::{BackingPackageStore, ChildObjectResolver, ParentSync},
+    sui_system_state::SuiSystemState,
+    temporary_store::TemporaryStore,
+    MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_STATE_OBJECT_ID,
+};
+use tokio::runtime::Handle;
+
+use crate::SuiClient;
+
+/// Where a fork fetches the objects it does not have yet
+pub trait ObjectSource: Send + Sync {
+    /// Return the latest version of the object, or `None` if it does not exist
+    fn get_object(&self, object_id: &ObjectID) -> anyhow::Result<Option<Object>>;
+}
+
+/// Objects fetched from a fullnode. Objects are fetched while transactions execute, so this must
+/// be used from a multi-threaded Tokio runtime.
+pub struct RpcObjectSource {
+    client: SuiClient,
+    runtime: Handle,
+}
+
+impl RpcObjectSource {
+    pub fn new(client: SuiClient) -> Self {
+        Self {
+            client,
+            runtime: Handle::current(),
+        }
+    }
+}
+
+impl ObjectSource for RpcObjectSource {
+    fn get_object(&self, object_id: &ObjectID) -> anyhow::Result<Option<Object>> {
+        let read_api = self.client.read_api();
+        let response =
+            tokio::task::block_in_place(|| self.runtime.block_on(read_api.get_object(*object_id)))?;
+        match response.into_object() {
+            Ok(object) => Ok(Some(object.try_into()?)),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+/// Objects recorded by `ForkExecutor::write_fixture`
+#[derive(Debug, Default)]
+pub struct FixtureObjectSource {
+    objects: BTreeMap<ObjectID, Object>,
+}
+
+impl FixtureObjectSource {
+    pub fn new(objects: Vec<Object>) -> Self {
+        Self {
+            objects: objects.into_iter().map(|o| (o.id(), o)).collect(),
+        }
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let objects: Vec<Object> = serde_json::from_slice(&fs::read(path)?)?;
+        Ok(Self::new(objects))
+    }
+}
+
+impl ObjectSource for FixtureObjectSource {
+    fn get_object(&self, object_id: &ObjectID) -> anyhow::Result<Option<Object>> {
+        Ok(self.objects.get(object_id).cloned())
+    }
+}
+
+pub struct ForkExecutor {
+    store: Arc<ForkStore>,
+    vm: Arc<MoveVM>,
+    native_functions: NativeFunctionTable,
+}
+
+impl ForkExecutor {
+    pub fn new(source: impl ObjectSource + 'static) -> anyhow::Result<Self> {
+        let native_functions =
+            sui_framework::natives::all_natives(MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS);
+        Ok(Self {
+            store: Arc::new(ForkStore {
+                source: Box::new(source),
+                state: RwLock::default(),
+            }),
+            vm: Arc::new(new_move_vm(native_functions.clone())?),
+            native_functions,
+        })
+    }
+
+    /// Return the object as the fork has it, fetching it if the fork does not have it yet
+    pub fn get_object(&self, object_id: &ObjectID) -> anyhow::Result<Option<Object>> {
+        self.store.get_object(object_id)
+    }
+
+    /// Replace the object in the fork, for the transactions executed afterwards
+    pub fn override_object(&self, object: Object) {
+        self.store
+            .state
+            .write()
+            .unwrap()
+            .objects
+            .insert_object(object);
+    }
+
+    /// Replace the BCS contents of a Move object in the fork, keeping its version
+    pub fn override_contents(&self, object_id: &ObjectID, contents: Vec<u8>) -> anyhow::Result<()> {
+        let mut object = self
+            .get_object(object_id)?
+            .ok_or_else(|| anyhow!("Object {object_id} does not exist"))?;
+        let Some(move_object) = object.data.try_as_move_mut() else {
+            bail!("Object {object_id} is a package");
+        };
+        move_object.update_contents_without_version_change(contents);
+        self.override_object(object);
+        Ok(())
+    }
+
+    /// Execute the transaction against the fork, and apply its effects to the fork. Transactions
+    /// are not signed, so the fork accepts any sender, and `digest` is used to derive the IDs of
+    /// the objects the transaction creates. The error is the reason the transaction failed, if
+    /// it did.
+    pub fn execute(
+        &self,
+        data: TransactionData,
+        digest: TransactionDigest,
+    ) -> anyhow::Result<(TransactionEffects, Option<ExecutionError>)> {
+        let mut objects = vec![];
+        for kind in data.input_objects()? {
+            let id = kind.object_id();
+            let object = self
+                .get_object(&id)?
+                .ok_or_else(|| anyhow!("Input object {id} does not exist"))?;
+            objects.push((kind, object));
+        }
+        let input_objects = InputObjects::new(objects);
+        let transaction_dependencies = input_objects.transaction_dependencies();
+        let shared_object_refs = input_objects.filter_shared_objects();
+
+        // the system state object, if the source has one, has the network's epoch and prices
+        let system_state = self
+            .get_object(&SUI_SYSTEM_STATE_OBJECT_ID)?
+            .and_then(|o| bcs::from_bytes::<SuiSystemState>(o.data.try_as_move()?.contents()).ok());
+        let (epoch, storage_gas_price) = match &system_state {
+            Some(state) => (state.epoch, state.parameters.storage_gas_price),
+            None => (0, data.gas_price),
+        };
+        let gas_status = if data.kind.is_system_tx() {
+            gas::SuiGasStatus::new_unmetered()
+        } else {
+            gas::start_gas_metering(data.gas_budget, data.gas_price, storage_gas_price)?
+        };
+
+        let temporary_store = TemporaryStore::new(self.store.clone(), input_objects, digest);
+        let (inner, effects, error) = execution_engine::execute_transaction_to_effects(
+            shared_object_refs,
+            temporary_store,
+            data,
+            digest,
+            transaction_dependencies,
+            &self.vm,
+            &self.native_functions,
+            gas_status,
+            epoch,
+        );
+        self.store
+            .state
+            .write()
+            .unwrap()
+            .objects
+            .finish(inner.written, inner.deleted);
+        Ok((effects, error))
+    }
+
+    /// Write the objects fetched from the source, as they were fetched, to a fixture that a
+    /// `FixtureObjectSource` can read, to fork the same state again without the source.
+    pub fn write_fixture(&self, path: &Path) -> anyhow::Result<()> {
+        let state = self.store.state.read().unwrap();
+        let objects: Vec<_> = state.fetched.values().collect();
+        fs::write(path, serde_json::to_vec_pretty(&objects)?)?;
+        Ok(())
+    }
+}
+
+struct ForkStore {
+    source: Box<dyn ObjectSource>,
+    state: RwLock<ForkState>,
+}
+
+#[derive(Default)]
+struct ForkState {
+    /// The objects of the fork, as transactions and overrides left them
+    objects: InMemoryStorage,
+    /// The objects fetched from the source, as they were fetched
+    fetched: BTreeMap<ObjectID, Object>,
+    /// The objects the source does not have, so that they are not fetched again
+    missing: BTreeSet<ObjectID>,
+}
+
+impl ForkStore {
+    fn get_object(&self, object_id: &ObjectID) -> anyhow::Result<Option<Object>> {
+        {
+            let state = self.state.read().unwrap();
+            if let Some(object) = state.objects.get_object(object_id) {
+                return Ok(Some(object.clone()));
+            }
+            // objects the fork deleted are not fetched again either
+            if state.missing.contains(object_id)
+                || state
+                    .objects
+                    .get_latest_parent_entry_ref(*object_id)?
+                    .is_some()
+            {
+                return Ok(None);
+            }
+        }
+
+        let object = self.source.get_object(object_id)?;
+        let mut state = self.state.write().unwrap();
+        match &object {
+            Some(object) => {
+                state.fetched.insert(*object_id, object.clone());
+                state.objects.insert_object(object.clone());
+            }
+            None => {
+                state.missing.insert(*object_id);
+            }
+        }
+        Ok(object)
+    }
+
+    fn get_object_for_storage(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
+        self.get_object(object_id)
+            .map_err(|e| SuiError::GenericStorageError(e.to_string()))
+    }
+}
+
+impl BackingPackageStore for ForkStore {
+    fn get_package(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        self.get_object_for_storage(package_id)
+    }
+}
+
+impl ChildObjectResolver for ForkStore {
+    fn read_child_object(&self, parent: &ObjectID, child: &ObjectID) -> SuiResult<Option<Object>> {
+        let Some(child_object) = self.get_object_for_storage(child)? else {
+            return Ok(None);
+        };
+        if child_object.owner != Owner::ObjectOwner((*parent).into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: *parent,
+                actual_owner: child_object.owner,
+            });
+        }
+        Ok(Some(child_object))
+    }
+}
+
+impl ParentSync for ForkStore {
+    fn get_latest_parent_entry_ref(&self, object_id: ObjectID) -> SuiResult<Option<ObjectRef>> {
+        if let Some(object) = self.get_object_for_storage(&object_id)? {
+            return Ok(Some(object.compute_object_reference()));
+        }
+        let state = self.state.read().unwrap();
+        state.objects.get_latest_parent_entry_ref(object_id)
+    }
+}
@@ -10,11 +10,13 @@ use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, bail};
 use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
 use futures::StreamExt;
 use futures_core::Stream;
 use jsonrpsee::core::client::{ClientT, Subscription};
 use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
 use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
+use tokio::sync::Mutex;
 
 use rpc_types::{
     GetPastObjectDataResponse, SuiCertifiedTransaction, SuiExecuteTransactionResponse,
@@ -33,7 +35,7 @@ use sui_json_rpc::api::TransactionExecutionApiClient;
 pub use sui_json_rpc_types as rpc_types;
 use sui_json_rpc_types::{
     EventPage, GetObjectDataResponse, GetRawObjectDataResponse, SuiEventEnvelope, SuiEventFilter,
-    SuiObjectInfo, SuiTransactionResponse, TransactionsPage,
+    SuiGasProfile, SuiObjectInfo, SuiTransactionResponse, TransactionsPage,
 };
 use sui_transaction_builder::{DataReader, TransactionBuilder};
 pub use sui_types as types;
@@ -42,12 +44,19 @@ use sui_types::event::EventID;
 use sui_types::messages::VerifiedTransaction;
 use sui_types::query::{EventQuery, TransactionQuery};
 use types::base_types::SequenceNumber;
-use types::committee::EpochId;
+use types::committee::{Committee, EpochId};
 use types::error::TRANSACTION_NOT_FOUND_MSG_PREFIX;
-use types::messages::{CommitteeInfoResponse, ExecuteTransactionRequestType};
+use types::light_client::{LightClient, TransactionInclusionProof};
+use types::messages::{
+    CommitteeInfoResponse, ExecuteTransactionRequestType, TransactionData, TransactionEffects,
+};
+use types::messages_checkpoint::{CheckpointContents, CheckpointSequenceNumber, CheckpointSummary};
+use types::object::Object;
 
 #[cfg(msim)]
 pub mod embedded_gateway;
+pub mod fork;
+pub mod move_call;
 
 const WAIT_FOR_TX_TIMEOUT_SEC: u64 = 10;
 
@@ -280,6 +289,41 @@ impl ReadApi {
         Ok(self.api.http.get_committee_info(epoch).await?)
     }
 
+    pub async fn get_latest_checkpoint_sequence_number(
+        &self,
+    ) -> anyhow::Result<CheckpointSequenceNumber> {
+        Ok(self
+            .api
+            .http
+            .get_latest_checkpoint_sequence_number()
+            .await?)
+    }
+
+    pub async fn get_checkpoint_summary(
+        &self,
+        seq_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CheckpointSummary> {
+        Ok(self.api.http.get_checkpoint_summary(seq_number).await?)
+    }
+
+    pub async fn get_checkpoint_contents(
+        &self,
+        seq_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CheckpointContents> {
+        Ok(self.api.http.get_checkpoint_contents(seq_number).await?)
+    }
+
+    pub async fn get_checkpoint_sequence_number_by_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+        Ok(self
+            .api
+            .http
+            .get_checkpoint_sequence_number_by_transaction(digest)
+            .await?)
+    }
+
     pub async fn get_transactions(
         &self,
         query: TransactionQuery,
@@ -293,6 +337,18 @@ impl ReadApi {
             .get_transactions(query, cursor, limit, descending_order)
             .await?)
     }
+
+    /// Dry run `tx`, recording the gas charged by each call stack of its Move calls
+    pub async fn dry_run_transaction_with_gas_profile(
+        &self,
+        tx: TransactionData,
+    ) -> anyhow::Result<SuiGasProfile> {
+        Ok(self
+            .api
+            .http
+            .dry_run_transaction_with_gas_profile(Base64::from_bytes(&tx.to_bytes()))
+            .await?)
+    }
 }
 
 #[derive(Clone)]
@@ -462,6 +518,83 @@ impl QuorumDriver {
     }
 }
 
+/// A read API that verifies every response against certified checkpoints, so that the
+/// server does not need to be trusted.
+pub struct VerifiedReadApi {
+    api: Arc<RpcClient>,
+    light_client: Mutex<LightClient>,
+}
+
+impl VerifiedReadApi {
+    /// Follow the certified checkpoints of the server up to its latest certified checkpoint,
+    /// and return the sequence number of the next checkpoint to follow.
+    pub async fn sync(&self) -> anyhow::Result<CheckpointSequenceNumber> {
+        let mut light_client = self.light_client.lock().await;
+        loop {
+            let next = light_client.next_checkpoint_sequence_number();
+            let Some(checkpoint) = self.api.http.get_certified_checkpoint(next).await? else {
+                return Ok(next);
+            };
+            light_client.sync_checkpoint(checkpoint.to_data()?)?;
+        }
+    }
+
+    /// Return the effects of a transaction, after verifying that they are the effects of this
+    /// transaction and that they were included in a certified checkpoint.
+    pub async fn get_transaction_effects(
+        &self,
+        digest: TransactionDigest,
+    ) -> anyhow::Result<TransactionEffects> {
+        let proof = self.get_transaction_inclusion_proof(digest).await?;
+        self.light_client
+            .lock()
+            .await
+            .verify_transaction(&digest, &proof)?;
+        Ok(proof.effects)
+    }
+
+    /// Return the latest version of an object known to the server, after verifying that it was
+    /// written by a transaction included in a certified checkpoint.
+    pub async fn get_object(&self, object_id: ObjectID) -> anyhow::Result<Object> {
+        let object: Object = self
+            .api
+            .http
+            .get_raw_object(object_id)
+            .await?
+            .into_object()?
+            .try_into()?;
+        let proof = self
+            .get_transaction_inclusion_proof(object.previous_transaction)
+            .await?;
+        self.light_client
+            .lock()
+            .await
+            .verify_object_inclusion(&object, &proof)?;
+        Ok(object)
+    }
+
+    async fn get_transaction_inclusion_proof(
+        &self,
+        digest: TransactionDigest,
+    ) -> anyhow::Result<TransactionInclusionProof> {
+        let proof = self
+            .api
+            .http
+            .get_transaction_inclusion_proof(digest)
+            .await?
+            .ok_or_else(|| {
+                anyhow!("Transaction {digest:?} is not included in a certified checkpoint yet")
+            })?
+            .to_data()?;
+        // Catch up with epoch changes before verifying proofs from a newer epoch.
+        let known_epoch = self.light_client.lock().await.committee().epoch;
+        if proof.checkpoint.epoch() > known_epoch {
+            self.sync().await?;
+        }
+        Ok(proof)
+    }
+}
+
 impl SuiClient {
     pub fn transaction_builder(&self) -> &TransactionBuilder {
         &self.transaction_builder
@@ -475,6 +608,14 @@ impl SuiClient {
     pub fn quorum_driver(&self) -> &QuorumDriver {
         &self.quorum_driver
     }
+    /// Create a read API that verifies responses of the server, starting from a trusted
+    /// committee, usually the genesis committee.
+    pub fn verified_read_api(&self, committee: Committee) -> VerifiedReadApi {
+        VerifiedReadApi {
+            api: self.api.clone(),
+            light_client: Mutex::new(LightClient::new(committee)),
+        }
+    }
 }
 
 #[async_trait]
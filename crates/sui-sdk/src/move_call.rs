@@ -0,0 +1,78 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversions of Rust values into Move call arguments, for the typed entry function wrappers
+//! generated by `sui move codegen --lang rust`.
+
+use serde_json::{json, Value};
+use sui_json::SuiJsonValue;
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+pub use move_core_types::u256::U256;
+
+/// A Rust value that can be passed as an argument of an entry function
+pub trait MoveCallArg {
+    /// The JSON form of the argument, as the transaction builder expects it
+    fn to_json(&self) -> Value;
+
+    fn to_sui_json(&self) -> anyhow::Result<SuiJsonValue> {
+        SuiJsonValue::new(self.to_json())
+    }
+}
+
+macro_rules! number_arg {
+    ($($t:ty),*) => {
+        $(impl MoveCallArg for $t {
+            fn to_json(&self) -> Value {
+                json!(self)
+            }
+        })*
+    };
+}
+
+number_arg!(bool, u8, u16, u32, u64);
+
+// integers that do not fit in a JSON number are passed as decimal strings
+impl MoveCallArg for u128 {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl MoveCallArg for U256 {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl MoveCallArg for String {
+    fn to_json(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl MoveCallArg for SuiAddress {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+/// Objects are passed by ID
+impl MoveCallArg for ObjectID {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_hex_literal())
+    }
+}
+
+impl<T: MoveCallArg> MoveCallArg for Vec<T> {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(MoveCallArg::to_json).collect())
+    }
+}
+
+/// Move options are vectors of at most one element
+impl<T: MoveCallArg> MoveCallArg for Option<T> {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(MoveCallArg::to_json).collect())
+    }
+}
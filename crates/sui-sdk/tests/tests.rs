@@ -5,10 +5,14 @@ use std::str::FromStr;
 use tempfile::TempDir;
 
 use fastcrypto::hash::{HashFunction, Sha3_256};
+use sui_adapter::genesis;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_sdk::fork::{FixtureObjectSource, ForkExecutor};
 use sui_types::crypto::{SignatureScheme, SuiSignatureInner};
+use sui_types::messages::{ExecutionStatus, TransactionData};
+use sui_types::object::{Object, Owner};
 use sui_types::{
-    base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
+    base_types::{ObjectID, SuiAddress, TransactionDigest, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
 };
 #[test]
@@ -68,3 +72,61 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+#[test]
+fn fork_executes_transactions_against_fixture() {
+    let (sender, recipient) = (
+        SuiAddress::random_for_testing_only(),
+        SuiAddress::random_for_testing_only(),
+    );
+    let gas = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let coin = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let mut objects = genesis::clone_genesis_packages();
+    objects.extend([gas.clone(), coin.clone()]);
+    let fork = ForkExecutor::new(FixtureObjectSource::new(objects)).unwrap();
+
+    let data = TransactionData::new_transfer(
+        recipient,
+        coin.compute_object_reference(),
+        sender,
+        gas.compute_object_reference(),
+        10000,
+    );
+    let (effects, error) = fork.execute(data, TransactionDigest::random()).unwrap();
+    assert!(
+        matches!(effects.status, ExecutionStatus::Success),
+        "{error:?}"
+    );
+    let transferred = fork.get_object(&coin.id()).unwrap().unwrap();
+    assert_eq!(transferred.owner, Owner::AddressOwner(recipient));
+
+    // the fixture records the objects as they were before the transaction
+    let temp_dir = TempDir::new().unwrap();
+    let fixture = temp_dir.path().join("fixture.json");
+    fork.write_fixture(&fixture).unwrap();
+    let replay = ForkExecutor::new(FixtureObjectSource::read(&fixture).unwrap()).unwrap();
+    assert_eq!(replay.get_object(&coin.id()).unwrap(), Some(coin.clone()));
+
+    // overridden objects are used by the transactions that follow
+    let mut taken_back = transferred;
+    taken_back.owner = Owner::AddressOwner(sender);
+    fork.override_object(taken_back);
+    let data = TransactionData::new_transfer(
+        recipient,
+        fork.get_object(&coin.id())
+            .unwrap()
+            .unwrap()
+            .compute_object_reference(),
+        sender,
+        fork.get_object(&gas.id())
+            .unwrap()
+            .unwrap()
+            .compute_object_reference(),
+        10000,
+    );
+    let (effects, error) = fork.execute(data, TransactionDigest::random()).unwrap();
+    assert!(
+        matches!(effects.status, ExecutionStatus::Success),
+        "{error:?}"
+    );
+}
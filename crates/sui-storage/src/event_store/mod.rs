@@ -26,6 +26,8 @@ use move_core_types::value::MoveValue;
 use serde_json::Value;
 use tokio_stream::StreamExt;
 
+pub use postgres::PgEventStore;
+pub use retention::{EventRetentionPolicy, EventStorePruner};
 pub use sql::SqlEventStore;
 use sui_json_rpc_types::{SuiEvent, SuiEventEnvelope};
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
@@ -35,6 +37,8 @@ use sui_types::event::{BalanceChangeType, Event, EventID};
 use sui_types::event::{EventEnvelope, EventType};
 use sui_types::object::Owner;
 
+pub mod postgres;
+pub mod retention;
 pub mod sql;
 pub mod test_utils;
 
@@ -491,6 +495,18 @@ pub trait EventStore {
         limit: usize,
         descending: bool,
     ) -> Result<Vec<StoredEvent>, SuiError>;
+
+    /// Deletes the events falling out of the retention policy as of `now` (UTC milliseconds),
+    /// and reclaims the space they used.
+    /// Events are deleted in small batches so that concurrent ingestion and queries are not
+    /// blocked for long.
+    ///
+    /// Returns the number of events deleted.
+    async fn prune_events(&self, policy: &EventRetentionPolicy, now: u64) -> Result<u64, SuiError>;
+
+    /// Returns the ID of the earliest event available in the store, or None if it is empty.
+    /// Events before it may have been pruned.
+    async fn earliest_available_event(&self) -> Result<Option<EventID>, SuiError>;
 }
 
 /// EventStoreType contains different implementations of EventStores, but implements the EventStore trait.
@@ -498,6 +514,7 @@ pub trait EventStore {
 #[enum_dispatch(EventStore)]
 pub enum EventStoreType {
     SqlEventStore,
+    PgEventStore,
 }
 
 /// A wrapper around streaming results which makes them easier to deal with
@@ -0,0 +1,85 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Retention of events in the EventStore.
+//!
+//! Without a retention policy, an EventStore keeps every event it ingests forever. A
+//! [EventRetentionPolicy] bounds the store by the age and/or the number of events, and can
+//! restrict the kept events to a set of Move packages, or drop some event types altogether.
+//! Events falling out of the policy are deleted by the [EventStorePruner] in the background.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sui_metrics::spawn_monitored_task;
+use sui_types::base_types::ObjectID;
+use sui_types::event::EventType;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::{EventStore, EventStoreType};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventRetentionPolicy {
+    /// Events older than this are pruned
+    pub max_age: Option<Duration>,
+    /// Only the most recent `max_events` events are kept
+    pub max_events: Option<u64>,
+    /// If set, only the events emitted by these packages are kept
+    pub packages: Option<Vec<ObjectID>>,
+    /// Events of these types are not kept
+    pub excluded_event_types: Vec<EventType>,
+}
+
+impl EventRetentionPolicy {
+    /// Returns true if the policy keeps every event, in which case there is nothing to prune.
+    pub fn is_unbounded(&self) -> bool {
+        self.max_age.is_none()
+            && self.max_events.is_none()
+            && self.packages.is_none()
+            && self.excluded_event_types.is_empty()
+    }
+
+    /// Returns the timestamp (UTC milliseconds) before which events are pruned, as of `now`.
+    pub fn min_timestamp(&self, now: u64) -> Option<u64> {
+        self.max_age
+            .map(|max_age| now.saturating_sub(max_age.as_millis() as u64))
+    }
+}
+
+/// Periodically prunes the events falling out of a retention policy from the EventStore.
+pub struct EventStorePruner {
+    handle: JoinHandle<()>,
+}
+
+impl EventStorePruner {
+    pub fn new(
+        event_store: Arc<EventStoreType>,
+        policy: EventRetentionPolicy,
+        pruning_interval: Duration,
+    ) -> Self {
+        info!(?policy, ?pruning_interval, "Starting EventStore pruner");
+        let handle = spawn_monitored_task!(async move {
+            let mut interval = tokio::time::interval(pruning_interval);
+            loop {
+                interval.tick().await;
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .expect("Time went backwards")
+                    .as_millis() as u64;
+                match event_store.prune_events(&policy, now).await {
+                    Ok(0) => debug!("No events to prune"),
+                    Ok(pruned) => info!(pruned, "Pruned events from the EventStore"),
+                    Err(e) => warn!(error = ?e, "Failed to prune events from the EventStore"),
+                }
+            }
+        });
+        Self { handle }
+    }
+}
+
+impl Drop for EventStorePruner {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
@@ -11,7 +11,9 @@ use async_trait::async_trait;
 use serde_json::{json, Value};
 use sqlx::ConnectOptions;
 use sqlx::{
-    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteRow, SqliteSynchronous},
+    sqlite::{
+        SqliteAutoVacuum, SqliteConnectOptions, SqliteJournalMode, SqliteRow, SqliteSynchronous,
+    },
     Executor, QueryBuilder, Row, SqlitePool,
 };
 use strum::{EnumMessage, IntoEnumIterator};
@@ -81,7 +83,7 @@ const SQL_INSERT_TX: &str =
     package_id, module_name, object_id, object_type, fields, move_event_name, contents, sender,  \
     recipient) ";
 
-const INDEXED_COLUMNS: &[&str] = &[
+pub(super) const INDEXED_COLUMNS: &[&str] = &[
     "seq_num",
     "event_num",
     "timestamp",
@@ -115,6 +117,9 @@ impl SqlEventStore {
             .journal_mode(SqliteJournalMode::Wal)
             // Normal vs Full sync mode also speeds up writes
             .synchronous(SqliteSynchronous::Normal)
+            // Lets pruning reclaim free pages in small steps, instead of a blocking full VACUUM.
+            // NOTE: only takes effect for newly created databases.
+            .auto_vacuum(SqliteAutoVacuum::Incremental)
             .create_if_missing(true);
         options.log_statements(log::LevelFilter::Off);
         let pool = SqlitePool::connect_with(options)
@@ -180,8 +185,7 @@ impl SqlEventStore {
         Ok(num_rows as usize)
     }
 
-    fn try_extract_object_id(row: &SqliteRow, col: usize) -> Result<Option<ObjectID>, SuiError> {
-        let raw_bytes: Option<Vec<u8>> = row.get(col);
+    fn try_extract_object_id(raw_bytes: Option<Vec<u8>>) -> Result<Option<ObjectID>, SuiError> {
         match raw_bytes {
             Some(bytes) => Ok(Some(ObjectID::try_from(bytes.as_slice()).map_err(
                 |_e| SuiError::BadObjectType {
@@ -192,8 +196,9 @@ impl SqlEventStore {
         }
     }
 
-    fn try_extract_sender_address(row: &SqliteRow) -> Result<Option<SuiAddress>, SuiError> {
-        let raw_bytes: Option<Vec<u8>> = row.get(EventsTableColumns::Sender as usize);
+    fn try_extract_sender_address(
+        raw_bytes: Option<Vec<u8>>,
+    ) -> Result<Option<SuiAddress>, SuiError> {
         match raw_bytes {
             Some(bytes) => Ok(Some(SuiAddress::try_from(bytes.as_slice()).map_err(
                 |_e| SuiError::BadObjectType {
@@ -207,9 +212,9 @@ impl SqlEventStore {
         }
     }
 
-    fn try_extract_recipient(row: &SqliteRow) -> Result<Option<Owner>, SuiError> {
-        let deserialized_owner: Option<String> = row.get(EventsTableColumns::Recipient as usize);
-
+    fn try_extract_recipient(
+        deserialized_owner: Option<String>,
+    ) -> Result<Option<Owner>, SuiError> {
         match deserialized_owner {
             Some(owner_string) => Ok(Some(serde_json::from_str::<Owner>(&owner_string).map_err(
                 |_e| SuiError::BadObjectType {
@@ -224,7 +229,7 @@ impl SqlEventStore {
     }
 
     // convert an event's extra fields into a stringified JSON Value.
-    fn event_to_json(event: &EventEnvelope) -> String {
+    pub(super) fn event_to_json(event: &EventEnvelope) -> String {
         // For move events, we only store the move_struct_json_value
         if let Some(json_value) = &event.move_struct_json_value {
             json_value.to_string()
@@ -246,33 +251,66 @@ impl SqlEventStore {
     }
 }
 
+/// Column values of a row of the events table, in the order of [EventsTableColumns].
+/// This is shared by the SQL-based Event Stores, which use the same data model.
+pub(super) struct EventsTableRow {
+    pub timestamp: i64,
+    pub seq_num: i64,
+    pub event_num: i64,
+    pub tx_digest: Option<Vec<u8>>,
+    pub event_type: u16,
+    pub package_id: Option<Vec<u8>>,
+    pub module_name: Option<String>,
+    pub function: Option<String>,
+    pub object_type: Option<String>,
+    pub object_id: Option<Vec<u8>>,
+    pub fields: String,
+    pub move_event_name: Option<String>,
+    pub contents: Option<Vec<u8>>,
+    pub sender: Option<Vec<u8>>,
+    pub recipient: Option<String>,
+}
+
 impl From<SqliteRow> for StoredEvent {
     // Translate a Row into StoredEvent
     // TODO: convert to use FromRow trait so query_as() could be used?
-    // TODO: gracefully handle data corruption/incompatibility without panicking
     fn from(row: SqliteRow) -> Self {
-        let timestamp: i64 = row.get(EventsTableColumns::Timestamp as usize);
-        let event_num: i64 = row.get(EventsTableColumns::EventNum as usize);
-        let seq_num: i64 = row.get(EventsTableColumns::SeqNum as usize);
-        let digest_raw: Option<Vec<u8>> = row.get(EventsTableColumns::TxDigest as usize);
-        let tx_digest = digest_raw.map(|bytes| {
+        EventsTableRow {
+            timestamp: row.get(EventsTableColumns::Timestamp as usize),
+            seq_num: row.get(EventsTableColumns::SeqNum as usize),
+            event_num: row.get(EventsTableColumns::EventNum as usize),
+            tx_digest: row.get(EventsTableColumns::TxDigest as usize),
+            event_type: row.get(EventsTableColumns::EventType as usize),
+            package_id: row.get(EventsTableColumns::PackageId as usize),
+            module_name: row.get(EventsTableColumns::ModuleName as usize),
+            function: row.get(EventsTableColumns::Function as usize),
+            object_type: row.get(EventsTableColumns::ObjectType as usize),
+            object_id: row.get(EventsTableColumns::ObjectId as usize),
+            fields: row.get(EventsTableColumns::Fields as usize),
+            move_event_name: row.get(EventsTableColumns::MoveEventName as usize),
+            contents: row.get(EventsTableColumns::Contents as usize),
+            sender: row.get(EventsTableColumns::Sender as usize),
+            recipient: row.get(EventsTableColumns::Recipient as usize),
+        }
+        .into()
+    }
+}
+
+impl From<EventsTableRow> for StoredEvent {
+    // TODO: gracefully handle data corruption/incompatibility without panicking
+    fn from(row: EventsTableRow) -> Self {
+        let tx_digest = row.tx_digest.map(|bytes| {
             TransactionDigest::new(
                 bytes
                     .try_into()
                     .expect("Error converting digest bytes to TxDigest"),
             )
         });
-        let event_type: u16 = row.get(EventsTableColumns::EventType as usize);
-        let package_id =
-            SqlEventStore::try_extract_object_id(&row, EventsTableColumns::PackageId as usize)
-                .expect("Error converting stored package ID bytes to ObjectID");
-        let object_id =
-            SqlEventStore::try_extract_object_id(&row, EventsTableColumns::ObjectId as usize)
-                .expect("Error converting stored object ID bytes to ObjectID");
-        let object_type: Option<String> = row.get(EventsTableColumns::ObjectType as usize);
-        let module_name: Option<String> = row.get(EventsTableColumns::ModuleName as usize);
-        let function: Option<String> = row.get(EventsTableColumns::Function as usize);
-        let fields_text: &str = row.get(EventsTableColumns::Fields as usize);
+        let package_id = SqlEventStore::try_extract_object_id(row.package_id)
+            .expect("Error converting stored package ID bytes to ObjectID");
+        let object_id = SqlEventStore::try_extract_object_id(row.object_id)
+            .expect("Error converting stored object ID bytes to ObjectID");
+        let fields_text = row.fields.as_str();
         let fields: BTreeMap<SharedStr, EventValue> = if fields_text.is_empty() {
             BTreeMap::new()
         } else {
@@ -290,26 +328,24 @@ impl From<SqliteRow> for StoredEvent {
                 BTreeMap::new()
             }
         };
-        let move_event_contents: Option<Vec<u8>> = row.get(EventsTableColumns::Contents as usize);
-        let move_event_name: Option<String> = row.get(EventsTableColumns::MoveEventName as usize);
-        let sender = SqlEventStore::try_extract_sender_address(&row)
+        let sender = SqlEventStore::try_extract_sender_address(row.sender)
             .expect("Error converting stored sender address bytes to SuiAddress");
-        let recipient = SqlEventStore::try_extract_recipient(&row)
+        let recipient = SqlEventStore::try_extract_recipient(row.recipient)
             .expect("Error converting stored recipient address to Owner");
 
         StoredEvent {
-            id: (seq_num, event_num).into(),
-            timestamp: timestamp as u64,
+            id: (row.seq_num, row.event_num).into(),
+            timestamp: row.timestamp as u64,
             tx_digest,
-            event_type: SharedStr::from(Event::name_from_ordinal(event_type as usize)),
+            event_type: SharedStr::from(Event::name_from_ordinal(row.event_type as usize)),
             package_id,
-            module_name: module_name.map(|s| s.into()),
-            function_name: function.map(SharedStr::from),
-            object_type,
+            module_name: row.module_name.map(|s| s.into()),
+            function_name: row.function.map(SharedStr::from),
+            object_type: row.object_type,
             object_id,
             fields,
-            move_event_contents,
-            move_event_name,
+            move_event_contents: row.contents,
+            move_event_name: row.move_event_name,
             sender,
             recipient,
         }
@@ -318,6 +354,87 @@ impl From<SqliteRow> for StoredEvent {
 /// Maximum number of rows to insert at once as a batch.  SQLite has 64k limit in binding values.
 const MAX_INSERT_BATCH: usize = 1000;
 
+/// Maximum number of rows to delete at once when pruning, so that the write lock is released
+/// regularly for ingestion.
+const MAX_PRUNE_BATCH: usize = 10_000;
+
+/// Maximum number of free pages to reclaim in one incremental vacuum step.
+const MAX_VACUUM_PAGES: usize = 1000;
+
+impl SqlEventStore {
+    /// Repeatedly deletes up to MAX_PRUNE_BATCH events matching the `WHERE` clause built by
+    /// `push_clause`, until none is left.
+    async fn delete_events_in_batches<F>(&self, push_clause: F) -> Result<u64, SuiError>
+    where
+        F: Fn(&mut QueryBuilder<'_, sqlx::Sqlite>),
+    {
+        let mut deleted = 0;
+        loop {
+            let mut query_builder = QueryBuilder::new(
+                "DELETE FROM events WHERE rowid IN (SELECT rowid FROM events WHERE ",
+            );
+            push_clause(&mut query_builder);
+            query_builder.push(" LIMIT ");
+            query_builder.push_bind(MAX_PRUNE_BATCH as i64);
+            query_builder.push(")");
+            let res = query_builder
+                .build()
+                .execute(&self.pool)
+                .await
+                .map_err(convert_sqlx_err)?;
+            deleted += res.rows_affected();
+            if res.rows_affected() < MAX_PRUNE_BATCH as u64 {
+                return Ok(deleted);
+            }
+            // Let ingestion and queries go through between batches
+            tokio::task::yield_now().await;
+        }
+    }
+
+    /// Returns the ID of the most recent event falling out of the `max_events` most recent ones.
+    async fn newest_pruned_event(&self, max_events: u64) -> Result<Option<EventID>, SuiError> {
+        let row = sqlx::query(
+            "SELECT seq_num, event_num FROM events ORDER BY seq_num DESC, event_num DESC \
+            LIMIT 1 OFFSET ?",
+        )
+        .bind(max_events as i64)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(convert_sqlx_err)?;
+        Ok(row.map(|row| (row.get::<i64, _>(0), row.get::<i64, _>(1)).into()))
+    }
+
+    /// Reclaims the free pages left by deleted events, MAX_VACUUM_PAGES at a time.
+    /// This is a no-op unless the database was created with incremental auto vacuum.
+    async fn incremental_vacuum(&self) -> Result<(), SuiError> {
+        let auto_vacuum: i64 = sqlx::query("PRAGMA auto_vacuum")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(convert_sqlx_err)?
+            .get(0);
+        // 2 is INCREMENTAL
+        if auto_vacuum != 2 {
+            return Ok(());
+        }
+        loop {
+            let free_pages: i64 = sqlx::query("PRAGMA freelist_count")
+                .fetch_one(&self.pool)
+                .await
+                .map_err(convert_sqlx_err)?
+                .get(0);
+            if free_pages == 0 {
+                return Ok(());
+            }
+            self.pool
+                .execute(format!("PRAGMA incremental_vacuum({MAX_VACUUM_PAGES})").as_str())
+                .await
+                .map_err(convert_sqlx_err)?;
+            // Let ingestion and queries go through between steps
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
 #[async_trait]
 impl EventStore for SqlEventStore {
     #[instrument(level = "debug", skip_all, err)]
@@ -583,6 +700,82 @@ impl EventStore for SqlEventStore {
             .map_err(convert_sqlx_err)?;
         Ok(rows)
     }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn prune_events(&self, policy: &EventRetentionPolicy, now: u64) -> Result<u64, SuiError> {
+        if policy.is_unbounded() {
+            return Ok(0);
+        }
+        let mut pruned = 0;
+
+        for event_type in &policy.excluded_event_types {
+            let event_type = *event_type as u16;
+            pruned += self
+                .delete_events_in_batches(|query_builder| {
+                    query_builder.push("event_type = ");
+                    query_builder.push_bind(event_type);
+                })
+                .await?;
+        }
+
+        if let Some(packages) = &policy.packages {
+            pruned += self
+                .delete_events_in_batches(|query_builder| {
+                    query_builder.push("package_id IS NULL");
+                    if !packages.is_empty() {
+                        query_builder.push(" OR package_id NOT IN (");
+                        let mut separated = query_builder.separated(", ");
+                        for package in packages {
+                            separated.push_bind(package.to_vec());
+                        }
+                        separated.push_unseparated(")");
+                    }
+                })
+                .await?;
+        }
+
+        if let Some(min_timestamp) = policy.min_timestamp(now) {
+            pruned += self
+                .delete_events_in_batches(|query_builder| {
+                    query_builder.push("timestamp < ");
+                    query_builder.push_bind(min_timestamp as i64);
+                })
+                .await?;
+        }
+
+        // Applied last, so that the count is over the events retained by the rules above
+        if let Some(max_events) = policy.max_events {
+            if let Some(newest_pruned) = self.newest_pruned_event(max_events).await? {
+                pruned += self
+                    .delete_events_in_batches(|query_builder| {
+                        query_builder.push("seq_num < ");
+                        query_builder.push_bind(newest_pruned.tx_seq);
+                        query_builder.push(" OR (seq_num = ");
+                        query_builder.push_bind(newest_pruned.tx_seq);
+                        query_builder.push(" AND event_num <= ");
+                        query_builder.push_bind(newest_pruned.event_seq);
+                        query_builder.push(")");
+                    })
+                    .await?;
+            }
+        }
+
+        if pruned > 0 {
+            self.incremental_vacuum().await?;
+        }
+        Ok(pruned)
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn earliest_available_event(&self) -> Result<Option<EventID>, SuiError> {
+        let row = sqlx::query(
+            "SELECT seq_num, event_num FROM events ORDER BY seq_num ASC, event_num ASC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(convert_sqlx_err)?;
+        Ok(row.map(|row| (row.get::<i64, _>(0), row.get::<i64, _>(1)).into()))
+    }
 }
 
 fn convert_sqlx_err(err: sqlx::Error) -> SuiError {
@@ -612,7 +805,7 @@ fn get_event_query(causes: Vec<(&str, Comparator)>, descending: bool) -> String
     query
 }
 
-enum Comparator {
+pub(super) enum Comparator {
     Equal,
     LessThanOrEq,
     MoreThanOrEq,
@@ -633,40 +826,11 @@ impl Display for Comparator {
 
 #[cfg(test)]
 mod tests {
-    use flexstr::shared_str;
     use move_core_types::{account_address::AccountAddress, identifier::Identifier};
 
-    use sui_types::event::EventEnvelope;
-
-    use super::test_utils;
+    use super::test_utils::{self, test_queried_event_vs_test_envelope};
     use super::*;
 
-    fn test_queried_event_vs_test_envelope(queried: &StoredEvent, orig: &EventEnvelope) {
-        assert_eq!(queried.timestamp, orig.timestamp);
-        assert_eq!(queried.tx_digest, orig.tx_digest);
-        assert_eq!(queried.event_type, shared_str!(orig.event_type()));
-        assert_eq!(queried.package_id, orig.event.package_id());
-        assert_eq!(
-            queried.module_name,
-            orig.event.module_name().map(SharedStr::from)
-        );
-        assert_eq!(queried.object_id, orig.event.object_id());
-        assert_eq!(queried.sender, orig.event.sender());
-        assert_eq!(queried.recipient.as_ref(), orig.event.recipient());
-        assert_eq!(queried.object_type, orig.event.object_type());
-        assert_eq!(
-            queried.object_version().unwrap().as_ref(),
-            orig.event.object_version()
-        );
-        assert_eq!(
-            queried.move_event_contents.as_deref(),
-            orig.event.move_event_contents()
-        );
-        assert_eq!(queried.amount().unwrap(), orig.event.amount());
-        let move_event_name = orig.event.move_event_name();
-        assert_eq!(queried.move_event_name.as_ref(), move_event_name.as_ref());
-    }
-
     #[tokio::test]
     async fn test_eventstore_basic_insert_read() -> Result<(), SuiError> {
         telemetry_subscribers::init_for_testing();
@@ -1450,4 +1614,32 @@ mod tests {
         );
         assert_eq!("SELECT * FROM events WHERE seq_num <= ? AND event_num <= ? AND package_id = ? AND module_name = ? ORDER BY seq_num DESC, event_num DESC LIMIT ?", query);
     }
+
+    async fn new_test_store() -> Result<SqlEventStore, SuiError> {
+        let db = SqlEventStore::new_memory_only_not_prod().await?;
+        db.initialize().await?;
+        Ok(db)
+    }
+
+    #[tokio::test]
+    async fn test_eventstore_conformance_insert_and_read() -> Result<(), SuiError> {
+        test_utils::test_insert_and_read(&new_test_store().await?).await
+    }
+
+    #[tokio::test]
+    async fn test_eventstore_conformance_query_filters() -> Result<(), SuiError> {
+        test_utils::test_query_filters(&new_test_store().await?).await
+    }
+
+    #[tokio::test]
+    async fn test_eventstore_conformance_prune_events() -> Result<(), SuiError> {
+        telemetry_subscribers::init_for_testing();
+
+        // Use a database on disk, for the incremental vacuum
+        let dir = tempfile::TempDir::new().unwrap(); // NOTE this must be its own line so dir isn't dropped
+        let db_file = dir.path().join("events.db");
+        let db = SqlEventStore::new_from_file(&db_file).await?;
+        db.initialize().await?;
+        test_utils::test_prune_events(&db).await
+    }
 }
@@ -1,6 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::time::Duration;
+
+use flexstr::shared_str;
 use move_core_types::account_address::AccountAddress;
 use move_core_types::ident_str;
 use move_core_types::language_storage::{StructTag, TypeTag};
@@ -239,3 +242,268 @@ pub fn new_test_move_event(
         move_struct_json_value: Some(json),
     }
 }
+
+pub fn test_queried_event_vs_test_envelope(queried: &StoredEvent, orig: &EventEnvelope) {
+    assert_eq!(queried.timestamp, orig.timestamp);
+    assert_eq!(queried.tx_digest, orig.tx_digest);
+    assert_eq!(queried.event_type, shared_str!(orig.event_type()));
+    assert_eq!(queried.package_id, orig.event.package_id());
+    assert_eq!(
+        queried.module_name,
+        orig.event.module_name().map(SharedStr::from)
+    );
+    assert_eq!(queried.object_id, orig.event.object_id());
+    assert_eq!(queried.sender, orig.event.sender());
+    assert_eq!(queried.recipient.as_ref(), orig.event.recipient());
+    assert_eq!(queried.object_type, orig.event.object_type());
+    assert_eq!(
+        queried.object_version().unwrap().as_ref(),
+        orig.event.object_version()
+    );
+    assert_eq!(
+        queried.move_event_contents.as_deref(),
+        orig.event.move_event_contents()
+    );
+    assert_eq!(queried.amount().unwrap(), orig.event.amount());
+    let move_event_name = orig.event.move_event_name();
+    assert_eq!(queried.move_event_name.as_ref(), move_event_name.as_ref());
+}
+
+fn assert_events_match(queried: &[StoredEvent], expected: &[&EventEnvelope]) {
+    assert_eq!(queried.len(), expected.len());
+    for (queried, expected) in queried.iter().zip(expected) {
+        test_queried_event_vs_test_envelope(queried, expected);
+    }
+}
+
+// Conformance tests, shared by all the EventStore implementations.
+// Each of them expects a freshly initialized, empty store.
+
+struct ConformanceEvents {
+    events: Vec<EventEnvelope>,
+    tx_digest: TransactionDigest,
+    sender: SuiAddress,
+    recipient: Owner,
+    object_id: ObjectID,
+    package_id: ObjectID,
+}
+
+fn new_conformance_events() -> ConformanceEvents {
+    let tx_digest = TransactionDigest::random();
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = Owner::AddressOwner(SuiAddress::random_for_testing_only());
+    let object_id = ObjectID::random();
+    let package_id = ObjectID::from_hex_literal("0x3").unwrap();
+    let object_type = "0x2::test::Object";
+    let events = vec![
+        new_test_newobj_event(
+            1_000_000,
+            tx_digest,
+            1,
+            0, // event_num
+            Some(object_id),
+            Some(sender),
+            Some(recipient),
+        ),
+        new_test_transfer_event(
+            1_000_000,
+            tx_digest,
+            1,
+            1, // event_num
+            1,
+            object_type,
+            Some(object_id),
+            Some(sender),
+            Some(recipient),
+        ),
+        new_test_publish_event(
+            1_001_000,
+            TransactionDigest::random(),
+            2,
+            0, // event_num
+            Some(sender),
+        ),
+        new_test_move_event(
+            1_002_000,
+            TransactionDigest::random(),
+            3,
+            0, // event_num
+            package_id,
+            "test_module",
+            "test_foo",
+        ),
+        new_test_move_event(
+            1_003_000,
+            TransactionDigest::random(),
+            4,
+            0, // event_num
+            package_id,
+            "test_module",
+            "test_bar",
+        ),
+        new_test_balance_change_event(1_004_000, 5, 0, None, None, None),
+        new_test_mutate_event(1_005_000, 6, 0, 2, object_type, Some(object_id), None),
+        new_test_deleteobj_event(
+            1_006_000,
+            TransactionDigest::random(),
+            7,
+            0, // event_num
+            Some(object_id),
+            None,
+        ),
+    ];
+    ConformanceEvents {
+        events,
+        tx_digest,
+        sender,
+        recipient,
+        object_id,
+        package_id,
+    }
+}
+
+/// Insertion is idempotent, and events are read back in order.
+pub async fn test_insert_and_read<S: EventStore>(db: &S) -> Result<(), SuiError> {
+    let ConformanceEvents { events, .. } = new_conformance_events();
+    let all: Vec<_> = events.iter().collect();
+
+    assert_eq!(db.add_events(&[]).await?, 0);
+    assert_eq!(db.earliest_available_event().await?, None);
+    assert_eq!(db.add_events(&events).await?, 8);
+    // Events with the same (seq_num, event_num) are ignored
+    assert_eq!(db.add_events(&events[..2]).await?, 0);
+    assert_eq!(db.earliest_available_event().await?, Some((1, 0).into()));
+
+    let queried = db.all_events((0, 0).into(), 20, false).await?;
+    assert_events_match(&queried, &all);
+
+    let queried = db.all_events((i64::MAX, i64::MAX).into(), 20, true).await?;
+    let reversed: Vec<_> = all.iter().rev().cloned().collect();
+    assert_events_match(&queried, &reversed);
+
+    // Limit and cursor
+    let queried = db.all_events((0, 0).into(), 3, false).await?;
+    assert_events_match(&queried, &all[..3]);
+    let queried = db.all_events((4, 0).into(), 2, false).await?;
+    assert_events_match(&queried, &all[4..6]);
+    let queried = db.all_events((4, 0).into(), 2, true).await?;
+    assert_events_match(&queried, &[all[4], all[3]]);
+
+    Ok(())
+}
+
+/// Every query filters on the right column.
+pub async fn test_query_filters<S: EventStore>(db: &S) -> Result<(), SuiError> {
+    let ConformanceEvents {
+        events,
+        tx_digest,
+        sender,
+        recipient,
+        object_id,
+        package_id,
+    } = new_conformance_events();
+    let e: Vec<_> = events.iter().collect();
+    assert_eq!(db.add_events(&events).await?, 8);
+    let start: EventID = (0, 0).into();
+
+    let queried = db.events_by_transaction(tx_digest, start.clone(), 20, false);
+    assert_events_match(&queried.await?, &[e[0], e[1]]);
+
+    let queried = db.events_by_type(EventType::MoveEvent, start.clone(), 20, false);
+    assert_events_match(&queried.await?, &[e[3], e[4]]);
+    let queried = db.events_by_type(EventType::CoinBalanceChange, start.clone(), 20, false);
+    assert_events_match(&queried.await?, &[e[5]]);
+    let queried = db.events_by_type(EventType::EpochChange, start.clone(), 20, false);
+    assert!(queried.await?.is_empty());
+
+    let module = ModuleId::new(
+        AccountAddress::from(package_id),
+        Identifier::new("test_module").unwrap(),
+    );
+    let queried = db.events_by_module_id(&module, start.clone(), 20, false);
+    assert_events_match(&queried.await?, &[e[3], e[4]]);
+
+    let move_event_name = events[4].event.move_event_name().unwrap();
+    let queried = db.events_by_move_event_struct_name(&move_event_name, start.clone(), 20, false);
+    assert_events_match(&queried.await?, &[e[4]]);
+
+    let queried = db.events_by_sender(&sender, start.clone(), 20, false);
+    assert_events_match(&queried.await?, &[e[0], e[1], e[2]]);
+
+    let queried = db.events_by_recipient(&recipient, start.clone(), 20, false);
+    assert_events_match(&queried.await?, &[e[0], e[1]]);
+
+    let queried = db.events_by_object(&object_id, start.clone(), 20, false);
+    assert_events_match(&queried.await?, &[e[0], e[1], e[6], e[7]]);
+    let queried = db.events_by_object(&object_id, (i64::MAX, i64::MAX).into(), 2, true);
+    assert_events_match(&queried.await?, &[e[7], e[6]]);
+
+    // Time range, end is exclusive
+    let queried = db.event_iterator(1_001_000, 1_004_000, start, 20, false);
+    assert_events_match(&queried.await?, &[e[2], e[3], e[4]]);
+    let queried = db.event_iterator(1_001_000, 1_004_000, (i64::MAX, i64::MAX).into(), 20, true);
+    assert_events_match(&queried.await?, &[e[4], e[3], e[2]]);
+
+    Ok(())
+}
+
+/// Pruning applies every rule of the retention policy, and moves the earliest available event.
+pub async fn test_prune_events<S: EventStore>(db: &S) -> Result<(), SuiError> {
+    let package_a = ObjectID::from_hex_literal("0xa").unwrap();
+    let package_b = ObjectID::from_hex_literal("0xb").unwrap();
+    let move_event = |timestamp, seq_num, package_id| {
+        new_test_move_event(
+            timestamp,
+            TransactionDigest::random(),
+            seq_num,
+            0, // event_num
+            package_id,
+            "test_module",
+            "test_foo",
+        )
+    };
+    let to_insert = vec![
+        move_event(1_000_000, 1, package_a),
+        new_test_balance_change_event(1_001_000, 2, 0, None, None, None),
+        move_event(1_002_000, 3, package_b),
+        move_event(1_003_000, 4, package_a),
+        move_event(1_004_000, 5, package_a),
+        move_event(1_005_000, 6, package_a),
+    ];
+    assert_eq!(db.add_events(&to_insert).await?, 6);
+    assert_eq!(db.earliest_available_event().await?, Some((1, 0).into()));
+
+    let now = 1_005_000;
+    // Nothing to prune without retention rules
+    let mut policy = EventRetentionPolicy::default();
+    assert_eq!(db.prune_events(&policy, now).await?, 0);
+
+    policy.excluded_event_types = vec![EventType::CoinBalanceChange];
+    assert_eq!(db.prune_events(&policy, now).await?, 1);
+    let events = db.events_by_type(EventType::CoinBalanceChange, (0, 0).into(), 10, false);
+    assert!(events.await?.is_empty());
+
+    policy.packages = Some(vec![package_a]);
+    assert_eq!(db.prune_events(&policy, now).await?, 1);
+    assert_eq!(db.all_events((0, 0).into(), 10, false).await?.len(), 4);
+
+    // Only events from the last 2.5 seconds are kept
+    policy.max_age = Some(Duration::from_millis(2_500));
+    assert_eq!(db.prune_events(&policy, now).await?, 1);
+    assert_eq!(db.earliest_available_event().await?, Some((4, 0).into()));
+
+    policy.max_events = Some(2);
+    assert_eq!(db.prune_events(&policy, now).await?, 1);
+    assert_eq!(db.earliest_available_event().await?, Some((5, 0).into()));
+    let events = db.all_events((0, 0).into(), 10, false).await?;
+    assert_events_match(&events, &[&to_insert[4], &to_insert[5]]);
+
+    // Pruning is idempotent
+    assert_eq!(db.prune_events(&policy, now).await?, 0);
+
+    policy.max_events = Some(0);
+    assert_eq!(db.prune_events(&policy, now).await?, 2);
+    assert_eq!(db.earliest_available_event().await?, None);
+
+    Ok(())
+}
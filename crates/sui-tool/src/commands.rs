@@ -5,6 +5,9 @@ use anyhow::Result;
 use futures::future::join_all;
 use itertools::Itertools;
 use multiaddr::Multiaddr;
+use narwhal_config::Parameters;
+use narwhal_consensus::dag_export::DagExporter;
+use narwhal_storage::NodeStorage;
 use std::cmp::min;
 use std::collections::BTreeMap;
 use std::path::PathBuf;
@@ -37,6 +40,12 @@ pub enum Verbosity {
     Verbose,
 }
 
+#[derive(Parser, Clone, ArgEnum)]
+pub enum DagFormat {
+    Json,
+    Dot,
+}
+
 #[derive(Parser)]
 #[clap(
     name = "sui-tool",
@@ -108,6 +117,34 @@ pub enum ToolCommand {
         cmd: Option<DbToolCommand>,
     },
 
+    /// Export the recent narwhal DAG from the consensus store of a stopped validator, marking
+    /// leaders, committed anchors, missing parents and the GC cutoff.
+    #[clap(name = "dump-narwhal-dag")]
+    DumpNarwhalDag {
+        /// Path of the narwhal store of an epoch, e.g. <consensus db>/epoch0
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+
+        #[clap(long = "genesis")]
+        genesis: PathBuf,
+
+        #[clap(
+            long,
+            help = "The GC depth of the consensus parameters of the validator",
+            default_value_t = 50
+        )]
+        gc_depth: u64,
+
+        #[clap(
+            long,
+            help = "Export the certificates above this round - if not specified, those above the GC round"
+        )]
+        from_round: Option<u64>,
+
+        #[clap(arg_enum, long = "format", default_value = "json", ignore_case = true)]
+        format: DagFormat,
+    },
+
     /// Pull down the batch stream for a validator(s).
     /// Note that this command currently operates sequentially, so it will block on the first
     /// validator indefinitely. Therefore you should generally use this with a --validator=
@@ -681,6 +718,30 @@ impl ToolCommand {
                     None => print_db_all_tables(path)?,
                 }
             }
+            ToolCommand::DumpNarwhalDag {
+                db_path,
+                genesis,
+                gc_depth,
+                from_round,
+                format,
+            } => {
+                let committee = Genesis::load(genesis)?.narwhal_committee().load();
+                let storage = NodeStorage::reopen(db_path);
+                let export = DagExporter::new(
+                    (**committee).clone(),
+                    storage.consensus_store,
+                    storage.certificate_store,
+                    Parameters {
+                        gc_depth,
+                        ..Parameters::default()
+                    },
+                )
+                .export(from_round)?;
+                match format {
+                    DagFormat::Json => println!("{}", serde_json::to_string_pretty(&export)?),
+                    DagFormat::Dot => print!("{}", export.to_dot()),
+                }
+            }
             ToolCommand::DumpValidators { genesis, concise } => {
                 let genesis = Genesis::load(genesis).unwrap();
                 if !concise {
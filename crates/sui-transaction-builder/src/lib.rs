@@ -24,7 +24,7 @@ use sui_types::messages::{
     CallArg, InputObjectKind, MoveCall, ObjectArg, SingleTransactionKind, TransactionData,
     TransactionKind, TransferObject,
 };
-use sui_types::move_package::MovePackage;
+use sui_types::move_package::{MovePackage, UpgradeCap};
 use sui_types::object::{Object, Owner};
 use sui_types::{coin, fp_ensure, SUI_FRAMEWORK_OBJECT_ID};
 
@@ -372,6 +372,57 @@ impl TransactionBuilder {
         ))
     }
 
+    /// Publish `compiled_modules`, granting their upgrade to `sender`.
+    pub async fn publish_upgradeable(
+        &self,
+        sender: SuiAddress,
+        compiled_modules: Vec<Vec<u8>>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
+        let gas = self.select_gas(sender, gas, gas_budget, vec![]).await?;
+        Ok(TransactionData::new_upgradeable_module(
+            sender,
+            gas,
+            compiled_modules,
+            gas_budget,
+        ))
+    }
+
+    /// Upgrade the package `upgrade_cap` grants the upgrade of to `compiled_modules`.
+    pub async fn upgrade(
+        &self,
+        sender: SuiAddress,
+        compiled_modules: Vec<Vec<u8>>,
+        upgrade_cap: ObjectID,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
+        let response = self.0.get_object(upgrade_cap).await?;
+        let object = response.object()?;
+        let cap = match object.data.try_as_move() {
+            Some(move_object) if move_object.type_ == UpgradeCap::type_().to_string() => {
+                bcs::from_bytes::<UpgradeCap>(&move_object.bcs_bytes)?
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Object [{upgrade_cap}] is not an upgrade capability."
+                ))
+            }
+        };
+        let gas = self
+            .select_gas(sender, gas, gas_budget, vec![upgrade_cap])
+            .await?;
+        Ok(TransactionData::new_upgrade(
+            sender,
+            gas,
+            compiled_modules,
+            cap.package.bytes,
+            object.reference.to_object_ref(),
+            gas_budget,
+        ))
+    }
+
     // TODO: consolidate this with Pay transactions
     pub async fn split_coin(
         &self,
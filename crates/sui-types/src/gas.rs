@@ -30,6 +30,8 @@ use sui_cost_tables::{
     units_types::GasUnit,
 };
 
+pub use sui_cost_tables::gas_profiler::GasProfiler;
+
 pub type GasUnits = GasQuantity<GasUnit>;
 pub enum GasPriceUnit {}
 pub enum SuiGasUnit {}
@@ -233,6 +235,11 @@ impl<'a> SuiGasStatus<'a> {
         !self.charge
     }
 
+    /// Record the gas charged by the Move calls of the transaction in `profiler`.
+    pub fn set_profiler(&mut self, profiler: GasProfiler) {
+        self.gas_status.set_profiler(profiler);
+    }
+
     pub fn create_move_gas_status(&mut self) -> &mut GasStatus<'a> {
         &mut self.gas_status
     }
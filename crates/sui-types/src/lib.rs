@@ -22,6 +22,7 @@ pub mod error;
 pub mod balance;
 pub mod base_types;
 pub mod batch;
+pub mod bytecode_rewriter;
 pub mod coin;
 pub mod collection_types;
 pub mod committee;
@@ -32,6 +33,7 @@ pub mod gas_coin;
 pub mod id;
 pub mod in_memory_storage;
 pub mod intent;
+pub mod light_client;
 pub mod message_envelope;
 pub mod messages;
 pub mod messages_checkpoint;
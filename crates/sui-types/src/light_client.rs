@@ -0,0 +1,227 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A light client that verifies data served by an untrusted full node.
+//!
+//! Starting from a trusted committee (usually the genesis committee), the client follows the
+//! chain of certified checkpoints one by one, adopting the committee of the next epoch whenever
+//! it sees the last checkpoint of an epoch. Once a checkpoint is verified, transaction effects
+//! and object versions can be checked against it using a [`TransactionInclusionProof`].
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::base_types::TransactionDigest;
+use crate::committee::{Committee, EpochId};
+use crate::error::{SuiError, SuiResult};
+use crate::messages::TransactionEffects;
+use crate::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber, VerifiedCheckpoint,
+};
+use crate::object::Object;
+
+#[cfg(test)]
+#[path = "unit_tests/light_client_tests.rs"]
+mod light_client_tests;
+
+/// Proof that the effects of a transaction were included in a certified checkpoint.
+///
+/// The checkpoint summary commits to the digest of its contents, and the contents commit to
+/// the digest of the effects of every transaction included in the checkpoint.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionInclusionProof {
+    pub checkpoint: CertifiedCheckpointSummary,
+    pub contents: CheckpointContents,
+    pub effects: TransactionEffects,
+}
+
+impl TransactionInclusionProof {
+    pub fn transaction_digest(&self) -> &TransactionDigest {
+        &self.effects.transaction_digest
+    }
+
+    pub fn checkpoint_sequence_number(&self) -> CheckpointSequenceNumber {
+        self.checkpoint.sequence_number()
+    }
+}
+
+pub struct LightClient {
+    /// Committees of every epoch the client has followed so far, keyed by epoch.
+    committees: BTreeMap<EpochId, Committee>,
+    /// The latest checkpoint in the chain verified by this client.
+    latest_checkpoint: Option<VerifiedCheckpoint>,
+}
+
+impl LightClient {
+    /// Create a light client that trusts the given committee, and has not followed any
+    /// checkpoint yet.
+    pub fn new(committee: Committee) -> Self {
+        Self {
+            committees: BTreeMap::from([(committee.epoch, committee)]),
+            latest_checkpoint: None,
+        }
+    }
+
+    /// The committee of the latest epoch known to this client.
+    pub fn committee(&self) -> &Committee {
+        self.committees
+            .values()
+            .next_back()
+            .expect("Light client always knows at least one committee")
+    }
+
+    pub fn committee_for_epoch(&self, epoch: EpochId) -> Option<&Committee> {
+        self.committees.get(&epoch)
+    }
+
+    pub fn latest_checkpoint(&self) -> Option<&VerifiedCheckpoint> {
+        self.latest_checkpoint.as_ref()
+    }
+
+    /// The sequence number of the next checkpoint this client expects to follow.
+    pub fn next_checkpoint_sequence_number(&self) -> CheckpointSequenceNumber {
+        self.latest_checkpoint
+            .as_ref()
+            .map(|c| c.sequence_number() + 1)
+            .unwrap_or_default()
+    }
+
+    /// Verify that a checkpoint is certified by the committee of its epoch.
+    /// The epoch must be known to this client, but the checkpoint does not need to be the next
+    /// one in the chain.
+    pub fn verify_checkpoint(
+        &self,
+        checkpoint: &CertifiedCheckpointSummary,
+        contents: Option<&CheckpointContents>,
+    ) -> SuiResult {
+        let committee = self.committee_for_epoch(checkpoint.epoch()).ok_or_else(|| {
+            SuiError::GenericAuthorityError {
+                error: format!(
+                    "Light client does not know the committee of epoch {}, latest known epoch is {}",
+                    checkpoint.epoch(),
+                    self.committee().epoch
+                ),
+            }
+        })?;
+        checkpoint.verify(committee, contents)
+    }
+
+    /// Verify the next checkpoint in the chain, and advance the client to it. If the checkpoint
+    /// is the last one of its epoch, the client adopts the committee of the next epoch.
+    pub fn sync_checkpoint(
+        &mut self,
+        checkpoint: CertifiedCheckpointSummary,
+    ) -> SuiResult<&VerifiedCheckpoint> {
+        let expected = self.next_checkpoint_sequence_number();
+        fp_ensure!(
+            checkpoint.sequence_number() == expected,
+            SuiError::GenericAuthorityError {
+                error: format!(
+                    "Light client expected checkpoint {}, received checkpoint {}",
+                    expected,
+                    checkpoint.sequence_number()
+                )
+            }
+        );
+        let previous_digest = self.latest_checkpoint.as_ref().map(|c| c.digest());
+        fp_ensure!(
+            checkpoint.previous_digest() == previous_digest,
+            SuiError::from("Checkpoint does not extend the chain followed by the light client")
+        );
+        let committee = self.committee();
+        fp_ensure!(
+            checkpoint.epoch() == committee.epoch,
+            SuiError::GenericAuthorityError {
+                error: format!(
+                    "Checkpoint {} is from epoch {}, but the light client is in epoch {}",
+                    checkpoint.sequence_number(),
+                    checkpoint.epoch(),
+                    committee.epoch
+                )
+            }
+        );
+
+        let checkpoint = VerifiedCheckpoint::new(checkpoint, committee).map_err(|(_, e)| e)?;
+        if let Some(next_epoch_committee) = checkpoint.next_epoch_committee() {
+            let next_epoch = checkpoint.epoch() + 1;
+            let committee =
+                Committee::new(next_epoch, next_epoch_committee.iter().cloned().collect())?;
+            self.committees.insert(next_epoch, committee);
+        }
+        Ok(self.latest_checkpoint.insert(checkpoint))
+    }
+
+    /// Verify that the effects in the proof were included in a checkpoint certified by the
+    /// committee of its epoch.
+    pub fn verify_transaction_inclusion(&self, proof: &TransactionInclusionProof) -> SuiResult {
+        self.verify_checkpoint(&proof.checkpoint, Some(&proof.contents))?;
+        let execution_digests = proof.effects.execution_digests();
+        fp_ensure!(
+            proof.contents.iter().any(|d| d == &execution_digests),
+            SuiError::GenericAuthorityError {
+                error: format!(
+                    "Effects of transaction {:?} are not included in checkpoint {}",
+                    proof.transaction_digest(),
+                    proof.checkpoint_sequence_number()
+                )
+            }
+        );
+        Ok(())
+    }
+
+    /// Verify that the proof is for transaction `digest`, and that the effects of this
+    /// transaction were included in a certified checkpoint.
+    pub fn verify_transaction(
+        &self,
+        digest: &TransactionDigest,
+        proof: &TransactionInclusionProof,
+    ) -> SuiResult {
+        fp_ensure!(
+            proof.transaction_digest() == digest,
+            SuiError::GenericAuthorityError {
+                error: format!(
+                    "Requested transaction {:?}, but the proof is for transaction {:?}",
+                    digest,
+                    proof.transaction_digest()
+                )
+            }
+        );
+        self.verify_transaction_inclusion(proof)
+    }
+
+    /// Verify that the given object version was written by the transaction in the proof, and
+    /// that the effects of this transaction were included in a certified checkpoint.
+    pub fn verify_object_inclusion(
+        &self,
+        object: &Object,
+        proof: &TransactionInclusionProof,
+    ) -> SuiResult {
+        fp_ensure!(
+            &object.previous_transaction == proof.transaction_digest(),
+            SuiError::GenericAuthorityError {
+                error: format!(
+                    "Object {} was last written by transaction {:?}, but the proof is for transaction {:?}",
+                    object.id(),
+                    object.previous_transaction,
+                    proof.transaction_digest()
+                )
+            }
+        );
+        let object_ref = object.compute_object_reference();
+        fp_ensure!(
+            proof
+                .effects
+                .all_mutated()
+                .any(|(written, _, _)| written == &object_ref),
+            SuiError::GenericAuthorityError {
+                error: format!(
+                    "Object {:?} is not written by transaction {:?}",
+                    object_ref,
+                    proof.transaction_digest()
+                )
+            }
+        );
+        self.verify_transaction_inclusion(proof)
+    }
+}
@@ -92,6 +92,22 @@ pub struct MoveModulePublish {
     pub modules: Vec<Vec<u8>>,
 }
 
+/// Publish `modules` as the next version of `package`.
+/// The upgraded package gets a fresh ID, and must preserve the layout of every struct and the
+/// signature of every public function of `package`.
+/// Its modules are linked at the original ID of `package`, so objects created with the previous
+/// versions can be used with its functions.
+#[serde_as]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct MoveModuleUpgrade {
+    #[serde_as(as = "Vec<Bytes>")]
+    pub modules: Vec<Vec<u8>>,
+    /// The latest version of the package being upgraded
+    pub package: ObjectID,
+    /// The `sui::package::UpgradeCap` of `package`
+    pub upgrade_cap: ObjectRef,
+}
+
 // TODO: we can deprecate TransferSui when its callsites on RPC & SDK are
 // fully replaced by PaySui and PayAllSui.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
@@ -187,9 +203,30 @@ pub enum SingleTransactionKind {
     /// A validator will not sign a transaction of this kind from outside. It only
     /// signs internally during epoch changes.
     ChangeEpoch(ChangeEpoch),
+    /// Upgrade a published Move package
+    Upgrade(MoveModuleUpgrade),
+    /// Publish a Move package that can be upgraded: the publisher receives a
+    /// `sui::package::UpgradeCap` granting its upgrade. Packages published with `Publish` can
+    /// never be upgraded.
+    PublishUpgradeable(MoveModulePublish),
     // .. more transaction types go here
 }
 
+/// Deserialize the modules of a package, skipping the malformed ones.
+fn deserialize_modules_ignoring_errors(modules: &[Vec<u8>]) -> Vec<CompiledModule> {
+    modules
+        .iter()
+        .filter_map(|bytes| match CompiledModule::deserialize(bytes) {
+            Ok(m) => Some(m),
+            // We will ignore this error here and simply let latter execution
+            // to discover this error again and fail the transaction.
+            // It's preferable to let transaction fail and charge gas when
+            // malformed package is provided.
+            Err(_) => None,
+        })
+        .collect()
+}
+
 impl SingleTransactionKind {
     pub fn contains_shared_object(&self) -> bool {
         self.shared_input_objects().next().is_some()
@@ -290,24 +327,31 @@ impl SingleTransactionKind {
                 .flatten()
                 .chain([InputObjectKind::MovePackage(package.0)])
                 .collect(),
-            Self::Publish(MoveModulePublish { modules }) => {
+            Self::Publish(MoveModulePublish { modules })
+            | Self::PublishUpgradeable(MoveModulePublish { modules }) => {
                 // For module publishing, all the dependent packages are implicit input objects
                 // because they must all be on-chain in order for the package to publish.
                 // All authorities must have the same view of those dependencies in order
                 // to achieve consistent publish results.
-                let compiled_modules = modules
-                    .iter()
-                    .filter_map(|bytes| match CompiledModule::deserialize(bytes) {
-                        Ok(m) => Some(m),
-                        // We will ignore this error here and simply let latter execution
-                        // to discover this error again and fail the transaction.
-                        // It's preferable to let transaction fail and charge gas when
-                        // malformed package is provided.
-                        Err(_) => None,
-                    })
-                    .collect::<Vec<_>>();
+                let compiled_modules = deserialize_modules_ignoring_errors(modules);
                 Transaction::input_objects_in_compiled_modules(&compiled_modules)
             }
+            Self::Upgrade(MoveModuleUpgrade {
+                modules,
+                package,
+                upgrade_cap,
+            }) => {
+                // Like for publishing, the dependent packages are implicit input objects.
+                // The upgraded package is read to check the compatibility of the upgrade,
+                // and the upgrade capability is updated to point to the new package.
+                let compiled_modules = deserialize_modules_ignoring_errors(modules);
+                let mut inputs = Transaction::input_objects_in_compiled_modules(&compiled_modules);
+                if !inputs.contains(&InputObjectKind::MovePackage(*package)) {
+                    inputs.push(InputObjectKind::MovePackage(*package));
+                }
+                inputs.push(InputObjectKind::ImmOrOwnedMoveObject(*upgrade_cap));
+                inputs
+            }
             Self::TransferSui(_) => {
                 vec![]
             }
@@ -414,6 +458,17 @@ impl Display for SingleTransactionKind {
             Self::Publish(_p) => {
                 writeln!(writer, "Transaction Kind : Publish")?;
             }
+            Self::PublishUpgradeable(_p) => {
+                writeln!(writer, "Transaction Kind : Publish Upgradeable")?;
+            }
+            Self::Upgrade(u) => {
+                writeln!(writer, "Transaction Kind : Upgrade")?;
+                writeln!(writer, "Package ID : {}", u.package.to_hex_literal())?;
+                let (object_id, seq, digest) = u.upgrade_cap;
+                writeln!(writer, "Upgrade Capability : {}", &object_id)?;
+                writeln!(writer, "Sequence Number : {:?}", seq)?;
+                writeln!(writer, "Object Digest : {}", Hex::encode(digest.0))?;
+            }
             Self::Call(c) => {
                 writeln!(writer, "Transaction Kind : Call")?;
                 writeln!(writer, "Package ID : {}", c.package.0.to_hex_literal())?;
@@ -529,7 +584,9 @@ impl TransactionKind {
                     | SingleTransactionKind::PaySui(_)
                     | SingleTransactionKind::PayAllSui(_)
                     | SingleTransactionKind::ChangeEpoch(_)
-                    | SingleTransactionKind::Publish(_) => false,
+                    | SingleTransactionKind::Publish(_)
+                    | SingleTransactionKind::PublishUpgradeable(_)
+                    | SingleTransactionKind::Upgrade(_) => false,
                 });
                 fp_ensure!(
                     valid,
@@ -544,6 +601,8 @@ impl TransactionKind {
                 | SingleTransactionKind::PayAllSui(_)
                 | SingleTransactionKind::Call(_)
                 | SingleTransactionKind::Publish(_)
+                | SingleTransactionKind::PublishUpgradeable(_)
+                | SingleTransactionKind::Upgrade(_)
                 | SingleTransactionKind::TransferObject(_)
                 | SingleTransactionKind::TransferSui(_)
                 | SingleTransactionKind::ChangeEpoch(_) => (),
@@ -720,6 +779,35 @@ impl TransactionData {
         Self::new(kind, sender, gas_payment, gas_budget)
     }
 
+    /// Publish `modules`, granting their upgrade to `sender`.
+    pub fn new_upgradeable_module(
+        sender: SuiAddress,
+        gas_payment: ObjectRef,
+        modules: Vec<Vec<u8>>,
+        gas_budget: u64,
+    ) -> Self {
+        let kind = TransactionKind::Single(SingleTransactionKind::PublishUpgradeable(
+            MoveModulePublish { modules },
+        ));
+        Self::new(kind, sender, gas_payment, gas_budget)
+    }
+
+    pub fn new_upgrade(
+        sender: SuiAddress,
+        gas_payment: ObjectRef,
+        modules: Vec<Vec<u8>>,
+        package: ObjectID,
+        upgrade_cap: ObjectRef,
+        gas_budget: u64,
+    ) -> Self {
+        let kind = TransactionKind::Single(SingleTransactionKind::Upgrade(MoveModuleUpgrade {
+            modules,
+            package,
+            upgrade_cap,
+        }));
+        Self::new(kind, sender, gas_payment, gas_budget)
+    }
+
     /// Returns the transaction kind as a &str (variant name, no fields)
     pub fn kind_as_str(&self) -> &'static str {
         self.kind.variant_name()
@@ -782,6 +870,8 @@ impl TransactionData {
                 SingleTransactionKind::Pay(_)
                 | SingleTransactionKind::Call(_)
                 | SingleTransactionKind::Publish(_)
+                | SingleTransactionKind::PublishUpgradeable(_)
+                | SingleTransactionKind::Upgrade(_)
                 | SingleTransactionKind::TransferObject(_)
                 | SingleTransactionKind::TransferSui(_)
                 | SingleTransactionKind::ChangeEpoch(_) => (),
@@ -1295,6 +1385,12 @@ pub enum ExecutionFailureStatus {
     MoveAbort(ModuleId, u64), // TODO func def + offset?
     VMVerificationOrDeserializationError,
     VMInvariantViolation,
+
+    //
+    // MoveUpgrade errors, after the others to keep their serialized index
+    //
+    PackageUpgradeErrorInvalidCapability,
+    PackageUpgradeErrorIncompatible,
 }
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize, Hash)]
@@ -1464,6 +1560,16 @@ impl Display for ExecutionFailureStatus {
                 "Sui Move Bytecode Verification Error. \
                 Please run the Sui Move Verifier for more information."
             ),
+            ExecutionFailureStatus::PackageUpgradeErrorInvalidCapability => write!(
+                f,
+                "Package Upgrade Error, Invalid Capability. \
+                The upgrade capability does not grant the upgrade of the latest version of the package."
+            ),
+            ExecutionFailureStatus::PackageUpgradeErrorIncompatible => write!(
+                f,
+                "Package Upgrade Error, Incompatible Upgrade. \
+                The upgrade must preserve the structs and public functions of the package."
+            ),
             ExecutionFailureStatus::MovePrimitiveRuntimeError => write!(
                 f,
                 "Move Primitive Runtime Error. \
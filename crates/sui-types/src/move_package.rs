@@ -3,13 +3,17 @@
 
 use crate::{
     base_types::ObjectID,
-    error::{SuiError, SuiResult},
+    error::{ExecutionError, ExecutionErrorKind, SuiError, SuiResult},
+    id::{ID, UID},
+    SUI_FRAMEWORK_ADDRESS,
 };
 use move_binary_format::access::ModuleAccess;
 use move_binary_format::binary_views::BinaryIndexedView;
 use move_binary_format::file_format::CompiledModule;
 use move_binary_format::normalized;
-use move_core_types::identifier::Identifier;
+use move_core_types::ident_str;
+use move_core_types::identifier::{IdentStr, Identifier};
+use move_core_types::language_storage::StructTag;
 use move_disassembler::disassembler::Disassembler;
 use move_ir_types::location::Spanned;
 use serde::{Deserialize, Serialize};
@@ -23,6 +27,12 @@ use std::collections::BTreeMap;
 // #[path = "unit_tests/move_package.rs"]
 // mod base_types_tests;
 
+pub const PACKAGE_MODULE_NAME: &IdentStr = ident_str!("package");
+pub const UPGRADE_CAP_STRUCT_NAME: &IdentStr = ident_str!("UpgradeCap");
+
+/// The version of a package published from scratch, as opposed to an upgrade.
+pub const INITIAL_PACKAGE_VERSION: u64 = 1;
+
 // serde_bytes::ByteBuf is an analog of Vec<u8> with built-in fast serialization.
 #[serde_as]
 #[derive(Eq, PartialEq, Debug, Clone, Deserialize, Serialize, Hash)]
@@ -31,6 +41,22 @@ pub struct MovePackage {
     // TODO use session cache
     #[serde_as(as = "BTreeMap<_, Bytes>")]
     module_map: BTreeMap<String, Vec<u8>>,
+    /// Where the package sits in its upgrade lineage, `None` if it is not an upgrade.
+    /// Not part of the serialized package, so that packages published before upgrades existed
+    /// keep their layout: upgraded packages are stored as a separate variant of `object::Data`.
+    #[serde(skip)]
+    linkage: Option<PackageLinkage>,
+}
+
+/// The upgrade lineage of an upgraded package
+#[derive(Eq, PartialEq, Debug, Clone, Deserialize, Serialize, Hash)]
+pub struct PackageLinkage {
+    /// Version of the package in its upgrade lineage, above `INITIAL_PACKAGE_VERSION`
+    pub version: u64,
+    /// The first package of the upgrade lineage
+    pub original_id: ObjectID,
+    /// The package this package upgrades
+    pub previous_id: ObjectID,
 }
 
 impl MovePackage {
@@ -38,13 +64,60 @@ impl MovePackage {
         Self {
             id,
             module_map: module_map.clone(),
+            linkage: None,
+        }
+    }
+
+    /// Create the package `id` as the next version of `previous` in its upgrade lineage
+    pub fn new_upgrade(
+        id: ObjectID,
+        previous: &MovePackage,
+        module_map: &BTreeMap<String, Vec<u8>>,
+    ) -> Self {
+        Self {
+            id,
+            module_map: module_map.clone(),
+            linkage: Some(PackageLinkage {
+                version: previous.version() + 1,
+                original_id: previous.original_id(),
+                previous_id: previous.id,
+            }),
         }
     }
 
+    pub(crate) fn with_linkage(mut self, linkage: PackageLinkage) -> Self {
+        self.linkage = Some(linkage);
+        self
+    }
+
     pub fn id(&self) -> ObjectID {
         self.id
     }
 
+    pub fn version(&self) -> u64 {
+        self.linkage
+            .as_ref()
+            .map_or(INITIAL_PACKAGE_VERSION, |linkage| linkage.version)
+    }
+
+    /// The first package of the upgrade lineage, `id` itself if the package is not an upgrade.
+    /// The modules of every version of the package are linked at this ID, so that the types they
+    /// define are the same across versions.
+    pub fn original_id(&self) -> ObjectID {
+        self.linkage
+            .as_ref()
+            .map_or(self.id, |linkage| linkage.original_id)
+    }
+
+    /// The package this package upgrades, if any
+    pub fn previous_id(&self) -> Option<ObjectID> {
+        self.linkage.as_ref().map(|linkage| linkage.previous_id)
+    }
+
+    pub fn linkage(&self) -> Option<&PackageLinkage> {
+        self.linkage.as_ref()
+    }
+
     pub fn serialized_module_map(&self) -> &BTreeMap<String, Vec<u8>> {
         &self.module_map
     }
@@ -114,6 +187,21 @@ where
     Ok(normalized_modules)
 }
 
+/// Serialize `modules` into a module map, keyed by module name
+pub fn serialize_modules<I>(modules: I) -> BTreeMap<String, Vec<u8>>
+where
+    I: IntoIterator<Item = CompiledModule>,
+{
+    modules
+        .into_iter()
+        .map(|module| {
+            let mut bytes = Vec::new();
+            module.serialize(&mut bytes).unwrap();
+            (module.self_id().name().to_string(), bytes)
+        })
+        .collect()
+}
+
 impl FromIterator<CompiledModule> for MovePackage {
     fn from_iter<T: IntoIterator<Item = CompiledModule>>(iter: T) -> Self {
         let mut iter = iter.into_iter().peekable();
@@ -125,15 +213,64 @@ impl FromIterator<CompiledModule> for MovePackage {
                 .address(),
         );
 
-        Self::new(
-            id,
-            &iter
-                .map(|module| {
-                    let mut bytes = Vec::new();
-                    module.serialize(&mut bytes).unwrap();
-                    (module.self_id().name().to_string(), bytes)
-                })
-                .collect(),
-        )
+        Self::new(id, &serialize_modules(iter))
+    }
+}
+
+// Rust version of the Move sui::package::UpgradeCap type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct UpgradeCap {
+    pub id: UID,
+    pub package: ID,
+    pub version: u64,
+}
+
+impl UpgradeCap {
+    pub fn new(id: ObjectID, package: ObjectID, version: u64) -> Self {
+        Self {
+            id: UID::new(id),
+            package: ID { bytes: package },
+            version,
+        }
+    }
+
+    pub fn type_() -> StructTag {
+        StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            module: PACKAGE_MODULE_NAME.to_owned(),
+            name: UPGRADE_CAP_STRUCT_NAME.to_owned(),
+            type_params: Vec::new(),
+        }
+    }
+
+    /// Is this other StructTag representing an UpgradeCap?
+    pub fn is_upgrade_cap(other: &StructTag) -> bool {
+        other == &Self::type_()
+    }
+
+    /// Create an UpgradeCap from BCS bytes
+    pub fn from_bcs_bytes(content: &[u8]) -> Result<Self, ExecutionError> {
+        bcs::from_bytes(content).map_err(|err| {
+            ExecutionError::new_with_source(
+                ExecutionErrorKind::PackageUpgradeErrorInvalidCapability,
+                format!("Unable to deserialize upgrade capability: {:?}", err),
+            )
+        })
+    }
+
+    pub fn id(&self) -> &ObjectID {
+        self.id.object_id()
+    }
+
+    /// Point the capability at `package`, the new latest version of the lineage
+    pub fn upgrade_to(&mut self, package: &MovePackage) {
+        self.package = ID {
+            bytes: package.id(),
+        };
+        self.version = package.version();
+    }
+
+    pub fn to_bcs_bytes(&self) -> Vec<u8> {
+        bcs::to_bytes(&self).unwrap()
     }
 }
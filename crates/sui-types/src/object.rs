@@ -5,6 +5,7 @@ use std::convert::TryFrom;
 use std::fmt::{Debug, Display, Formatter};
 use std::mem::size_of;
 
+use move_binary_format::access::ModuleAccess;
 use move_binary_format::CompiledModule;
 use move_bytecode_utils::layout::TypeLayoutBuilder;
 use move_bytecode_utils::module_cache::GetModule;
@@ -20,7 +21,7 @@ use crate::crypto::sha3_hash;
 use crate::error::{ExecutionError, ExecutionErrorKind};
 use crate::error::{SuiError, SuiResult};
 use crate::messages::InputObjectKind;
-use crate::move_package::MovePackage;
+use crate::move_package::{serialize_modules, MovePackage, PackageLinkage, UpgradeCap};
 use crate::{
     base_types::{
         ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest,
@@ -92,6 +93,11 @@ impl MoveObject {
         unsafe { Self::new_from_execution(coin_type, true, version, contents) }
     }
 
+    pub fn new_upgrade_cap(version: SequenceNumber, contents: Vec<u8>) -> Self {
+        // upgrade capabilities have store, and thus public transfer
+        unsafe { Self::new_from_execution(UpgradeCap::type_(), true, version, contents) }
+    }
+
     pub fn has_public_transfer(&self) -> bool {
         self.has_public_transfer
     }
@@ -215,7 +221,7 @@ impl MoveObject {
     }
 }
 
-#[derive(Eq, PartialEq, Debug, Clone, Deserialize, Serialize, Hash)]
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
 #[allow(clippy::large_enum_variant)]
 pub enum Data {
     /// An object whose governing logic lives in a published Move module
@@ -225,6 +231,55 @@ pub enum Data {
     // ... Sui "native" types go here
 }
 
+/// The serialized form of `Data`. Upgraded packages are serialized as their own variant, with
+/// their upgrade lineage, so that the packages and objects stored before package upgrades
+/// existed keep their layout and digest.
+#[derive(Serialize)]
+#[serde(rename = "Data")]
+enum SerializedDataRef<'a> {
+    Move(&'a MoveObject),
+    Package(&'a MovePackage),
+    UpgradedPackage(&'a MovePackage, &'a PackageLinkage),
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Data")]
+#[allow(clippy::large_enum_variant)]
+enum SerializedData {
+    Move(MoveObject),
+    Package(MovePackage),
+    UpgradedPackage(MovePackage, PackageLinkage),
+}
+
+impl Serialize for Data {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Data::Move(m) => SerializedDataRef::Move(m),
+            Data::Package(p) => match p.linkage() {
+                Some(linkage) => SerializedDataRef::UpgradedPackage(p, linkage),
+                None => SerializedDataRef::Package(p),
+            },
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Data {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(match SerializedData::deserialize(deserializer)? {
+            SerializedData::Move(m) => Data::Move(m),
+            SerializedData::Package(p) => Data::Package(p),
+            SerializedData::UpgradedPackage(p, linkage) => Data::Package(p.with_linkage(linkage)),
+        })
+    }
+}
+
 impl Data {
     pub fn try_as_move(&self) -> Option<&MoveObject> {
         use Data::*;
@@ -378,6 +433,26 @@ impl Object {
         }
     }
 
+    /// Create the package `id` upgrading `previous`. The `modules` are linked at the original ID
+    /// of `previous`, like the modules of every version of the package.
+    pub fn new_upgraded_package(
+        id: ObjectID,
+        modules: Vec<CompiledModule>,
+        previous: &MovePackage,
+        previous_transaction: TransactionDigest,
+    ) -> Self {
+        Object {
+            data: Data::Package(MovePackage::new_upgrade(
+                id,
+                previous,
+                &serialize_modules(modules),
+            )),
+            owner: Owner::Immutable,
+            previous_transaction,
+            storage_rebate: 0,
+        }
+    }
+
     pub fn is_immutable(&self) -> bool {
         self.owner.is_immutable()
     }
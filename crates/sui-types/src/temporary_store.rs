@@ -22,6 +22,7 @@ use crate::{
     fp_bail, gas,
     gas::{GasCostSummary, SuiGasStatus},
     messages::{ExecutionStatus, InputObjects, TransactionEffects},
+    move_package::MovePackage,
     object::Owner,
     object::{Data, Object},
     storage::{
@@ -676,6 +677,33 @@ impl<S> TemporaryStore<S> {
             .or_else(|| self.input_objects.get(id))
     }
 
+    /// Returns the ID of the package the modules at `original_id` are loaded from: the latest
+    /// version of that package among the objects of the transaction, or the package `original_id`
+    /// itself if the transaction has no other version of it.
+    pub fn linked_package_id(&self, original_id: &ObjectID) -> ObjectID {
+        self.packages()
+            .filter(|package| package.original_id() == *original_id)
+            .max_by_key(|package| package.version())
+            .map_or(*original_id, |package| package.id())
+    }
+
+    /// Whether the transaction loads the modules of an upgraded package. All the versions of a
+    /// package share the module IDs of its first version, so their code must not be cached in a
+    /// VM shared with the other transactions.
+    pub fn links_upgraded_packages(&self) -> bool {
+        self.packages().any(|package| package.linkage().is_some())
+    }
+
+    fn packages(&self) -> impl Iterator<Item = &MovePackage> {
+        self.input_objects
+            .values()
+            .chain(self.written.values().map(|(_, obj, _)| obj))
+            .filter_map(|obj| match &obj.data {
+                Data::Package(package) => Some(package),
+                _ => None,
+            })
+    }
+
     pub fn apply_object_changes(&mut self, changes: BTreeMap<ObjectID, ObjectChange>) {
         for (id, change) in changes {
             match change {
@@ -724,7 +752,7 @@ impl<S: ChildObjectResolver> Storage for TemporaryStore<S> {
 impl<S: BackingPackageStore> ModuleResolver for TemporaryStore<S> {
     type Error = SuiError;
     fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
-        let package_id = &ObjectID::from(*module_id.address());
+        let package_id = &self.linked_package_id(&ObjectID::from(*module_id.address()));
         let package_obj;
         let package = match self.read_object(package_id) {
             Some(object) => object,
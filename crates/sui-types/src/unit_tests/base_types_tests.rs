@@ -16,7 +16,8 @@ use crate::crypto::{
 use crate::{
     crypto::{get_key_pair, Signature},
     gas_coin::GasCoin,
-    object::Object,
+    move_package::MovePackage,
+    object::{Data, Object},
     SUI_FRAMEWORK_ADDRESS,
 };
 
@@ -339,6 +340,37 @@ fn test_move_package_size_for_gas_metering() {
     assert_eq!(size + 2, serialized.len());
 }
 
+#[test]
+fn test_move_package_layout() {
+    let module = file_format::empty_module();
+    let package = Object::new_package(vec![module.clone()], TransactionDigest::genesis());
+    let package = package.data.try_as_package().unwrap();
+
+    // packages that are not upgrades keep the layout they had before upgrades existed
+    let legacy = bcs::to_bytes(&(1u8, package.id(), package.serialized_module_map())).unwrap();
+    assert_eq!(
+        bcs::to_bytes(&Data::Package(package.clone())).unwrap(),
+        legacy
+    );
+    let decoded: Data = bcs::from_bytes(&legacy).unwrap();
+    let decoded = decoded.try_as_package().unwrap();
+    assert_eq!(decoded, package);
+    assert_eq!(decoded.version(), 1);
+    assert_eq!(decoded.original_id(), package.id());
+    assert_eq!(decoded.previous_id(), None);
+
+    // upgraded packages round-trip with their lineage
+    let upgrade =
+        MovePackage::new_upgrade(ObjectID::random(), package, package.serialized_module_map());
+    let data = Data::Package(upgrade.clone());
+    let decoded: Data = bcs::from_bytes(&bcs::to_bytes(&data).unwrap()).unwrap();
+    let decoded = decoded.try_as_package().unwrap();
+    assert_eq!(decoded, &upgrade);
+    assert_eq!(decoded.version(), 2);
+    assert_eq!(decoded.original_id(), package.id());
+    assert_eq!(decoded.previous_id(), Some(package.id()));
+}
+
 // A sample address in hex generated by the current address derivation algorithm.
 #[cfg(test)]
 const SAMPLE_ADDRESS: &str = "32866f0109fa1ba911392dcd2d4260f1d8243133";
@@ -0,0 +1,200 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use fastcrypto::traits::KeyPair;
+use rand::prelude::StdRng;
+use rand::SeedableRng;
+
+use super::*;
+use crate::base_types::{ExecutionDigests, ObjectID, SuiAddress};
+use crate::crypto::AuthorityKeyPair;
+use crate::gas::GasCostSummary;
+use crate::messages_checkpoint::{CheckpointDigest, SignedCheckpointSummary};
+use crate::object::Owner;
+use crate::utils::make_committee_key;
+
+fn certify(
+    keys: &[AuthorityKeyPair],
+    committee: &Committee,
+    sequence_number: CheckpointSequenceNumber,
+    contents: &CheckpointContents,
+    previous_digest: Option<CheckpointDigest>,
+    next_epoch_committee: Option<Committee>,
+) -> CertifiedCheckpointSummary {
+    let signed_checkpoints = keys
+        .iter()
+        .map(|k| {
+            SignedCheckpointSummary::new(
+                committee.epoch,
+                sequence_number,
+                k.public().into(),
+                k,
+                contents,
+                previous_digest,
+                GasCostSummary::default(),
+                next_epoch_committee.clone(),
+            )
+        })
+        .collect();
+    CertifiedCheckpointSummary::aggregate(signed_checkpoints, committee).unwrap()
+}
+
+fn random_contents() -> CheckpointContents {
+    CheckpointContents::new_with_causally_ordered_transactions(
+        [ExecutionDigests::random()].into_iter(),
+    )
+}
+
+#[test]
+fn test_follow_checkpoints() {
+    let mut rng = StdRng::from_seed([0; 32]);
+    let (keys, committee) = make_committee_key(&mut rng);
+    let mut client = LightClient::new(committee.clone());
+
+    let c0 = certify(&keys, &committee, 0, &random_contents(), None, None);
+    let c1 = certify(
+        &keys,
+        &committee,
+        1,
+        &random_contents(),
+        Some(c0.digest()),
+        None,
+    );
+
+    // Checkpoints must be followed in order.
+    assert!(client.sync_checkpoint(c1.clone()).is_err());
+    client.sync_checkpoint(c0).unwrap();
+    assert_eq!(client.next_checkpoint_sequence_number(), 1);
+
+    // A checkpoint that does not extend the followed chain is rejected.
+    let forked = certify(&keys, &committee, 1, &random_contents(), None, None);
+    assert!(client.sync_checkpoint(forked).is_err());
+
+    client.sync_checkpoint(c1).unwrap();
+    assert_eq!(client.latest_checkpoint().unwrap().sequence_number(), 1);
+
+    // A checkpoint not certified by the committee is rejected.
+    let (other_keys, other_committee) = make_committee_key(&mut rng);
+    let c2 = certify(
+        &other_keys,
+        &other_committee,
+        2,
+        &random_contents(),
+        client.latest_checkpoint().map(|c| c.digest()),
+        None,
+    );
+    assert!(client.sync_checkpoint(c2).is_err());
+}
+
+#[test]
+fn test_follow_epoch_change() {
+    let mut rng = StdRng::from_seed([0; 32]);
+    let (keys, committee) = make_committee_key(&mut rng);
+    let (next_keys, next_committee) = make_committee_key(&mut rng);
+    let next_committee =
+        Committee::new(1, next_committee.voting_rights.into_iter().collect()).unwrap();
+    let mut client = LightClient::new(committee.clone());
+
+    let c0 = certify(
+        &keys,
+        &committee,
+        0,
+        &random_contents(),
+        None,
+        Some(next_committee.clone()),
+    );
+    client.sync_checkpoint(c0.clone()).unwrap();
+    assert_eq!(client.committee().epoch, 1);
+
+    // The old committee can no longer certify checkpoints of the new epoch.
+    let stale = certify(
+        &keys,
+        &committee,
+        1,
+        &random_contents(),
+        Some(c0.digest()),
+        None,
+    );
+    assert!(client.sync_checkpoint(stale).is_err());
+
+    let c1 = certify(
+        &next_keys,
+        &next_committee,
+        1,
+        &random_contents(),
+        Some(c0.digest()),
+        None,
+    );
+    client.sync_checkpoint(c1).unwrap();
+    assert_eq!(client.latest_checkpoint().unwrap().epoch(), 1);
+}
+
+#[test]
+fn test_verify_inclusion() {
+    let mut rng = StdRng::from_seed([0; 32]);
+    let (keys, committee) = make_committee_key(&mut rng);
+    let client = LightClient::new(committee.clone());
+
+    let transaction_digest = TransactionDigest::random();
+    let mut object = Object::with_id_owner_for_testing(ObjectID::random(), SuiAddress::default());
+    object.previous_transaction = transaction_digest;
+    let effects = TransactionEffects {
+        transaction_digest,
+        created: vec![(object.compute_object_reference(), object.owner)],
+        ..Default::default()
+    };
+
+    let contents = CheckpointContents::new_with_causally_ordered_transactions(
+        [ExecutionDigests::random(), effects.execution_digests()].into_iter(),
+    );
+    let proof = TransactionInclusionProof {
+        checkpoint: certify(&keys, &committee, 0, &contents, None, None),
+        contents,
+        effects,
+    };
+    client.verify_transaction_inclusion(&proof).unwrap();
+    client
+        .verify_transaction(&transaction_digest, &proof)
+        .unwrap();
+    client.verify_object_inclusion(&object, &proof).unwrap();
+
+    // A valid proof of another transaction is rejected.
+    let other_effects = TransactionEffects {
+        transaction_digest: TransactionDigest::random(),
+        ..Default::default()
+    };
+    let other_contents = CheckpointContents::new_with_causally_ordered_transactions(
+        [other_effects.execution_digests()].into_iter(),
+    );
+    let other_proof = TransactionInclusionProof {
+        checkpoint: certify(&keys, &committee, 0, &other_contents, None, None),
+        contents: other_contents,
+        effects: other_effects,
+    };
+    client.verify_transaction_inclusion(&other_proof).unwrap();
+    assert!(client
+        .verify_transaction(&transaction_digest, &other_proof)
+        .is_err());
+    assert!(client
+        .verify_object_inclusion(&object, &other_proof)
+        .is_err());
+
+    // Effects that are not part of the checkpoint contents are rejected.
+    let mut bad_proof = proof.clone();
+    bad_proof.effects.gas_used.computation_cost += 1;
+    assert!(client.verify_transaction_inclusion(&bad_proof).is_err());
+
+    // Contents that do not match the checkpoint are rejected.
+    let mut bad_proof = proof.clone();
+    bad_proof.contents = CheckpointContents::new_with_causally_ordered_transactions(
+        [proof.effects.execution_digests()].into_iter(),
+    );
+    assert!(client.verify_transaction_inclusion(&bad_proof).is_err());
+
+    // Object versions not written by the proven transaction are rejected.
+    let other = Object::with_id_owner_for_testing(ObjectID::random(), SuiAddress::default());
+    assert!(client.verify_object_inclusion(&other, &proof).is_err());
+    let mut mutated = object;
+    mutated.owner = Owner::Immutable;
+    assert!(client.verify_object_inclusion(&mutated, &proof).is_err());
+}
@@ -4,7 +4,10 @@
 use move_binary_format::{
     access::ModuleAccess,
     binary_views::BinaryIndexedView,
-    file_format::{AbilitySet, Bytecode, FunctionDefinition, SignatureToken, Visibility},
+    file_format::{
+        AbilitySet, Bytecode, CodeOffset, FunctionDefinition, FunctionDefinitionIndex,
+        SignatureToken, Visibility,
+    },
     CompiledModule,
 };
 use move_core_types::{account_address::AccountAddress, identifier::IdentStr};
@@ -19,7 +22,9 @@ use sui_types::{
     MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS,
 };
 
-use crate::{format_signature_token, resolve_struct, verification_failure, INIT_FN_NAME};
+use crate::{
+    format_signature_token, resolve_struct, verification_failure, BytecodeLocation, INIT_FN_NAME,
+};
 
 /// Checks valid rules rules for entry points, both for module initialization and transactions
 ///
@@ -38,13 +43,17 @@ use crate::{format_signature_token, resolve_struct, verification_failure, INIT_F
 ///   - The transaction context parameter must be the last parameter
 /// - The function cannot have any return values
 pub fn verify_module(module: &CompiledModule) -> Result<(), ExecutionError> {
-    for func_def in &module.function_defs {
-        verify_init_not_called(module, func_def).map_err(verification_failure)?;
+    for (idx, func_def) in module.function_defs.iter().enumerate() {
+        let idx = FunctionDefinitionIndex(idx as u16);
+        verify_init_not_called(module, func_def)
+            .map_err(|(offset, e)| verification_failure(BytecodeLocation::Code(idx, offset), e))?;
 
+        let location = BytecodeLocation::Function(idx);
         let handle = module.function_handle_at(func_def.function);
         let name = module.identifier_at(handle.name);
         if name == INIT_FN_NAME {
-            verify_init_function(module, func_def).map_err(verification_failure)?;
+            verify_init_function(module, func_def)
+                .map_err(|e| verification_failure(location, e))?;
             continue;
         }
 
@@ -54,15 +63,17 @@ pub fn verify_module(module: &CompiledModule) -> Result<(), ExecutionError> {
             // it's not an entry function
             continue;
         }
-        verify_entry_function_impl(module, func_def).map_err(verification_failure)?;
+        verify_entry_function_impl(module, func_def)
+            .map_err(|e| verification_failure(location, e))?;
     }
     Ok(())
 }
 
+/// Checks that `fdef` does not call `init`, returning the offset of the call if it does
 fn verify_init_not_called(
     module: &CompiledModule,
     fdef: &FunctionDefinition,
-) -> Result<(), String> {
+) -> Result<(), (CodeOffset, String)> {
     let code = match &fdef.code {
         None => return Ok(()),
         Some(code) => code,
@@ -81,12 +92,15 @@ fn verify_init_not_called(
         .try_for_each(|(idx, fhandle)| {
             let name = module.identifier_at(fhandle.name);
             if name == INIT_FN_NAME {
-                Err(format!(
-                    "{}::{} at offset {}. Cannot call a module's '{}' function from another Move function",
-                    module.self_id(),
-                    name,
-                    idx,
-                    INIT_FN_NAME
+                Err((
+                    idx as CodeOffset,
+                    format!(
+                        "{}::{} at offset {}. Cannot call a module's '{}' function from another Move function",
+                        module.self_id(),
+                        name,
+                        idx,
+                        INIT_FN_NAME
+                    ),
                 ))
             } else {
                 Ok(())
@@ -178,7 +192,7 @@ fn verify_entry_function_impl(
     Ok(())
 }
 
-fn verify_param_type(
+pub(crate) fn verify_param_type(
     view: &BinaryIndexedView,
     function_type_args: &[AbilitySet],
     param: &SignatureToken,
@@ -1,10 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::verification_failure;
+use crate::{verification_failure, BytecodeLocation};
 use move_binary_format::{
     binary_views::BinaryIndexedView,
-    file_format::{Bytecode, CompiledModule},
+    file_format::{Bytecode, CodeOffset, CompiledModule, FunctionDefinitionIndex},
 };
 use sui_types::error::ExecutionError;
 
@@ -16,13 +16,14 @@ pub fn verify_module(module: &CompiledModule) -> Result<(), ExecutionError> {
 /// Hence we want to forbid any global storage access in Move.
 fn verify_global_storage_access(module: &CompiledModule) -> Result<(), ExecutionError> {
     let view = BinaryIndexedView::Module(module);
-    for func_def in &module.function_defs {
+    for (idx, func_def) in module.function_defs.iter().enumerate() {
         if func_def.code.is_none() {
             continue;
         }
         let code = &func_def.code.as_ref().unwrap().code;
         let mut invalid_bytecode = vec![];
-        for bytecode in code {
+        let mut first_invalid_offset = None;
+        for (offset, bytecode) in code.iter().enumerate() {
             match bytecode {
                 Bytecode::MoveFrom(_)
                 | Bytecode::MoveFromGeneric(_)
@@ -34,6 +35,7 @@ fn verify_global_storage_access(module: &CompiledModule) -> Result<(), Execution
                 | Bytecode::MutBorrowGlobalGeneric(_)
                 | Bytecode::Exists(_)
                 | Bytecode::ExistsGeneric(_) => {
+                    first_invalid_offset.get_or_insert(offset as CodeOffset);
                     invalid_bytecode.push(bytecode);
                 }
                 Bytecode::Pop
@@ -105,12 +107,16 @@ fn verify_global_storage_access(module: &CompiledModule) -> Result<(), Execution
                 | Bytecode::VecSwap(_) => {}
             }
         }
-        if !invalid_bytecode.is_empty() {
-            return Err(verification_failure(format!(
-                "Access to Move global storage is not allowed. Found in function {}: {:?}",
-                view.identifier_at(view.function_handle_at(func_def.function).name),
-                invalid_bytecode,
-            )));
+        if let Some(offset) = first_invalid_offset {
+            let location = BytecodeLocation::Code(FunctionDefinitionIndex(idx as u16), offset);
+            return Err(verification_failure(
+                location,
+                format!(
+                    "Access to Move global storage is not allowed. Found in function {}: {:?}",
+                    view.identifier_at(view.function_handle_at(func_def.function).name),
+                    invalid_bytecode,
+                ),
+            ));
         }
     }
     Ok(())
@@ -27,6 +27,8 @@ use move_core_types::vm_status::StatusCode;
 use std::collections::BTreeMap;
 use sui_types::{error::ExecutionError, id::OBJECT_MODULE_NAME, SUI_FRAMEWORK_ADDRESS};
 
+use crate::{vm_verification_failure, BytecodeLocation};
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum AbstractValue {
     ID,
@@ -59,9 +61,17 @@ fn verify_id_leak(module: &CompiledModule) -> Result<(), ExecutionError> {
             FunctionView::function(module, FunctionDefinitionIndex(index as u16), code, handle);
         let initial_state = AbstractState::new(&func_view);
         let mut verifier = IDLeakAnalysis::new(&binary_view, &func_view);
-        verifier
-            .analyze_function(initial_state, &func_view)
-            .map_err(|e| e.finish(Location::Module(module.self_id())))?;
+        if let Err(e) = verifier.analyze_function(initial_state, &func_view) {
+            let index = FunctionDefinitionIndex(index as u16);
+            let location = match verifier.error_offset {
+                Some(offset) => BytecodeLocation::Code(index, offset),
+                None => BytecodeLocation::Function(index),
+            };
+            return Err(vm_verification_failure(
+                location,
+                e.finish(Location::Module(module.self_id())),
+            ));
+        }
     }
 
     Ok(())
@@ -110,6 +120,8 @@ struct IDLeakAnalysis<'a> {
     binary_view: &'a BinaryIndexedView<'a>,
     function_view: &'a FunctionView<'a>,
     stack: Vec<AbstractValue>,
+    /// Offset of the instruction the analysis failed at, if it did
+    error_offset: Option<CodeOffset>,
 }
 
 impl<'a> IDLeakAnalysis<'a> {
@@ -118,6 +130,7 @@ impl<'a> IDLeakAnalysis<'a> {
             binary_view,
             function_view,
             stack: vec![],
+            error_offset: None,
         }
     }
 }
@@ -132,7 +145,10 @@ impl<'a> TransferFunctions for IDLeakAnalysis<'a> {
         index: CodeOffset,
         _: CodeOffset,
     ) -> PartialVMResult<()> {
-        execute_inner(self, state, bytecode, index)
+        execute_inner(self, state, bytecode, index).map_err(|e| {
+            self.error_offset = Some(index);
+            e
+        })
     }
 }
 
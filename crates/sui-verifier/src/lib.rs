@@ -6,21 +6,95 @@ pub mod verifier;
 pub mod entry_points_verifier;
 pub mod global_storage_access_verifier;
 pub mod id_leak_verifier;
+pub mod linter;
 pub mod one_time_witness_verifier;
 pub mod private_generics;
 pub mod struct_with_key_verifier;
 
+use std::fmt;
+
 use move_binary_format::{
     binary_views::BinaryIndexedView,
-    file_format::{SignatureToken, StructHandleIndex},
+    errors::VMError,
+    file_format::{
+        CodeOffset, FunctionDefinitionIndex, SignatureToken, StructDefinitionIndex,
+        StructHandleIndex,
+    },
 };
 use move_core_types::{account_address::AccountAddress, ident_str, identifier::IdentStr};
-use sui_types::error::{ExecutionError, ExecutionErrorKind};
+use sui_types::error::{BoxError, ExecutionError, ExecutionErrorKind};
 
 pub const INIT_FN_NAME: &IdentStr = ident_str!("init");
 
-fn verification_failure(error: String) -> ExecutionError {
-    ExecutionError::new_with_source(ExecutionErrorKind::SuiMoveVerificationError, error)
+/// The bytecode a verification failure or a lint warning is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BytecodeLocation {
+    Struct(StructDefinitionIndex),
+    Function(FunctionDefinitionIndex),
+    Code(FunctionDefinitionIndex, CodeOffset),
+}
+
+/// The source of the errors returned by the Sui verifier passes: the error, along with the
+/// bytecode that fails verification. It formats as the error it wraps, so that the location
+/// does not show up in transaction effects.
+pub struct VerificationFailure {
+    pub location: BytecodeLocation,
+    error: BoxError,
+}
+
+impl VerificationFailure {
+    /// The verification failure behind `error`, if a Sui verifier pass returned it
+    pub fn from_error(error: &ExecutionError) -> Option<&Self> {
+        std::error::Error::source(error)?.downcast_ref()
+    }
+
+    /// A description of the failure, without the details of the error type it wraps
+    pub fn message(&self) -> String {
+        match self.error.downcast_ref::<VMError>() {
+            Some(e) => e
+                .message()
+                .cloned()
+                .unwrap_or_else(|| format!("{:?}", e.major_status())),
+            None => self.error.to_string(),
+        }
+    }
+}
+
+impl fmt::Debug for VerificationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.error, f)
+    }
+}
+
+impl fmt::Display for VerificationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl std::error::Error for VerificationFailure {}
+
+fn verification_failure(location: BytecodeLocation, error: String) -> ExecutionError {
+    ExecutionError::new_with_source(
+        ExecutionErrorKind::SuiMoveVerificationError,
+        VerificationFailure {
+            location,
+            error: error.into(),
+        },
+    )
+}
+
+/// Like the conversion of a `VMError` into an `ExecutionError`, for errors reported through the
+/// Move bytecode verifier's error type
+fn vm_verification_failure(location: BytecodeLocation, error: VMError) -> ExecutionError {
+    let kind = ExecutionError::from(error.clone()).kind().clone();
+    ExecutionError::new_with_source(
+        kind,
+        VerificationFailure {
+            location,
+            error: error.into(),
+        },
+    )
 }
 
 // TODO move these to move bytecode utils
@@ -0,0 +1,295 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lints over Sui Move bytecode. Unlike the verifier passes, lints do not reject modules: they
+//! flag code that is valid but likely to be a mistake, such as
+//! - transferring an object to the sender of the transaction in a function that could return it
+//!   instead (`self_transfer`)
+//! - sharing an object that was not created in the function sharing it (`share_owned`)
+//! - transferring objects of a type without `store`, that only its module can transfer
+//!   (`missing_store`)
+//! - declaring a one-time witness that the module initializer does not use
+//!   (`unused_one_time_witness`)
+//! - declaring a function that looks like a transaction entry point as `public` but not `entry`
+//!   (`public_not_entry`)
+//!
+//! Warnings point at bytecode locations, which a source map translates back to the sources.
+
+use std::{collections::BTreeSet, fmt};
+
+use move_binary_format::{
+    access::ModuleAccess,
+    binary_views::BinaryIndexedView,
+    file_format::{
+        Ability, AbilitySet, Bytecode, CodeOffset, FunctionDefinition, FunctionDefinitionIndex,
+        SignatureToken, StructDefinitionIndex, Visibility,
+    },
+    CompiledModule,
+};
+use move_core_types::{account_address::AccountAddress, identifier::IdentStr};
+use sui_types::SUI_FRAMEWORK_ADDRESS;
+
+use crate::{
+    entry_points_verifier::{is_tx_context, verify_param_type},
+    BytecodeLocation, INIT_FN_NAME,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Lint {
+    SelfTransfer,
+    ShareOwned,
+    MissingStore,
+    UnusedOneTimeWitness,
+    PublicNotEntry,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Lint::SelfTransfer => "self_transfer",
+            Lint::ShareOwned => "share_owned",
+            Lint::MissingStore => "missing_store",
+            Lint::UnusedOneTimeWitness => "unused_one_time_witness",
+            Lint::PublicNotEntry => "public_not_entry",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+    pub lint: Lint,
+    pub location: BytecodeLocation,
+    pub message: String,
+}
+
+/// Run every lint over `module`, returning the warnings in the order of the module's definitions.
+pub fn lint_module(module: &CompiledModule) -> Vec<LintWarning> {
+    let view = &BinaryIndexedView::Module(module);
+    let mut warnings = vec![];
+    for (idx, fdef) in module.function_defs.iter().enumerate() {
+        let idx = FunctionDefinitionIndex(idx as u16);
+        lint_self_transfer(view, idx, fdef, &mut warnings);
+        lint_share_owned(view, idx, fdef, &mut warnings);
+        lint_public_not_entry(view, idx, fdef, &mut warnings);
+    }
+    lint_missing_store(module, &mut warnings);
+    lint_unused_one_time_witness(module, &mut warnings);
+    warnings.sort_by_key(|w| w.location);
+    warnings
+}
+
+fn lint_self_transfer(
+    view: &BinaryIndexedView,
+    idx: FunctionDefinitionIndex,
+    fdef: &FunctionDefinition,
+    warnings: &mut Vec<LintWarning>,
+) {
+    // entry functions cannot return objects, and init functions cannot be called again
+    let handle = view.function_handle_at(fdef.function);
+    if fdef.visibility != Visibility::Public
+        || fdef.is_entry
+        || view.identifier_at(handle.name) == INIT_FN_NAME
+    {
+        return;
+    }
+    let Some(code) = &fdef.code else { return };
+    // locals the sender is stored in
+    let mut sender_locals = BTreeSet::new();
+    for (offset, pair) in code.code.windows(2).enumerate() {
+        let is_sender = match &pair[0] {
+            Bytecode::MoveLoc(local) | Bytecode::CopyLoc(local) => sender_locals.contains(local),
+            instr => is_call_to(view, instr, "tx_context", "sender"),
+        };
+        if let (true, Bytecode::StLoc(local)) = (is_sender, &pair[1]) {
+            sender_locals.insert(*local);
+        }
+        if is_sender && is_call_to(view, &pair[1], "transfer", "transfer") {
+            warnings.push(LintWarning {
+                lint: Lint::SelfTransfer,
+                location: BytecodeLocation::Code(idx, offset as CodeOffset + 1),
+                message: format!(
+                    "Public function {} transfers an object to the sender of the transaction. \
+                     Consider returning it instead, so callers can decide what to do with it",
+                    view.identifier_at(handle.name)
+                ),
+            });
+        }
+    }
+}
+
+fn lint_share_owned(
+    view: &BinaryIndexedView,
+    idx: FunctionDefinitionIndex,
+    fdef: &FunctionDefinition,
+    warnings: &mut Vec<LintWarning>,
+) {
+    let handle = view.function_handle_at(fdef.function);
+    let num_params = view.signature_at(handle.parameters).len();
+    let Some(code) = &fdef.code else { return };
+    for (offset, pair) in code.code.windows(2).enumerate() {
+        let shared_param = match &pair[0] {
+            Bytecode::MoveLoc(local) | Bytecode::CopyLoc(local) => (*local as usize) < num_params,
+            _ => false,
+        };
+        if shared_param && is_call_to(view, &pair[1], "transfer", "share_object") {
+            warnings.push(LintWarning {
+                lint: Lint::ShareOwned,
+                location: BytecodeLocation::Code(idx, offset as CodeOffset + 1),
+                message: format!(
+                    "Function {} shares an object it receives as an argument. Objects can only be \
+                     shared by the transaction creating them, so this aborts unless the caller \
+                     created the object in the same transaction",
+                    view.identifier_at(handle.name)
+                ),
+            });
+        }
+    }
+}
+
+fn lint_missing_store(module: &CompiledModule, warnings: &mut Vec<LintWarning>) {
+    let view = &BinaryIndexedView::Module(module);
+    for (idx, sdef) in module.struct_defs.iter().enumerate() {
+        let shandle = module.struct_handle_at(sdef.struct_handle);
+        if !shandle.abilities.has_key() || shandle.abilities.has_store() {
+            continue;
+        }
+        let transferred = module.function_defs.iter().any(|fdef| {
+            let Some(code) = &fdef.code else { return false };
+            code.code.iter().any(|instr| {
+                let Bytecode::CallGeneric(finst_idx) = instr else {
+                    return false;
+                };
+                let finst = view.function_instantiation_at(*finst_idx);
+                is_call_to(view, &Bytecode::Call(finst.handle), "transfer", "transfer")
+                    && matches!(
+                        view.signature_at(finst.type_parameters).0.first(),
+                        Some(SignatureToken::Struct(s) | SignatureToken::StructInstantiation(s, _))
+                            if *s == sdef.struct_handle
+                    )
+            })
+        });
+        if transferred {
+            let name = module.identifier_at(shandle.name);
+            warnings.push(LintWarning {
+                lint: Lint::MissingStore,
+                location: BytecodeLocation::Struct(StructDefinitionIndex(idx as u16)),
+                message: format!(
+                    "Objects of type {name} are transferred, but {name} does not have store: only \
+                     module {} can transfer them. Add store to let their owners transfer them",
+                    module.self_id(),
+                ),
+            });
+        }
+    }
+}
+
+fn lint_unused_one_time_witness(module: &CompiledModule, warnings: &mut Vec<LintWarning>) {
+    let module_name = module.name().as_str().to_ascii_uppercase();
+    let drop_set = AbilitySet::EMPTY | Ability::Drop;
+    let Some((witness_idx, _)) = module.struct_defs.iter().enumerate().find(|(_, sdef)| {
+        let shandle = module.struct_handle_at(sdef.struct_handle);
+        module.identifier_at(shandle.name).as_str() == module_name
+            && shandle.abilities == drop_set
+            && matches!(sdef.declared_field_count(), Ok(1))
+            && sdef.field(0).unwrap().signature.0 == SignatureToken::Bool
+    }) else {
+        return;
+    };
+
+    let init = module.function_defs.iter().enumerate().find(|(_, fdef)| {
+        module.identifier_at(module.function_handle_at(fdef.function).name) == INIT_FN_NAME
+    });
+    let (location, message) = match init {
+        None => (
+            BytecodeLocation::Struct(StructDefinitionIndex(witness_idx as u16)),
+            format!(
+                "Module {} declares the one-time witness {module_name}, but has no initializer \
+                 to receive it",
+                module.self_id()
+            ),
+        ),
+        Some((init_idx, fdef)) => {
+            // the witness is the first parameter of the initializer, local 0
+            let used = fdef.code.iter().flat_map(|code| &code.code).any(|instr| {
+                matches!(
+                    instr,
+                    Bytecode::MoveLoc(0)
+                        | Bytecode::CopyLoc(0)
+                        | Bytecode::ImmBorrowLoc(0)
+                        | Bytecode::MutBorrowLoc(0)
+                )
+            });
+            let takes_witness = module
+                .signature_at(module.function_handle_at(fdef.function).parameters)
+                .len()
+                == 2;
+            if used || !takes_witness {
+                return;
+            }
+            (
+                BytecodeLocation::Function(FunctionDefinitionIndex(init_idx as u16)),
+                format!(
+                    "The one-time witness {module_name} is passed to the initializer of module {}, \
+                     but the initializer never uses it",
+                    module.self_id()
+                ),
+            )
+        }
+    };
+    warnings.push(LintWarning {
+        lint: Lint::UnusedOneTimeWitness,
+        location,
+        message,
+    });
+}
+
+fn lint_public_not_entry(
+    view: &BinaryIndexedView,
+    idx: FunctionDefinitionIndex,
+    fdef: &FunctionDefinition,
+    warnings: &mut Vec<LintWarning>,
+) {
+    if fdef.visibility != Visibility::Public || fdef.is_entry {
+        return;
+    }
+    let handle = view.function_handle_at(fdef.function);
+    let params = &view.signature_at(handle.parameters).0;
+    // a function taking the transaction context and returning nothing only has an effect on
+    // objects, and could be called from transactions if it was an entry function
+    let Some((last, other_params)) = params.split_last() else {
+        return;
+    };
+    if !is_tx_context(view, last)
+        || !view.signature_at(handle.return_).is_empty()
+        || other_params
+            .iter()
+            .any(|p| verify_param_type(view, &handle.type_parameters, p).is_err())
+    {
+        return;
+    }
+    let name = view.identifier_at(handle.name);
+    warnings.push(LintWarning {
+        lint: Lint::PublicNotEntry,
+        location: BytecodeLocation::Function(idx),
+        message: format!(
+            "Public function {name} takes a TxContext and returns nothing, but it cannot be \
+             called from a transaction. Consider declaring it `public entry`"
+        ),
+    });
+}
+
+/// Return true if `instr` calls function `function` of module `module` of the Sui framework
+fn is_call_to(view: &BinaryIndexedView, instr: &Bytecode, module: &str, function: &str) -> bool {
+    let fhandle_idx = match instr {
+        Bytecode::Call(idx) => *idx,
+        Bytecode::CallGeneric(idx) => view.function_instantiation_at(*idx).handle,
+        _ => return false,
+    };
+    let fhandle = view.function_handle_at(fhandle_idx);
+    let mhandle = view.module_handle_at(fhandle.module);
+    let address: &AccountAddress = view.address_identifier_at(mhandle.address);
+    let module_name: &IdentStr = view.identifier_at(mhandle.name);
+    *address == SUI_FRAMEWORK_ADDRESS
+        && module_name.as_str() == module
+        && view.identifier_at(fhandle.name).as_str() == function
+}
@@ -19,8 +19,9 @@ use move_binary_format::{
     access::ModuleAccess,
     binary_views::BinaryIndexedView,
     file_format::{
-        Ability, AbilitySet, Bytecode, CompiledModule, FunctionDefinition, FunctionHandle,
-        SignatureToken, StructDefinition, StructHandle,
+        Ability, AbilitySet, Bytecode, CodeOffset, CompiledModule, FunctionDefinition,
+        FunctionDefinitionIndex, FunctionHandle, SignatureToken, StructDefinition,
+        StructDefinitionIndex, StructHandle,
     },
 };
 use move_core_types::{ident_str, language_storage::ModuleId};
@@ -30,7 +31,7 @@ use sui_types::{
     SUI_FRAMEWORK_ADDRESS,
 };
 
-use crate::{verification_failure, INIT_FN_NAME};
+use crate::{verification_failure, BytecodeLocation, INIT_FN_NAME};
 
 pub fn verify_module(module: &CompiledModule) -> Result<(), ExecutionError> {
     // In Sui's framework code there is an exception to the one-time witness type rule - we have a
@@ -48,7 +49,7 @@ pub fn verify_module(module: &CompiledModule) -> Result<(), ExecutionError> {
     let struct_defs = &module.struct_defs;
     let mut one_time_witness_candidate = None;
     // find structs that can potentially represent a one-time witness type
-    for def in struct_defs {
+    for (idx, def) in struct_defs.iter().enumerate() {
         let struct_handle = module.struct_handle_at(def.struct_handle);
         let struct_name = view.identifier_at(struct_handle.name).as_str();
         if mod_name.to_ascii_uppercase() == struct_name {
@@ -59,8 +60,10 @@ pub fn verify_module(module: &CompiledModule) -> Result<(), ExecutionError> {
                 if field_count == 1 && def.field(0).unwrap().signature.0 == SignatureToken::Bool {
                     // a single boolean field means that we found a one-time witness candidate -
                     // make sure that the remaining properties hold
-                    verify_one_time_witness(module, struct_name, struct_handle)
-                        .map_err(verification_failure)?;
+                    verify_one_time_witness(module, struct_name, struct_handle).map_err(|e| {
+                        let location = BytecodeLocation::Struct(StructDefinitionIndex(idx as u16));
+                        verification_failure(location, e)
+                    })?;
                     // if we reached this point, it means we have a legitimate one-time witness type
                     // candidate and we have to make sure that both the init function's signature
                     // reflects this and that this type is not instantiated in any function of the
@@ -71,7 +74,9 @@ pub fn verify_module(module: &CompiledModule) -> Result<(), ExecutionError> {
             }
         }
     }
-    for fn_def in &module.function_defs {
+    for (idx, fn_def) in module.function_defs.iter().enumerate() {
+        let idx = FunctionDefinitionIndex(idx as u16);
+        let location = BytecodeLocation::Function(idx);
         let fn_handle = module.function_handle_at(fn_def.function);
         let fn_name = module.identifier_at(fn_handle.name);
         if fn_name == INIT_FN_NAME {
@@ -79,18 +84,20 @@ pub fn verify_module(module: &CompiledModule) -> Result<(), ExecutionError> {
                 // only verify if init function conforms to one-time witness type requirements if we
                 // have a one-time witness type candidate
                 verify_init_one_time_witness(module, fn_handle, candidate_name, candidate_handle)
-                    .map_err(verification_failure)?;
+                    .map_err(|e| verification_failure(location, e))?;
             } else {
                 // if there is no one-time witness type candidate than the init function should have
                 // only one parameter of TxContext type
-                verify_init_single_param(module, fn_handle).map_err(verification_failure)?;
+                verify_init_single_param(module, fn_handle)
+                    .map_err(|e| verification_failure(location, e))?;
             }
         }
         if let Some((candidate_name, _, def)) = one_time_witness_candidate {
             // only verify lack of one-time witness type instantiations if we have a one-time
             // witness type candidate
-            verify_no_instantiations(module, fn_def, candidate_name, def)
-                .map_err(verification_failure)?;
+            verify_no_instantiations(module, fn_def, candidate_name, def).map_err(
+                |(offset, e)| verification_failure(BytecodeLocation::Code(idx, offset), e),
+            )?;
         }
     }
 
@@ -184,18 +191,19 @@ fn verify_init_single_param(
     Ok(())
 }
 
-/// Checks if this module function does not contain instantiation of the one-time witness type
+/// Checks if this module function does not contain instantiation of the one-time witness type,
+/// returning the offset of the instantiation if it does
 fn verify_no_instantiations(
     module: &CompiledModule,
     fn_def: &FunctionDefinition,
     struct_name: &str,
     struct_def: &StructDefinition,
-) -> Result<(), String> {
+) -> Result<(), (CodeOffset, String)> {
     let view = &BinaryIndexedView::Module(module);
     if fn_def.code.is_none() {
         return Ok(());
     }
-    for bcode in &fn_def.code.as_ref().unwrap().code {
+    for (offset, bcode) in fn_def.code.as_ref().unwrap().code.iter().enumerate() {
         let struct_def_idx = match bcode {
             Bytecode::Pack(idx) => idx,
             _ => continue,
@@ -205,13 +213,16 @@ fn verify_no_instantiations(
         if view.struct_def_at(*struct_def_idx).unwrap() == struct_def {
             let fn_handle = module.function_handle_at(fn_def.function);
             let fn_name = module.identifier_at(fn_handle.name);
-            return Err(format!(
-                "one-time witness type {}::{} is instantiated \
+            return Err((
+                offset as CodeOffset,
+                format!(
+                    "one-time witness type {}::{} is instantiated \
                          in the {}::{} function and must never be",
-                module.self_id(),
-                struct_name,
-                module.self_id(),
-                fn_name,
+                    module.self_id(),
+                    struct_name,
+                    module.self_id(),
+                    fn_name,
+                ),
             ));
         }
     }
@@ -5,15 +5,15 @@ use move_binary_format::{
     access::ModuleAccess,
     binary_views::BinaryIndexedView,
     file_format::{
-        AbilitySet, Bytecode, FunctionDefinition, FunctionHandle, FunctionInstantiation,
-        ModuleHandle, SignatureToken,
+        AbilitySet, Bytecode, CodeOffset, FunctionDefinition, FunctionDefinitionIndex,
+        FunctionHandle, FunctionInstantiation, ModuleHandle, SignatureToken,
     },
     CompiledModule,
 };
 use move_core_types::{account_address::AccountAddress, identifier::IdentStr};
 use sui_types::{error::ExecutionError, SUI_FRAMEWORK_ADDRESS};
 
-use crate::{format_signature_token, verification_failure};
+use crate::{format_signature_token, verification_failure, BytecodeLocation};
 
 const TEST_SCENARIO_MODULE_NAME: &str = "test_scenario";
 
@@ -37,26 +37,35 @@ pub fn verify_module(module: &CompiledModule) -> Result<(), ExecutionError> {
     }
     let view = &BinaryIndexedView::Module(module);
     // do not need to check the sui::transfer module itself
-    for func_def in &module.function_defs {
-        verify_function(view, func_def).map_err(|error| {
-            verification_failure(format!(
-                "{}::{}. {}",
-                module.self_id(),
-                module.identifier_at(module.function_handle_at(func_def.function).name),
-                error
-            ))
+    for (idx, func_def) in module.function_defs.iter().enumerate() {
+        verify_function(view, func_def).map_err(|(offset, error)| {
+            let location = BytecodeLocation::Code(FunctionDefinitionIndex(idx as u16), offset);
+            verification_failure(
+                location,
+                format!(
+                    "{}::{}. {}",
+                    module.self_id(),
+                    module.identifier_at(module.function_handle_at(func_def.function).name),
+                    error
+                ),
+            )
         })?;
     }
     Ok(())
 }
 
-fn verify_function(view: &BinaryIndexedView, fdef: &FunctionDefinition) -> Result<(), String> {
+/// Checks the calls `fdef` makes to private generic functions, returning the offset of the first
+/// invalid call if there is one
+fn verify_function(
+    view: &BinaryIndexedView,
+    fdef: &FunctionDefinition,
+) -> Result<(), (CodeOffset, String)> {
     let code = match &fdef.code {
         None => return Ok(()),
         Some(code) => code,
     };
     let function_type_parameters = &view.function_handle_at(fdef.function).type_parameters;
-    for instr in &code.code {
+    for (offset, instr) in code.code.iter().enumerate() {
         if let Bytecode::CallGeneric(finst_idx) = instr {
             let FunctionInstantiation {
                 handle,
@@ -67,18 +76,16 @@ fn verify_function(view: &BinaryIndexedView, fdef: &FunctionDefinition) -> Resul
             let mhandle = view.module_handle_at(fhandle.module);
 
             let type_arguments = &view.signature_at(*type_parameters).0;
-            match addr_module(view, mhandle) {
-                (SUI_FRAMEWORK_ADDRESS, "transfer") => verify_private_transfer(
-                    view,
-                    function_type_parameters,
-                    fhandle,
-                    type_arguments,
-                )?,
+            let result = match addr_module(view, mhandle) {
+                (SUI_FRAMEWORK_ADDRESS, "transfer") => {
+                    verify_private_transfer(view, function_type_parameters, fhandle, type_arguments)
+                }
                 (SUI_FRAMEWORK_ADDRESS, "event") => {
-                    verify_private_event_emit(view, fhandle, type_arguments)?
+                    verify_private_event_emit(view, fhandle, type_arguments)
                 }
-                _ => (),
-            }
+                _ => Ok(()),
+            };
+            result.map_err(|e| (offset as CodeOffset, e))?;
         }
     }
     Ok(())
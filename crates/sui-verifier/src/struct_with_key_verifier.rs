@@ -6,11 +6,11 @@
 //! - The first field is named "id"
 //! - The first field has type `sui::object::UID`
 
-use crate::verification_failure;
+use crate::{verification_failure, BytecodeLocation};
 use move_binary_format::{
     access::ModuleAccess,
     binary_views::BinaryIndexedView,
-    file_format::{CompiledModule, SignatureToken},
+    file_format::{CompiledModule, SignatureToken, StructDefinitionIndex},
 };
 use sui_types::{
     error::ExecutionError,
@@ -26,7 +26,8 @@ pub fn verify_module(module: &CompiledModule) -> Result<(), ExecutionError> {
 fn verify_key_structs(module: &CompiledModule) -> Result<(), ExecutionError> {
     let view = BinaryIndexedView::Module(module);
     let struct_defs = &module.struct_defs;
-    for def in struct_defs {
+    for (idx, def) in struct_defs.iter().enumerate() {
+        let location = BytecodeLocation::Struct(StructDefinitionIndex(idx as u16));
         let handle = module.struct_handle_at(def.struct_handle);
         if !handle.abilities.has_key() {
             continue;
@@ -37,29 +38,38 @@ fn verify_key_structs(module: &CompiledModule) -> Result<(), ExecutionError> {
         let first_field = match def.field(0) {
             Some(field) => field,
             None => {
-                return Err(verification_failure(format!(
-                    "First field of struct {} must be 'id', no field found",
-                    name
-                )))
+                return Err(verification_failure(
+                    location,
+                    format!(
+                        "First field of struct {} must be 'id', no field found",
+                        name
+                    ),
+                ))
             }
         };
         let first_field_name = view.identifier_at(first_field.name).as_str();
         if first_field_name != "id" {
-            return Err(verification_failure(format!(
-                "First field of struct {} must be 'id', {} found",
-                name, first_field_name
-            )));
+            return Err(verification_failure(
+                location,
+                format!(
+                    "First field of struct {} must be 'id', {} found",
+                    name, first_field_name
+                ),
+            ));
         }
         // Check that the "id" field must have a struct type.
         let uid_field_type = &first_field.signature.0;
         let uid_field_type = match uid_field_type {
             SignatureToken::Struct(struct_type) => struct_type,
             _ => {
-                return Err(verification_failure(format!(
-                    "First field of struct {} must be of type {}::object::UID, \
+                return Err(verification_failure(
+                    location,
+                    format!(
+                        "First field of struct {} must be of type {}::object::UID, \
                     {:?} type found",
-                    name, SUI_FRAMEWORK_ADDRESS, uid_field_type
-                )))
+                        name, SUI_FRAMEWORK_ADDRESS, uid_field_type
+                    ),
+                ))
             }
         };
         // check that the struct type for "id" field must be SUI_FRAMEWORK_ADDRESS::object::UID.
@@ -72,15 +82,18 @@ fn verify_key_structs(module: &CompiledModule) -> Result<(), ExecutionError> {
             uid_type_struct_name == UID_STRUCT_NAME
                 && uid_type_module_address == &SUI_FRAMEWORK_ADDRESS
                 && uid_type_module_name == OBJECT_MODULE_NAME,
-            verification_failure(format!(
-                "First field of struct {} must be of type {}::object::UID, \
+            verification_failure(
+                location,
+                format!(
+                    "First field of struct {} must be of type {}::object::UID, \
                 {}::{}::{} type found",
-                name,
-                SUI_FRAMEWORK_ADDRESS,
-                uid_type_module_address,
-                uid_type_module_name,
-                uid_type_struct_name
-            ))
+                    name,
+                    SUI_FRAMEWORK_ADDRESS,
+                    uid_type_module_address,
+                    uid_type_module_name,
+                    uid_type_struct_name
+                )
+            )
         );
     }
     Ok(())
@@ -24,13 +24,18 @@ use serde_json::json;
 use tracing::info;
 
 use sui_framework::build_move_package;
-use sui_framework_build::compiled_package::BuildConfig;
+use sui_framework_build::compiled_package::{BuildConfig, CompiledPackage};
+use sui_framework_build::published::PublishedAddresses;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
     GetObjectDataResponse, SuiObjectInfo, SuiParsedObject, SuiTransactionResponse,
 };
-use sui_json_rpc_types::{GetRawObjectDataResponse, SuiData};
-use sui_json_rpc_types::{SuiCertifiedTransaction, SuiExecutionStatus, SuiTransactionEffects};
+use sui_json_rpc_types::{
+    GetRawObjectDataResponse, SuiData, SuiEvent, SuiObject, SuiObjectRead, SuiRawData,
+};
+use sui_json_rpc_types::{
+    SuiCertifiedTransaction, SuiExecutionStatus, SuiGasProfile, SuiTransactionEffects,
+};
 use sui_keys::keystore::AccountKeystore;
 use sui_sdk::TransactionExecutionResult;
 use sui_types::crypto::SignableBytes;
@@ -127,6 +132,42 @@ pub enum SuiClientCommands {
         /// Gas budget for running module initializers
         #[clap(long)]
         gas_budget: u64,
+
+        /// Receive an upgrade capability, allowing to upgrade the package with `sui client upgrade`
+        #[clap(long)]
+        upgradeable: bool,
+    },
+
+    /// Upgrade a published Move package. The upgraded package must preserve the structs and
+    /// public functions of the package it upgrades
+    #[clap(name = "upgrade")]
+    Upgrade {
+        /// Path to directory containing the upgraded Move package
+        #[clap(
+            long = "path",
+            short = 'p',
+            global = true,
+            parse(from_os_str),
+            default_value = "."
+        )]
+        package_path: PathBuf,
+
+        /// ID of the upgrade capability of the package, received when the package was published
+        #[clap(long)]
+        upgrade_capability: ObjectID,
+
+        /// Package build options
+        #[clap(flatten)]
+        build_config: MoveBuildConfig,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for running the initializers of new modules
+        #[clap(long)]
+        gas_budget: u64,
     },
 
     /// Call Move function
@@ -161,6 +202,10 @@ pub enum SuiClientCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+        /// Dry run the call instead of executing it, and write the gas used by each of its call
+        /// stacks to this file, as folded stacks for flamegraph tools
+        #[clap(long, value_name = "FILE")]
+        profile: Option<PathBuf>,
     },
 
     /// Transfer object
@@ -411,30 +456,65 @@ impl SuiClientCommands {
                 gas,
                 build_config,
                 gas_budget,
+                upgradeable,
             } => {
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
 
-                let compiled_modules = build_move_package(
-                    &package_path,
-                    BuildConfig {
-                        config: build_config,
-                        run_bytecode_verifier: true,
-                        print_diags_to_stderr: true,
-                    },
-                )?
-                .get_package_bytes();
+                let compiled_modules =
+                    build_and_resolve_package(context, &package_path, build_config)
+                        .await?
+                        .get_package_bytes();
+                let builder = context.client.transaction_builder();
+                let data = if upgradeable {
+                    builder
+                        .publish_upgradeable(sender, compiled_modules, gas, gas_budget)
+                        .await?
+                } else {
+                    builder
+                        .publish(sender, compiled_modules, gas, gas_budget)
+                        .await?
+                };
+                let signature = context.config.keystore.sign(&sender, &data.to_bytes())?;
+                let response = context
+                    .execute_transaction(Transaction::from_data(data, signature).verify()?)
+                    .await?;
+                record_published_package(context, &package_path, &response)?;
+
+                SuiClientCommandResult::Publish(response)
+            }
+
+            SuiClientCommands::Upgrade {
+                package_path,
+                upgrade_capability,
+                build_config,
+                gas,
+                gas_budget,
+            } => {
+                let sender = context.get_object_owner(&upgrade_capability).await?;
+
+                let compiled_modules =
+                    build_and_resolve_package(context, &package_path, build_config)
+                        .await?
+                        .get_package_bytes();
                 let data = context
                     .client
                     .transaction_builder()
-                    .publish(sender, compiled_modules, gas, gas_budget)
+                    .upgrade(
+                        sender,
+                        compiled_modules,
+                        upgrade_capability,
+                        gas,
+                        gas_budget,
+                    )
                     .await?;
                 let signature = context.config.keystore.sign(&sender, &data.to_bytes())?;
                 let response = context
                     .execute_transaction(Transaction::from_data(data, signature).verify()?)
                     .await?;
+                record_published_package(context, &package_path, &response)?;
 
-                SuiClientCommandResult::Publish(response)
+                SuiClientCommandResult::Upgrade(response)
             }
 
             SuiClientCommands::Object { id } => {
@@ -450,6 +530,7 @@ impl SuiClientCommands {
                 gas,
                 gas_budget,
                 args,
+                profile: None,
             } => {
                 let (cert, effects) = call_move(
                     package, &module, &function, type_args, gas, gas_budget, args, context,
@@ -458,6 +539,29 @@ impl SuiClientCommands {
                 SuiClientCommandResult::Call(cert, effects)
             }
 
+            SuiClientCommands::Call {
+                package,
+                module,
+                function,
+                type_args,
+                gas,
+                gas_budget,
+                args,
+                profile: Some(path),
+            } => {
+                let (_, data) = move_call_data(
+                    package, &module, &function, type_args, gas, gas_budget, args, context,
+                )
+                .await?;
+                let profile = context
+                    .client
+                    .read_api()
+                    .dry_run_transaction_with_gas_profile(data)
+                    .await?;
+                std::fs::write(&path, profile.to_folded())?;
+                SuiClientCommandResult::Profile(path, profile)
+            }
+
             SuiClientCommands::Transfer {
                 to,
                 object_id,
@@ -862,6 +966,64 @@ impl SuiClientCommands {
     }
 }
 
+/// Build the package at `package_path` for publishing in the active environment: dependencies
+/// built with address 0x0 are resolved to the addresses they are published at there, and their
+/// on-chain bytecode is checked against the local build.
+async fn build_and_resolve_package(
+    context: &WalletContext,
+    package_path: &Path,
+    build_config: MoveBuildConfig,
+) -> Result<CompiledPackage, anyhow::Error> {
+    let mut compiled_package = build_move_package(
+        package_path,
+        BuildConfig {
+            config: build_config,
+            run_bytecode_verifier: true,
+            print_diags_to_stderr: true,
+        },
+    )?;
+    compiled_package.resolve_published_dependencies(&context.config.get_active_env()?.alias)?;
+    for package_id in compiled_package.published_dependencies.values() {
+        let on_chain_modules = match context.client.read_api().get_object(*package_id).await? {
+            SuiObjectRead::Exists(SuiObject {
+                data: SuiRawData::Package(package),
+                ..
+            }) => package.module_map,
+            _ => {
+                return Err(anyhow!(
+                    "Cannot find the dependency published at {package_id} in the active environment"
+                ))
+            }
+        };
+        compiled_package.verify_published_dependency(*package_id, &on_chain_modules)?;
+    }
+    Ok(compiled_package)
+}
+
+/// Record the package published by a successful publish or upgrade transaction in the
+/// `Move.published` file of the package at `package_path`, so packages depending on it can be
+/// resolved to it.
+fn record_published_package(
+    context: &WalletContext,
+    package_path: &Path,
+    response: &SuiTransactionResponse,
+) -> Result<(), anyhow::Error> {
+    let package_id = response
+        .effects
+        .events
+        .iter()
+        .find_map(|event| match event {
+            SuiEvent::Publish { package_id, .. } => Some(*package_id),
+            _ => None,
+        });
+    if let Some(package_id) = package_id {
+        let mut published = PublishedAddresses::read(package_path)?;
+        published.insert(&context.config.get_active_env()?.alias, package_id);
+        published.write(package_path)?;
+    }
+    Ok(())
+}
+
 pub struct WalletContext {
     pub config: PersistedConfig<SuiClientConfig>,
     pub client: SuiClient,
@@ -1041,6 +1203,13 @@ impl Display for SuiClientCommandResult {
                     writeln!(writer, "{}", parsed_resp)?;
                 }
             }
+            SuiClientCommandResult::Upgrade(response) => {
+                write!(
+                    writer,
+                    "{}",
+                    write_cert_and_effects(&response.certificate, &response.effects)?
+                )?;
+            }
             SuiClientCommandResult::Object(object_read) => {
                 let object = unwrap_err_to_string(|| Ok(object_read.object()?));
                 writeln!(writer, "{}", object)?;
@@ -1048,6 +1217,20 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::Call(cert, effects) => {
                 write!(writer, "{}", write_cert_and_effects(cert, effects)?)?;
             }
+            SuiClientCommandResult::Profile(path, profile) => {
+                writeln!(
+                    writer,
+                    "{}",
+                    "----- Transaction Effects (dry run) ----".bold()
+                )?;
+                write!(writer, "{}", profile.effects)?;
+                writeln!(
+                    writer,
+                    "Gas profile of {} call stacks written to {}",
+                    profile.stacks.len(),
+                    path.display()
+                )?;
+            }
             SuiClientCommandResult::Transfer(time_elapsed, cert, effects) => {
                 writeln!(writer, "Transfer confirmed after {} us", time_elapsed)?;
                 write!(writer, "{}", write_cert_and_effects(cert, effects)?)?;
@@ -1196,6 +1379,34 @@ pub async fn call_move(
     args: Vec<SuiJsonValue>,
     context: &mut WalletContext,
 ) -> Result<(SuiCertifiedTransaction, SuiTransactionEffects), anyhow::Error> {
+    let (sender, data) = move_call_data(
+        package, module, function, type_args, gas, gas_budget, args, context,
+    )
+    .await?;
+    let signature = context.config.keystore.sign(&sender, &data.to_bytes())?;
+    let transaction = Transaction::from_data(data, signature).verify()?;
+
+    let response = context.execute_transaction(transaction).await?;
+    let cert = response.certificate;
+    let effects = response.effects;
+
+    if matches!(effects.status, SuiExecutionStatus::Failure { .. }) {
+        return Err(anyhow!("Error calling module: {:#?}", effects.status));
+    }
+    Ok((cert, effects))
+}
+
+/// The transaction calling `function`, and its sender
+async fn move_call_data(
+    package: ObjectID,
+    module: &str,
+    function: &str,
+    type_args: Vec<TypeTag>,
+    gas: Option<ObjectID>,
+    gas_budget: u64,
+    args: Vec<SuiJsonValue>,
+    context: &mut WalletContext,
+) -> Result<(SuiAddress, TransactionData), anyhow::Error> {
     let gas_owner = context.try_get_object_owner(&gas).await?;
     let sender = gas_owner.unwrap_or(context.active_address()?);
 
@@ -1216,17 +1427,7 @@ pub async fn call_move(
             gas_budget,
         )
         .await?;
-    let signature = context.config.keystore.sign(&sender, &data.to_bytes())?;
-    let transaction = Transaction::from_data(data, signature).verify()?;
-
-    let response = context.execute_transaction(transaction).await?;
-    let cert = response.certificate;
-    let effects = response.effects;
-
-    if matches!(effects.status, SuiExecutionStatus::Failure { .. }) {
-        return Err(anyhow!("Error calling module: {:#?}", effects.status));
-    }
-    Ok((cert, effects))
+    Ok((sender, data))
 }
 
 fn unwrap_or<'a>(val: &'a Option<String>, default: &'a str) -> &'a str {
@@ -1288,8 +1489,10 @@ impl SuiClientCommandResult {
 #[serde(untagged)]
 pub enum SuiClientCommandResult {
     Publish(SuiTransactionResponse),
+    Upgrade(SuiTransactionResponse),
     Object(GetObjectDataResponse),
     Call(SuiCertifiedTransaction, SuiTransactionEffects),
+    Profile(PathBuf, SuiGasProfile),
     Transfer(
         // Skipping serialisation for elapsed time.
         #[serde(skip)] u128,
@@ -224,7 +224,7 @@ impl SuiCommand {
                 package_path,
                 build_config,
                 cmd,
-            } => execute_move_command(package_path, build_config, cmd),
+            } => execute_move_command(package_path, build_config, cmd).await,
         }
     }
 }
@@ -28,6 +28,9 @@ pub struct Build {
     /// and events.
     #[clap(long, global = true)]
     pub generate_struct_layouts: bool,
+    /// If true, run the Sui lints over the package and report their warnings
+    #[clap(long, global = true)]
+    pub lint: bool,
 }
 
 impl Build {
@@ -42,6 +45,7 @@ impl Build {
             build_config,
             self.dump_bytecode_as_base64,
             self.generate_struct_layouts,
+            self.lint,
         )
     }
 
@@ -50,6 +54,7 @@ impl Build {
         config: MoveBuildConfig,
         dump_bytecode_as_base64: bool,
         generate_struct_layouts: bool,
+        lint: bool,
     ) -> anyhow::Result<()> {
         let pkg = sui_framework::build_move_package(
             rerooted_path,
@@ -59,6 +64,16 @@ impl Build {
                 print_diags_to_stderr: true,
             },
         )?;
+        if lint {
+            let warnings = pkg.lint();
+            for warning in &warnings {
+                eprintln!("{}\n", warning);
+            }
+            if !warnings.is_empty() {
+                eprintln!("{} lint warning(s)", warnings.len());
+            }
+        }
+
         if dump_bytecode_as_base64 {
             println!("{}", json!(pkg.get_package_base64()))
         }
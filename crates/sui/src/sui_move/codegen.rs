@@ -0,0 +1,49 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::{ArgEnum, Parser};
+use move_cli::base;
+use move_package::BuildConfig as MoveBuildConfig;
+use std::{fs, path::PathBuf};
+use sui_framework_build::{codegen, compiled_package::BuildConfig};
+
+#[derive(Clone, Copy, ArgEnum)]
+pub enum Language {
+    Rust,
+}
+
+#[derive(Parser)]
+pub struct Codegen {
+    /// The language to generate the package's types and entry function wrappers in
+    #[clap(long, arg_enum)]
+    pub lang: Language,
+    /// The file to write the generated code to. Printed to stdout if not set
+    #[clap(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+impl Codegen {
+    pub fn execute(
+        self,
+        path: Option<PathBuf>,
+        build_config: MoveBuildConfig,
+    ) -> anyhow::Result<()> {
+        let rerooted_path = base::reroot_path(path)?;
+        let pkg = sui_framework::build_move_package(
+            &rerooted_path,
+            BuildConfig {
+                config: build_config,
+                run_bytecode_verifier: true,
+                print_diags_to_stderr: true,
+            },
+        )?;
+        let code = match self.lang {
+            Language::Rust => codegen::generate_rust(&pkg),
+        };
+        match self.output {
+            Some(output) => fs::write(output, code)?,
+            None => print!("{code}"),
+        }
+        Ok(())
+    }
+}
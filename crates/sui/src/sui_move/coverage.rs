@@ -2,19 +2,61 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use clap::Parser;
-use move_cli::base::coverage;
-use move_package::BuildConfig;
-use std::path::PathBuf;
+use move_cli::base::{
+    self,
+    coverage::{self, CoverageSummaryOptions},
+};
+use move_coverage::coverage_map::CoverageMap;
+use move_package::BuildConfig as MoveBuildConfig;
+use std::{fs, path::PathBuf};
+use sui_framework_build::{compiled_package::BuildConfig, lcov};
+
+/// Where `sui move test --coverage` stores the coverage of the tests, in the package directory
+const COVERAGE_MAP_FILE: &str = ".coverage_map.mvcov";
 
 #[derive(Parser)]
-pub struct Coverage {
+pub enum Coverage {
+    /// Export the coverage of the package's modules in the lcov format
+    Lcov {
+        /// Write the coverage to this file instead of standard output
+        #[clap(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
     #[clap(flatten)]
-    pub coverage: coverage::Coverage,
+    Report(CoverageSummaryOptions),
 }
 
 impl Coverage {
-    pub fn execute(self, path: Option<PathBuf>, build_config: BuildConfig) -> anyhow::Result<()> {
-        self.coverage.execute(path, build_config)?;
+    pub fn execute(
+        self,
+        path: Option<PathBuf>,
+        build_config: MoveBuildConfig,
+    ) -> anyhow::Result<()> {
+        match self {
+            Coverage::Lcov { output } => {
+                let rerooted_path = base::reroot_path(path)?;
+                let coverage_map =
+                    CoverageMap::from_binary_file(rerooted_path.join(COVERAGE_MAP_FILE))?;
+                // tests, and their coverage, are compiled in test mode
+                let pkg = BuildConfig {
+                    config: MoveBuildConfig {
+                        test_mode: true,
+                        ..build_config
+                    },
+                    run_bytecode_verifier: false,
+                    print_diags_to_stderr: true,
+                }
+                .build(rerooted_path)?;
+                let lcov = lcov::lcov(&pkg, &coverage_map.to_unified_exec_map());
+                match output {
+                    Some(output) => fs::write(output, lcov)?,
+                    None => print!("{lcov}"),
+                }
+            }
+            Coverage::Report(options) => {
+                coverage::Coverage { options }.execute(path, build_config)?;
+            }
+        }
         Ok(())
     }
 }
@@ -8,20 +8,24 @@ use move_unit_test::UnitTestingConfig;
 use std::path::PathBuf;
 
 pub mod build;
+pub mod codegen;
 pub mod coverage;
 pub mod disassemble;
 pub mod new;
 pub mod prove;
 pub mod unit_test;
+pub mod verify_source;
 
 #[derive(Parser)]
 pub enum Command {
     Build(build::Build),
+    Codegen(codegen::Codegen),
     Coverage(coverage::Coverage),
     Disassemble(disassemble::Disassemble),
     New(new::New),
     Prove(prove::Prove),
     Test(unit_test::Test),
+    VerifySource(verify_source::VerifySource),
     CalibrateCosts(Calib),
 }
 #[derive(Parser)]
@@ -32,13 +36,14 @@ pub struct Calib {
     summarize: bool,
 }
 
-pub fn execute_move_command(
+pub async fn execute_move_command(
     package_path: Option<PathBuf>,
     build_config: BuildConfig,
     command: Command,
 ) -> anyhow::Result<()> {
     match command {
         Command::Build(c) => c.execute(package_path, build_config),
+        Command::Codegen(c) => c.execute(package_path, build_config),
         Command::Coverage(c) => c.execute(package_path, build_config),
         Command::Disassemble(c) => c.execute(package_path, build_config),
         Command::New(c) => c.execute(package_path),
@@ -65,6 +70,7 @@ pub fn execute_move_command(
 
             Ok(())
         }
+        Command::VerifySource(c) => c.execute(package_path, build_config).await,
         Command::CalibrateCosts(c) => {
             sui_framework::cost_calib::run_calibration(c.runs, c.summarize);
             Ok(())
@@ -9,25 +9,53 @@ use move_cli::base::{
 };
 use move_package::BuildConfig;
 use move_unit_test::UnitTestingConfig;
-use std::path::PathBuf;
+use std::{
+    cmp::Reverse,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use sui_framework_build::{
+    compiled_package::{BuildConfig as SuiBuildConfig, CompiledPackage},
+    listing::{self, SourceLines},
+};
+
+/// Where `sui move test --debug` writes the listings of the package's modules, in the package's
+/// build directory
+const LISTINGS_DIR: &str = "debug";
+
+/// Set in the environment of the process `sui move test --debug` runs the tests in, whose output
+/// it annotates
+const DEBUGGEE_ENV_VAR: &str = "SUI_MOVE_TEST_DEBUGGEE";
 
 #[derive(Parser)]
 pub struct Test {
     #[clap(flatten)]
     pub test: test::Test,
+    /// Report the gas used by each test, most expensive first
+    #[clap(long)]
+    pub gas_report: bool,
+    /// Fail the tests that use more gas than this
+    #[clap(long, value_name = "GAS")]
+    pub gas_threshold: Option<u64>,
+    /// Run the tests in the Move VM's step debugger, and write listings of the package's bytecode
+    /// annotated with its sources, to follow the debugger in the sources
+    #[clap(long)]
+    pub debug: bool,
 }
 impl Test {
     pub fn execute(
         &self,
         path: Option<PathBuf>,
         build_config: BuildConfig,
-        unit_test_config: UnitTestingConfig,
+        mut unit_test_config: UnitTestingConfig,
     ) -> anyhow::Result<UnitTestResult> {
         // find manifest file directory from a given path or (if missing) from current dir
         let rerooted_path = base::reroot_path(path)?;
         // pre build for Sui-specific verifications
         let dump_bytecode_as_base64 = false;
         let generate_struct_layouts: bool = false;
+        let lint = false;
         build::Build::execute_internal(
             &rerooted_path,
             BuildConfig {
@@ -36,12 +64,99 @@ impl Test {
             },
             dump_bytecode_as_base64,
             generate_struct_layouts,
+            lint,
         )?;
-        sui_framework::run_move_unit_tests(
+        if self.debug {
+            if env::var_os(DEBUGGEE_ENV_VAR).is_none() {
+                return debug_tests(&rerooted_path, build_config);
+            }
+            // the debugger reads commands from standard input, which tests can only share one at a
+            // time. It is enabled when the VM first executes, so this must come before any test.
+            unit_test_config.num_threads = 1;
+            env::set_var("MOVE_VM_STEP", "1");
+        }
+        if !self.gas_report && self.gas_threshold.is_none() {
+            return sui_framework::run_move_unit_tests(
+                &rerooted_path,
+                build_config,
+                Some(unit_test_config),
+                self.test.compute_coverage,
+            );
+        }
+
+        let (mut result, mut gas) = sui_framework::run_move_unit_tests_with_gas(
             &rerooted_path,
             build_config,
             Some(unit_test_config),
             self.test.compute_coverage,
-        )
+        )?;
+        gas.sort_by_key(|test| Reverse(test.gas_used));
+        if self.gas_report && !gas.is_empty() {
+            println!("\nGas used by each test:");
+            let width = gas[0].gas_used.to_string().len();
+            for test in &gas {
+                println!("  {:>width$}  {}", test.gas_used, test.name);
+            }
+        }
+        if let Some(threshold) = self.gas_threshold {
+            let over_threshold: Vec<_> = gas.iter().filter(|t| t.gas_used > threshold).collect();
+            for test in &over_threshold {
+                println!(
+                    "[ FAIL    ] {} used {} gas, more than the threshold of {threshold}",
+                    test.name, test.gas_used
+                );
+            }
+            if !over_threshold.is_empty() {
+                result = UnitTestResult::Failure;
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Run the tests in the Move VM's step debugger, in a process of their own whose output is
+/// annotated with the source line of each program counter the debugger stops at.
+fn debug_tests(rerooted_path: &Path, build_config: BuildConfig) -> anyhow::Result<UnitTestResult> {
+    // tests are compiled in test mode, and so must the bytecode they step through
+    let pkg = SuiBuildConfig {
+        config: BuildConfig {
+            test_mode: true,
+            ..build_config
+        },
+        run_bytecode_verifier: false,
+        print_diags_to_stderr: false,
+    }
+    .build(rerooted_path.to_path_buf())?;
+    write_listings(&pkg)?;
+
+    let mut debuggee = Command::new(env::current_exe()?)
+        .args(env::args_os().skip(1))
+        .env(DEBUGGEE_ENV_VAR, "1")
+        .stdout(Stdio::piped())
+        .spawn()?;
+    // Safe to unwrap, the output of the debuggee is piped
+    let output = debuggee.stdout.take().unwrap();
+    SourceLines::new(&pkg).annotate(output, io::stdout())?;
+    Ok(if debuggee.wait()?.success() {
+        UnitTestResult::Success
+    } else {
+        UnitTestResult::Failure
+    })
+}
+
+fn write_listings(pkg: &CompiledPackage) -> anyhow::Result<()> {
+    // store under <package_path>/build/<package_name>/debug/<module>.txt
+    let mut dir = pkg.path.clone();
+    dir.push("build");
+    dir.push(pkg.package.compiled_package_info.package_name.as_str());
+    dir.push(LISTINGS_DIR);
+    fs::create_dir_all(&dir)?;
+    for (module, listing) in listing::listings(pkg) {
+        fs::write(dir.join(format!("{module}.txt")), listing)?;
     }
+    println!(
+        "Listings of the package's bytecode are in {}",
+        dir.display()
+    );
+    Ok(())
 }
@@ -0,0 +1,334 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use anyhow::bail;
+use clap::Parser;
+use move_binary_format::{access::ModuleAccess, normalized, CompiledModule};
+use move_cli::base;
+use move_core_types::account_address::AccountAddress;
+use move_package::BuildConfig as MoveBuildConfig;
+use serde::{Deserialize, Serialize};
+use sui_adapter::adapter::substitute_package_id;
+use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
+use sui_framework_build::compiled_package::{BuildConfig, CompiledPackage};
+use sui_json_rpc_types::{SuiObjectRead, SuiRawData};
+use sui_types::base_types::ObjectID;
+
+#[cfg(msim)]
+use sui_sdk::embedded_gateway::ReadApi;
+#[cfg(not(msim))]
+use sui_sdk::ReadApi;
+
+use crate::config::{Config, SuiEnv};
+
+#[derive(Parser)]
+pub struct VerifySource {
+    /// The ID of the on-chain package the local package is expected to be built into
+    #[clap(long)]
+    pub package: ObjectID,
+    /// Only verify the package itself, not the on-chain packages it depends on
+    #[clap(long)]
+    pub skip_deps: bool,
+    /// The client config whose active environment is used to fetch the on-chain packages
+    #[clap(long = "client.config")]
+    pub config: Option<PathBuf>,
+}
+
+impl VerifySource {
+    pub async fn execute(
+        self,
+        path: Option<PathBuf>,
+        build_config: MoveBuildConfig,
+    ) -> anyhow::Result<()> {
+        let rerooted_path = base::reroot_path(path)?;
+        let mut compiled_package = sui_framework::build_move_package(
+            &rerooted_path,
+            BuildConfig {
+                config: build_config,
+                run_bytecode_verifier: true,
+                print_diags_to_stderr: true,
+            },
+        )?;
+
+        let config_path = self
+            .config
+            .unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
+        let envs = ClientEnvs::load(&config_path)?;
+        let env = envs.active_env()?;
+        // Dependencies that are not published in the environment keep the address 0x0, which
+        // verifying the dependencies reports
+        compiled_package.resolve_available_dependencies(&env.alias)?;
+
+        #[cfg(not(msim))]
+        let client = env.create_rpc_client(None).await?;
+        #[cfg(msim)]
+        let client = sui_sdk::embedded_gateway::SuiClient::new(&config_path.parent().unwrap())?;
+        let verifier = BytecodeSourceVerifier::new(client.read_api());
+        let mut mismatches = verifier
+            .verify_package(&compiled_package, self.package)
+            .await?;
+        if !self.skip_deps {
+            mismatches.extend(verifier.verify_dependencies(&compiled_package).await?);
+        }
+
+        if !mismatches.is_empty() {
+            for mismatch in &mismatches {
+                eprintln!("{mismatch}");
+            }
+            bail!(
+                "Source verification failed for package {}: {} mismatch(es) found",
+                self.package,
+                mismatches.len()
+            );
+        }
+        println!(
+            "Source verification succeeded: package {} matches the local sources",
+            self.package
+        );
+        Ok(())
+    }
+}
+
+/// The environments of the client config. Verifying sources only reads the chain, so unlike
+/// `WalletContext` it does not open the keystore.
+#[derive(Serialize, Deserialize)]
+struct ClientEnvs {
+    envs: Vec<SuiEnv>,
+    active_env: Option<String>,
+}
+
+impl Config for ClientEnvs {}
+
+impl ClientEnvs {
+    fn active_env(&self) -> anyhow::Result<&SuiEnv> {
+        let env = match &self.active_env {
+            Some(alias) => self.envs.iter().find(|env| &env.alias == alias),
+            None => self.envs.first(),
+        };
+        env.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Environment configuration not found for env [{}]",
+                self.active_env.as_deref().unwrap_or("None")
+            )
+        })
+    }
+}
+
+/// A difference between a locally built package and the packages published on chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceMismatch {
+    /// There is no package at this address on chain
+    PackageNotFound { package: ObjectID },
+    /// The dependency has not been published, its modules still have the address 0x0
+    DependencyNotPublished { dependency: String },
+    /// A module built locally is missing from the on-chain package
+    ModuleNotOnChain { package: ObjectID, module: String },
+    /// A module of the on-chain package is not built from the local sources
+    ModuleNotLocal { package: ObjectID, module: String },
+    /// The local and on-chain bytecode of the module differ
+    ModuleBytecode {
+        package: ObjectID,
+        module: String,
+        differences: Vec<String>,
+    },
+}
+
+impl Display for SourceMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceMismatch::PackageNotFound { package } => {
+                write!(f, "No package found on chain at {package}")
+            }
+            SourceMismatch::DependencyNotPublished { dependency } => {
+                write!(
+                    f,
+                    "Dependency {dependency} is not published: its modules have address 0x0"
+                )
+            }
+            SourceMismatch::ModuleNotOnChain { package, module } => {
+                write!(
+                    f,
+                    "Module {module} is built locally but is not in on-chain package {package}"
+                )
+            }
+            SourceMismatch::ModuleNotLocal { package, module } => {
+                write!(
+                    f,
+                    "Module {module} of on-chain package {package} is not built locally"
+                )
+            }
+            SourceMismatch::ModuleBytecode {
+                package,
+                module,
+                differences,
+            } => {
+                write!(
+                    f,
+                    "Module {module} of on-chain package {package} does not match the local bytecode: {}",
+                    differences.join("; ")
+                )
+            }
+        }
+    }
+}
+
+/// Compares the bytecode built from local sources with the bytecode of packages published on
+/// chain, module by module.
+pub struct BytecodeSourceVerifier<'a> {
+    read_api: &'a ReadApi,
+}
+
+impl<'a> BytecodeSourceVerifier<'a> {
+    pub fn new(read_api: &'a ReadApi) -> Self {
+        Self { read_api }
+    }
+
+    /// Verify that the root modules of `compiled_package` are the modules of the on-chain package
+    /// `package_id`. The local modules are bound to `package_id` the same way publishing does
+    /// before they are compared, so dependencies must be resolved to the addresses they are
+    /// published at first.
+    pub async fn verify_package(
+        &self,
+        compiled_package: &CompiledPackage,
+        package_id: ObjectID,
+    ) -> anyhow::Result<Vec<SourceMismatch>> {
+        let mut modules = compiled_package.get_dependency_sorted_modules();
+        substitute_package_id(&mut modules, package_id)
+            .map_err(|e| anyhow::anyhow!("Cannot bind the local modules to {package_id}: {e}"))?;
+        self.compare(package_id, modules.iter()).await
+    }
+
+    /// Verify that every dependency of `compiled_package` is published on chain at the address it
+    /// is built with, or it is resolved to, and that the on-chain package is built from the same
+    /// sources.
+    pub async fn verify_dependencies(
+        &self,
+        compiled_package: &CompiledPackage,
+    ) -> anyhow::Result<Vec<SourceMismatch>> {
+        let published_modules = compiled_package.get_published_dependency_modules();
+        let mut mismatches = vec![];
+        for (dependency, modules) in compiled_package.get_dependencies_by_package() {
+            if let Some(published_at) = compiled_package.published_dependencies.get(&dependency) {
+                mismatches.extend(
+                    self.compare(*published_at, published_modules[published_at].iter())
+                        .await?,
+                );
+                continue;
+            }
+            let address = *modules[0].address();
+            if address == AccountAddress::ZERO {
+                mismatches.push(SourceMismatch::DependencyNotPublished { dependency });
+                continue;
+            }
+            mismatches.extend(
+                self.compare(ObjectID::from(address), modules.into_iter())
+                    .await?,
+            );
+        }
+        Ok(mismatches)
+    }
+
+    async fn compare(
+        &self,
+        package_id: ObjectID,
+        local_modules: impl Iterator<Item = &CompiledModule>,
+    ) -> anyhow::Result<Vec<SourceMismatch>> {
+        let mut on_chain_modules = match self.read_api.get_object(package_id).await? {
+            SuiObjectRead::Exists(object) => match object.data {
+                SuiRawData::Package(package) => package.module_map,
+                SuiRawData::MoveObject(_) => {
+                    return Ok(vec![SourceMismatch::PackageNotFound {
+                        package: package_id,
+                    }])
+                }
+            },
+            SuiObjectRead::NotExists(_) | SuiObjectRead::Deleted(_) => {
+                return Ok(vec![SourceMismatch::PackageNotFound {
+                    package: package_id,
+                }])
+            }
+        };
+
+        let mut mismatches = vec![];
+        for local in local_modules {
+            let module = local.self_id().name().to_string();
+            let Some(on_chain_bytes) = on_chain_modules.remove(&module) else {
+                mismatches.push(SourceMismatch::ModuleNotOnChain {
+                    package: package_id,
+                    module,
+                });
+                continue;
+            };
+            let mut local_bytes = vec![];
+            local.serialize(&mut local_bytes)?;
+            if local_bytes == on_chain_bytes {
+                continue;
+            }
+            let differences = match CompiledModule::deserialize(&on_chain_bytes) {
+                Ok(on_chain) => module_differences(local, &on_chain),
+                Err(e) => vec![format!("the on-chain bytecode cannot be deserialized: {e}")],
+            };
+            mismatches.push(SourceMismatch::ModuleBytecode {
+                package: package_id,
+                module,
+                differences,
+            });
+        }
+        mismatches.extend(on_chain_modules.into_keys().map(|module| {
+            SourceMismatch::ModuleNotLocal {
+                package: package_id,
+                module,
+            }
+        }));
+        Ok(mismatches)
+    }
+}
+
+/// Describe how the declarations of two versions of a module differ. If their declarations
+/// match, the difference is in code that does not show up in the module's interface.
+fn module_differences(local: &CompiledModule, on_chain: &CompiledModule) -> Vec<String> {
+    let local = normalized::Module::new(local);
+    let on_chain = normalized::Module::new(on_chain);
+
+    let mut differences = vec![];
+    if local.friends != on_chain.friends {
+        differences.push("friend declarations differ".to_string());
+    }
+    diff_declarations(
+        "struct",
+        &local.structs,
+        &on_chain.structs,
+        &mut differences,
+    );
+    diff_declarations(
+        "function",
+        &local.exposed_functions,
+        &on_chain.exposed_functions,
+        &mut differences,
+    );
+    if differences.is_empty() {
+        differences.push("function bodies, private functions or constants differ".to_string());
+    }
+    differences
+}
+
+fn diff_declarations<K: Ord + Display, V: PartialEq>(
+    kind: &str,
+    local: &BTreeMap<K, V>,
+    on_chain: &BTreeMap<K, V>,
+    differences: &mut Vec<String>,
+) {
+    let names: BTreeSet<&K> = local.keys().chain(on_chain.keys()).collect();
+    for name in names {
+        match (local.get(name), on_chain.get(name)) {
+            (Some(_), None) => differences.push(format!("{kind} {name} is not on chain")),
+            (None, Some(_)) => differences.push(format!("{kind} {name} is only on chain")),
+            (Some(l), Some(o)) if l != o => differences.push(format!("{kind} {name} differs")),
+            _ => (),
+        }
+    }
+}
@@ -12,6 +12,7 @@ use sui::{
     client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext},
     config::SuiClientConfig,
     sui_commands::SuiCommand,
+    sui_move::verify_source::{BytecodeSourceVerifier, SourceMismatch},
 };
 use sui_config::genesis_config::{AccountConfig, GenesisConfig, ObjectConfig};
 use sui_config::{
@@ -20,7 +21,7 @@ use sui_config::{
 };
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    GetObjectDataResponse, SuiData, SuiObject, SuiParsedData, SuiParsedObject,
+    GetObjectDataResponse, SuiData, SuiExecutionStatus, SuiObject, SuiParsedData, SuiParsedObject,
     SuiTransactionEffects,
 };
 use sui_keys::keystore::AccountKeystore;
@@ -29,6 +30,7 @@ use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{
     Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
 };
+use sui_types::object::Owner;
 use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
 use sui_types::{sui_framework_address_concat_string, SUI_FRAMEWORK_ADDRESS};
 use test_utils::messages::make_transactions_with_wallet_context;
@@ -308,6 +310,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         build_config,
         gas: Some(gas_obj_id),
         gas_budget: 20_000,
+        upgradeable: false,
     }
     .execute(context)
     .await?;
@@ -355,6 +358,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args,
         gas: None,
         gas_budget: 20_000,
+        profile: None,
     }
     .execute(context)
     .await?;
@@ -395,6 +399,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 20_000,
+        profile: None,
     }
     .execute(context)
     .await;
@@ -419,6 +424,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 20_000,
+        profile: None,
     }
     .execute(context)
     .await;
@@ -444,6 +450,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 20_000,
+        profile: None,
     }
     .execute(context)
     .await?;
@@ -451,6 +458,81 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_move_call_profile() -> Result<(), anyhow::Error> {
+    let mut test_cluster = init_cluster_builder_env_aware().build().await?;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+
+    let object_refs = context
+        .client
+        .read_api()
+        .get_objects_owned_by_address(address)
+        .await?;
+    let gas_obj_id = object_refs.first().unwrap().object_id;
+    let mut package_path = PathBuf::from(TEST_DATA_DIR);
+    package_path.push("move_call_args_linter");
+    let resp = SuiClientCommands::Publish {
+        package_path,
+        build_config: BuildConfig::default(),
+        gas: Some(gas_obj_id),
+        gas_budget: 20_000,
+        upgradeable: false,
+    }
+    .execute(context)
+    .await?;
+    let package = if let SuiClientCommandResult::Publish(response) = resp {
+        response.effects.created[0].reference.object_id
+    } else {
+        unreachable!("Invalid response");
+    };
+
+    let temp_dir = tempfile::tempdir()?;
+    let profile_path = temp_dir.path().join("create.folded");
+    let resp = SuiClientCommands::Call {
+        package,
+        module: "object_basics".to_string(),
+        function: "create".to_string(),
+        type_args: vec![],
+        args: vec![
+            SuiJsonValue::new(json!(123u8))?,
+            SuiJsonValue::new(json!(address))?,
+        ],
+        gas: None,
+        gas_budget: 20_000,
+        profile: Some(profile_path.clone()),
+    }
+    .execute(context)
+    .await?;
+
+    let SuiClientCommandResult::Profile(path, profile) = &resp else {
+        panic!("Expected a gas profile, got {resp}");
+    };
+    assert_eq!(path, &profile_path);
+    assert_eq!(profile.effects.status, SuiExecutionStatus::Success);
+
+    // every call stack starts at the function called, and the calls it makes are profiled too
+    let root = format!("{}::object_basics::create", package.to_hex_literal());
+    assert!(!profile.stacks.is_empty());
+    assert!(profile.stacks.iter().all(|stack| stack.frames[0] == root));
+    assert!(profile.stacks.iter().any(|stack| stack.frames.len() > 1));
+    assert_eq!(std::fs::read_to_string(&profile_path)?, profile.to_folded());
+    assert!(format!("{resp}").contains(&format!(
+        "Gas profile of {} call stacks written to {}",
+        profile.stacks.len(),
+        profile_path.display()
+    )));
+
+    // the call is only dry run: the object it creates does not exist
+    let created = profile.effects.created[0].reference.object_id;
+    assert!(matches!(
+        context.client.read_api().get_parsed_object(created).await?,
+        GetObjectDataResponse::NotExists(_)
+    ));
+
+    Ok(())
+}
+
 #[allow(clippy::assertions_on_constants)]
 #[sim_test]
 async fn test_package_publish_command() -> Result<(), anyhow::Error> {
@@ -476,6 +558,7 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
         build_config,
         gas: Some(gas_obj_id),
         gas_budget: 20_000,
+        upgradeable: false,
     }
     .execute(context)
     .await?;
@@ -502,6 +585,84 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_verify_source() -> Result<(), anyhow::Error> {
+    let mut test_cluster = init_cluster_builder_env_aware().build().await?;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+
+    let object_refs = context
+        .client
+        .read_api()
+        .get_objects_owned_by_address(address)
+        .await?;
+    let gas_obj_id = object_refs.first().unwrap().object_id;
+
+    let mut package_path = PathBuf::from(TEST_DATA_DIR);
+    package_path.push("dummy_modules_publish");
+    let resp = SuiClientCommands::Publish {
+        package_path: package_path.clone(),
+        build_config: BuildConfig::default(),
+        gas: Some(gas_obj_id),
+        gas_budget: 20_000,
+        upgradeable: false,
+    }
+    .execute(context)
+    .await?;
+    let package_id = if let SuiClientCommandResult::Publish(response) = resp {
+        response
+            .effects
+            .created
+            .iter()
+            .find(|refe| refe.owner == Owner::Immutable)
+            .unwrap()
+            .reference
+            .object_id
+    } else {
+        unreachable!("Invalid response");
+    };
+
+    let compiled_package = sui_framework::build_move_package(
+        &package_path,
+        sui_framework_build::compiled_package::BuildConfig::default(),
+    )?;
+    let verifier = BytecodeSourceVerifier::new(context.client.read_api());
+
+    // The published package and the framework it depends on match the local sources
+    assert_eq!(
+        verifier
+            .verify_package(&compiled_package, package_id)
+            .await?,
+        vec![]
+    );
+    assert_eq!(
+        verifier.verify_dependencies(&compiled_package).await?,
+        vec![]
+    );
+
+    // Every module of the framework package is reported as mismatching
+    let mismatches = verifier
+        .verify_package(&compiled_package, ObjectID::from(SUI_FRAMEWORK_ADDRESS))
+        .await?;
+    assert!(mismatches.contains(&SourceMismatch::ModuleNotOnChain {
+        package: ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+        module: "trusted_coin".to_string(),
+    }));
+    assert!(mismatches.contains(&SourceMismatch::ModuleNotLocal {
+        package: ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+        module: "coin".to_string(),
+    }));
+
+    // There is no package at a random address
+    let missing = ObjectID::random();
+    assert_eq!(
+        verifier.verify_package(&compiled_package, missing).await?,
+        vec![SourceMismatch::PackageNotFound { package: missing }]
+    );
+
+    Ok(())
+}
+
 #[allow(clippy::assertions_on_constants)]
 #[sim_test]
 async fn test_native_transfer() -> Result<(), anyhow::Error> {
@@ -0,0 +1,81 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// @generated by `sui move codegen --lang rust`, do not edit by hand
+
+use serde::{Deserialize, Serialize};
+
+/// Move type `0000000000000000000000000000000000000000::counter::Counter`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Counter {
+    pub id: sui_sdk::types::id::UID,
+    pub name: String,
+    pub value: u64,
+}
+
+/// Move type `0000000000000000000000000000000000000000::counter::Incremented`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Incremented {
+    pub counter: sui_sdk::types::base_types::SuiAddress,
+    pub by: u64,
+}
+
+/// Entry functions of module `counter`
+pub mod counter {
+
+    /// Build a transaction calling entry function `counter::create`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        client: &sui_sdk::SuiClient,
+        package: sui_sdk::types::base_types::ObjectID,
+        signer: sui_sdk::types::base_types::SuiAddress,
+        name: String,
+        gas: Option<sui_sdk::types::base_types::ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<sui_sdk::types::messages::TransactionData> {
+        client
+            .transaction_builder()
+            .move_call(
+                signer,
+                package,
+                "counter",
+                "create",
+                vec![],
+                vec![
+                    sui_sdk::move_call::MoveCallArg::to_sui_json(&name)?,
+                ],
+                gas,
+                gas_budget,
+            )
+            .await
+    }
+
+    /// Build a transaction calling entry function `counter::increment`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn increment(
+        client: &sui_sdk::SuiClient,
+        package: sui_sdk::types::base_types::ObjectID,
+        signer: sui_sdk::types::base_types::SuiAddress,
+        counter: sui_sdk::types::base_types::ObjectID,
+        by: u64,
+        gas: Option<sui_sdk::types::base_types::ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<sui_sdk::types::messages::TransactionData> {
+        client
+            .transaction_builder()
+            .move_call(
+                signer,
+                package,
+                "counter",
+                "increment",
+                vec![],
+                vec![
+                    sui_sdk::move_call::MoveCallArg::to_sui_json(&counter)?,
+                    sui_sdk::move_call::MoveCallArg::to_sui_json(&by)?,
+                ],
+                gas,
+                gas_budget,
+            )
+            .await
+    }
+}
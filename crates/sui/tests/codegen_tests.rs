@@ -0,0 +1,160 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks the Rust client generated by `sui move codegen --lang rust` for the `codegen` test
+//! package of `sui-framework-build`: the generated code is checked in under `tests/codegen`, so
+//! that it is compiled with these tests, and is used to call the package on a local network and
+//! read back its objects and events.
+
+use std::path::PathBuf;
+
+use sui::client_commands::WalletContext;
+use sui_framework_build::{codegen, compiled_package::BuildConfig};
+use sui_json_rpc_types::{SuiEvent, SuiExecutionStatus, SuiRawData, SuiTransactionEffects};
+use sui_keys::keystore::AccountKeystore;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::messages::{ExecuteTransactionRequestType, Transaction, TransactionData};
+use sui_types::object::Owner;
+use test_utils::network::TestClusterBuilder;
+
+/// The generated client, behind the license header of the checked in file
+mod generated {
+    include!("codegen/counter.rs");
+}
+
+const GENERATED: &str = include_str!("codegen/counter.rs");
+
+const LICENSE_HEADER: &str =
+    "// Copyright (c) Mysten Labs, Inc.\n// SPDX-License-Identifier: Apache-2.0\n\n";
+
+fn package_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../sui-framework-build/src/unit_tests/data/codegen");
+    path
+}
+
+#[test]
+fn generated_client_is_up_to_date() {
+    let pkg = sui_framework::build_move_package(&package_path(), BuildConfig::default()).unwrap();
+    let code = codegen::generate_rust(&pkg);
+    assert_eq!(
+        GENERATED.strip_prefix(LICENSE_HEADER),
+        Some(code.as_str()),
+        "tests/codegen/counter.rs is out of date: regenerate it with `sui move codegen --lang rust`"
+    );
+}
+
+#[tokio::test]
+async fn generated_client_reads_objects_and_events() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let sender = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+
+    let modules = sui_framework::build_move_package(&package_path(), BuildConfig::default())?
+        .get_package_bytes();
+    let data = context
+        .client
+        .transaction_builder()
+        .publish(sender, modules, None, 50000)
+        .await?;
+    let effects = execute(context, sender, data).await;
+    let package = effects
+        .created
+        .iter()
+        .find(|object| object.owner == Owner::Immutable)
+        .unwrap()
+        .reference
+        .object_id;
+
+    let data = generated::counter::create(
+        &context.client,
+        package,
+        sender,
+        "hits".to_string(),
+        None,
+        50000,
+    )
+    .await?;
+    let effects = execute(context, sender, data).await;
+    let counter_id = effects
+        .created
+        .iter()
+        .find(|object| object.owner == Owner::AddressOwner(sender))
+        .unwrap()
+        .reference
+        .object_id;
+
+    let (counter, bytes) = read_counter(context, counter_id).await?;
+    assert_eq!(counter.id.object_id(), &counter_id);
+    assert_eq!(counter.name, "hits");
+    assert_eq!(counter.value, 0);
+    assert_eq!(bcs::to_bytes(&counter)?, bytes);
+
+    let data =
+        generated::counter::increment(&context.client, package, sender, counter_id, 7, None, 50000)
+            .await?;
+    let effects = execute(context, sender, data).await;
+    let event = effects
+        .events
+        .iter()
+        .find_map(|event| match event {
+            SuiEvent::MoveEvent { bcs, .. } => Some(bcs),
+            _ => None,
+        })
+        .unwrap();
+    let incremented: generated::Incremented = bcs::from_bytes(event)?;
+    assert_eq!(
+        incremented,
+        generated::Incremented {
+            counter: SuiAddress::from(counter_id),
+            by: 7,
+        }
+    );
+    assert_eq!(&bcs::to_bytes(&incremented)?, event);
+
+    let (counter, _) = read_counter(context, counter_id).await?;
+    assert_eq!(counter.value, 7);
+    Ok(())
+}
+
+/// Read the counter object `id` with the generated type, along with its BCS bytes
+async fn read_counter(
+    context: &WalletContext,
+    id: ObjectID,
+) -> Result<(generated::Counter, Vec<u8>), anyhow::Error> {
+    let object = context
+        .client
+        .read_api()
+        .get_object(id)
+        .await?
+        .into_object()?;
+    let SuiRawData::MoveObject(object) = object.data else {
+        panic!("Object {id} is not a Move object");
+    };
+    Ok((bcs::from_bytes(&object.bcs_bytes)?, object.bcs_bytes))
+}
+
+async fn execute(
+    context: &WalletContext,
+    sender: SuiAddress,
+    data: TransactionData,
+) -> SuiTransactionEffects {
+    let signature = context
+        .config
+        .keystore
+        .sign(&sender, &data.to_bytes())
+        .unwrap();
+    let transaction = Transaction::from_data(data, signature).verify().unwrap();
+    let response = context
+        .client
+        .quorum_driver()
+        .execute_transaction(
+            transaction,
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await
+        .unwrap();
+    let effects = response.effects.unwrap();
+    assert_eq!(effects.status, SuiExecutionStatus::Success);
+    effects
+}
@@ -12,9 +12,10 @@ use move_core_types::value::MoveStructLayout;
 use prometheus::Registry;
 use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
 use sui_json_rpc_types::{
-    type_and_fields_from_move_struct, EventPage, SuiEvent, SuiEventEnvelope, SuiEventFilter,
-    SuiExecuteTransactionResponse, SuiExecutionStatus, SuiMoveStruct, SuiMoveValue,
-    SuiTransactionFilter, SuiTransactionResponse,
+    type_and_fields_from_move_struct, CertifiedCheckpointBytes, EventPage, SuiEvent,
+    SuiEventEnvelope, SuiEventFilter, SuiExecuteTransactionResponse, SuiExecutionStatus,
+    SuiMoveStruct, SuiMoveValue, SuiTransactionFilter, SuiTransactionResponse,
+    TransactionInclusionProofBytes,
 };
 use sui_keys::keystore::AccountKeystore;
 use sui_macros::*;
@@ -1293,3 +1294,61 @@ async fn test_get_objects_read() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_full_node_transaction_inclusion_proof() -> Result<(), anyhow::Error> {
+    let mut test_cluster = init_cluster_builder_env_aware().build().await?;
+    let committee = test_cluster.swarm.config().genesis.committee()?;
+    let (object_id, _, _, digest, _, _) = transfer_coin(&mut test_cluster.wallet).await?;
+    let fullnode = test_cluster.fullnode_handle.as_ref().unwrap();
+    let jsonrpc_client = &fullnode.rpc_client;
+
+    // Wait for the transaction to be included in a certified checkpoint.
+    let proof = timeout(Duration::from_secs(60), async {
+        loop {
+            let proof: Option<TransactionInclusionProofBytes> = jsonrpc_client
+                .request("sui_getTransactionInclusionProof", rpc_params![digest])
+                .await
+                .unwrap();
+            match proof {
+                Some(proof) => break proof,
+                None => sleep(Duration::from_millis(500)).await,
+            }
+        }
+    })
+    .await?;
+    let checkpoint_sequence_number = proof.checkpoint_sequence_number;
+    let proof = proof.to_data()?;
+    assert_eq!(proof.transaction_digest(), &digest);
+    assert_eq!(
+        proof.checkpoint_sequence_number(),
+        checkpoint_sequence_number
+    );
+
+    // The checkpoint of the proof is served and certified by the committee.
+    let checkpoint: Option<CertifiedCheckpointBytes> = jsonrpc_client
+        .request(
+            "sui_getCertifiedCheckpoint",
+            rpc_params![checkpoint_sequence_number],
+        )
+        .await?;
+    let checkpoint = checkpoint.unwrap().to_data()?;
+    assert_eq!(checkpoint.digest(), proof.checkpoint.digest());
+    checkpoint.verify(&committee, Some(&proof.contents))?;
+
+    // Checkpoints that are not certified yet are not served.
+    let checkpoint: Option<CertifiedCheckpointBytes> = jsonrpc_client
+        .request("sui_getCertifiedCheckpoint", rpc_params![u64::MAX])
+        .await?;
+    assert!(checkpoint.is_none());
+
+    // The verified read API follows the checkpoints from the genesis committee.
+    let verified_read_api = fullnode.sui_client.verified_read_api(committee);
+    assert!(verified_read_api.sync().await? > checkpoint_sequence_number);
+    let effects = verified_read_api.get_transaction_effects(digest).await?;
+    assert_eq!(effects.transaction_digest, digest);
+    let object = verified_read_api.get_object(object_id).await?;
+    assert_eq!(object.previous_transaction, digest);
+
+    Ok(())
+}
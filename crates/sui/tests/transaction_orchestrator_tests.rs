@@ -29,8 +29,8 @@ async fn test_blocking_execution() -> Result<(), anyhow::Error> {
 
     let active = node.active();
 
-    // Disable node sync process
-    active.cancel_node_sync_process_for_tests().await;
+    // Disable checkpoint execution
+    node.cancel_checkpoint_executor_for_tests().await;
 
     let net = active.agg_aggregator();
     let node_sync_handle = active.clone().node_sync_handle();
@@ -56,7 +56,7 @@ async fn test_blocking_execution() -> Result<(), anyhow::Error> {
         })
         .await
         .unwrap_or_else(|e| panic!("Failed to execute transaction {:?}: {:?}", digest, e));
-    // Since node sync is turned off, this node does not know about this txn
+    // Since checkpoint execution is turned off, this node does not know about this txn
     assert!(node.state().get_transaction(digest).await.is_err());
 
     // Transaction Orchestrator proactivcely executes txn locally
@@ -75,7 +75,7 @@ async fn test_blocking_execution() -> Result<(), anyhow::Error> {
         assert!(executed_locally);
     };
 
-    // This node knows about this txn even though node sync is toggled off.
+    // This node knows about this txn even though checkpoint execution is toggled off.
     assert!(node.state().get_transaction(digest).await.is_ok());
 
     Ok(())
@@ -89,8 +89,8 @@ async fn test_non_blocking_execution() -> Result<(), anyhow::Error> {
 
     let active = node.active();
 
-    // Disable node sync process
-    active.cancel_node_sync_process_for_tests().await;
+    // Disable checkpoint execution
+    node.cancel_checkpoint_executor_for_tests().await;
 
     let net = active.agg_aggregator();
     let node_sync_handle = active.clone().node_sync_handle();
@@ -155,8 +155,8 @@ async fn test_local_execution_with_missing_parents() -> Result<(), anyhow::Error
 
     let active = node.active();
 
-    // Disable node sync process
-    active.cancel_node_sync_process_for_tests().await;
+    // Disable checkpoint execution
+    node.cancel_checkpoint_executor_for_tests().await;
 
     let net = active.agg_aggregator();
     let node_sync_handle = active.clone().node_sync_handle();
@@ -170,7 +170,7 @@ async fn test_local_execution_with_missing_parents() -> Result<(), anyhow::Error
     // 0. Execute transaction through Quorum Driver
     info!("Execute with a Quorum Driver");
     let digests0 = increment(context, &signer, counter_id.0, 20, pkg_ref).await;
-    // Since the node sync process is disabled, the node does not know about these txns
+    // Since checkpoint execution is disabled, the node does not know about these txns
     tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
     node_does_not_know_txes(node, &digests0).await;
 
@@ -133,6 +133,30 @@ pub struct Parameters {
     /// is not reached.
     #[serde(with = "duration_format")]
     pub max_batch_delay: Duration,
+    /// The compression the workers apply to the transactions of the batches they seal, in the
+    /// batch store and on the wire to the other workers.
+    #[serde(default)]
+    pub batch_compression: BatchCompression,
+    /// How the workers disseminate their batches to the other workers, and fetch the batches
+    /// they miss from them.
+    #[serde(default)]
+    pub batch_dissemination: BatchDissemination,
+    /// The maximum size of the transactions the workers hold while they wait to be sealed into
+    /// batches. When it is reached, transactions of low priority are dropped. Denominated in bytes.
+    #[serde(default = "Parameters::default_max_pending_transactions_size")]
+    pub max_pending_transactions_size: usize,
+    /// The maximum number of transactions of a sender the workers hold while they wait to be
+    /// sealed into batches.
+    #[serde(default = "Parameters::default_max_pending_transactions_per_sender")]
+    pub max_pending_transactions_per_sender: usize,
+    /// The number of committed sub-dags the reputation of the authorities is scored over, before
+    /// the leader schedule changes.
+    #[serde(default = "Parameters::default_num_sub_dags_per_schedule")]
+    pub num_sub_dags_per_schedule: u64,
+    /// The share of the total stake, in percent, of the authorities with the lowest reputation
+    /// that the leader schedule swaps out for the authorities with the highest reputation.
+    #[serde(default = "Parameters::default_bad_nodes_stake_threshold")]
+    pub bad_nodes_stake_threshold: Stake,
     /// The parameters for the block synchronizer
     pub block_synchronizer: BlockSynchronizerParameters,
     /// The parameters for the Consensus API gRPC server
@@ -153,6 +177,49 @@ impl Parameters {
     fn default_max_header_num_of_batches() -> usize {
         1_000
     }
+
+    fn default_max_pending_transactions_size() -> usize {
+        50_000_000
+    }
+
+    fn default_max_pending_transactions_per_sender() -> usize {
+        1_000
+    }
+
+    fn default_num_sub_dags_per_schedule() -> u64 {
+        100
+    }
+
+    fn default_bad_nodes_stake_threshold() -> Stake {
+        33
+    }
+}
+
+/// A compression algorithm for the transactions of a batch. Every batch records the compression
+/// it was sealed with, and every worker reads every compression, so that workers configured with
+/// different compressions exchange batches with each other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchCompression {
+    #[default]
+    None,
+    Zstd,
+    Lz4,
+}
+
+/// How the workers disseminate their batches to the other workers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchDissemination {
+    /// Every batch is sent whole to every other worker, and a missing batch is fetched whole
+    /// from the worker of the header referencing it, then from a few others.
+    #[default]
+    Broadcast,
+    /// Every batch is erasure coded into one chunk per authority, of which any `f + 1` rebuild
+    /// the batch. Every other worker is sent its own chunk, and rebuilds the batch from the
+    /// chunks of the others. A missing batch is rebuilt from the first chunks received from all
+    /// the other workers, so that a slow worker does not delay it.
+    ErasureCoded,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -303,6 +370,12 @@ impl Default for Parameters {
             sync_retry_nodes: 3,
             batch_size: 500_000,
             max_batch_delay: Duration::from_millis(100),
+            batch_compression: BatchCompression::None,
+            batch_dissemination: BatchDissemination::Broadcast,
+            max_pending_transactions_size: 50_000_000,
+            max_pending_transactions_per_sender: 1_000,
+            num_sub_dags_per_schedule: 100,
+            bad_nodes_stake_threshold: 33,
             block_synchronizer: BlockSynchronizerParameters::default(),
             consensus_api_grpc: ConsensusAPIGrpcParameters::default(),
             max_concurrent_requests: 500_000,
@@ -337,6 +410,24 @@ impl Parameters {
             "Max batch delay set to {} ms",
             self.max_batch_delay.as_millis()
         );
+        info!("Batch compression set to {:?}", self.batch_compression);
+        info!("Batch dissemination set to {:?}", self.batch_dissemination);
+        info!(
+            "Max pending transactions size set to {} B",
+            self.max_pending_transactions_size
+        );
+        info!(
+            "Max pending transactions per sender set to {}",
+            self.max_pending_transactions_per_sender
+        );
+        info!(
+            "Leader schedule changes every {} committed sub-dags",
+            self.num_sub_dags_per_schedule
+        );
+        info!(
+            "Leader schedule swaps out up to {}% of the stake",
+            self.bad_nodes_stake_threshold
+        );
         info!(
             "Synchronize range timeout set to {} s",
             self.block_synchronizer.range_synchronize_timeout.as_secs()
@@ -411,10 +502,10 @@ pub struct WorkerInfo {
 
 pub type SharedWorkerCache = Arc<ArcSwap<WorkerCache>>;
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct WorkerIndex(pub BTreeMap<WorkerId, WorkerInfo>);
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct WorkerCache {
     /// The authority to worker index.
     pub workers: BTreeMap<PublicKey, WorkerIndex>,
@@ -796,6 +887,12 @@ mod tests {
         assert!(logs_contain("Sync retry nodes set to 3 nodes"));
         assert!(logs_contain("Batch size set to 500000 B"));
         assert!(logs_contain("Max batch delay set to 100 ms"));
+        assert!(logs_contain(
+            "Leader schedule changes every 100 committed sub-dags"
+        ));
+        assert!(logs_contain(
+            "Leader schedule swaps out up to 33% of the stake"
+        ));
         assert!(logs_contain("Synchronize certificates timeout set to 30 s"));
         assert!(logs_contain(
             "Payload (batches) availability timeout set to 30 s"
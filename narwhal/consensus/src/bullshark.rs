@@ -1,20 +1,21 @@
 // Copyright (c) 2021, Facebook, Inc. and its affiliates
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use crate::leader_schedule::{LeaderSchedule, LeaderSwapTable};
 use crate::metrics::ConsensusMetrics;
 use crate::{
-    consensus::{ConsensusProtocol, ConsensusState, Dag},
+    consensus::{ConsensusProtocol, ConsensusState},
     utils,
 };
-use config::{Committee, Stake};
+use config::{Committee, Parameters, Stake};
 use crypto::PublicKey;
 use fastcrypto::{hash::Hash, traits::EncodeDecodeBase64};
 use std::{collections::BTreeSet, sync::Arc};
 use tokio::time::Instant;
 use tracing::{debug, error};
 use types::{
-    Certificate, CertificateDigest, CommittedSubDag, ConsensusOutput, ConsensusStore, Round,
-    SequenceNumber, StoreResult,
+    Certificate, CertificateDigest, CommittedSubDag, ConsensusOutput, ConsensusStore,
+    ReputationScores, Round, SequenceNumber, StoreResult,
 };
 
 #[cfg(test)]
@@ -51,6 +52,14 @@ pub struct Bullshark {
     pub last_leader_election: LastRound,
     /// The most recent round of inserted certificate
     pub max_inserted_certificate_round: Round,
+    /// The leader of each round, based on the reputation of the authorities.
+    pub leader_schedule: LeaderSchedule,
+    /// The reputation scores after the last committed sub-dag.
+    pub reputation_scores: ReputationScores,
+    /// The number of committed sub-dags after which the leader schedule changes.
+    pub num_sub_dags_per_schedule: u64,
+    /// The share of the total stake, in percent, that the leader schedule can swap out.
+    pub bad_nodes_stake_threshold: Stake,
 }
 
 impl ConsensusProtocol for Bullshark {
@@ -100,13 +109,149 @@ impl ConsensusProtocol for Bullshark {
             return Ok(Vec::new());
         }
 
+        // Commit the leader of round r, and the leaders before it. When committing changes the
+        // leader schedule, the leaders after the last one committed were elected with the previous
+        // schedule, so they are elected again.
+        let mut committed_sub_dags = Vec::new();
+        loop {
+            let (sub_dags, schedule_changed) =
+                self.commit_leader(state, &mut consensus_index, r, round)?;
+            committed_sub_dags.extend(sub_dags);
+            if !schedule_changed {
+                break;
+            }
+        }
+
+        Ok(committed_sub_dags)
+    }
+
+    fn update_committee(&mut self, new_committee: Committee) -> StoreResult<()> {
+        // the reputation of the previous committee is not carried over
+        self.reputation_scores = ReputationScores::new(new_committee.authorities.keys(), None);
+        self.leader_schedule =
+            LeaderSchedule::new(new_committee.clone(), LeaderSwapTable::default());
+        self.metrics.leader_schedule_bad_nodes_stake.set(0);
+        self.committee = new_committee;
+        self.store.clear()
+    }
+}
+
+impl Bullshark {
+    /// Create a new Bullshark consensus instance. The leader schedule and the reputation scores
+    /// are recovered from the store, so that a restarted node elects the same leaders as the
+    /// rest of the committee.
+    pub fn new(
+        committee: Committee,
+        store: Arc<ConsensusStore>,
+        parameters: &Parameters,
+        metrics: Arc<ConsensusMetrics>,
+    ) -> Self {
+        let bad_nodes_stake_threshold = parameters.bad_nodes_stake_threshold;
+        let (reputation_scores, final_scores) = store
+            .read_reputation_scores()
+            .expect("Failed to read the reputation scores from the consensus store");
+        let swap_table = final_scores.map_or_else(LeaderSwapTable::default, |scores| {
+            LeaderSwapTable::new(&committee, &scores, bad_nodes_stake_threshold)
+        });
+        let leader_schedule = LeaderSchedule::new(committee.clone(), swap_table);
+        metrics
+            .leader_schedule_bad_nodes_stake
+            .set(leader_schedule.bad_nodes_stake() as i64);
+
+        Self {
+            reputation_scores: reputation_scores
+                .unwrap_or_else(|| ReputationScores::new(committee.authorities.keys(), None)),
+            committee,
+            store,
+            gc_depth: parameters.gc_depth,
+            last_successful_leader_election_timestamp: Instant::now(),
+            last_leader_election: LastRound::default(),
+            max_inserted_certificate_round: 0,
+            metrics,
+            leader_schedule,
+            num_sub_dags_per_schedule: parameters.num_sub_dags_per_schedule,
+            bad_nodes_stake_threshold,
+        }
+    }
+
+    /// Score the votes the committed sub-dag has for the leader committed before it, and change
+    /// the leader schedule when the sub-dag completes a scoring window. Returns whether the
+    /// leader schedule changed. Sub-dags are committed in the same order by every node, so every
+    /// node changes its schedule at the same sub-dag.
+    fn update_reputation_scores(&mut self, sub_dag: &CommittedSubDag) -> bool {
+        if self.reputation_scores.final_of_schedule {
+            self.reputation_scores = ReputationScores::new(
+                self.committee.authorities.keys(),
+                self.reputation_scores.last_leader,
+            );
+        }
+
+        let scores = &mut self.reputation_scores;
+        if let Some((last_leader_round, last_leader)) = scores.last_leader {
+            for output in &sub_dag.certificates {
+                let certificate = &output.certificate;
+                if certificate.round() == last_leader_round + 1
+                    && certificate.header.parents.contains(&last_leader)
+                {
+                    scores.add_score(&certificate.origin(), 1);
+                }
+            }
+        }
+        scores.last_leader = Some((sub_dag.leader.round(), sub_dag.leader.digest()));
+        scores.num_commits += 1;
+
+        if scores.num_commits < self.num_sub_dags_per_schedule {
+            return false;
+        }
+        scores.final_of_schedule = true;
+        let swap_table =
+            LeaderSwapTable::new(&self.committee, scores, self.bad_nodes_stake_threshold);
+        debug!(
+            "Leader schedule changed at round {}, swapping out {:?}",
+            sub_dag.round(),
+            swap_table.bad_nodes()
+        );
+        self.leader_schedule.update_swap_table(swap_table);
+        self.metrics
+            .leader_schedule_bad_nodes_stake
+            .set(self.leader_schedule.bad_nodes_stake() as i64);
+        true
+    }
+
+    // Returns the PublicKey of the authority which is the leader for the provided `round`.
+    // Pay attention that this method will return always the first authority as the leader
+    // when used under a test environment.
+    pub fn leader_authority(committee: &Committee, _round: Round) -> PublicKey {
+        cfg_if::cfg_if! {
+            if #[cfg(test)] {
+                // consensus tests rely on returning the same leader.
+                committee.authorities.iter().next().expect("Empty authorities table!").0.clone()
+            } else {
+                // Elect the leader in a stake-weighted choice seeded by the round
+                committee.leader(_round)
+            }
+        }
+    }
+
+    /// Commit the leader of `leader_round` if it has enough support in `round`, along with the
+    /// uncommitted leaders it links to. Returns the committed sub-dags, and whether committing
+    /// them changed the leader schedule, in which case committing stopped at the sub-dag that
+    /// changed it.
+    fn commit_leader(
+        &mut self,
+        state: &mut ConsensusState,
+        consensus_index: &mut SequenceNumber,
+        leader_round: Round,
+        round: Round,
+    ) -> StoreResult<(Vec<CommittedSubDag>, bool)> {
         // Get the certificate's digest of the leader. If we already ordered this leader,
         // there is nothing to do.
-        let leader_round = r;
         if leader_round <= state.last_committed_round {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), false));
         }
-        let (leader_digest, leader) = match Self::leader(&self.committee, leader_round, &state.dag)
+        let (leader_digest, leader) = match self
+            .leader_schedule
+            .leader_certificate(leader_round, &state.dag)
         {
             Some(x) => x,
             None => {
@@ -115,7 +260,7 @@ impl ConsensusProtocol for Bullshark {
                     leader_has_support: false,
                 };
                 // leader has not been found - we don't have any certificate
-                return Ok(Vec::new());
+                return Ok((Vec::new(), false));
             }
         };
 
@@ -139,7 +284,7 @@ impl ConsensusProtocol for Bullshark {
         // a leader block means committing all its dependencies.
         if stake < self.committee.validity_threshold() {
             debug!("Leader {:?} does not have enough support", leader);
-            return Ok(Vec::new());
+            return Ok((Vec::new(), false));
         }
 
         self.last_leader_election.leader_has_support = true;
@@ -147,12 +292,14 @@ impl ConsensusProtocol for Bullshark {
         // Get an ordered list of past leaders that are linked to the current leader.
         debug!("Leader {:?} has enough support", leader);
         let mut committed_sub_dags = Vec::new();
+        let mut schedule_changed = false;
 
         // TODO: duplicated in tusk.rs
-        for leader in utils::order_leaders(&self.committee, leader, state, Self::leader)
-            .iter()
-            .rev()
-        {
+        let leader_schedule = &self.leader_schedule;
+        let leaders = utils::order_leaders(&self.committee, leader, state, |_, round, dag| {
+            leader_schedule.leader_certificate(round, dag)
+        });
+        for leader in leaders.iter().rev() {
             debug!("Previous Leader {:?} has enough support", leader);
             let mut sequence = Vec::new();
 
@@ -166,32 +313,36 @@ impl ConsensusProtocol for Bullshark {
                 // Add the certificate to the sequence.
                 sequence.push(ConsensusOutput {
                     certificate: x,
-                    consensus_index,
+                    consensus_index: *consensus_index,
                 });
 
                 // Increase the global consensus index.
-                consensus_index += 1;
+                *consensus_index += 1;
 
                 // Persist the update.
                 // TODO [issue #116]: Ensure this is not a performance bottleneck.
                 self.store.write_consensus_state(
                     &state.last_committed,
-                    &consensus_index,
+                    consensus_index,
                     &digest,
                 )?;
-                debug!(
-                    "Store commit index:{}, digest:{}",
-                    &consensus_index, &digest
-                );
+                debug!("Store commit index:{}, digest:{}", consensus_index, &digest);
             }
 
             let sub_dag = CommittedSubDag {
                 certificates: sequence,
                 leader: leader.clone(),
             };
-            self.store
-                .write_committed_sub_dag(&state.last_committed, &sub_dag)?;
+            schedule_changed = self.update_reputation_scores(&sub_dag);
+            self.store.write_committed_sub_dag(
+                &state.last_committed,
+                &sub_dag,
+                &self.reputation_scores,
+            )?;
             committed_sub_dags.push(sub_dag);
+            if schedule_changed {
+                break;
+            }
         }
 
         // record the last time we got a successful leader election
@@ -222,47 +373,7 @@ impl ConsensusProtocol for Bullshark {
             .committed_certificates
             .observe(total_commits as f64);
 
-        Ok(committed_sub_dags)
-    }
-
-    fn update_committee(&mut self, new_committee: Committee) -> StoreResult<()> {
-        self.committee = new_committee;
-        self.store.clear()
-    }
-}
-
-impl Bullshark {
-    /// Create a new Bullshark consensus instance.
-    pub fn new(
-        committee: Committee,
-        store: Arc<ConsensusStore>,
-        gc_depth: Round,
-        metrics: Arc<ConsensusMetrics>,
-    ) -> Self {
-        Self {
-            committee,
-            store,
-            gc_depth,
-            last_successful_leader_election_timestamp: Instant::now(),
-            last_leader_election: LastRound::default(),
-            max_inserted_certificate_round: 0,
-            metrics,
-        }
-    }
-
-    // Returns the PublicKey of the authority which is the leader for the provided `round`.
-    // Pay attention that this method will return always the first authority as the leader
-    // when used under a test environment.
-    pub fn leader_authority(committee: &Committee, _round: Round) -> PublicKey {
-        cfg_if::cfg_if! {
-            if #[cfg(test)] {
-                // consensus tests rely on returning the same leader.
-                committee.authorities.iter().next().expect("Empty authorities table!").0.clone()
-            } else {
-                // Elect the leader in a stake-weighted choice seeded by the round
-                committee.leader(_round)
-            }
-        }
+        Ok((committed_sub_dags, schedule_changed))
     }
 
     // Checks that the provided certificate's parents exist and prints the necessary
@@ -300,20 +411,4 @@ impl Bullshark {
             }
         }
     }
-
-    // TODO: duplicated in tusk.rs
-    /// Returns the certificate (and the certificate's digest) originated by the leader of the
-    /// specified round (if any).
-    fn leader<'a>(
-        committee: &Committee,
-        round: Round,
-        dag: &'a Dag,
-    ) -> Option<&'a (CertificateDigest, Certificate)> {
-        // Note: this function is often called with even rounds only. While we do not aim at random selection
-        // yet (see issue #10), repeated calls to this function should still pick from the whole roster of leaders.
-        let leader = Self::leader_authority(committee, round);
-
-        // Return its certificate and the certificate's digest.
-        dag.get(&round).and_then(|x| x.get(&leader))
-    }
 }
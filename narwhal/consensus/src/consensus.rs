@@ -237,6 +237,13 @@ pub struct Consensus<ConsensusProtocol> {
 
     /// Inner state
     state: ConsensusState,
+
+    /// The round of the epoch change scheduled in place, if any: consensus stops committing
+    /// once it commits a leader at or above this round.
+    epoch_change_round: Option<Round>,
+    /// Whether consensus committed the last sub-dag of the epoch, and ignores the certificates
+    /// of the epoch until the next committee is adopted.
+    drained: bool,
 }
 
 impl<Protocol> Consensus<Protocol>
@@ -280,6 +287,8 @@ where
             protocol,
             metrics,
             state,
+            epoch_change_round: None,
+            drained: false,
         };
 
         spawn_monitored_task!(s.run())
@@ -290,11 +299,59 @@ where
         self.protocol.update_committee(new_committee)?;
 
         self.consensus_index = 0;
+        self.epoch_change_round = None;
+        self.drained = false;
 
         let genesis = Certificate::genesis(&self.committee);
         Ok(ConsensusState::new(genesis, self.metrics.clone()))
     }
 
+    fn schedule_epoch_change(&mut self, round: Round) {
+        if self.epoch_change_round != Some(round) {
+            info!(
+                "Epoch change scheduled at round {round}, last committed round {}",
+                self.state.last_committed_round
+            );
+            self.epoch_change_round = Some(round);
+        }
+    }
+
+    /// Pick up the epoch change scheduled since the last certificate, if the reconfiguration
+    /// channel was not polled since.
+    fn check_scheduled_epoch(&mut self) {
+        let scheduled = match &*self.rx_reconfigure.borrow() {
+            ReconfigureNotification::ScheduleEpoch { round, .. } => Some(*round),
+            _ => None,
+        };
+        if let Some(round) = scheduled {
+            self.schedule_epoch_change(round);
+        }
+    }
+
+    /// Drop the sub-dags committed after the first leader at or above the round of the
+    /// scheduled epoch change, which is the last sub-dag of the epoch. Every node commits the
+    /// same sequence of leaders, so every node ends the epoch at the same sub-dag. The dropped
+    /// sub-dags are never output, although the protocol may have persisted them already.
+    fn drain_after_epoch_change_round(&mut self, committed_sub_dags: &mut Vec<CommittedSubDag>) {
+        let Some(epoch_change_round) = self.epoch_change_round else {
+            return;
+        };
+        let last = match committed_sub_dags
+            .iter()
+            .position(|sub_dag| sub_dag.round() >= epoch_change_round)
+        {
+            Some(last) => last,
+            None => return,
+        };
+        committed_sub_dags.truncate(last + 1);
+        self.drained = true;
+        info!(
+            "Epoch {} drained at the sub-dag committed by the leader of round {}",
+            self.committee.epoch(),
+            committed_sub_dags[last].round()
+        );
+    }
+
     async fn run(self) {
         self.run_inner().await.expect("Failed to run consensus")
     }
@@ -317,6 +374,8 @@ where
                                     self.committee = new_committee;
                                 }
                                 ReconfigureNotification::Shutdown => return Ok(()),
+                                // The next committee is not adopted yet.
+                                ReconfigureNotification::ScheduleEpoch { .. } => continue,
                             }
                             tracing::debug!("Committee updated to {}", self.committee);
                         }
@@ -330,11 +389,19 @@ where
                         }
                     }
 
+                    // The certificates of an epoch drained for an epoch change are not committed.
+                    self.check_scheduled_epoch();
+                    if self.drained {
+                        tracing::debug!("Ignoring {certificate:?} of the drained epoch");
+                        continue;
+                    }
+
                     // Process the certificate using the selected consensus protocol.
                     let commit_round_leader = certificate.header.round;
-                    let committed_sub_dags =
+                    let mut committed_sub_dags =
                         self.protocol
                             .process_certificate(&mut self.state, self.consensus_index, certificate)?;
+                    self.drain_after_epoch_change_round(&mut committed_sub_dags);
 
                     // Update the consensus index.
                     let total_commits: usize = committed_sub_dags.iter().map(|x| x.len()).sum();
@@ -405,7 +472,11 @@ where
                         ReconfigureNotification::UpdateCommittee(new_committee) => {
                             self.committee = new_committee;
                         }
-                        ReconfigureNotification::Shutdown => return Ok(())
+                        ReconfigureNotification::Shutdown => return Ok(()),
+                        ReconfigureNotification::ScheduleEpoch { round, .. } => {
+                            self.schedule_epoch_change(round);
+                            continue;
+                        }
                     }
                     tracing::debug!("Committee updated to {}", self.committee);
                 }
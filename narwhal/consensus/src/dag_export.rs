@@ -0,0 +1,155 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{bullshark::Bullshark, consensus::ConsensusState, metrics::ConsensusMetrics};
+use config::{Committee, Parameters};
+use fastcrypto::{hash::Hash, traits::EncodeDecodeBase64};
+use prometheus::Registry;
+use std::{collections::HashSet, sync::Arc};
+use storage::CertificateStore;
+use types::{
+    Certificate, CertificateDigest, ConsensusStore, DagExport, DagVertex, Round, StoreResult,
+};
+
+#[cfg(test)]
+#[path = "tests/dag_export_tests.rs"]
+pub mod dag_export_tests;
+
+/// Exports the recent DAG of a primary from its stores, to inspect stalled rounds. The last
+/// committed rounds of the consensus state are read from the consensus store, as a restarting
+/// node recovers them, so the stores may be those of a running node or of a stopped one.
+#[derive(Clone)]
+pub struct DagExporter {
+    committee: Committee,
+    consensus_store: Arc<ConsensusStore>,
+    certificate_store: CertificateStore,
+    /// The consensus parameters of the node, which the leader schedule is built with.
+    parameters: Parameters,
+}
+
+impl DagExporter {
+    pub fn new(
+        committee: Committee,
+        consensus_store: Arc<ConsensusStore>,
+        certificate_store: CertificateStore,
+        parameters: Parameters,
+    ) -> Self {
+        Self {
+            committee,
+            consensus_store,
+            certificate_store,
+            parameters,
+        }
+    }
+
+    /// Export the certificates with a round above `from_round`, or above the GC round of
+    /// consensus if `None`.
+    ///
+    /// The committed anchors are the leaders of the committed sub-dags. The leaders of the rounds
+    /// above the last committed round are elected from the current leader schedule, and those of
+    /// the rounds below are the committed anchors, as leaders skipped then may have been elected
+    /// from an older schedule.
+    pub fn export(&self, from_round: Option<Round>) -> StoreResult<DagExport> {
+        // A throwaway registry, not to report the recovery of the state as the node's.
+        let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
+        let genesis = Certificate::genesis(&self.committee);
+        let genesis_digests: HashSet<CertificateDigest> = genesis
+            .iter()
+            .map(|certificate| certificate.digest())
+            .collect();
+        // The last committed rounds consensus recovers its state from, without rebuilding its
+        // DAG: the certificates are read from the store below.
+        let mut state = ConsensusState::new(genesis, metrics.clone());
+        state.last_committed = self.consensus_store.read_last_committed();
+        state.last_committed_round = state.last_committed.values().max().cloned().unwrap_or(0);
+        let leader_schedule = Bullshark::new(
+            self.committee.clone(),
+            self.consensus_store.clone(),
+            &self.parameters,
+            metrics,
+        )
+        .leader_schedule;
+
+        let gc_round = state
+            .last_committed_round
+            .saturating_sub(self.parameters.gc_depth);
+        let from_round = from_round.unwrap_or(gc_round);
+        let certificates = self.certificate_store.after_round(from_round + 1)?;
+        let anchors: HashSet<CertificateDigest> = self
+            .consensus_store
+            .read_committed_sub_dags_from(&(from_round + 1))?
+            .into_iter()
+            .map(|sub_dag| sub_dag.leader)
+            .collect();
+
+        // The parents neither exported nor genesis may be missing from the store.
+        let mut known: HashSet<CertificateDigest> =
+            certificates.iter().map(|c| c.digest()).collect();
+        known.extend(genesis_digests);
+        let unknown: Vec<CertificateDigest> = certificates
+            .iter()
+            .filter(|certificate| certificate.round() > gc_round + 1)
+            .flat_map(|certificate| certificate.header.parents.iter())
+            .filter(|parent| !known.contains(*parent))
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let missing: HashSet<CertificateDigest> = self
+            .certificate_store
+            .read_all(unknown.iter().cloned())?
+            .into_iter()
+            .zip(unknown)
+            .filter_map(|(certificate, digest)| certificate.is_none().then_some(digest))
+            .collect();
+
+        let vertices = certificates
+            .iter()
+            .map(|certificate| {
+                let digest = certificate.digest();
+                let round = certificate.round();
+                let origin = certificate.origin();
+                let committed_anchor = anchors.contains(&digest);
+                let leader = committed_anchor
+                    || (round > state.last_committed_round
+                        && round % 2 == 0
+                        && leader_schedule.leader(round) == origin);
+                let committed = state
+                    .last_committed
+                    .get(&origin)
+                    .map_or(false, |last_committed| round <= *last_committed);
+                let missing_parents = if round > gc_round + 1 {
+                    certificate
+                        .header
+                        .parents
+                        .iter()
+                        .filter(|parent| missing.contains(*parent))
+                        .map(|parent| format!("{parent:?}"))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                DagVertex {
+                    digest: format!("{digest:?}"),
+                    round,
+                    author: origin.encode_base64(),
+                    parents: certificate
+                        .header
+                        .parents
+                        .iter()
+                        .map(|parent| format!("{parent:?}"))
+                        .collect(),
+                    missing_parents,
+                    leader,
+                    committed_anchor,
+                    committed,
+                }
+            })
+            .collect();
+
+        Ok(DagExport {
+            last_committed_round: state.last_committed_round,
+            gc_round,
+            vertices,
+        })
+    }
+}
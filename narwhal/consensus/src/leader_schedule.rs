@@ -0,0 +1,134 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{bullshark::Bullshark, consensus::Dag};
+use config::{Committee, Stake};
+use crypto::PublicKey;
+use std::collections::BTreeSet;
+use types::{Certificate, CertificateDigest, ReputationScores, Round};
+
+#[cfg(test)]
+#[path = "tests/leader_schedule_tests.rs"]
+pub mod leader_schedule_tests;
+
+/// The authorities swapped in and out of the leader schedule, based on their reputation.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct LeaderSwapTable {
+    /// The authorities with the highest reputation, which lead in place of the bad nodes.
+    good_nodes: Vec<PublicKey>,
+    /// The authorities with the lowest reputation, which do not lead.
+    bad_nodes: BTreeSet<PublicKey>,
+}
+
+impl LeaderSwapTable {
+    /// Swap out the authorities with the lowest reputation, up to `bad_nodes_stake_threshold`
+    /// percent of the total stake, for as many authorities with the highest reputation, or all
+    /// the others if there are fewer. Only the authorities scoring less than the best score are
+    /// swapped out, so that a committee with the same reputation throughout keeps its schedule.
+    pub fn new(
+        committee: &Committee,
+        reputation_scores: &ReputationScores,
+        bad_nodes_stake_threshold: Stake,
+    ) -> Self {
+        // sort by score, then by name, so that every node builds the same table
+        let mut authorities: Vec<_> = reputation_scores
+            .scores_per_authority
+            .iter()
+            .map(|(name, score)| (*score, name))
+            .collect();
+        authorities.sort();
+        let Some(best_score) = authorities.last().map(|(score, _)| *score) else {
+            return Self::default();
+        };
+
+        let total_stake: Stake = committee.authorities.values().map(|x| x.stake).sum();
+        let max_bad_stake = total_stake * bad_nodes_stake_threshold / 100;
+        let mut bad_stake = 0;
+        let mut bad_nodes = BTreeSet::new();
+        for (score, name) in &authorities {
+            let stake = committee.stake(name);
+            if *score == best_score || bad_stake + stake > max_bad_stake {
+                break;
+            }
+            bad_stake += stake;
+            bad_nodes.insert((*name).clone());
+        }
+
+        // with skewed stake, the bad nodes can outnumber the others: only the others lead instead
+        let good_nodes = authorities
+            .iter()
+            .rev()
+            .filter(|(_, name)| !bad_nodes.contains(*name))
+            .take(bad_nodes.len())
+            .map(|(_, name)| (*name).clone())
+            .collect();
+        Self {
+            good_nodes,
+            bad_nodes,
+        }
+    }
+
+    /// Return the authority leading `round` in place of `leader`, if `leader` is swapped out.
+    pub fn swap(&self, leader: &PublicKey, round: Round) -> Option<PublicKey> {
+        if !self.bad_nodes.contains(leader) {
+            return None;
+        }
+        // leaders are elected on even rounds only
+        let index = (round / 2) as usize % self.good_nodes.len();
+        Some(self.good_nodes[index].clone())
+    }
+
+    pub fn bad_nodes(&self) -> &BTreeSet<PublicKey> {
+        &self.bad_nodes
+    }
+}
+
+/// The leader of each round: the authority elected by stake, unless the swap table built from
+/// the last reputation scores swaps it out. Every node commits the same leaders and so scores
+/// the same reputation, which keeps the schedule deterministic across nodes.
+#[derive(Debug, Clone)]
+pub struct LeaderSchedule {
+    committee: Committee,
+    swap_table: LeaderSwapTable,
+}
+
+impl LeaderSchedule {
+    pub fn new(committee: Committee, swap_table: LeaderSwapTable) -> Self {
+        Self {
+            committee,
+            swap_table,
+        }
+    }
+
+    /// Returns the PublicKey of the authority which is the leader for the provided `round`.
+    pub fn leader(&self, round: Round) -> PublicKey {
+        let leader = Bullshark::leader_authority(&self.committee, round);
+        self.swap_table.swap(&leader, round).unwrap_or(leader)
+    }
+
+    /// Returns the certificate (and the certificate's digest) originated by the leader of the
+    /// specified round (if any).
+    pub fn leader_certificate<'a>(
+        &self,
+        round: Round,
+        dag: &'a Dag,
+    ) -> Option<&'a (CertificateDigest, Certificate)> {
+        dag.get(&round).and_then(|x| x.get(&self.leader(round)))
+    }
+
+    pub fn update_swap_table(&mut self, swap_table: LeaderSwapTable) {
+        self.swap_table = swap_table;
+    }
+
+    pub fn swap_table(&self) -> &LeaderSwapTable {
+        &self.swap_table
+    }
+
+    /// The stake of the authorities swapped out of the schedule.
+    pub fn bad_nodes_stake(&self) -> Stake {
+        self.swap_table
+            .bad_nodes
+            .iter()
+            .map(|name| self.committee.stake(name))
+            .sum()
+    }
+}
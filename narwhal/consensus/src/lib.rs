@@ -13,6 +13,8 @@ pub mod consensus;
 #[path = "tests/consensus_utils.rs"]
 pub mod consensus_utils;
 pub mod dag;
+pub mod dag_export;
+pub mod leader_schedule;
 pub mod metrics;
 pub mod tusk;
 mod utils;
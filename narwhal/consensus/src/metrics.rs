@@ -43,6 +43,8 @@ pub struct ConsensusMetrics {
     /// The time it takes for a certificate from the moment it gets created
     /// up to the moment it gets committed.
     pub certificate_commit_latency: Histogram,
+    /// The stake of the authorities the leader schedule swaps out for their low reputation
+    pub leader_schedule_bad_nodes_stake: IntGauge,
 }
 
 impl ConsensusMetrics {
@@ -115,6 +117,11 @@ impl ConsensusMetrics {
                 "The time it takes for a certificate from the moment it gets created up to the moment it gets committed.",
                 LATENCY_SEC_BUCKETS.to_vec(),
                 registry
+            ).unwrap(),
+            leader_schedule_bad_nodes_stake: register_int_gauge_with_registry!(
+                "leader_schedule_bad_nodes_stake",
+                "The stake of the authorities the leader schedule swaps out for their low reputation",
+                registry
             ).unwrap()
         }
     }
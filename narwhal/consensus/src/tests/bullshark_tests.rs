@@ -13,7 +13,7 @@ use std::collections::{BTreeSet, VecDeque};
 use test_utils::CommitteeFixture;
 #[allow(unused_imports)]
 use tokio::sync::mpsc::channel;
-use tokio::sync::watch;
+use tokio::{sync::watch, time::Duration};
 use types::ReconfigureNotification;
 
 // Run for 4 dag rounds in ideal conditions (all nodes reference all other nodes). We should commit
@@ -51,7 +51,15 @@ async fn commit_one() {
     let cert_store = make_certificate_store(&test_utils::temp_dir());
     let gc_depth = 50;
     let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
-    let bullshark = Bullshark::new(committee.clone(), store.clone(), gc_depth, metrics.clone());
+    let bullshark = Bullshark::new(
+        committee.clone(),
+        store.clone(),
+        &Parameters {
+            gc_depth,
+            ..Parameters::default()
+        },
+        metrics.clone(),
+    );
 
     let _consensus_handle = Consensus::spawn(
         committee,
@@ -116,7 +124,15 @@ async fn dead_node() {
     let cert_store = make_certificate_store(&test_utils::temp_dir());
     let gc_depth = 50;
     let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
-    let bullshark = Bullshark::new(committee.clone(), store.clone(), gc_depth, metrics.clone());
+    let bullshark = Bullshark::new(
+        committee.clone(),
+        store.clone(),
+        &Parameters {
+            gc_depth,
+            ..Parameters::default()
+        },
+        metrics.clone(),
+    );
 
     let _consensus_handle = Consensus::spawn(
         committee,
@@ -236,7 +252,15 @@ async fn not_enough_support() {
     let cert_store = make_certificate_store(&test_utils::temp_dir());
     let gc_depth = 50;
     let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
-    let bullshark = Bullshark::new(committee.clone(), store.clone(), gc_depth, metrics.clone());
+    let bullshark = Bullshark::new(
+        committee.clone(),
+        store.clone(),
+        &Parameters {
+            gc_depth,
+            ..Parameters::default()
+        },
+        metrics.clone(),
+    );
 
     let _consensus_handle = Consensus::spawn(
         committee,
@@ -326,7 +350,15 @@ async fn missing_leader() {
     let cert_store = make_certificate_store(&test_utils::temp_dir());
     let gc_depth = 50;
     let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
-    let bullshark = Bullshark::new(committee.clone(), store.clone(), gc_depth, metrics.clone());
+    let bullshark = Bullshark::new(
+        committee.clone(),
+        store.clone(),
+        &Parameters {
+            gc_depth,
+            ..Parameters::default()
+        },
+        metrics.clone(),
+    );
 
     let _consensus_handle = Consensus::spawn(
         committee,
@@ -387,7 +419,15 @@ async fn epoch_change() {
     let cert_store = make_certificate_store(&test_utils::temp_dir());
     let gc_depth = 50;
     let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
-    let bullshark = Bullshark::new(committee.clone(), store.clone(), gc_depth, metrics.clone());
+    let bullshark = Bullshark::new(
+        committee.clone(),
+        store.clone(),
+        &Parameters {
+            gc_depth,
+            ..Parameters::default()
+        },
+        metrics.clone(),
+    );
 
     let _consensus_handle = Consensus::spawn(
         committee.clone(),
@@ -457,6 +497,118 @@ async fn epoch_change() {
     }
 }
 
+// Run for 5 dag rounds in ideal conditions with an epoch change scheduled at round 2. We should
+// commit the leader of round 2 and then drain the epoch: the leader of round 4 is not committed.
+// Once the next committee is adopted, the certificates of the new epoch are committed.
+#[tokio::test]
+async fn scheduled_epoch_change_drains_the_epoch() {
+    let fixture = CommitteeFixture::builder().build();
+    let mut committee = fixture.committee();
+    let keys: Vec<_> = fixture.authorities().map(|a| a.public_key()).collect();
+
+    // Spawn the consensus engine and sink the primary channel.
+    let (tx_waiter, rx_waiter) = test_utils::test_channel!(1);
+    let (tx_primary, mut rx_primary) = test_utils::test_channel!(1);
+    let (tx_output, mut rx_output) = test_utils::test_channel!(1);
+
+    let initial_committee = ReconfigureNotification::NewEpoch(committee.clone());
+    let (tx_reconfigure, rx_reconfigure) = watch::channel(initial_committee);
+
+    let store = make_consensus_store(&test_utils::temp_dir());
+    let cert_store = make_certificate_store(&test_utils::temp_dir());
+    let gc_depth = 50;
+    let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
+    let bullshark = Bullshark::new(
+        committee.clone(),
+        store.clone(),
+        &Parameters {
+            gc_depth,
+            ..Parameters::default()
+        },
+        metrics.clone(),
+    );
+
+    let _consensus_handle = Consensus::spawn(
+        committee.clone(),
+        store,
+        cert_store,
+        rx_reconfigure,
+        rx_waiter,
+        tx_primary,
+        tx_output,
+        bullshark,
+        metrics,
+        gc_depth,
+    );
+    tokio::spawn(async move { while rx_primary.recv().await.is_some() {} });
+
+    // Schedule the move to the next epoch at round 2.
+    let mut next_committee = committee.clone();
+    next_committee.epoch = 1;
+    let mut worker_cache = fixture.worker_cache();
+    worker_cache.epoch = 1;
+    tx_reconfigure
+        .send(ReconfigureNotification::ScheduleEpoch {
+            round: 2,
+            committee: next_committee.clone(),
+            worker_cache,
+        })
+        .unwrap();
+
+    // Feed the certificates of rounds 1 to 5, which commit the leaders of rounds 2 and 4.
+    let genesis = Certificate::genesis(&committee)
+        .iter()
+        .map(|x| x.digest())
+        .collect::<BTreeSet<_>>();
+    let (mut certificates, _) =
+        test_utils::make_optimal_certificates(&committee, 1..=5, &genesis, &keys);
+    let feeder = tx_waiter.clone();
+    let feeding = tokio::spawn(async move {
+        while let Some(certificate) = certificates.pop_front() {
+            feeder.send(certificate).await.unwrap();
+        }
+    });
+
+    // Only the leader of round 2 is committed.
+    let committed_sub_dag = rx_output.recv().await.unwrap();
+    assert_eq!(committed_sub_dag.round(), 2);
+    feeding.await.unwrap();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), rx_output.recv())
+            .await
+            .is_err()
+    );
+
+    // Adopt the next committee and commit the leader of round 2 of the new epoch.
+    committee = next_committee;
+    tx_reconfigure
+        .send(ReconfigureNotification::NewEpoch(committee.clone()))
+        .unwrap();
+    let genesis = Certificate::genesis(&committee)
+        .iter()
+        .map(|x| x.digest())
+        .collect::<BTreeSet<_>>();
+    let (mut certificates, next_parents) =
+        test_utils::make_certificates_with_epoch(&committee, 1..=2, 1, &genesis, &keys);
+    for key in &keys[..2] {
+        let (_, certificate) = test_utils::mock_certificate_with_epoch(
+            &committee,
+            key.clone(),
+            3,
+            1,
+            next_parents.clone(),
+        );
+        certificates.push_back(certificate);
+    }
+    while let Some(certificate) = certificates.pop_front() {
+        tx_waiter.send(certificate).await.unwrap();
+    }
+
+    let committed_sub_dag = rx_output.recv().await.unwrap();
+    assert_eq!(committed_sub_dag.round(), 2);
+    assert_eq!(committed_sub_dag.leader.epoch(), 1);
+}
+
 // Run for 4 dag rounds in ideal conditions (all nodes reference all other nodes). We should commit
 // the leader of round 2. Then shutdown consensus and restart it in a
 #[tokio::test]
@@ -478,7 +630,15 @@ async fn restart_with_new_committee() {
         let cert_store = make_certificate_store(&test_utils::temp_dir());
         let gc_depth = 50;
         let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
-        let bullshark = Bullshark::new(committee.clone(), store.clone(), gc_depth, metrics.clone());
+        let bullshark = Bullshark::new(
+            committee.clone(),
+            store.clone(),
+            &Parameters {
+                gc_depth,
+                ..Parameters::default()
+            },
+            metrics.clone(),
+        );
 
         let handle = Consensus::spawn(
             committee.clone(),
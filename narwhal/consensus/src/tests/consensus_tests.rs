@@ -3,6 +3,7 @@
 
 #![allow(clippy::mutable_key_type)]
 
+use config::Parameters;
 use fastcrypto::hash::Hash;
 use prometheus::Registry;
 use std::collections::BTreeSet;
@@ -63,7 +64,10 @@ async fn test_consensus_recovery_with_bullshark() {
     let bullshark = Bullshark::new(
         committee.clone(),
         consensus_store.clone(),
-        gc_depth,
+        &Parameters {
+            gc_depth,
+            ..Parameters::default()
+        },
         metrics.clone(),
     );
 
@@ -152,7 +156,10 @@ async fn test_consensus_recovery_with_bullshark() {
     let bullshark = Bullshark::new(
         committee.clone(),
         consensus_store.clone(),
-        gc_depth,
+        &Parameters {
+            gc_depth,
+            ..Parameters::default()
+        },
         metrics.clone(),
     );
 
@@ -219,7 +226,10 @@ async fn test_consensus_recovery_with_bullshark() {
     let bullshark = Bullshark::new(
         committee.clone(),
         consensus_store.clone(),
-        gc_depth,
+        &Parameters {
+            gc_depth,
+            ..Parameters::default()
+        },
         metrics.clone(),
     );
 
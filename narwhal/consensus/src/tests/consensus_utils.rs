@@ -5,31 +5,40 @@ use std::sync::Arc;
 use storage::CertificateStore;
 use store::{reopen, rocks, rocks::DBMap};
 use types::{
-    Certificate, CertificateDigest, CommittedSubDagShell, ConsensusStore, Round, SequenceNumber,
+    Certificate, CertificateDigest, CommittedSubDagShell, ConsensusStore, ReputationScores, Round,
+    SequenceNumber,
 };
 
 pub fn make_consensus_store(store_path: &std::path::Path) -> Arc<ConsensusStore> {
     const LAST_COMMITTED_CF: &str = "last_committed";
     const SEQUENCE_CF: &str = "sequence";
     const SUB_DAG_CF: &str = "sub_dag";
+    const REPUTATION_SCORES_CF: &str = "reputation_scores";
 
     let rocksdb = rocks::open_cf(
         store_path,
         None,
-        &[LAST_COMMITTED_CF, SEQUENCE_CF, SUB_DAG_CF],
+        &[
+            LAST_COMMITTED_CF,
+            SEQUENCE_CF,
+            SUB_DAG_CF,
+            REPUTATION_SCORES_CF,
+        ],
     )
     .expect("Failed to create database");
 
-    let (last_committed_map, sequence_map, sub_dag_map) = reopen!(&rocksdb,
+    let (last_committed_map, sequence_map, sub_dag_map, reputation_scores_map) = reopen!(&rocksdb,
         LAST_COMMITTED_CF;<PublicKey, Round>,
         SEQUENCE_CF;<SequenceNumber, CertificateDigest>,
-        SUB_DAG_CF;<SequenceNumber, CommittedSubDagShell>
+        SUB_DAG_CF;<SequenceNumber, CommittedSubDagShell>,
+        REPUTATION_SCORES_CF;<SequenceNumber, ReputationScores>
     );
 
     Arc::new(ConsensusStore::new(
         last_committed_map,
         sequence_map,
         sub_dag_map,
+        reputation_scores_map,
     ))
 }
 
@@ -0,0 +1,130 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use fastcrypto::hash::Hash;
+use std::collections::{BTreeSet, HashMap};
+use storage::NodeStorage;
+use test_utils::{temp_dir, CommitteeFixture};
+use types::{CommittedSubDag, ConsensusOutput, ReputationScores};
+
+// Store the certificates of rounds 1 to 5, and commit the leader of round 2 with its round 1
+// parents. Returns the exporter and the certificates.
+fn commit_round_2(gc_depth: Round) -> (DagExporter, NodeStorage, Vec<Certificate>) {
+    let storage = NodeStorage::reopen(temp_dir());
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let keys: Vec<_> = fixture.authorities().map(|a| a.public_key()).collect();
+    let genesis = Certificate::genesis(&committee)
+        .iter()
+        .map(|x| x.digest())
+        .collect::<BTreeSet<_>>();
+    let (certificates, _) =
+        test_utils::make_optimal_certificates(&committee, 1..=5, &genesis, &keys);
+    let mut certificates: Vec<_> = certificates.into_iter().collect();
+    certificates.sort_by_key(|certificate| certificate.round());
+    storage
+        .certificate_store
+        .write_all(certificates.clone())
+        .unwrap();
+
+    let leader_name = Bullshark::leader_authority(&committee, 2);
+    let leader = certificates
+        .iter()
+        .find(|c| c.round() == 2 && c.origin() == leader_name)
+        .unwrap()
+        .clone();
+    let mut committed: Vec<_> = certificates
+        .iter()
+        .filter(|c| c.round() == 1)
+        .cloned()
+        .collect();
+    committed.push(leader.clone());
+    let last_committed: HashMap<_, _> = committed.iter().map(|c| (c.origin(), c.round())).collect();
+    let sub_dag = CommittedSubDag {
+        certificates: committed
+            .into_iter()
+            .enumerate()
+            .map(|(i, certificate)| ConsensusOutput {
+                certificate,
+                consensus_index: i as u64,
+            })
+            .collect(),
+        leader,
+    };
+    storage
+        .consensus_store
+        .write_committed_sub_dag(&last_committed, &sub_dag, &ReputationScores::default())
+        .unwrap();
+
+    let exporter = DagExporter::new(
+        committee,
+        storage.consensus_store.clone(),
+        storage.certificate_store.clone(),
+        Parameters {
+            gc_depth,
+            ..Parameters::default()
+        },
+    );
+    (exporter, storage, certificates)
+}
+
+fn vertex<'a>(export: &'a DagExport, certificate: &Certificate) -> &'a DagVertex {
+    let digest = format!("{:?}", certificate.digest());
+    export.vertices.iter().find(|v| v.digest == digest).unwrap()
+}
+
+#[tokio::test]
+async fn export_marks_leaders_and_commits() {
+    let (exporter, _storage, certificates) = commit_round_2(50);
+    let export = exporter.export(None).unwrap();
+
+    assert_eq!(export.last_committed_round, 2);
+    assert_eq!(export.gc_round, 0);
+    assert_eq!(export.vertices.len(), certificates.len());
+
+    for certificate in &certificates {
+        let vertex = vertex(&export, certificate);
+        let is_leader = certificate.round() % 2 == 0
+            && certificate.origin() == Bullshark::leader_authority(&exporter.committee, 2);
+        assert_eq!(vertex.leader, is_leader, "{vertex:?}");
+        assert_eq!(
+            vertex.committed_anchor,
+            is_leader && certificate.round() == 2
+        );
+        assert_eq!(
+            vertex.committed,
+            certificate.round() == 1 || (is_leader && certificate.round() == 2)
+        );
+        assert!(vertex.missing_parents.is_empty());
+    }
+
+    // exporting from a later round skips the earlier certificates
+    let export = exporter.export(Some(3)).unwrap();
+    assert!(export.vertices.iter().all(|v| v.round > 3));
+    assert_eq!(export.vertices.len(), 8);
+}
+
+#[tokio::test]
+async fn export_reports_missing_parents_above_gc_round() {
+    let (exporter, storage, certificates) = commit_round_2(1);
+    let removed = certificates.iter().find(|c| c.round() == 3).unwrap();
+    storage.certificate_store.delete(removed.digest()).unwrap();
+
+    let export = exporter.export(None).unwrap();
+    assert_eq!(export.gc_round, 1);
+    assert!(export.vertices.iter().all(|v| v.round > 1));
+    for certificate in certificates.iter().filter(|c| c.round() == 4) {
+        assert_eq!(
+            vertex(&export, certificate).missing_parents,
+            vec![format!("{:?}", removed.digest())]
+        );
+    }
+
+    let dot = export.to_dot();
+    assert!(dot.starts_with("digraph dag {"));
+    assert!(dot.contains("fillcolor=gold"));
+    assert!(dot.contains(&format!(
+        "\"{:?}\" [label=\"missing\", style=dashed, color=red];",
+        removed.digest()
+    )));
+}
@@ -0,0 +1,152 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+
+use crate::{consensus_utils::make_consensus_store, metrics::ConsensusMetrics};
+use config::Parameters;
+use prometheus::Registry;
+use std::{collections::HashMap, num::NonZeroUsize, sync::Arc};
+use store::{rocks, rocks::DBMap, Map};
+use test_utils::CommitteeFixture;
+use types::CommittedSubDag;
+
+// Score the authorities in the order of their names.
+fn scores(committee: &Committee, scores: &[u64]) -> ReputationScores {
+    let mut reputation_scores = ReputationScores::new(committee.authorities.keys(), None);
+    for (name, score) in committee.authorities.keys().zip(scores) {
+        reputation_scores.add_score(name, *score);
+    }
+    reputation_scores
+}
+
+#[test]
+fn low_reputation_leaders_are_swapped_out() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let names: Vec<_> = committee.authorities.keys().cloned().collect();
+
+    // one authority in four is 25% of the stake, two are 50%
+    let swap_table = LeaderSwapTable::new(&committee, &scores(&committee, &[0, 1, 5, 7]), 33);
+    assert_eq!(swap_table.bad_nodes(), &BTreeSet::from([names[0].clone()]));
+
+    // in tests, the first authority leads every round before swaps
+    let schedule = LeaderSchedule::new(committee.clone(), swap_table);
+    assert_eq!(schedule.leader(2), names[3]);
+    assert_eq!(schedule.leader(4), names[3]);
+    assert_eq!(schedule.bad_nodes_stake(), 1);
+}
+
+#[test]
+fn bad_nodes_are_not_swapped_in() {
+    let fixture = CommitteeFixture::builder()
+        .committee_size(NonZeroUsize::new(7).unwrap())
+        .build();
+    let mut committee = fixture.committee();
+    let names: Vec<_> = committee.authorities.keys().cloned().collect();
+    // the authority with the best reputation holds most of the stake, so that the six others fit
+    // within the stake that can be swapped out
+    committee.authorities.get_mut(&names[6]).unwrap().stake = 100;
+
+    let swap_table =
+        LeaderSwapTable::new(&committee, &scores(&committee, &[0, 1, 2, 3, 4, 5, 9]), 33);
+    assert_eq!(
+        swap_table.bad_nodes(),
+        &names[..6].iter().cloned().collect::<BTreeSet<_>>()
+    );
+    assert_eq!(swap_table.good_nodes, vec![names[6].clone()]);
+
+    let schedule = LeaderSchedule::new(committee, swap_table);
+    for round in (2..=20).step_by(2) {
+        assert_eq!(schedule.leader(round), names[6]);
+    }
+    assert_eq!(schedule.bad_nodes_stake(), 6);
+}
+
+#[test]
+fn equal_reputation_keeps_the_schedule() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let names: Vec<_> = committee.authorities.keys().cloned().collect();
+
+    let swap_table = LeaderSwapTable::new(&committee, &scores(&committee, &[3, 3, 3, 3]), 33);
+    assert!(swap_table.bad_nodes().is_empty());
+    let schedule = LeaderSchedule::new(committee, swap_table);
+    assert_eq!(schedule.leader(2), names[0]);
+}
+
+#[test]
+fn leader_schedule_is_recovered_from_the_store() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let names: Vec<_> = committee.authorities.keys().cloned().collect();
+    let store = make_consensus_store(&test_utils::temp_dir());
+    let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
+
+    // the scores the schedule last changed with, and the scores of the next window so far
+    let mut final_scores = scores(&committee, &[0, 4, 4, 4]);
+    final_scores.final_of_schedule = true;
+    let ongoing_scores = scores(&committee, &[2, 0, 0, 0]);
+    for (round, scores) in [(2, &final_scores), (4, &ongoing_scores)] {
+        let (_, leader) =
+            test_utils::mock_certificate(&committee, names[1].clone(), round, BTreeSet::new());
+        let sub_dag = CommittedSubDag {
+            certificates: vec![],
+            leader,
+        };
+        store
+            .write_committed_sub_dag(&HashMap::new(), &sub_dag, scores)
+            .unwrap();
+    }
+
+    let bullshark = Bullshark::new(committee, store, &Parameters::default(), metrics);
+    assert_eq!(bullshark.reputation_scores, ongoing_scores);
+    assert!(bullshark
+        .leader_schedule
+        .swap_table()
+        .bad_nodes()
+        .contains(&names[0]));
+    assert_ne!(bullshark.leader_schedule.leader(6), names[0]);
+}
+
+#[test]
+fn reputation_scores_are_pruned_when_the_schedule_changes() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let names: Vec<_> = committee.authorities.keys().cloned().collect();
+    let path = test_utils::temp_dir();
+    let store = make_consensus_store(&path);
+
+    // two scoring windows, each made of an ongoing and a final score
+    let ongoing_scores = scores(&committee, &[1, 0, 0, 0]);
+    let mut final_scores = scores(&committee, &[1, 1, 0, 0]);
+    final_scores.final_of_schedule = true;
+    for (round, scores) in [
+        (2, &ongoing_scores),
+        (4, &final_scores),
+        (6, &ongoing_scores),
+        (8, &final_scores),
+        (10, &ongoing_scores),
+    ] {
+        let (_, leader) =
+            test_utils::mock_certificate(&committee, names[1].clone(), round, BTreeSet::new());
+        let sub_dag = CommittedSubDag {
+            certificates: vec![],
+            leader,
+        };
+        store
+            .write_committed_sub_dag(&HashMap::new(), &sub_dag, scores)
+            .unwrap();
+    }
+    assert_eq!(
+        store.read_reputation_scores().unwrap(),
+        (Some(ongoing_scores), Some(final_scores))
+    );
+    drop(store);
+
+    // only the scores since the last schedule change are kept
+    let rocksdb = rocks::open_cf(&path, None, &["reputation_scores"]).unwrap();
+    let reputation_scores_map =
+        DBMap::<Round, ReputationScores>::reopen(&rocksdb, Some("reputation_scores")).unwrap();
+    let rounds: Vec<_> = reputation_scores_map.keys().collect();
+    assert_eq!(rounds, vec![8, 10]);
+}
@@ -35,6 +35,9 @@ pub enum SubscriberError {
     #[error("Error occurred while retrieving certificate {0} payload: {1}")]
     PayloadRetrieveError(CertificateDigest, String),
 
+    #[error("Certificate {0} committed by consensus not found in the certificate store")]
+    CertificateNotFound(CertificateDigest),
+
     #[error("Consensus referenced unexpected worker id {0}")]
     UnexpectedWorkerId(WorkerId),
 
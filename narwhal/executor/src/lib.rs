@@ -6,8 +6,10 @@ mod subscriber;
 
 mod metrics;
 mod notifier;
+mod replay;
 
 pub use errors::{SubscriberError, SubscriberResult};
+pub use replay::{ConsensusReplay, ReplayMismatch, ReplaySummary};
 pub use state::ExecutionIndices;
 use tracing::info;
 
@@ -126,6 +128,8 @@ pub async fn get_restored_consensus_output<State: ExecutionState>(
         .await
         .last_committed_round;
 
+    // Unlike the replay tool, the restore skips the certificates missing from the store, such as
+    // the ones the `BlockRemover` removed under external consensus.
     let compressed_sub_dags =
         consensus_store.read_committed_sub_dags_from(&last_committed_leader)?;
 
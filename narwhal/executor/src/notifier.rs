@@ -41,12 +41,15 @@ impl<State: ExecutionState + Send + Sync + 'static> Notifier<State> {
             debug!(
                 "Notifier processes batch {}, num of transactions: {}",
                 batch.digest(),
-                batch.transactions.len()
+                batch.transactions().len()
             );
             self.metrics.notifier_processed_batches.inc();
 
+            let created_at = batch.metadata.created_at;
             let mut bytes = 0usize;
-            for (transaction_index, transaction) in batch.transactions.into_iter().enumerate() {
+            for (transaction_index, transaction) in
+                batch.into_transactions().into_iter().enumerate()
+            {
                 let execution_indices = ExecutionIndices {
                     last_committed_round: index.sub_dag.round(),
                     next_certificate_index: index.next_certificate_index,
@@ -71,7 +74,7 @@ impl<State: ExecutionState + Send + Sync + 'static> Notifier<State> {
 
             self.metrics
                 .batch_execution_latency
-                .observe(batch.metadata.created_at.elapsed().as_secs_f64());
+                .observe(created_at.elapsed().as_secs_f64());
             self.metrics.notifier_processed_bytes.inc_by(bytes as u64);
         }
     }
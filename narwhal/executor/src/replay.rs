@@ -0,0 +1,270 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{ExecutionIndices, ExecutionState, SubscriberError, SubscriberResult};
+use config::WorkerId;
+use fastcrypto::hash::Hash;
+use std::{collections::HashMap, sync::Arc};
+use storage::CertificateStore;
+use store::Store;
+use tracing::{debug, warn};
+use types::{
+    Batch, BatchDigest, CertificateDigest, CommittedSubDag, CommittedSubDagShell, ConsensusOutput,
+    ConsensusStore, Round, SequenceNumber,
+};
+
+/// An inconsistency found while replaying the consensus output in verifier mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayMismatch {
+    /// The consensus store sequenced another certificate at the index of a committed certificate.
+    Sequence {
+        consensus_index: SequenceNumber,
+        committed: CertificateDigest,
+        sequenced: Option<CertificateDigest>,
+    },
+    /// A committed certificate does not follow the certificate committed before it.
+    OutOfOrder {
+        consensus_index: SequenceNumber,
+        previous: SequenceNumber,
+    },
+    /// After a commit, the execution state loads other indices than those of the last
+    /// transaction it was fed.
+    ExecutionIndices {
+        round: Round,
+        expected: ExecutionIndices,
+        loaded: ExecutionIndices,
+    },
+}
+
+/// What a replay fed the execution state.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReplaySummary {
+    pub sub_dags: u64,
+    pub certificates: u64,
+    pub batches: u64,
+    pub transactions: u64,
+    /// The execution indices of the last transaction replayed, if any.
+    pub last_execution_indices: Option<ExecutionIndices>,
+    /// The inconsistencies found, in verifier mode.
+    pub mismatches: Vec<ReplayMismatch>,
+}
+
+/// Re-runs, offline, the consensus output a node committed. The committed sub-dags are read from
+/// the consensus and certificate stores in commit order, and their transactions are fed to an
+/// `ExecutionState` from the batch stores of the node's workers, with the same execution indices
+/// and commit boundaries as the executor feeds them.
+pub struct ConsensusReplay {
+    consensus_store: Arc<ConsensusStore>,
+    certificate_store: CertificateStore,
+    batch_stores: HashMap<WorkerId, Store<BatchDigest, Batch>>,
+}
+
+impl ConsensusReplay {
+    pub fn new(
+        consensus_store: Arc<ConsensusStore>,
+        certificate_store: CertificateStore,
+        batch_stores: HashMap<WorkerId, Store<BatchDigest, Batch>>,
+    ) -> Self {
+        Self {
+            consensus_store,
+            certificate_store,
+            batch_stores,
+        }
+    }
+
+    /// Load the sub-dags committed by a leader with round number of at least `from`, in commit
+    /// order.
+    pub fn read_sub_dags(&self, from: Round) -> SubscriberResult<Vec<CommittedSubDag>> {
+        read_committed_sub_dags(&self.consensus_store, &self.certificate_store, from)
+    }
+
+    /// Feed the transactions of the sub-dags committed from round `from` to the execution state.
+    pub async fn replay<State: ExecutionState>(
+        &self,
+        execution_state: &State,
+        from: Round,
+    ) -> SubscriberResult<ReplaySummary> {
+        self.run(execution_state, from, false).await
+    }
+
+    /// Replay like `replay`, and check the replayed output against what the node recorded: the
+    /// consensus index of each committed certificate against the consensus sequence, and the
+    /// execution indices the execution state loads after each commit against the indices of the
+    /// last transaction it was fed.
+    pub async fn verify<State: ExecutionState>(
+        &self,
+        execution_state: &State,
+        from: Round,
+    ) -> SubscriberResult<ReplaySummary> {
+        self.run(execution_state, from, true).await
+    }
+
+    async fn run<State: ExecutionState>(
+        &self,
+        execution_state: &State,
+        from: Round,
+        verify: bool,
+    ) -> SubscriberResult<ReplaySummary> {
+        let mut summary = ReplaySummary::default();
+        let mut previous_index: Option<SequenceNumber> = None;
+
+        // Read the certificates of one sub-dag at a time, as the history may be long.
+        for shell in self.consensus_store.read_committed_sub_dags_from(&from)? {
+            let sub_dag = Arc::new(read_sub_dag(&self.certificate_store, shell)?);
+            debug!("Replaying sub-dag committed at round {}", sub_dag.round());
+            summary.sub_dags += 1;
+
+            for output in &sub_dag.certificates {
+                summary.certificates += 1;
+                if verify {
+                    self.verify_sequence(output, previous_index, &mut summary.mismatches)?;
+                }
+                previous_index = Some(output.consensus_index);
+
+                let output_arc = Arc::new(output.clone());
+                let payload = &output.certificate.header.payload;
+                for (batch_index, (digest, worker_id)) in payload.iter().enumerate() {
+                    let batch = self.read_batch(output, *digest, *worker_id).await?;
+                    summary.batches += 1;
+
+                    // The same indices and boundaries as the notifier's.
+                    for (transaction_index, transaction) in
+                        batch.into_transactions().into_iter().enumerate()
+                    {
+                        let execution_indices = ExecutionIndices {
+                            last_committed_round: sub_dag.round(),
+                            next_certificate_index: output.consensus_index,
+                            next_batch_index: batch_index as u64 + 1,
+                            next_transaction_index: transaction_index as u64 + 1,
+                        };
+                        summary.transactions += 1;
+                        summary.last_execution_indices = Some(execution_indices.clone());
+                        execution_state
+                            .handle_consensus_transaction(
+                                &output_arc,
+                                execution_indices,
+                                transaction,
+                            )
+                            .await;
+                    }
+
+                    if batch_index + 1 == payload.len() && sub_dag.is_last(output) {
+                        execution_state.notify_commit_boundary(&sub_dag).await;
+                    }
+                }
+            }
+
+            if verify {
+                if let Some(expected) = &summary.last_execution_indices {
+                    let loaded = execution_state.load_execution_indices().await;
+                    if &loaded != expected {
+                        warn!(
+                            "Execution indices {loaded:?} loaded after the commit at round {} differ from the replayed {expected:?}",
+                            sub_dag.round()
+                        );
+                        summary.mismatches.push(ReplayMismatch::ExecutionIndices {
+                            round: sub_dag.round(),
+                            expected: expected.clone(),
+                            loaded,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    fn verify_sequence(
+        &self,
+        output: &ConsensusOutput,
+        previous_index: Option<SequenceNumber>,
+        mismatches: &mut Vec<ReplayMismatch>,
+    ) -> SubscriberResult<()> {
+        let consensus_index = output.consensus_index;
+        if let Some(previous) = previous_index {
+            if consensus_index != previous + 1 {
+                mismatches.push(ReplayMismatch::OutOfOrder {
+                    consensus_index,
+                    previous,
+                });
+            }
+        }
+
+        // The sequence is keyed by the index following the one of each certificate.
+        let committed = output.certificate.digest();
+        let sequenced = self
+            .consensus_store
+            .read_sequenced_certificate(&(consensus_index + 1))?;
+        if sequenced != Some(committed) {
+            mismatches.push(ReplayMismatch::Sequence {
+                consensus_index,
+                committed,
+                sequenced,
+            });
+        }
+        Ok(())
+    }
+
+    async fn read_batch(
+        &self,
+        output: &ConsensusOutput,
+        digest: BatchDigest,
+        worker_id: WorkerId,
+    ) -> SubscriberResult<Batch> {
+        let store = self
+            .batch_stores
+            .get(&worker_id)
+            .ok_or(SubscriberError::UnexpectedWorkerId(worker_id))?;
+        store.read(digest).await?.ok_or_else(|| {
+            SubscriberError::PayloadRetrieveError(
+                output.certificate.digest(),
+                format!("batch {digest} not found in the store of worker {worker_id}"),
+            )
+        })
+    }
+}
+
+/// Load the sub-dags committed by a leader with round number of at least `from`, in commit order.
+/// Fails on the first certificate missing from the store, so that a replay never skips one.
+fn read_committed_sub_dags(
+    consensus_store: &ConsensusStore,
+    certificate_store: &CertificateStore,
+    from: Round,
+) -> SubscriberResult<Vec<CommittedSubDag>> {
+    consensus_store
+        .read_committed_sub_dags_from(&from)?
+        .into_iter()
+        .map(|shell| read_sub_dag(certificate_store, shell))
+        .collect()
+}
+
+fn read_sub_dag(
+    certificate_store: &CertificateStore,
+    shell: CommittedSubDagShell,
+) -> SubscriberResult<CommittedSubDag> {
+    let (certificate_digests, consensus_indices): (Vec<_>, Vec<_>) =
+        shell.certificates.into_iter().unzip();
+
+    let certificates = certificate_store.read_all(certificate_digests.iter().cloned())?;
+    let mut outputs = Vec::with_capacity(certificates.len());
+    for ((certificate, digest), consensus_index) in certificates
+        .into_iter()
+        .zip(certificate_digests)
+        .zip(consensus_indices)
+    {
+        let certificate = certificate.ok_or(SubscriberError::CertificateNotFound(digest))?;
+        outputs.push(ConsensusOutput {
+            certificate,
+            consensus_index,
+        });
+    }
+
+    let leader = certificate_store
+        .read(shell.leader)?
+        .ok_or(SubscriberError::CertificateNotFound(shell.leader))?;
+
+    Ok(CommittedSubDag {
+        certificates: outputs,
+        leader,
+    })
+}
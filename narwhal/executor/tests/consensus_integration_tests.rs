@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use bytes::Bytes;
+use config::Parameters;
 use consensus::bullshark::Bullshark;
 use consensus::metrics::ConsensusMetrics;
 use consensus::Consensus;
@@ -59,7 +60,10 @@ async fn test_recovery() {
     let bullshark = Bullshark::new(
         committee.clone(),
         consensus_store.clone(),
-        gc_depth,
+        &Parameters {
+            gc_depth,
+            ..Parameters::default()
+        },
         metrics.clone(),
     );
 
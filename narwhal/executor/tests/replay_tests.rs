@@ -0,0 +1,257 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use async_trait::async_trait;
+use fastcrypto::hash::Hash;
+use narwhal_executor::{
+    get_restored_consensus_output, ConsensusReplay, ExecutionIndices, ExecutionState,
+    ReplayMismatch, SubscriberError,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use storage::NodeStorage;
+use test_utils::{fixture_batch_with_transactions, temp_dir, CommitteeFixture};
+use types::{
+    Certificate, CommittedSubDag, ConsensusOutput, ReputationScores, SequenceNumber, Transaction,
+};
+
+/// Records what it is fed, and persists the indices of every transaction but those it skips.
+#[derive(Default)]
+struct RecordingExecutionState {
+    skip: Option<SequenceNumber>,
+    transactions: Mutex<Vec<(ExecutionIndices, Transaction)>>,
+    commit_boundaries: Mutex<Vec<u64>>,
+    indices: Mutex<ExecutionIndices>,
+}
+
+#[async_trait]
+impl ExecutionState for RecordingExecutionState {
+    async fn handle_consensus_transaction(
+        &self,
+        _consensus_output: &Arc<ConsensusOutput>,
+        execution_indices: ExecutionIndices,
+        transaction: Vec<u8>,
+    ) {
+        if self.skip != Some(execution_indices.next_certificate_index) {
+            *self.indices.lock().unwrap() = execution_indices.clone();
+        }
+        self.transactions
+            .lock()
+            .unwrap()
+            .push((execution_indices, transaction));
+    }
+
+    async fn notify_commit_boundary(&self, committed_dag: &Arc<CommittedSubDag>) {
+        self.commit_boundaries
+            .lock()
+            .unwrap()
+            .push(committed_dag.round());
+    }
+
+    async fn load_execution_indices(&self) -> ExecutionIndices {
+        self.indices.lock().unwrap().clone()
+    }
+}
+
+// Commit two sub-dags, led at rounds 2 and 4, of two certificates with a batch of two
+// transactions each, as consensus persists them. Returns the committed transactions in order.
+async fn commit_sub_dags(storage: &NodeStorage) -> Vec<Transaction> {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let authorities: Vec<_> = fixture.authorities().collect();
+
+    let mut transactions = Vec::new();
+    let mut consensus_index = 0;
+    for round in [2, 4] {
+        let mut certificates: Vec<Certificate> = Vec::new();
+        for (i, author) in authorities.iter().take(2).enumerate() {
+            let batch = fixture_batch_with_transactions(2);
+            transactions.extend(batch.transactions().iter().cloned());
+            let header = author
+                .header_builder(&committee)
+                .round(round - 1 + i as u64)
+                .with_payload_batch(batch.clone(), 0)
+                .build(author.keypair())
+                .unwrap();
+            let certificate = fixture.certificate(&header);
+            storage
+                .certificate_store
+                .write(certificate.clone())
+                .unwrap();
+            storage
+                .batch_store
+                .sync_write(batch.digest(), batch)
+                .await
+                .unwrap();
+            certificates.push(certificate);
+        }
+
+        let mut outputs = Vec::new();
+        for certificate in certificates {
+            consensus_index += 1;
+            storage
+                .consensus_store
+                .write_consensus_state(&HashMap::new(), &consensus_index, &certificate.digest())
+                .unwrap();
+            outputs.push(ConsensusOutput {
+                certificate,
+                consensus_index: consensus_index - 1,
+            });
+        }
+        let sub_dag = CommittedSubDag {
+            leader: outputs.last().unwrap().certificate.clone(),
+            certificates: outputs,
+        };
+        storage
+            .consensus_store
+            .write_committed_sub_dag(&HashMap::new(), &sub_dag, &ReputationScores::default())
+            .unwrap();
+    }
+    transactions
+}
+
+fn replay(storage: &NodeStorage) -> ConsensusReplay {
+    ConsensusReplay::new(
+        storage.consensus_store.clone(),
+        storage.certificate_store.clone(),
+        HashMap::from([(0, storage.batch_store.clone())]),
+    )
+}
+
+#[tokio::test]
+async fn replay_feeds_committed_transactions_in_order() {
+    let storage = NodeStorage::reopen(temp_dir());
+    let transactions = commit_sub_dags(&storage).await;
+    let execution_state = RecordingExecutionState::default();
+
+    let summary = replay(&storage).replay(&execution_state, 0).await.unwrap();
+    assert_eq!(summary.sub_dags, 2);
+    assert_eq!(summary.certificates, 4);
+    assert_eq!(summary.batches, 4);
+    assert_eq!(summary.transactions, 8);
+    assert!(summary.mismatches.is_empty());
+
+    let fed = execution_state.transactions.lock().unwrap();
+    assert_eq!(
+        fed.iter().map(|(_, t)| t.clone()).collect::<Vec<_>>(),
+        transactions
+    );
+    assert_eq!(
+        fed[5].0,
+        ExecutionIndices {
+            last_committed_round: 4,
+            next_certificate_index: 2,
+            next_batch_index: 1,
+            next_transaction_index: 2,
+        }
+    );
+    assert_eq!(summary.last_execution_indices.as_ref(), Some(&fed[7].0));
+    assert_eq!(
+        *execution_state.commit_boundaries.lock().unwrap(),
+        vec![2, 4]
+    );
+
+    // replaying from a later round skips the earlier commits
+    let execution_state = RecordingExecutionState::default();
+    let summary = replay(&storage).replay(&execution_state, 3).await.unwrap();
+    assert_eq!(summary.sub_dags, 1);
+    assert_eq!(summary.transactions, 4);
+}
+
+#[tokio::test]
+async fn verify_accepts_a_consistent_execution() {
+    let storage = NodeStorage::reopen(temp_dir());
+    commit_sub_dags(&storage).await;
+    let execution_state = RecordingExecutionState::default();
+
+    let summary = replay(&storage).verify(&execution_state, 0).await.unwrap();
+    assert_eq!(summary.transactions, 8);
+    assert!(summary.mismatches.is_empty());
+}
+
+#[tokio::test]
+async fn verify_reports_diverging_execution_indices() {
+    let storage = NodeStorage::reopen(temp_dir());
+    commit_sub_dags(&storage).await;
+    let execution_state = RecordingExecutionState {
+        skip: Some(3),
+        ..Default::default()
+    };
+
+    let summary = replay(&storage).verify(&execution_state, 0).await.unwrap();
+    assert_eq!(summary.mismatches.len(), 1);
+    let ReplayMismatch::ExecutionIndices { round, expected, loaded } = &summary.mismatches[0] else {
+        panic!("Unexpected mismatch {:?}", summary.mismatches[0]);
+    };
+    assert_eq!(*round, 4);
+    assert_eq!(expected.next_certificate_index, 3);
+    assert_eq!(loaded.next_certificate_index, 2);
+}
+
+#[tokio::test]
+async fn verify_reports_diverging_sequence() {
+    let storage = NodeStorage::reopen(temp_dir());
+    commit_sub_dags(&storage).await;
+
+    // sequence another certificate at the index of the first one
+    let sub_dags = replay(&storage).read_sub_dags(0).unwrap();
+    let other = sub_dags[1].certificates[0].certificate.digest();
+    storage
+        .consensus_store
+        .write_consensus_state(&HashMap::new(), &1, &other)
+        .unwrap();
+
+    let execution_state = RecordingExecutionState::default();
+    let summary = replay(&storage).verify(&execution_state, 0).await.unwrap();
+    assert_eq!(
+        summary.mismatches,
+        vec![ReplayMismatch::Sequence {
+            consensus_index: 0,
+            committed: sub_dags[0].certificates[0].certificate.digest(),
+            sequenced: Some(other),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn replay_fails_on_missing_batch() {
+    let storage = NodeStorage::reopen(temp_dir());
+    commit_sub_dags(&storage).await;
+    let replay = ConsensusReplay::new(
+        storage.consensus_store.clone(),
+        storage.certificate_store.clone(),
+        HashMap::from([(0, test_utils::open_batch_store())]),
+    );
+
+    let execution_state = RecordingExecutionState::default();
+    assert!(matches!(
+        replay.replay(&execution_state, 0).await,
+        Err(SubscriberError::PayloadRetrieveError(..))
+    ));
+}
+
+#[tokio::test]
+async fn restore_skips_missing_certificates_unlike_replay() {
+    let storage = NodeStorage::reopen(temp_dir());
+    commit_sub_dags(&storage).await;
+
+    // remove a certificate of the first sub-dag, as the `BlockRemover` does
+    let sub_dags = replay(&storage).read_sub_dags(0).unwrap();
+    let removed = sub_dags[0].certificates[0].certificate.digest();
+    storage.certificate_store.delete(removed).unwrap();
+
+    let restored = get_restored_consensus_output(
+        storage.consensus_store.clone(),
+        storage.certificate_store.clone(),
+        &RecordingExecutionState::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(restored.len(), 2);
+    assert_eq!(restored[0].certificates.len(), 1);
+    assert_eq!(restored[1].certificates.len(), 2);
+
+    assert!(matches!(
+        replay(&storage).read_sub_dags(0),
+        Err(SubscriberError::CertificateNotFound(digest)) if digest == removed
+    ));
+}
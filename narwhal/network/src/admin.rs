@@ -1,21 +1,38 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use axum::response::{IntoResponse, Response};
 use axum::routing::post;
-use axum::{extract::Extension, http::StatusCode, routing::get, Json, Router};
+use axum::{
+    extract::{Extension, Query},
+    http::{header, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
 use sui_metrics::spawn_monitored_task;
-use tokio::sync::watch;
+use tokio::sync::{oneshot, watch};
 use tokio::task::JoinHandle;
-use tracing::info;
+use tracing::{info, warn};
 use types::metered_channel::Sender;
-use types::ReconfigureNotification;
+use types::{DagExport, ReconfigureNotification, ReconfigureRequest, Round, StoreResult};
+
+#[cfg(test)]
+#[path = "tests/admin_tests.rs"]
+mod admin_tests;
+
+/// Exports the recent DAG of a primary, from the certificates above the given round or above
+/// the GC round of consensus.
+pub type ExportDag = Arc<dyn Fn(Option<Round>) -> StoreResult<DagExport> + Send + Sync>;
 
 pub fn start_admin_server(
     port: u16,
     network: anemo::Network,
     mut rx_reconfigure: watch::Receiver<ReconfigureNotification>,
-    tx_state_handler: Option<Sender<ReconfigureNotification>>,
+    tx_state_handler: Option<Sender<ReconfigureRequest>>,
+    export_dag: Option<ExportDag>,
 ) -> Vec<JoinHandle<()>> {
     let mut router = Router::new()
         .route("/peers", get(get_peers))
@@ -29,6 +46,11 @@ pub fn start_admin_server(
         router = router.merge(r);
     }
 
+    // Primaries running the internal consensus will have this service enabled
+    if let Some(export_dag) = export_dag {
+        router = router.merge(dag_router(export_dag));
+    }
+
     router = router.layer(Extension(network));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -63,6 +85,12 @@ pub fn start_admin_server(
     handles
 }
 
+fn dag_router(export_dag: ExportDag) -> Router {
+    Router::new()
+        .route("/dag", get(get_dag))
+        .layer(Extension(export_dag))
+}
+
 async fn get_peers(
     Extension(network): Extension<anemo::Network>,
 ) -> (StatusCode, Json<Vec<String>>) {
@@ -88,10 +116,70 @@ async fn get_known_peers(
     )
 }
 
+/// Answers with a conflict when the primary rejects the request, e.g. an epoch change scheduled at
+/// a round consensus already committed.
 async fn reconfigure(
-    Extension(tx_state_handler): Extension<Sender<ReconfigureNotification>>,
+    Extension(tx_state_handler): Extension<Sender<ReconfigureRequest>>,
     Json(reconfigure_notification): Json<ReconfigureNotification>,
 ) -> StatusCode {
-    let _ = tx_state_handler.send(reconfigure_notification).await;
-    StatusCode::OK
+    let (tx_accepted, rx_accepted) = oneshot::channel();
+    if tx_state_handler
+        .send((reconfigure_notification, tx_accepted))
+        .await
+        .is_err()
+    {
+        return StatusCode::SERVICE_UNAVAILABLE;
+    }
+    match rx_accepted.await {
+        Ok(true) => StatusCode::OK,
+        Ok(false) => StatusCode::CONFLICT,
+        Err(_) => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+#[derive(Deserialize)]
+struct DagQuery {
+    /// `json` (the default) or `dot`.
+    format: Option<String>,
+    from_round: Option<Round>,
+}
+
+/// The export reads the consensus and certificate stores, so it runs on the blocking thread pool.
+async fn get_dag(
+    Extension(export_dag): Extension<ExportDag>,
+    Query(query): Query<DagQuery>,
+) -> Response {
+    let dot = match query.format.as_deref() {
+        None | Some("json") => false,
+        Some("dot") => true,
+        Some(format) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("Unknown DAG format {format}, expected json or dot"),
+            )
+                .into_response()
+        }
+    };
+    let from_round = query.from_round;
+    let export = match tokio::task::spawn_blocking(move || export_dag(from_round)).await {
+        Ok(Ok(export)) => export,
+        Ok(Err(e)) => {
+            warn!("Failed to export the DAG: {e}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+        }
+        Err(e) => {
+            warn!("Failed to export the DAG: {e}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if dot {
+        (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/vnd.graphviz")],
+            export.to_dot(),
+        )
+            .into_response()
+    } else {
+        (StatusCode::OK, Json(export)).into_response()
+    }
 }
@@ -0,0 +1,187 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Faults injected in the messages nodes send each other, to test narwhal under adverse network
+//! conditions. The nodes of a test share the faults installed in the process, and an outbound
+//! `FaultInjectionLayer` delays or drops the requests of each node as the faults of the link to
+//! their destination say. Every fault is sampled from a seeded random generator, so that a test
+//! running in the simulator fails the same way every time it runs with the same seed.
+
+use anemo::{PeerId, Request, Response};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tower::{Layer, Service};
+use tracing::trace;
+
+/// The faults of the messages sent from a node to another.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinkFaults {
+    /// The probability that a message is dropped.
+    pub drop_probability: f64,
+    /// The delay of each message is sampled uniformly between `min_delay` and `max_delay`, so
+    /// that messages overtake the messages sent before them when the range is wider than the
+    /// interval between them.
+    pub min_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl LinkFaults {
+    /// A link dropping every message.
+    pub fn partition() -> Self {
+        Self {
+            drop_probability: 1.0,
+            ..Default::default()
+        }
+    }
+
+    /// A link delaying messages by `min_delay` to `max_delay`, reordering them.
+    pub fn delay(min_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            min_delay,
+            max_delay,
+            ..Default::default()
+        }
+    }
+}
+
+static NETWORK_FAULTS: RwLock<Option<Arc<NetworkFaults>>> = RwLock::new(None);
+
+/// The faults of the links between the nodes of a test.
+pub struct NetworkFaults {
+    seed: u64,
+    rng: Mutex<StdRng>,
+    links: RwLock<HashMap<(PeerId, PeerId), LinkFaults>>,
+}
+
+impl NetworkFaults {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+            links: RwLock::default(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Apply these faults to the nodes of the process, in place of the faults installed before.
+    pub fn install(self: &Arc<Self>) {
+        *NETWORK_FAULTS.write().unwrap() = Some(self.clone());
+    }
+
+    /// Stop injecting faults in the nodes of the process.
+    pub fn uninstall() {
+        *NETWORK_FAULTS.write().unwrap() = None;
+    }
+
+    /// The faults installed in the process, if any.
+    pub fn installed() -> Option<Arc<Self>> {
+        NETWORK_FAULTS.read().unwrap().clone()
+    }
+
+    /// Inject faults in the messages `from` sends `to`.
+    pub fn set_link(&self, from: PeerId, to: PeerId, faults: LinkFaults) {
+        self.links.write().unwrap().insert((from, to), faults);
+    }
+
+    /// Stop injecting faults in the messages `from` sends `to`.
+    pub fn clear_link(&self, from: PeerId, to: PeerId) {
+        self.links.write().unwrap().remove(&(from, to));
+    }
+
+    /// Stop injecting faults in any message.
+    pub fn clear(&self) {
+        self.links.write().unwrap().clear();
+    }
+
+    /// Sample the delay of a message `from` sends `to`, and whether it is dropped.
+    fn sample(&self, from: PeerId, to: PeerId) -> Option<(Duration, bool)> {
+        let faults = self.links.read().unwrap().get(&(from, to))?.clone();
+        let mut rng = self.rng.lock().unwrap();
+        let delay = if faults.max_delay > faults.min_delay {
+            rng.gen_range(faults.min_delay..faults.max_delay)
+        } else {
+            faults.min_delay
+        };
+        let dropped = rng.gen_bool(faults.drop_probability.clamp(0.0, 1.0));
+        Some((delay, dropped))
+    }
+}
+
+/// Injects the installed `NetworkFaults` in the outbound requests of a node.
+#[derive(Clone)]
+pub struct FaultInjectionLayer {
+    peer_id: PeerId,
+}
+
+impl FaultInjectionLayer {
+    /// `peer_id` is the peer id of the node sending the requests.
+    pub fn new(peer_id: PeerId) -> Self {
+        Self { peer_id }
+    }
+}
+
+impl<S> Layer<S> for FaultInjectionLayer {
+    type Service = FaultInjection<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FaultInjection {
+            inner,
+            peer_id: self.peer_id,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FaultInjection<S> {
+    inner: S,
+    peer_id: PeerId,
+}
+
+impl<S> Service<Request<Bytes>> for FaultInjection<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>> + Clone + Send + 'static,
+    S::Error: From<anemo::Error>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Bytes>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let fault = NetworkFaults::installed()
+            .zip(request.peer_id().copied())
+            .and_then(|(faults, to)| Some((to, faults.sample(self.peer_id, to)?)));
+
+        // Call the service that was polled ready, and leave a clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let from = self.peer_id;
+        Box::pin(async move {
+            let Some((to, (delay, dropped))) = fault else {
+                return inner.call(request).await;
+            };
+            tokio::time::sleep(delay).await;
+            if dropped {
+                trace!("Dropping request from {from} to {to}");
+                return Err(
+                    anemo::Error::msg(format!("Request from {from} to {to} dropped")).into(),
+                );
+            }
+            inner.call(request).await
+        })
+    }
+}
@@ -13,6 +13,7 @@ pub mod admin;
 pub mod anemo_ext;
 mod bounded_executor;
 pub mod connectivity;
+pub mod fault_injection;
 pub mod metrics;
 mod p2p;
 mod retry;
@@ -80,3 +81,82 @@ pub fn multiaddr_to_address(
         _ => Err(anyhow::anyhow!("invalid address")),
     }
 }
+
+/// Make `peers` the known peers of the network, in place of the current ones. The network
+/// connects to the new peers, keeps its connections to the peers that remain, and disconnects
+/// from the others. Peers whose address cannot be parsed are skipped.
+pub fn update_known_peers(
+    network: &anemo::Network,
+    peers: impl IntoIterator<Item = (crypto::NetworkPublicKey, multiaddr::Multiaddr)>,
+) {
+    use anemo::types::{PeerAffinity, PeerInfo};
+
+    let peers: std::collections::HashMap<_, _> = peers
+        .into_iter()
+        .filter_map(|(public_key, address)| {
+            let peer_id = anemo::PeerId(public_key.0.to_bytes());
+            match multiaddr_to_address(&address) {
+                Ok(address) => Some((peer_id, address)),
+                Err(e) => {
+                    tracing::warn!("Skipping peer {peer_id} with invalid address {address}: {e}");
+                    None
+                }
+            }
+        })
+        .collect();
+
+    for known in network.known_peers().get_all() {
+        if !peers.contains_key(&known.peer_id) {
+            tracing::info!("Removing peer {}", known.peer_id);
+            network.known_peers().remove(&known.peer_id);
+            let _ = network.disconnect(known.peer_id);
+        }
+    }
+    for (peer_id, address) in peers {
+        network.known_peers().insert(PeerInfo {
+            peer_id,
+            affinity: PeerAffinity::High,
+            address: vec![address],
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::CommitteeFixture;
+
+    #[tokio::test]
+    async fn update_known_peers_skips_invalid_addresses() {
+        let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+        let mut authorities = fixture.authorities();
+        let network = authorities
+            .next()
+            .unwrap()
+            .new_network(anemo::Router::new());
+        let valid = authorities.next().unwrap();
+        let invalid = authorities.next().unwrap();
+
+        update_known_peers(
+            &network,
+            [
+                (valid.network_public_key(), valid.address().clone()),
+                (
+                    invalid.network_public_key(),
+                    "/ip4/127.0.0.1".parse().unwrap(),
+                ),
+            ],
+        );
+
+        let known_peers: Vec<_> = network
+            .known_peers()
+            .get_all()
+            .into_iter()
+            .map(|peer| peer.peer_id)
+            .collect();
+        assert_eq!(
+            known_peers,
+            vec![anemo::PeerId(valid.network_public_key().0.to_bytes())]
+        );
+    }
+}
@@ -20,9 +20,9 @@ use types::{
     Batch, BatchDigest, FetchCertificatesRequest, FetchCertificatesResponse,
     GetCertificatesRequest, GetCertificatesResponse, LatestHeaderRequest, LatestHeaderResponse,
     PrimaryMessage, PrimaryToPrimaryClient, PrimaryToWorkerClient, RequestBatchRequest,
-    WorkerBatchMessage, WorkerDeleteBatchesMessage, WorkerOthersBatchMessage,
-    WorkerOurBatchMessage, WorkerReconfigureMessage, WorkerSynchronizeMessage,
-    WorkerToPrimaryClient, WorkerToWorkerClient,
+    WorkerBatchChunkMessage, WorkerBatchMessage, WorkerDeleteBatchesMessage,
+    WorkerOthersBatchMessage, WorkerOurBatchMessage, WorkerReconfigureMessage,
+    WorkerSynchronizeMessage, WorkerToPrimaryClient, WorkerToWorkerClient,
 };
 
 fn default_executor() -> BoundedExecutor {
@@ -414,6 +414,28 @@ impl ReliableNetwork<WorkerBatchMessage> for P2pNetwork {
     }
 }
 
+#[async_trait]
+impl ReliableNetwork<WorkerBatchChunkMessage> for P2pNetwork {
+    type Response = ();
+    async fn send(
+        &mut self,
+        peer: NetworkPublicKey,
+        message: &WorkerBatchChunkMessage,
+    ) -> CancelOnDropHandler<Result<anemo::Response<()>>> {
+        let message = message.to_owned();
+        let f = move |peer| {
+            let message = message.clone();
+            async move {
+                WorkerToWorkerClient::new(peer)
+                    .report_batch_chunk(message)
+                    .await
+            }
+        };
+
+        self.send(peer, f).await
+    }
+}
+
 #[async_trait]
 impl PrimaryToWorkerRpc for P2pNetwork {
     async fn delete_batches(
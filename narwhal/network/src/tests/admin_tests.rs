@@ -0,0 +1,99 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{dag_router, ExportDag};
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+use types::{DagExport, DagVertex, Round};
+
+fn dag_export() -> DagExport {
+    DagExport {
+        last_committed_round: 2,
+        gc_round: 0,
+        vertices: vec![DagVertex {
+            digest: "digest".to_string(),
+            round: 3,
+            author: "author".to_string(),
+            parents: vec![],
+            missing_parents: vec![],
+            leader: false,
+            committed_anchor: false,
+            committed: false,
+        }],
+    }
+}
+
+// Export `dag_export()`, recording the rounds the exports were asked from
+fn export_dag() -> (ExportDag, Arc<Mutex<Vec<Option<Round>>>>) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let export_dag: ExportDag = Arc::new(move |from_round| {
+        recorded.lock().unwrap().push(from_round);
+        Ok(dag_export())
+    });
+    (export_dag, requests)
+}
+
+async fn get(export_dag: ExportDag, uri: &str) -> (StatusCode, Option<String>, Vec<u8>) {
+    let response = dag_router(export_dag)
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, content_type, body.to_vec())
+}
+
+#[tokio::test]
+async fn get_dag_as_json() {
+    let (export_dag, requests) = export_dag();
+
+    let (status, content_type, body) = get(export_dag.clone(), "/dag").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+    assert_eq!(
+        serde_json::from_slice::<DagExport>(&body).unwrap(),
+        dag_export()
+    );
+
+    let (status, _, body) = get(export_dag, "/dag?format=json&from_round=3").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        serde_json::from_slice::<DagExport>(&body).unwrap(),
+        dag_export()
+    );
+    assert_eq!(*requests.lock().unwrap(), vec![None, Some(3)]);
+}
+
+#[tokio::test]
+async fn get_dag_as_dot() {
+    let (export_dag, requests) = export_dag();
+
+    let (status, content_type, body) = get(export_dag, "/dag?format=dot").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("text/vnd.graphviz"));
+    assert_eq!(String::from_utf8(body).unwrap(), dag_export().to_dot());
+    assert_eq!(*requests.lock().unwrap(), vec![None]);
+}
+
+#[tokio::test]
+async fn get_dag_in_unknown_format() {
+    let (export_dag, requests) = export_dag();
+
+    let (status, _, body) = get(export_dag, "/dag?format=svg").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        String::from_utf8(body).unwrap(),
+        "Unknown DAG format svg, expected json or dot"
+    );
+    // the DAG is not exported for a request that can't be answered
+    assert!(requests.lock().unwrap().is_empty());
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use async_trait::async_trait;
 use executor::{ExecutionIndices, ExecutionState};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use tokio::sync::mpsc::Sender;
 use types::ConsensusOutput;
@@ -37,3 +37,26 @@ impl ExecutionState for SimpleExecutionState {
         ExecutionIndices::default()
     }
 }
+
+/// An execution engine keeping track of the transactions replayed to it, to replay the
+/// consensus output of a node's stores without executing it.
+#[derive(Default)]
+pub struct ReplayExecutionState {
+    execution_indices: Mutex<ExecutionIndices>,
+}
+
+#[async_trait]
+impl ExecutionState for ReplayExecutionState {
+    async fn handle_consensus_transaction(
+        &self,
+        _consensus_output: &Arc<ConsensusOutput>,
+        execution_indices: ExecutionIndices,
+        _transaction: Vec<u8>,
+    ) {
+        *self.execution_indices.lock().unwrap() = execution_indices;
+    }
+
+    async fn load_execution_indices(&self) -> ExecutionIndices {
+        self.execution_indices.lock().unwrap().clone()
+    }
+}
@@ -1,6 +1,6 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use config::{Authority, Committee, Epoch, WorkerIndex, WorkerInfo};
+use config::{Authority, Committee, Epoch, WorkerCache, WorkerIndex, WorkerInfo};
 use crypto::{KeyPair, NetworkKeyPair};
 use fastcrypto::{
     hash::Hash,
@@ -12,9 +12,9 @@ use serde_reflection::{Registry, Result, Samples, Tracer, TracerConfig};
 use std::{fs::File, io::Write};
 use structopt::{clap::arg_enum, StructOpt};
 use types::{
-    Batch, BatchDigest, Certificate, CertificateDigest, HeaderBuilder, HeaderDigest, Metadata,
-    ReconfigureNotification, WorkerOthersBatchMessage, WorkerOurBatchMessage,
-    WorkerReconfigureMessage, WorkerSynchronizeMessage,
+    Batch, BatchChunk, BatchDigest, Certificate, CertificateDigest, HeaderBuilder, HeaderDigest,
+    Metadata, ReconfigureNotification, WorkerBatchChunkMessage, WorkerOthersBatchMessage,
+    WorkerOurBatchMessage, WorkerReconfigureMessage, WorkerSynchronizeMessage,
 };
 
 fn get_registry() -> Result<Registry> {
@@ -118,21 +118,47 @@ fn get_registry() -> Result<Registry> {
     };
     let sync = WorkerSynchronizeMessage {
         digests: vec![BatchDigest([0u8; 32])],
-        target: pk,
+        target: pk.clone(),
     };
     let epoch_change = WorkerReconfigureMessage {
         message: ReconfigureNotification::NewEpoch(committee.clone()),
     };
+    let schedule_epoch = WorkerReconfigureMessage {
+        message: ReconfigureNotification::ScheduleEpoch {
+            round: 1,
+            committee: committee.clone(),
+            worker_cache: WorkerCache {
+                workers: vec![(pk.clone(), worker_index)].into_iter().collect(),
+                epoch: 0,
+            },
+        },
+    };
     let update_committee = WorkerReconfigureMessage {
         message: ReconfigureNotification::NewEpoch(committee),
     };
     let shutdown = WorkerReconfigureMessage {
         message: ReconfigureNotification::Shutdown,
     };
+    let batch_chunk = WorkerBatchChunkMessage {
+        digest: BatchDigest([0u8; 32]),
+        chunk: BatchChunk {
+            index: 0,
+            data_shards: 2,
+            parity_shards: 2,
+            batch_length: 8,
+            root: [0u8; 32],
+            proof: vec![[0u8; 32]; 2],
+            data: vec![0u8; 4],
+        },
+    };
+    let batch = Batch::new(vec![vec![0u8; 8]]);
+    tracer.trace_value(&mut samples, &batch)?;
+    tracer.trace_value(&mut samples, &batch_chunk)?;
     tracer.trace_value(&mut samples, &our_batch)?;
     tracer.trace_value(&mut samples, &others_batch)?;
     tracer.trace_value(&mut samples, &sync)?;
     tracer.trace_value(&mut samples, &epoch_change)?;
+    tracer.trace_value(&mut samples, &schedule_epoch)?;
     tracer.trace_value(&mut samples, &update_committee)?;
     tracer.trace_value(&mut samples, &shutdown)?;
 
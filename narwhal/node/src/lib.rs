@@ -1,8 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use config::{Parameters, SharedCommittee, SharedWorkerCache, WorkerId};
+use config::{Committee, Parameters, SharedCommittee, SharedWorkerCache, WorkerId};
 use consensus::{
     bullshark::Bullshark,
+    consensus::ConsensusProtocol,
     dag::Dag,
     metrics::{ChannelMetrics, ConsensusMetrics},
     Consensus,
@@ -19,8 +20,8 @@ use storage::NodeStorage;
 use tokio::sync::oneshot;
 use tokio::{sync::watch, task::JoinHandle};
 use tracing::{debug, info};
-use types::{metered_channel, Certificate, ReconfigureNotification, Round};
-use worker::{metrics::initialise_metrics, Worker};
+use types::{metered_channel, Certificate, ConsensusStore, ReconfigureNotification, Round};
+use worker::{metrics::initialise_metrics, BatchPolicy, Worker};
 
 pub mod execution_state;
 pub mod metrics;
@@ -60,6 +61,46 @@ impl Node {
     ) -> SubscriberResult<Vec<JoinHandle<()>>>
     where
         State: ExecutionState + Send + Sync + 'static,
+    {
+        Self::spawn_primary_with_protocol(
+            keypair,
+            network_keypair,
+            committee,
+            worker_cache,
+            store,
+            parameters,
+            internal_consensus,
+            execution_state,
+            registry,
+            Bullshark::new,
+        )
+        .await
+    }
+
+    /// Spawn a new primary as `spawn_primary` does, with a consensus ordering the certificates
+    /// with the protocol built by `new_protocol` rather than with Bullshark. Every authority of a
+    /// committee must run the same protocol: this is only meant for the benchmarks comparing
+    /// protocols.
+    pub async fn spawn_primary_with_protocol<State, Protocol>(
+        keypair: KeyPair,
+        network_keypair: NetworkKeyPair,
+        committee: SharedCommittee,
+        worker_cache: SharedWorkerCache,
+        store: &NodeStorage,
+        parameters: Parameters,
+        internal_consensus: bool,
+        execution_state: Arc<State>,
+        registry: &Registry,
+        new_protocol: fn(
+            Committee,
+            Arc<ConsensusStore>,
+            &Parameters,
+            Arc<ConsensusMetrics>,
+        ) -> Protocol,
+    ) -> SubscriberResult<Vec<JoinHandle<()>>>
+    where
+        State: ExecutionState + Send + Sync + 'static,
+        Protocol: ConsensusProtocol + Send + 'static,
     {
         let initial_committee = ReconfigureNotification::NewEpoch((**committee.load()).clone());
         let (tx_reconfigure, _rx_reconfigure) = watch::channel(initial_committee);
@@ -107,6 +148,7 @@ impl Node {
                 rx_new_certificates,
                 tx_committed_certificates.clone(),
                 registry,
+                new_protocol,
             )
             .await?;
 
@@ -146,6 +188,7 @@ impl Node {
             rx_committed_certificates,
             dag,
             network_model,
+            internal_consensus.then(|| store.consensus_store.clone()),
             tx_reconfigure,
             tx_committed_certificates,
             registry,
@@ -174,7 +217,7 @@ impl Node {
     }
 
     /// Spawn the consensus core and the client executing transactions.
-    async fn spawn_consensus<State>(
+    async fn spawn_consensus<State, Protocol>(
         name: PublicKey,
         network: oneshot::Receiver<P2pNetwork>,
         worker_cache: SharedWorkerCache,
@@ -186,10 +229,17 @@ impl Node {
         rx_new_certificates: metered_channel::Receiver<Certificate>,
         tx_committed_certificates: metered_channel::Sender<(Round, Vec<Certificate>)>,
         registry: &Registry,
+        new_protocol: fn(
+            Committee,
+            Arc<ConsensusStore>,
+            &Parameters,
+            Arc<ConsensusMetrics>,
+        ) -> Protocol,
     ) -> SubscriberResult<Vec<JoinHandle<()>>>
     where
         PublicKey: VerifyingKey,
         State: ExecutionState + Send + Sync + 'static,
+        Protocol: ConsensusProtocol + Send + 'static,
     {
         let consensus_metrics = Arc::new(ConsensusMetrics::new(registry));
         let channel_metrics = ChannelMetrics::new(registry);
@@ -217,10 +267,10 @@ impl Node {
             .inc_by(num_certificates as u64);
 
         // Spawn the consensus core who only sequences transactions.
-        let ordering_engine = Bullshark::new(
+        let ordering_engine = new_protocol(
             (**committee.load()).clone(),
             store.consensus_store.clone(),
-            parameters.gc_depth,
+            &parameters,
             consensus_metrics.clone(),
         );
         let consensus_handles = Consensus::spawn(
@@ -270,6 +320,8 @@ impl Node {
         store: &NodeStorage,
         // The configuration parameters.
         parameters: Parameters,
+        // The order in which client transactions are sealed into batches.
+        batch_policy: Arc<dyn BatchPolicy>,
         // The prometheus metrics Registry
         registry: &Registry,
     ) -> Vec<JoinHandle<()>> {
@@ -286,6 +338,8 @@ impl Node {
                 worker_cache.clone(),
                 parameters.clone(),
                 store.batch_store.clone(),
+                store.batch_chunk_store.clone(),
+                batch_policy.clone(),
                 metrics.clone(),
             );
             handles.extend(worker_handles);
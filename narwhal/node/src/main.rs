@@ -12,18 +12,18 @@ use arc_swap::ArcSwap;
 use clap::{crate_name, crate_version, App, AppSettings, ArgMatches, SubCommand};
 use config::{Committee, Import, Parameters, WorkerCache, WorkerId};
 use crypto::{KeyPair, NetworkKeyPair};
-use executor::SerializedTransaction;
+use executor::{ConsensusReplay, SerializedTransaction};
 use eyre::Context;
 use fastcrypto::{generate_production_keypair, traits::KeyPair as _};
 use futures::future::join_all;
 use narwhal_node as node;
 use node::{
-    execution_state::SimpleExecutionState,
+    execution_state::{ReplayExecutionState, SimpleExecutionState},
     metrics::{primary_metrics_registry, start_prometheus_server, worker_metrics_registry},
     Node,
 };
 use prometheus::Registry;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use storage::NodeStorage;
 use telemetry_subscribers::TelemetryGuards;
 use tokio::sync::mpsc::{channel, Receiver};
@@ -32,6 +32,8 @@ use tracing::info;
 use tracing::subscriber::set_global_default;
 #[cfg(feature = "benchmark")]
 use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use types::Round;
+use worker::FifoBatchPolicy;
 
 #[cfg(feature = "dhat-heap")]
 #[global_allocator]
@@ -74,6 +76,14 @@ async fn main() -> Result<(), eyre::Report> {
                 )
                 .setting(AppSettings::SubcommandRequiredElseHelp),
         )
+        .subcommand(
+            SubCommand::with_name("replay")
+                .about("Replay the consensus output committed in a node's data stores")
+                .args_from_usage("--store=<PATH> 'The path of the primary's data store'")
+                .args_from_usage("--worker-store=<ID:PATH>... 'The id of a worker and the path of its data store'")
+                .args_from_usage("--from-round=[INT] 'The round of the first committed leader to replay from'")
+                .args_from_usage("--verify 'Check the replayed output against the consensus sequence'"),
+        )
         .setting(AppSettings::SubcommandRequiredElseHelp)
         .get_matches();
 
@@ -149,6 +159,10 @@ async fn main() -> Result<(), eyre::Report> {
             )
             .await?
         }
+        ("replay", Some(sub_matches)) => {
+            let _guard = setup_telemetry(tracing_level, network_tracing_level, None);
+            replay(sub_matches).await?
+        }
         _ => unreachable!(),
     }
     Ok(())
@@ -272,6 +286,7 @@ async fn run(
                 worker_cache,
                 &store,
                 parameters.clone(),
+                Arc::new(FifoBatchPolicy),
                 &registry,
             )
         }
@@ -296,6 +311,53 @@ async fn run(
     Ok(())
 }
 
+// Replays the consensus output of a node's data stores.
+async fn replay(matches: &ArgMatches<'_>) -> Result<(), eyre::Report> {
+    let store = NodeStorage::reopen(matches.value_of("store").unwrap());
+    let mut batch_stores = HashMap::new();
+    for worker_store in matches.values_of("worker-store").unwrap() {
+        let (id, path) = worker_store
+            .split_once(':')
+            .ok_or_else(|| eyre::eyre!("A worker store must be given as <ID:PATH>"))?;
+        let id = id
+            .parse::<WorkerId>()
+            .context("The worker id must be a positive integer")?;
+        batch_stores.insert(id, NodeStorage::reopen(path).batch_store);
+    }
+    let from_round = matches
+        .value_of("from-round")
+        .map(|round| round.parse::<Round>())
+        .transpose()
+        .context("The round must be a positive integer")?
+        .unwrap_or_default();
+
+    let replay = ConsensusReplay::new(store.consensus_store, store.certificate_store, batch_stores);
+    let execution_state = ReplayExecutionState::default();
+    let summary = if matches.is_present("verify") {
+        replay.verify(&execution_state, from_round).await?
+    } else {
+        replay.replay(&execution_state, from_round).await?
+    };
+
+    println!(
+        "Replayed {} sub-dags, {} certificates, {} batches and {} transactions",
+        summary.sub_dags, summary.certificates, summary.batches, summary.transactions
+    );
+    if let Some(execution_indices) = &summary.last_execution_indices {
+        println!("Last execution indices: {execution_indices:?}");
+    }
+    for mismatch in &summary.mismatches {
+        println!("Mismatch: {mismatch:?}");
+    }
+    if !summary.mismatches.is_empty() {
+        eyre::bail!(
+            "The replayed output diverges from the stores in {} places",
+            summary.mismatches.len()
+        );
+    }
+    Ok(())
+}
+
 /// Receives an ordered list of certificates and apply any application-specific logic.
 async fn analyze(mut rx_output: Receiver<SerializedTransaction>) {
     while let Some(_message) = rx_output.recv().await {
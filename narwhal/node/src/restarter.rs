@@ -10,10 +10,13 @@ use futures::future::join_all;
 use prometheus::Registry;
 use std::{path::PathBuf, sync::Arc};
 use tokio::sync::mpsc::Receiver;
-use types::ReconfigureNotification;
+use types::{ReconfigureNotification, Round};
+use worker::BatchPolicy;
 
 // Module to start a node (primary, workers and default consensus), keep it running, and restarting it
-/// every time the committee changes.
+/// every time the committee changes. When the keys and the workers of the node do not change, the
+/// node moves to the new committee in place instead, once consensus commits the round the committee
+/// agreed on, without a pause of consensus.
 pub struct NodeRestarter;
 
 impl NodeRestarter {
@@ -26,12 +29,14 @@ impl NodeRestarter {
         storage_base_path: PathBuf,
         execution_state: Arc<State>,
         parameters: Parameters,
+        batch_policy: Arc<dyn BatchPolicy>,
         mut rx_reconfigure: Receiver<(
             KeyPair,
             NetworkKeyPair,
             Committee,
             Vec<(WorkerId, NetworkKeyPair)>,
             WorkerCache,
+            Round,
         )>,
         registry: &Registry,
     ) where
@@ -46,7 +51,7 @@ impl NodeRestarter {
         let mut handles = Vec::new();
 
         // Listen for new committees.
-        loop {
+        'epochs: loop {
             tracing::info!("Starting epoch E{}", committee.epoch());
 
             // Get a fresh store for the new epoch.
@@ -76,39 +81,75 @@ impl NodeRestarter {
                 worker_cache.clone(),
                 &store,
                 parameters.clone(),
+                batch_policy.clone(),
                 registry,
             );
 
             handles.extend(primary_handles);
             handles.extend(worker_handles);
 
-            // Wait for a committee change.
+            // Wait for a committee change the node cannot move to in place.
             let (
                 new_keypair,
                 new_network_keypair,
                 new_committee,
                 new_worker_ids_and_keypairs,
                 new_worker_cache,
-            ) = match rx_reconfigure.recv().await {
-                Some(x) => x,
-                None => break,
+            ) = loop {
+                let (
+                    new_keypair,
+                    new_network_keypair,
+                    new_committee,
+                    new_worker_ids_and_keypairs,
+                    new_worker_cache,
+                    round,
+                ) = match rx_reconfigure.recv().await {
+                    Some(x) => x,
+                    None => break 'epochs,
+                };
+
+                let same_workers = new_worker_ids_and_keypairs
+                    .iter()
+                    .map(|(id, keypair)| (*id, keypair.public()))
+                    .eq(worker_ids_and_keypairs
+                        .iter()
+                        .map(|(id, keypair)| (*id, keypair.public())));
+                if new_keypair.public() == &name
+                    && new_network_keypair.public() == primary_network_keypair.public()
+                    && same_workers
+                {
+                    tracing::info!("Moving in place to committee {new_committee} at round {round}");
+                    let scheduled = Self::reconfigure(
+                        &parameters,
+                        &ReconfigureNotification::ScheduleEpoch {
+                            round,
+                            committee: new_committee.clone(),
+                            worker_cache: new_worker_cache.clone(),
+                        },
+                    )
+                    .await;
+                    if scheduled {
+                        committee = new_committee;
+                        continue;
+                    }
+                    tracing::warn!(
+                        "The primary rejected the move to committee {new_committee} at round {round}, restarting instead"
+                    );
+                }
+
+                break (
+                    new_keypair,
+                    new_network_keypair,
+                    new_committee,
+                    new_worker_ids_and_keypairs,
+                    new_worker_cache,
+                );
             };
             tracing::info!("Starting reconfiguration with committee {committee}");
 
             // Shutdown all relevant components.
             // Send shutdown message to the primary, who will forward it to its workers
-            let client = reqwest::Client::new();
-            client
-                .post(format!(
-                    "http://127.0.0.1:{}/reconfigure",
-                    parameters
-                        .network_admin_server
-                        .primary_network_admin_server_port
-                ))
-                .json(&ReconfigureNotification::Shutdown)
-                .send()
-                .await
-                .unwrap();
+            Self::reconfigure(&parameters, &ReconfigureNotification::Shutdown).await;
 
             tracing::debug!("Committee reconfiguration message successfully sent");
 
@@ -130,4 +171,23 @@ impl NodeRestarter {
             worker_cache.swap(Arc::new(new_worker_cache));
         }
     }
+
+    /// Send a reconfiguration message to the primary, who will forward it to its workers. Returns
+    /// whether the primary accepted it.
+    async fn reconfigure(parameters: &Parameters, message: &ReconfigureNotification) -> bool {
+        let client = reqwest::Client::new();
+        client
+            .post(format!(
+                "http://127.0.0.1:{}/reconfigure",
+                parameters
+                    .network_admin_server
+                    .primary_network_admin_server_port
+            ))
+            .json(message)
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .is_success()
+    }
 }
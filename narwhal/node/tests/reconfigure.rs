@@ -23,8 +23,13 @@ use tokio::{
     sync::mpsc::{channel, Receiver, Sender},
     time::{interval, sleep, Duration, MissedTickBehavior},
 };
-use types::ConsensusOutput;
+use types::{ConsensusOutput, Round};
 use types::{ReconfigureNotification, TransactionProto, TransactionsClient};
+use worker::FifoBatchPolicy;
+
+/// The number of rounds after the certificate triggering an epoch change at which the nodes move
+/// to the new committee, leaving consensus the time to commit past that certificate.
+const EPOCH_CHANGE_DELAY: Round = 10;
 
 /// A simple/dumb execution engine.
 struct SimpleExecutionState {
@@ -40,6 +45,7 @@ struct SimpleExecutionState {
         Committee,
         Vec<(WorkerId, NetworkKeyPair)>,
         WorkerCache,
+        Round,
     )>,
 }
 
@@ -57,6 +63,7 @@ impl SimpleExecutionState {
             Committee,
             Vec<(WorkerId, NetworkKeyPair)>,
             WorkerCache,
+            Round,
         )>,
     ) -> Self {
         Self {
@@ -75,7 +82,7 @@ impl SimpleExecutionState {
 impl ExecutionState for SimpleExecutionState {
     async fn handle_consensus_transaction(
         &self,
-        _consensus_output: &Arc<ConsensusOutput>,
+        consensus_output: &Arc<ConsensusOutput>,
         execution_indices: ExecutionIndices,
         transaction: Vec<u8>,
     ) {
@@ -95,6 +102,8 @@ impl ExecutionState for SimpleExecutionState {
             let worker_ids_and_keypairs = worker_ids.zip(worker_keypairs).collect();
 
             let new_committee = self.committee.lock().unwrap().clone();
+            let mut new_worker_cache = self.worker_cache.clone();
+            new_worker_cache.epoch = epoch;
 
             self.tx_reconfigure
                 .send((
@@ -102,7 +111,8 @@ impl ExecutionState for SimpleExecutionState {
                     self.network_keypair.copy(),
                     new_committee,
                     worker_ids_and_keypairs,
-                    self.worker_cache.clone(),
+                    new_worker_cache,
+                    consensus_output.certificate.round() + EPOCH_CHANGE_DELAY,
                 ))
                 .await
                 .unwrap();
@@ -209,6 +219,7 @@ async fn restart() {
                 /* base_store_path */ test_utils::temp_dir(),
                 execution_state,
                 parameters,
+                Arc::new(FifoBatchPolicy),
                 rx_node_reconfigure,
                 &Registry::new(),
             )
@@ -296,7 +307,7 @@ async fn epoch_change() {
         tokio::spawn(async move {
             let client = reqwest::Client::new();
 
-            while let Some((_, _, committee, _, _)) = rx_node_reconfigure.recv().await {
+            while let Some((_, _, committee, _, _, _)) = rx_node_reconfigure.recv().await {
                 let message = ReconfigureNotification::NewEpoch(committee.clone());
                 client
                     .post(format!(
@@ -334,6 +345,7 @@ async fn epoch_change() {
             worker_cache.clone(),
             &store,
             p,
+            Arc::new(FifoBatchPolicy),
             &Registry::new(),
         );
 
@@ -305,7 +305,8 @@ impl BlockSynchronizer {
                         ReconfigureNotification::UpdateCommittee(new_committee)=> {
                             self.committee = new_committee;
                         }
-                        ReconfigureNotification::Shutdown => return
+                        ReconfigureNotification::Shutdown => return,
+                        ReconfigureNotification::ScheduleEpoch { .. } => continue,
                     }
                     tracing::debug!("Committee updated to {}", self.committee);
                 }
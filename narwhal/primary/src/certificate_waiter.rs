@@ -202,7 +202,8 @@ impl CertificateWaiter {
                             // There should be no committee membership change so self.targets does
                             // not need to be updated.
                         },
-                        ReconfigureNotification::Shutdown => return
+                        ReconfigureNotification::Shutdown => return,
+                        ReconfigureNotification::ScheduleEpoch { .. } => continue,
                     }
                     debug!("Committee updated to {}", self.committee);
                 }
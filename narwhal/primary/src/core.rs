@@ -823,7 +823,8 @@ impl Core {
                             // Update the committee.
                             self.committee = new_committee;
                         },
-                        ReconfigureNotification::Shutdown => return
+                        ReconfigureNotification::Shutdown => return,
+                        ReconfigureNotification::ScheduleEpoch { .. } => continue,
                     }
                     tracing::debug!("Committee updated to {}", self.committee);
                     Ok(())
@@ -364,7 +364,8 @@ impl HeaderWaiter {
                         ReconfigureNotification::UpdateCommittee(new_committee) => {
                             self.committee = new_committee;
                         },
-                        ReconfigureNotification::Shutdown => return
+                        ReconfigureNotification::Shutdown => return,
+                        ReconfigureNotification::ScheduleEpoch { .. } => continue,
                     }
                     tracing::debug!("Committee updated to {}", self.committee);
                 },
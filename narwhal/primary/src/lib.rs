@@ -18,6 +18,7 @@ mod grpc_server;
 mod header_waiter;
 mod primary;
 mod proposer;
+mod recovery;
 mod state_handler;
 mod synchronizer;
 mod utils;
@@ -394,6 +394,19 @@ pub struct PrimaryMetrics {
     pub proposer_batch_latency: Histogram,
     /// Time it takes for a header to be materialised to a certificate
     pub header_to_certificate_latency: HistogramVec,
+    /// 1 while the primary audits its certificate store on startup and fetches the gaps it
+    /// found, before proposing, 0 otherwise.
+    pub recovery_in_progress: IntGaugeVec,
+    /// Number of certificates above the garbage collection round audited on startup.
+    pub recovery_certificates_audited: IntCounterVec,
+    /// Number of parents of the audited certificates missing from the certificate store.
+    pub recovery_missing_parents: IntGaugeVec,
+    /// Number of missing certificates fetched by the recovery.
+    pub recovery_certificates_fetched: IntCounterVec,
+    /// Number of inconsistencies of the certificate store found on startup, by kind.
+    pub recovery_inconsistencies: IntCounterVec,
+    /// Time it takes to audit the certificate store and fetch its gaps on startup.
+    pub recovery_latency: HistogramVec,
 }
 
 impl PrimaryMetrics {
@@ -579,6 +592,43 @@ impl PrimaryMetrics {
                 &["epoch"],
                 LATENCY_SEC_BUCKETS.to_vec(),
                 registry
+            ).unwrap(),
+            recovery_in_progress: register_int_gauge_vec_with_registry!(
+                "recovery_in_progress",
+                "1 while the primary audits its certificate store and fetches its gaps on startup, 0 otherwise",
+                &["epoch"],
+                registry
+            ).unwrap(),
+            recovery_certificates_audited: register_int_counter_vec_with_registry!(
+                "recovery_certificates_audited",
+                "Number of certificates above the garbage collection round audited on startup",
+                &["epoch"],
+                registry
+            ).unwrap(),
+            recovery_missing_parents: register_int_gauge_vec_with_registry!(
+                "recovery_missing_parents",
+                "Number of parents of the audited certificates missing from the certificate store",
+                &["epoch"],
+                registry
+            ).unwrap(),
+            recovery_certificates_fetched: register_int_counter_vec_with_registry!(
+                "recovery_certificates_fetched",
+                "Number of missing certificates fetched by the recovery",
+                &["epoch"],
+                registry
+            ).unwrap(),
+            recovery_inconsistencies: register_int_counter_vec_with_registry!(
+                "recovery_inconsistencies",
+                "Number of inconsistencies of the certificate store found on startup, by kind",
+                &["epoch", "kind"],
+                registry
+            ).unwrap(),
+            recovery_latency: register_histogram_vec_with_registry!(
+                "recovery_latency",
+                "Time it takes to audit the certificate store and fetch its gaps on startup",
+                &["epoch"],
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry
             ).unwrap()
         }
     }
@@ -10,6 +10,7 @@ use crate::{
     header_waiter::HeaderWaiter,
     metrics::initialise_metrics,
     proposer::{OurDigestMessage, Proposer},
+    recovery::Recovery,
     state_handler::StateHandler,
     synchronizer::Synchronizer,
     BlockRemover,
@@ -24,13 +25,14 @@ use anemo_tower::{
 };
 use async_trait::async_trait;
 use config::{Parameters, SharedCommittee, SharedWorkerCache, WorkerId, WorkerInfo};
-use consensus::dag::Dag;
+use consensus::{dag::Dag, dag_export::DagExporter};
 use crypto::{KeyPair, NetworkKeyPair, NetworkPublicKey, PublicKey};
 use fastcrypto::{
     traits::{EncodeDecodeBase64, KeyPair as _},
     SignatureService,
 };
 use multiaddr::{Multiaddr, Protocol};
+use network::admin::ExportDag;
 use network::metrics::MetricsMakeCallbackHandler;
 use network::P2pNetwork;
 use prometheus::Registry;
@@ -49,7 +51,7 @@ use tracing::{error, info};
 pub use types::PrimaryMessage;
 use types::{
     metered_channel::{channel_with_total, Receiver, Sender},
-    BatchDigest, Certificate, CertificateDigest, FetchCertificatesRequest,
+    BatchDigest, Certificate, CertificateDigest, ConsensusStore, FetchCertificatesRequest,
     FetchCertificatesResponse, GetCertificatesRequest, GetCertificatesResponse, Header,
     HeaderDigest, LatestHeaderRequest, LatestHeaderResponse, PayloadAvailabilityRequest,
     PayloadAvailabilityResponse, PrimaryToPrimary, PrimaryToPrimaryServer, ReconfigureNotification,
@@ -91,6 +93,9 @@ impl Primary {
         rx_committed_certificates: Receiver<(Round, Vec<Certificate>)>,
         dag: Option<Arc<Dag>>,
         network_model: NetworkModel,
+        // The store of the internal consensus, to export the DAG from the admin server. None
+        // when running with external consensus.
+        consensus_store: Option<Arc<ConsensusStore>>,
         tx_reconfigure: watch::Sender<ReconfigureNotification>,
         tx_committed_certificates: Sender<(Round, Vec<Certificate>)>,
         registry: &Registry,
@@ -244,8 +249,14 @@ impl Primary {
             .layer(TraceLayer::new_for_client_and_server_errors())
             .layer(CallbackLayer::new(MetricsMakeCallbackHandler::new(
                 outbound_network_metrics,
-            )))
-            .into_inner();
+            )));
+        // In the simulator, the requests go through the network faults the tests inject.
+        #[cfg(msim)]
+        let outbound_layer =
+            outbound_layer.layer(network::fault_injection::FaultInjectionLayer::new(PeerId(
+                network_signer.public().0.to_bytes(),
+            )));
+        let outbound_layer = outbound_layer.into_inner();
 
         let anemo_config = {
             let mut quic_config = anemo::QuicConfig::default();
@@ -320,6 +331,7 @@ impl Primary {
                 .primary_network_admin_server_port
         );
 
+        let recovery_consensus_store = consensus_store.clone();
         let admin_handles = network::admin::start_admin_server(
             parameters
                 .network_admin_server
@@ -327,6 +339,21 @@ impl Primary {
             network.clone(),
             tx_reconfigure.subscribe(),
             Some(tx_state_handler),
+            consensus_store.map(|consensus_store| {
+                let committee = committee.clone();
+                let certificate_store = certificate_store.clone();
+                let parameters = parameters.clone();
+                let export_dag: ExportDag = Arc::new(move |from_round| {
+                    DagExporter::new(
+                        (**committee.load()).clone(),
+                        consensus_store.clone(),
+                        certificate_store.clone(),
+                        parameters.clone(),
+                    )
+                    .export(from_round)
+                });
+                export_dag
+            }),
         );
 
         // The `Synchronizer` provides auxiliary methods helping the `Core` to sync.
@@ -385,6 +412,21 @@ impl Primary {
                 .handler_certificate_deliver_timeout,
         ));
 
+        // Audits the certificate store and fetches the certificates missing from it, before
+        // letting the `Proposer` propose.
+        let (tx_recovered, rx_recovered) = oneshot::channel();
+        let recovery_handle = Recovery::spawn(
+            name.clone(),
+            (**committee.load()).clone(),
+            certificate_store.clone(),
+            proposer_store.clone(),
+            recovery_consensus_store,
+            parameters.gc_depth,
+            block_synchronizer_handler.clone(),
+            node_metrics.clone(),
+            tx_recovered,
+        );
+
         // Indicator variable for components to operate in internal vs external consensus modes.
         let internal_consensus = dag.is_none();
 
@@ -444,7 +486,7 @@ impl Primary {
             name.clone(),
             (**committee.load()).clone(),
             signature_service,
-            proposer_store,
+            proposer_store.clone(),
             parameters.header_num_of_batches_threshold,
             parameters.max_header_num_of_batches,
             parameters.max_header_delay,
@@ -456,6 +498,7 @@ impl Primary {
             tx_headers,
             rx_commited_own_headers,
             node_metrics,
+            Some(rx_recovered),
         );
 
         // Keeps track of the latest consensus round and allows other tasks to clean up their their internal state
@@ -468,6 +511,9 @@ impl Primary {
             rx_state_handler,
             tx_reconfigure,
             Some(tx_commited_own_headers),
+            certificate_store.clone(),
+            header_store.clone(),
+            proposer_store,
             P2pNetwork::new(network.clone()),
         );
 
@@ -528,6 +574,7 @@ impl Primary {
             header_waiter_handle,
             certificate_waiter_handle,
             proposer_handle,
+            recovery_handle,
             state_handler_handle,
             connection_monitor_handle,
         ];
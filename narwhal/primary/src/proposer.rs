@@ -112,9 +112,16 @@ impl Proposer {
         tx_headers: Sender<Header>,
         rx_commited_own_headers: Receiver<(Round, Vec<Round>)>,
         metrics: Arc<PrimaryMetrics>,
+        // Resolves once the certificate store is recovered, if the proposer should wait for it
+        // before proposing.
+        rx_recovered: Option<oneshot::Receiver<()>>,
     ) -> JoinHandle<()> {
         let genesis = Certificate::genesis(&committee);
         spawn_monitored_task!(async move {
+            if let Some(rx_recovered) = rx_recovered {
+                // The recovery may have failed, in which case we propose anyway.
+                let _ = rx_recovered.await;
+            }
             Self {
                 name,
                 committee,
@@ -450,6 +457,7 @@ impl Proposer {
                                     self.committee = new_committee;
                                 },
                                 ReconfigureNotification::Shutdown => return,
+                                ReconfigureNotification::ScheduleEpoch { .. } => continue,
                             }
                             tracing::debug!("Committee updated to {}", self.committee);
                         }
@@ -535,6 +543,7 @@ impl Proposer {
                             self.committee = new_committee;
                         },
                         ReconfigureNotification::Shutdown => return,
+                        ReconfigureNotification::ScheduleEpoch { .. } => continue,
                     }
                     tracing::debug!("Committee updated to {}", self.committee);
 
@@ -0,0 +1,323 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{block_synchronizer::handler::Handler, metrics::PrimaryMetrics};
+use config::Committee;
+use crypto::PublicKey;
+use fastcrypto::hash::Hash as _;
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+    sync::Arc,
+};
+use storage::{CertificateStore, ProposerStore};
+use sui_metrics::spawn_monitored_task;
+use tokio::{
+    sync::oneshot,
+    task::JoinHandle,
+    time::{Duration, Instant},
+};
+use tracing::{error, info, warn};
+use types::{CertificateDigest, ConsensusStore, Round, StoreResult};
+
+#[cfg(test)]
+#[path = "tests/recovery_tests.rs"]
+pub mod recovery_tests;
+
+/// The number of times the recovery fetches the missing parents it found and audits the store
+/// again, before it gives up and lets the primary propose.
+const MAX_FETCH_ATTEMPTS: usize = 3;
+
+/// The delay between two attempts to fetch the missing parents.
+const FETCH_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// An inconsistency of the certificate store found by the audit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// The indices of the store disagree on the certificate of an authority for a round.
+    IndexMismatch { origin: PublicKey, round: Round },
+    /// The certificate is authored by an authority that is not in the committee.
+    UnknownOrigin { digest: CertificateDigest },
+    /// Our certificate for a round does not certify the header we last proposed for that round:
+    /// we proposed two headers for the round.
+    OwnEquivocation { round: Round },
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Inconsistency::IndexMismatch { origin, round } => write!(
+                f,
+                "the indices disagree on the certificate of {origin} for round {round}"
+            ),
+            Inconsistency::UnknownOrigin { digest } => {
+                write!(f, "certificate {digest} is authored out of the committee")
+            }
+            Inconsistency::OwnEquivocation { round } => write!(
+                f,
+                "our certificate for round {round} is not for the header we last proposed"
+            ),
+        }
+    }
+}
+
+impl Inconsistency {
+    /// The label of the inconsistency in the metrics.
+    fn kind(&self) -> &'static str {
+        match self {
+            Inconsistency::IndexMismatch { .. } => "index_mismatch",
+            Inconsistency::UnknownOrigin { .. } => "unknown_origin",
+            Inconsistency::OwnEquivocation { .. } => "own_equivocation",
+        }
+    }
+}
+
+/// The outcome of an audit of the certificate store.
+#[derive(Debug, Default)]
+pub struct Audit {
+    /// The certificates of rounds up to this one are garbage collected and not audited.
+    pub gc_round: Round,
+    /// The number of certificates of the current epoch audited.
+    pub certificates: usize,
+    /// The parents of the audited certificates that are above the garbage collection round but
+    /// missing from the store.
+    pub missing_parents: BTreeSet<CertificateDigest>,
+    pub inconsistencies: Vec<Inconsistency>,
+}
+
+/// Audits the certificate store when the primary starts, as the `Core` otherwise only rebuilds
+/// the DAG it lost in a crash when headers and certificates of later rounds reference it. The
+/// recovery checks the certificates above the garbage collection round against the DAG they
+/// reference and the last header we proposed, fetches the missing parents with the block
+/// synchronizer, and only then lets the proposer propose.
+pub struct Recovery<SynchronizerHandler: Handler + Send + Sync + 'static> {
+    /// The public key of this primary.
+    name: PublicKey,
+    /// The committee information.
+    committee: Committee,
+    /// The persistent storage for certificates.
+    certificate_store: CertificateStore,
+    /// The persistent storage of the last header we proposed.
+    proposer_store: ProposerStore,
+    /// The store of the internal consensus, to find the garbage collection round. None when
+    /// running with external consensus, in which case the highest round of the certificate store
+    /// is used instead.
+    consensus_store: Option<Arc<ConsensusStore>>,
+    /// The depth of the garbage collection.
+    gc_depth: Round,
+    /// Fetches the missing certificates from the other primaries, and has the `Core` process
+    /// them.
+    block_synchronizer_handler: Arc<SynchronizerHandler>,
+    metrics: Arc<PrimaryMetrics>,
+}
+
+impl<SynchronizerHandler: Handler + Send + Sync + 'static> Recovery<SynchronizerHandler> {
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn spawn(
+        name: PublicKey,
+        committee: Committee,
+        certificate_store: CertificateStore,
+        proposer_store: ProposerStore,
+        consensus_store: Option<Arc<ConsensusStore>>,
+        gc_depth: Round,
+        block_synchronizer_handler: Arc<SynchronizerHandler>,
+        metrics: Arc<PrimaryMetrics>,
+        tx_recovered: oneshot::Sender<()>,
+    ) -> JoinHandle<()> {
+        spawn_monitored_task!(async move {
+            Self {
+                name,
+                committee,
+                certificate_store,
+                proposer_store,
+                consensus_store,
+                gc_depth,
+                block_synchronizer_handler,
+                metrics,
+            }
+            .run()
+            .await;
+            let _ = tx_recovered.send(());
+        })
+    }
+
+    async fn run(&self) {
+        let epoch = self.committee.epoch().to_string();
+        let started = Instant::now();
+        self.metrics
+            .recovery_in_progress
+            .with_label_values(&[&epoch])
+            .set(1);
+
+        for attempt in 0..=MAX_FETCH_ATTEMPTS {
+            let audit = match self.audit() {
+                Ok(audit) => audit,
+                Err(e) => {
+                    error!("Failed to audit the certificate store: {e}");
+                    break;
+                }
+            };
+            self.metrics
+                .recovery_missing_parents
+                .with_label_values(&[&epoch])
+                .set(audit.missing_parents.len() as i64);
+
+            // The store is audited again after every fetch: only the first audit is reported,
+            // not to count the same certificates and inconsistencies twice.
+            if attempt == 0 {
+                info!(
+                    "Audited {} certificates above garbage collection round {}",
+                    audit.certificates, audit.gc_round
+                );
+                self.metrics
+                    .recovery_certificates_audited
+                    .with_label_values(&[&epoch])
+                    .inc_by(audit.certificates as u64);
+                for inconsistency in &audit.inconsistencies {
+                    error!("Certificate store inconsistency: {inconsistency}");
+                    self.metrics
+                        .recovery_inconsistencies
+                        .with_label_values(&[&epoch, inconsistency.kind()])
+                        .inc();
+                }
+            }
+
+            if audit.missing_parents.is_empty() {
+                info!("Certificate store recovered, no parent is missing");
+                break;
+            }
+            if attempt == MAX_FETCH_ATTEMPTS {
+                warn!(
+                    "Proposing with {} parents still missing from the certificate store",
+                    audit.missing_parents.len()
+                );
+                break;
+            }
+            if attempt > 0 {
+                tokio::time::sleep(FETCH_RETRY_DELAY).await;
+            }
+
+            info!(
+                "Fetching {} parents missing from the certificate store",
+                audit.missing_parents.len()
+            );
+            let fetched = self
+                .block_synchronizer_handler
+                .get_and_synchronize_block_headers(audit.missing_parents.into_iter().collect())
+                .await
+                .into_iter()
+                .filter(Result::is_ok)
+                .count();
+            self.metrics
+                .recovery_certificates_fetched
+                .with_label_values(&[&epoch])
+                .inc_by(fetched as u64);
+        }
+
+        self.metrics
+            .recovery_latency
+            .with_label_values(&[&epoch])
+            .observe(started.elapsed().as_secs_f64());
+        self.metrics
+            .recovery_in_progress
+            .with_label_values(&[&epoch])
+            .set(0);
+    }
+
+    /// Audit the certificates of the current epoch above the garbage collection round.
+    pub fn audit(&self) -> StoreResult<Audit> {
+        let gc_round = self.gc_round()?;
+        let mut audit = Audit {
+            gc_round,
+            ..Audit::default()
+        };
+
+        let certificates = self.certificate_store.after_round(gc_round + 1)?;
+        let stored: HashSet<_> = certificates.iter().map(|c| c.digest()).collect();
+        let mut candidates = BTreeSet::new();
+        for certificate in &certificates {
+            if certificate.epoch() != self.committee.epoch() {
+                continue;
+            }
+            audit.certificates += 1;
+
+            let digest = certificate.digest();
+            if self.committee.stake(&certificate.origin()) == 0 {
+                audit
+                    .inconsistencies
+                    .push(Inconsistency::UnknownOrigin { digest });
+                continue;
+            }
+
+            let indexed = self
+                .certificate_store
+                .read_by_index(certificate.origin(), certificate.round())?;
+            if indexed.map(|c| c.digest()) != Some(digest) {
+                audit.inconsistencies.push(Inconsistency::IndexMismatch {
+                    origin: certificate.origin(),
+                    round: certificate.round(),
+                });
+            }
+
+            // The parents of the first round are the genesis, which is not stored.
+            if certificate.round() > gc_round + 1 && certificate.round() > 1 {
+                candidates.extend(
+                    certificate
+                        .header
+                        .parents
+                        .iter()
+                        .filter(|parent| !stored.contains(parent))
+                        .cloned(),
+                );
+            }
+        }
+
+        // A parent missing from the rounds above may still be stored under another index.
+        let candidates: Vec<_> = candidates.into_iter().collect();
+        let found = self.certificate_store.read_all(candidates.clone())?;
+        audit.missing_parents = candidates
+            .into_iter()
+            .zip(found)
+            .filter_map(|(digest, certificate)| certificate.is_none().then_some(digest))
+            .collect();
+
+        if let Some(header) = self.proposer_store.get_last_proposed()? {
+            if header.epoch == self.committee.epoch() {
+                if let Some(ours) = self
+                    .certificate_store
+                    .read_by_index(self.name.clone(), header.round)?
+                {
+                    if ours.header.digest() != header.digest() {
+                        audit.inconsistencies.push(Inconsistency::OwnEquivocation {
+                            round: header.round,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(audit)
+    }
+
+    /// The round up to which certificates are garbage collected: `gc_depth` rounds below the
+    /// last committed round, or below the highest round of the store without internal consensus.
+    fn gc_round(&self) -> StoreResult<Round> {
+        let last_round = match &self.consensus_store {
+            Some(consensus_store) => consensus_store
+                .read_last_committed()
+                .into_values()
+                .max()
+                .unwrap_or_default(),
+            None => {
+                let mut last_round = 0;
+                for name in self.committee.authorities.keys() {
+                    if let Some(round) = self.certificate_store.last_round_number(name)? {
+                        last_round = last_round.max(round);
+                    }
+                }
+                last_round
+            }
+        };
+        Ok(last_round.saturating_sub(self.gc_depth))
+    }
+}
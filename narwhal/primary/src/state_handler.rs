@@ -5,13 +5,16 @@ use config::{Committee, SharedCommittee, SharedWorkerCache, WorkerCache, WorkerI
 use crypto::PublicKey;
 use network::{P2pNetwork, UnreliableNetwork};
 use std::{collections::BTreeMap, sync::Arc};
+use storage::{CertificateStore, ProposerStore};
+use store::Store;
 use sui_metrics::spawn_monitored_task;
 use tap::TapOptional;
 use tokio::{sync::watch, task::JoinHandle};
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 use types::{
     metered_channel::{Receiver, Sender},
-    Certificate, ReconfigureNotification, Round, WorkerReconfigureMessage,
+    Certificate, Header, HeaderDigest, ReconfigureNotification, ReconfigureRequest, Round,
+    WorkerReconfigureMessage,
 };
 
 /// Receives the highest round reached by consensus and update it for all tasks.
@@ -26,14 +29,22 @@ pub struct StateHandler {
     rx_committed_certificates: Receiver<(Round, Vec<Certificate>)>,
     /// Signals a new consensus round
     tx_consensus_round_updates: watch::Sender<u64>,
-    /// Receives notifications to reconfigure the system.
-    rx_state_handler: Receiver<ReconfigureNotification>,
+    /// Receives notifications to reconfigure the system, answered with whether they are accepted.
+    rx_state_handler: Receiver<ReconfigureRequest>,
     /// Channel to signal committee changes.
     tx_reconfigure: watch::Sender<ReconfigureNotification>,
     /// The latest round committed by consensus.
     last_committed_round: Round,
     /// A channel to update the committed rounds
     tx_commited_own_headers: Option<Sender<(Round, Vec<Round>)>>,
+    /// The committee and workers adopted in place once consensus commits a leader at or
+    /// above the round.
+    scheduled_epoch: Option<(Round, Committee, WorkerCache)>,
+    /// The stores of the DAG, which are not indexed by epoch: they are cleared when the node
+    /// moves to the next committee in place, as the restarted node gets fresh ones.
+    certificate_store: CertificateStore,
+    header_store: Store<HeaderDigest, Header>,
+    proposer_store: ProposerStore,
 
     network: P2pNetwork,
 }
@@ -46,9 +57,12 @@ impl StateHandler {
         worker_cache: SharedWorkerCache,
         rx_committed_certificates: Receiver<(Round, Vec<Certificate>)>,
         tx_consensus_round_updates: watch::Sender<u64>,
-        rx_state_handler: Receiver<ReconfigureNotification>,
+        rx_state_handler: Receiver<ReconfigureRequest>,
         tx_reconfigure: watch::Sender<ReconfigureNotification>,
         tx_commited_own_headers: Option<Sender<(Round, Vec<Round>)>>,
+        certificate_store: CertificateStore,
+        header_store: Store<HeaderDigest, Header>,
+        proposer_store: ProposerStore,
         network: P2pNetwork,
     ) -> JoinHandle<()> {
         spawn_monitored_task!(async move {
@@ -62,6 +76,10 @@ impl StateHandler {
                 tx_reconfigure,
                 last_committed_round: 0,
                 tx_commited_own_headers,
+                scheduled_epoch: None,
+                certificate_store,
+                header_store,
+                proposer_store,
                 network,
             }
             .run()
@@ -98,6 +116,112 @@ impl StateHandler {
         if let Some(sender) = &self.tx_commited_own_headers {
             let _ = sender.send((round, own_rounds_committed)).await;
         }
+
+        // Consensus stops committing after the leader of the epoch change round, which is the
+        // certificate of the highest round of the sub-dags it commits.
+        let leader_round = certificates.iter().map(|x| x.round()).max();
+        if let Some((epoch_change_round, _, _)) = &self.scheduled_epoch {
+            if leader_round >= Some(*epoch_change_round) {
+                let (_, committee, worker_cache) = self.scheduled_epoch.take().unwrap();
+                self.adopt_epoch(committee, worker_cache).await;
+            }
+        }
+    }
+
+    /// Schedule the move to the next committee, if it is ahead of the current one and of the
+    /// rounds already committed, and no other epoch change is pending. Returns whether the epoch
+    /// change was scheduled.
+    fn schedule_epoch(
+        &mut self,
+        round: Round,
+        committee: &Committee,
+        worker_cache: &WorkerCache,
+    ) -> bool {
+        let epoch = self.committee.load().epoch();
+        if committee.epoch() <= epoch || worker_cache.epoch != committee.epoch() {
+            warn!(
+                "Ignoring the change of epoch E{epoch} to committee E{} and workers E{}",
+                committee.epoch(),
+                worker_cache.epoch
+            );
+            return false;
+        }
+        if let Some((scheduled_round, scheduled, _)) = &self.scheduled_epoch {
+            warn!(
+                "Ignoring the change to epoch E{}, epoch E{} is already scheduled at round {scheduled_round}",
+                committee.epoch(),
+                scheduled.epoch()
+            );
+            return false;
+        }
+        if round <= self.last_committed_round {
+            warn!(
+                "Ignoring the change to epoch E{} at round {round}, consensus already committed round {}",
+                committee.epoch(),
+                self.last_committed_round
+            );
+            return false;
+        }
+        info!(
+            "Moving to epoch E{} once consensus commits round {round}",
+            committee.epoch()
+        );
+        self.scheduled_epoch = Some((round, committee.clone(), worker_cache.clone()));
+        true
+    }
+
+    /// Move to the next committee in place: the network keeps its connections to the peers
+    /// that remain in the committee, and every task moves to the new epoch as on `NewEpoch`.
+    async fn adopt_epoch(&mut self, committee: Committee, worker_cache: WorkerCache) {
+        info!("Adopting the committee of epoch E{}", committee.epoch());
+
+        // The certificates, headers and last proposal of the previous epoch are at rounds the
+        // new epoch starts below.
+        if let Err(e) = self.certificate_store.clear() {
+            error!("Failed to clear the certificate store: {e}");
+        }
+        let keys = self.header_store.iter(None).await.into_keys();
+        if let Err(e) = self.header_store.remove_all(keys).await {
+            error!("Failed to clear the header store: {e}");
+        }
+        if let Err(e) = self.proposer_store.clear() {
+            error!("Failed to clear the proposer store: {e}");
+        }
+
+        let peers: Vec<_> = committee
+            .others_primaries(&self.name)
+            .into_iter()
+            .map(|(_, address, network_key)| (network_key, address))
+            .chain(
+                worker_cache
+                    .our_workers(&self.name)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .chain(
+                        worker_cache
+                            .others_workers(&self.name)
+                            .into_iter()
+                            .map(|(_, worker)| worker),
+                    )
+                    .map(|worker| (worker.name, worker.worker_address)),
+            )
+            .collect();
+        network::update_known_peers(&self.network.network(), peers);
+
+        self.worker_cache.swap(Arc::new(worker_cache));
+        self.committee.swap(Arc::new(committee.clone()));
+        tracing::debug!("Committee updated to {}", self.committee);
+
+        let message = ReconfigureNotification::NewEpoch(committee);
+        self.notify_our_workers(message.clone());
+
+        // Trigger cleanup on the primary.
+        let _ = self.tx_consensus_round_updates.send(0); // ignore error when receivers dropped.
+
+        // Notify all other tasks.
+        self.tx_reconfigure
+            .send(message)
+            .expect("Reconfigure channel dropped");
     }
 
     fn update_committee(&mut self, committee: Committee) {
@@ -157,7 +281,18 @@ impl StateHandler {
                     self.handle_sequenced(round, certificates).await;
                 },
 
-                Some(message) = self.rx_state_handler.recv() => {
+                Some((message, tx_accepted)) = self.rx_state_handler.recv() => {
+                    let accepted = match &message {
+                        ReconfigureNotification::ScheduleEpoch { round, committee, worker_cache } => {
+                            self.schedule_epoch(*round, committee, worker_cache)
+                        }
+                        _ => true,
+                    };
+                    let _ = tx_accepted.send(accepted); // ignore error when the requester left.
+                    if !accepted {
+                        continue;
+                    }
+
                     // Notify our workers
                     self.notify_our_workers(message.to_owned());
 
@@ -176,6 +311,7 @@ impl StateHandler {
                             false
                         }
                         ReconfigureNotification::Shutdown => true,
+                        ReconfigureNotification::ScheduleEpoch { .. } => false,
                     };
 
                     // Notify all other tasks.
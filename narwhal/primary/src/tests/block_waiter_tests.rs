@@ -99,7 +99,7 @@ async fn test_successfully_retrieve_block() {
     assert_eq!(block.batches.len(), expected_block_count);
     assert_eq!(block.digest, digest.clone());
     for batch in block.batches {
-        assert_eq!(batch.batch.transactions.len(), 2);
+        assert_eq!(batch.batch.transactions().len(), 2);
     }
 }
 
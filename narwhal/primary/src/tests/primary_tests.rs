@@ -32,7 +32,7 @@ use types::{
     BatchDigest, Certificate, CertificateDigest, FetchCertificatesRequest,
     PayloadAvailabilityRequest, PrimaryToPrimary, ReconfigureNotification, Round,
 };
-use worker::{metrics::initialise_metrics, Worker};
+use worker::{metrics::initialise_metrics, FifoBatchPolicy, Worker};
 
 #[tokio::test]
 async fn get_network_peers_from_admin_server() {
@@ -94,6 +94,7 @@ async fn get_network_peers_from_admin_server() {
             Dag::new(&committee, rx_new_certificates, consensus_metrics).1,
         )),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback,
         &Registry::new(),
@@ -120,6 +121,8 @@ async fn get_network_peers_from_admin_server() {
         worker_cache.clone(),
         worker_1_parameters.clone(),
         store.batch_store,
+        store.batch_chunk_store,
+        Arc::new(FifoBatchPolicy),
         metrics_1,
     );
 
@@ -205,6 +208,7 @@ async fn get_network_peers_from_admin_server() {
             Dag::new(&committee, rx_new_certificates_2, consensus_metrics).1,
         )),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure_2,
         tx_feedback_2,
         &Registry::new(),
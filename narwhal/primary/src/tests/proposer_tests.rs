@@ -42,6 +42,7 @@ async fn propose_empty() {
         /* tx_core */ tx_headers,
         rx_commited_own_headers,
         metrics,
+        None,
     );
 
     // Ensure the proposer makes a correct empty header.
@@ -90,6 +91,7 @@ async fn propose_payload_and_repropose_after_n_seconds() {
         /* tx_core */ tx_headers,
         rx_commited_own_headers,
         metrics,
+        None,
     );
 
     // Send enough digests for the header payload.
@@ -205,6 +207,7 @@ async fn equivocation_protection() {
         /* tx_core */ tx_headers,
         rx_commited_own_headers,
         metrics,
+        None,
     );
 
     // Send enough digests for the header payload.
@@ -271,6 +274,7 @@ async fn equivocation_protection() {
         /* tx_core */ tx_headers,
         rx_commited_own_headers,
         metrics,
+        None,
     );
 
     // Send enough digests for the header payload.
@@ -0,0 +1,164 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use crate::{block_synchronizer::handler::MockHandler, common::create_db_stores};
+use fastcrypto::hash::Hash;
+use mockall::predicate;
+use prometheus::Registry;
+use test_utils::{make_optimal_certificates, CommitteeFixture};
+use types::Certificate;
+
+fn recovery(
+    fixture: &CommitteeFixture,
+    certificate_store: CertificateStore,
+    proposer_store: ProposerStore,
+    handler: MockHandler,
+) -> Recovery<MockHandler> {
+    Recovery {
+        name: fixture.authorities().next().unwrap().public_key(),
+        committee: fixture.committee(),
+        certificate_store,
+        proposer_store,
+        consensus_store: None,
+        gc_depth: 50,
+        block_synchronizer_handler: Arc::new(handler),
+        metrics: Arc::new(PrimaryMetrics::new(&Registry::new())),
+    }
+}
+
+// Makes 5 rounds of certificates, and returns them along the one of the second authority in
+// round 3.
+fn certificates(fixture: &CommitteeFixture) -> (Vec<Certificate>, Certificate) {
+    let committee = fixture.committee();
+    let keys: Vec<_> = fixture.authorities().map(|a| a.public_key()).collect();
+    let genesis = Certificate::genesis(&committee)
+        .iter()
+        .map(|x| x.digest())
+        .collect::<BTreeSet<_>>();
+    let (certificates, _) = make_optimal_certificates(&committee, 1..=5, &genesis, &keys);
+    let missing = certificates
+        .iter()
+        .find(|c| c.round() == 3 && c.origin() == keys[1])
+        .unwrap()
+        .clone();
+    (certificates.into_iter().collect(), missing)
+}
+
+#[tokio::test]
+async fn audit_finds_missing_parents() {
+    let fixture = CommitteeFixture::builder().build();
+    let (_, certificate_store, _) = create_db_stores();
+    let (certificates, missing) = certificates(&fixture);
+    for certificate in &certificates {
+        if certificate.digest() != missing.digest() {
+            certificate_store.write(certificate.clone()).unwrap();
+        }
+    }
+
+    let recovery = recovery(
+        &fixture,
+        certificate_store,
+        ProposerStore::new_for_tests(),
+        MockHandler::new(),
+    );
+    let audit = recovery.audit().unwrap();
+    assert_eq!(audit.gc_round, 0);
+    assert_eq!(audit.certificates, certificates.len() - 1);
+    assert_eq!(
+        audit.missing_parents,
+        BTreeSet::from_iter([missing.digest()])
+    );
+    assert!(audit.inconsistencies.is_empty());
+}
+
+#[tokio::test]
+async fn audit_detects_own_equivocation() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let (_, certificate_store, _) = create_db_stores();
+    let (certificates, _) = certificates(&fixture);
+    for certificate in &certificates {
+        certificate_store.write(certificate.clone()).unwrap();
+    }
+
+    // We stored a certificate for round 5, but the header we last proposed for round 5 is
+    // another one.
+    let proposer_store = ProposerStore::new_for_tests();
+    let header = fixture
+        .authorities()
+        .next()
+        .unwrap()
+        .header_with_round(&committee, 5);
+    proposer_store.write_last_proposed(&header).unwrap();
+
+    let recovery = recovery(
+        &fixture,
+        certificate_store,
+        proposer_store,
+        MockHandler::new(),
+    );
+    let audit = recovery.audit().unwrap();
+    assert!(audit.missing_parents.is_empty());
+    assert_eq!(
+        audit.inconsistencies,
+        vec![Inconsistency::OwnEquivocation { round: 5 }]
+    );
+}
+
+#[tokio::test]
+async fn recovery_fetches_missing_parents() {
+    let fixture = CommitteeFixture::builder().build();
+    let (_, certificate_store, _) = create_db_stores();
+    let (certificates, missing) = certificates(&fixture);
+    for certificate in &certificates {
+        if certificate.digest() != missing.digest() {
+            certificate_store.write(certificate.clone()).unwrap();
+        }
+    }
+
+    // The block synchronizer fetches the missing certificate, which the core stores.
+    let mut handler = MockHandler::new();
+    let store = certificate_store.clone();
+    let fetched = missing.clone();
+    handler
+        .expect_get_and_synchronize_block_headers()
+        .with(predicate::eq(vec![missing.digest()]))
+        .times(1)
+        .returning(move |_| {
+            store.write(fetched.clone()).unwrap();
+            vec![Ok(fetched.clone())]
+        });
+
+    let recovery = recovery(
+        &fixture,
+        certificate_store.clone(),
+        ProposerStore::new_for_tests(),
+        handler,
+    );
+    let metrics = recovery.metrics.clone();
+    recovery.run().await;
+
+    assert!(certificate_store.read(missing.digest()).unwrap().is_some());
+    let epoch = fixture.committee().epoch().to_string();
+    assert_eq!(
+        metrics
+            .recovery_certificates_fetched
+            .with_label_values(&[&epoch])
+            .get(),
+        1
+    );
+    assert_eq!(
+        metrics
+            .recovery_missing_parents
+            .with_label_values(&[&epoch])
+            .get(),
+        0
+    );
+    assert_eq!(
+        metrics
+            .recovery_in_progress
+            .with_label_values(&[&epoch])
+            .get(),
+        0
+    );
+}
@@ -4,7 +4,7 @@
 #![allow(clippy::mutable_key_type)]
 
 use arc_swap::ArcSwap;
-use config::{Committee, Parameters};
+use config::{Committee, Parameters, WorkerCache};
 use fastcrypto::traits::KeyPair;
 use futures::future::{join_all, try_join_all};
 use narwhal_primary as primary;
@@ -74,6 +74,7 @@ async fn test_simple_epoch_change() {
             /* rx_consensus */ rx_feedback,
             /* dag */ None,
             NetworkModel::Asynchronous,
+            /* consensus_store */ None,
             tx_reconfigure,
             /* tx_committed_certificates */ tx_feedback,
             &Registry::new(),
@@ -194,6 +195,7 @@ async fn test_partial_committee_change() {
             /* rx_consensus */ rx_feedback,
             /* dag */ None,
             NetworkModel::Asynchronous,
+            /* consensus_store */ None,
             tx_reconfigure,
             /* tx_committed_certificates */ tx_feedback,
             &Registry::new(),
@@ -282,6 +284,7 @@ async fn test_partial_committee_change() {
             /* rx_consensus */ rx_feedback,
             /* dag */ None,
             NetworkModel::Asynchronous,
+            /* consensus_store */ None,
             tx_reconfigure,
             /* tx_committed_certificates */ tx_feedback,
             &Registry::new(),
@@ -361,6 +364,7 @@ async fn test_restart_with_new_committee_change() {
             /* rx_consensus */ rx_feedback,
             /* dag */ None,
             NetworkModel::Asynchronous,
+            /* consensus_store */ None,
             tx_reconfigure,
             /* tx_committed_certificates */ tx_feedback,
             &Registry::new(),
@@ -449,6 +453,7 @@ async fn test_restart_with_new_committee_change() {
                 /* rx_consensus */ rx_feedback,
                 /* dag */ None,
                 NetworkModel::Asynchronous,
+                /* consensus_store */ None,
                 tx_reconfigure,
                 /* tx_committed_certificates */ tx_feedback,
                 &Registry::new(),
@@ -551,6 +556,7 @@ async fn test_simple_committee_update() {
             /* rx_consensus */ rx_feedback,
             /* dag */ None,
             NetworkModel::Asynchronous,
+            /* consensus_store */ None,
             tx_reconfigure,
             /* tx_committed_certificates */ tx_feedback,
             &Registry::new(),
@@ -611,3 +617,79 @@ async fn test_simple_committee_update() {
         old_committee = new_committee;
     }
 }
+
+/// The primary answers whether it scheduled an in-place epoch change.
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn test_schedule_epoch_rejection() {
+    ensure_test_environment();
+
+    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let committee_0 = fixture.committee();
+    let worker_cache_0 = fixture.shared_worker_cache();
+    let parameters = Parameters::default();
+
+    let authority = fixture.authorities().next().unwrap();
+    let (tx_new_certificates, _rx_new_certificates) =
+        test_utils::test_new_certificates_channel!(CHANNEL_CAPACITY);
+    let (tx_feedback, rx_feedback) =
+        test_utils::test_committed_certificates_channel!(CHANNEL_CAPACITY);
+    let initial_committee = ReconfigureNotification::NewEpoch(committee_0.clone());
+    let (tx_reconfigure, _rx_reconfigure) = watch::channel(initial_committee);
+    let store = NodeStorage::reopen(temp_dir());
+    Primary::spawn(
+        authority.public_key(),
+        authority.keypair().copy(),
+        authority.network_keypair().copy(),
+        Arc::new(ArcSwap::from_pointee(committee_0.clone())),
+        worker_cache_0.clone(),
+        parameters.clone(),
+        store.header_store.clone(),
+        store.certificate_store.clone(),
+        store.proposer_store.clone(),
+        store.payload_store.clone(),
+        store.vote_digest_store.clone(),
+        /* tx_consensus */ tx_new_certificates,
+        /* rx_consensus */ rx_feedback,
+        /* dag */ None,
+        NetworkModel::Asynchronous,
+        /* consensus_store */ None,
+        tx_reconfigure,
+        /* tx_committed_certificates */ tx_feedback,
+        &Registry::new(),
+        None,
+    );
+
+    let client = reqwest::Client::new();
+    let schedule = |epoch, round| {
+        let message = ReconfigureNotification::ScheduleEpoch {
+            round,
+            committee: Committee {
+                epoch,
+                ..committee_0.clone()
+            },
+            worker_cache: WorkerCache {
+                epoch,
+                ..(**worker_cache_0.load()).clone()
+            },
+        };
+        client
+            .post(format!(
+                "http://127.0.0.1:{}/reconfigure",
+                parameters
+                    .network_admin_server
+                    .primary_network_admin_server_port
+            ))
+            .json(&message)
+            .send()
+    };
+
+    // The current epoch cannot be scheduled.
+    let response = schedule(0, 100).await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+
+    // The next one can, once.
+    let response = schedule(1, 100).await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response = schedule(2, 200).await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+}
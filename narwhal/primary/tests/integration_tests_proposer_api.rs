@@ -115,6 +115,7 @@ async fn test_rounds_errors() {
             Dag::new(&no_name_committee, rx_new_certificates, consensus_metrics).1,
         )),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback,
         &Registry::new(),
@@ -197,6 +198,7 @@ async fn test_rounds_return_successful_response() {
         /* rx_consensus */ rx_feedback,
         /* external_consensus */ Some(dag.clone()),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback,
         &Registry::new(),
@@ -348,6 +350,7 @@ async fn test_node_read_causal_signed_certificates() {
         /* rx_consensus */ rx_feedback,
         /* dag */ Some(dag.clone()),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback,
         &Registry::new(),
@@ -389,6 +392,7 @@ async fn test_node_read_causal_signed_certificates() {
             Dag::new(&committee, rx_new_certificates_2, consensus_metrics_2).1,
         )),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback_2,
         &Registry::new(),
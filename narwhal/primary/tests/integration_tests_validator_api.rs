@@ -29,7 +29,7 @@ use types::{
     ReadCausalRequest, ReconfigureNotification, RemoveCollectionsRequest, RetrievalResult,
     Transaction, ValidatorClient,
 };
-use worker::{metrics::initialise_metrics, Worker};
+use worker::{metrics::initialise_metrics, FifoBatchPolicy, Worker};
 
 #[tokio::test(flavor = "current_thread", start_paused = true)]
 async fn test_get_collections() {
@@ -126,6 +126,7 @@ async fn test_get_collections() {
             Dag::new(&committee, rx_new_certificates, consensus_metrics).1,
         )),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback,
         &Registry::new(),
@@ -144,6 +145,8 @@ async fn test_get_collections() {
         worker_cache.clone(),
         parameters.clone(),
         store.batch_store.clone(),
+        store.batch_chunk_store.clone(),
+        Arc::new(FifoBatchPolicy),
         metrics,
     );
 
@@ -317,6 +320,7 @@ async fn test_remove_collections() {
         /* rx_consensus */ rx_feedback,
         /* dag */ Some(dag.clone()),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback,
         &Registry::new(),
@@ -362,6 +366,8 @@ async fn test_remove_collections() {
         worker_cache.clone(),
         parameters.clone(),
         store.batch_store.clone(),
+        store.batch_chunk_store.clone(),
+        Arc::new(FifoBatchPolicy),
         metrics,
     );
 
@@ -528,6 +534,7 @@ async fn test_read_causal_signed_certificates() {
         /* rx_consensus */ rx_feedback,
         /* dag */ Some(dag.clone()),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback,
         &Registry::new(),
@@ -570,6 +577,7 @@ async fn test_read_causal_signed_certificates() {
             Dag::new(&committee, rx_new_certificates_2, consensus_metrics_2).1,
         )),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback_2,
         &Registry::new(),
@@ -739,6 +747,7 @@ async fn test_read_causal_unsigned_certificates() {
         /* rx_consensus */ rx_feedback,
         /* dag */ Some(dag.clone()),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback,
         &Registry::new(),
@@ -774,6 +783,7 @@ async fn test_read_causal_unsigned_certificates() {
             Dag::new(&committee, rx_new_certificates_2, consensus_metrics_2).1,
         )),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback_2,
         &Registry::new(),
@@ -941,6 +951,7 @@ async fn test_get_collections_with_missing_certificates() {
             Dag::new(&committee, rx_new_certificates_1, consensus_metrics).1,
         )),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback_1,
         &Registry::new(),
@@ -959,6 +970,8 @@ async fn test_get_collections_with_missing_certificates() {
         worker_cache.clone(),
         parameters_1.clone(),
         store_primary_1.batch_store,
+        store_primary_1.batch_chunk_store,
+        Arc::new(FifoBatchPolicy),
         metrics_1,
     );
 
@@ -999,6 +1012,7 @@ async fn test_get_collections_with_missing_certificates() {
         /* external_consensus */
         None,
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback_2,
         &Registry::new(),
@@ -1017,6 +1031,8 @@ async fn test_get_collections_with_missing_certificates() {
         worker_cache.clone(),
         parameters_2.clone(),
         store_primary_2.batch_store,
+        store_primary_2.batch_chunk_store,
+        Arc::new(FifoBatchPolicy),
         metrics_2,
     );
 
@@ -1061,7 +1077,8 @@ async fn test_get_collections_with_missing_certificates() {
 
                 if let Some(expected_batch) = batches_map.get(&id) {
                     assert_eq!(
-                        result_transactions, *expected_batch.transactions,
+                        result_transactions,
+                        expected_batch.transactions(),
                         "Batch payload doesn't match"
                     );
                 } else {
@@ -9,8 +9,9 @@ use store::rocks::open_cf;
 use store::rocks::DBMap;
 use store::{reopen, Store};
 use types::{
-    Batch, BatchDigest, Certificate, CertificateDigest, CommittedSubDagShell, ConsensusStore,
-    Header, HeaderDigest, Round, RoundVoteDigestPair, SequenceNumber,
+    Batch, BatchChunk, BatchDigest, Certificate, CertificateDigest, CommittedSubDagShell,
+    ConsensusStore, Header, HeaderDigest, ReputationScores, Round, RoundVoteDigestPair,
+    SequenceNumber,
 };
 
 // A type alias marking the "payload" tokens sent by workers to their primary as batch acknowledgements
@@ -26,6 +27,7 @@ pub struct NodeStorage {
     pub batch_store: Store<BatchDigest, Batch>,
     pub consensus_store: Arc<ConsensusStore>,
     pub temp_batch_store: Store<(CertificateDigest, BatchDigest), Batch>,
+    pub batch_chunk_store: Store<BatchDigest, BatchChunk>,
 }
 
 impl NodeStorage {
@@ -41,7 +43,9 @@ impl NodeStorage {
     const LAST_COMMITTED_CF: &'static str = "last_committed";
     const SEQUENCE_CF: &'static str = "sequence";
     const SUB_DAG_CF: &'static str = "sub_dag";
+    const REPUTATION_SCORES_CF: &'static str = "reputation_scores";
     const TEMP_BATCH_CF: &'static str = "temp_batches";
+    const BATCH_CHUNKS_CF: &'static str = "batch_chunks";
 
     /// Open or reopen all the storage of the node.
     pub fn reopen<Path: AsRef<std::path::Path>>(store_path: Path) -> Self {
@@ -60,7 +64,9 @@ impl NodeStorage {
                 Self::LAST_COMMITTED_CF,
                 Self::SEQUENCE_CF,
                 Self::SUB_DAG_CF,
+                Self::REPUTATION_SCORES_CF,
                 Self::TEMP_BATCH_CF,
+                Self::BATCH_CHUNKS_CF,
             ],
         )
         .expect("Cannot open database");
@@ -77,7 +83,9 @@ impl NodeStorage {
             last_committed_map,
             sequence_map,
             sub_dag_map,
+            reputation_scores_map,
             temp_batch_map,
+            batch_chunk_map,
         ) = reopen!(&rocksdb,
             Self::LAST_PROPOSED_CF;<ProposerKey, Header>,
             Self::VOTES_CF;<PublicKey, RoundVoteDigestPair>,
@@ -90,7 +98,9 @@ impl NodeStorage {
             Self::LAST_COMMITTED_CF;<PublicKey, Round>,
             Self::SEQUENCE_CF;<SequenceNumber, CertificateDigest>,
             Self::SUB_DAG_CF;<Round, CommittedSubDagShell>,
-            Self::TEMP_BATCH_CF;<(CertificateDigest, BatchDigest), Batch>
+            Self::REPUTATION_SCORES_CF;<Round, ReputationScores>,
+            Self::TEMP_BATCH_CF;<(CertificateDigest, BatchDigest), Batch>,
+            Self::BATCH_CHUNKS_CF;<BatchDigest, BatchChunk>
         );
 
         let proposer_store = ProposerStore::new(last_proposed_map);
@@ -107,8 +117,10 @@ impl NodeStorage {
             last_committed_map,
             sequence_map,
             sub_dag_map,
+            reputation_scores_map,
         ));
         let temp_batch_store = Store::new(temp_batch_map);
+        let batch_chunk_store = Store::new(batch_chunk_map);
 
         Self {
             proposer_store,
@@ -119,6 +131,7 @@ impl NodeStorage {
             batch_store,
             consensus_store,
             temp_batch_store,
+            batch_chunk_store,
         }
     }
 }
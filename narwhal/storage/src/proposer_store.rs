@@ -38,6 +38,11 @@ impl ProposerStore {
     pub fn get_last_proposed(&self) -> StoreResult<Option<Header>> {
         self.last_proposed.get(&LAST_PROPOSAL_KEY)
     }
+
+    /// Clears the last header
+    pub fn clear(&self) -> StoreResult<()> {
+        self.last_proposed.clear()
+    }
 }
 
 #[cfg(test)]
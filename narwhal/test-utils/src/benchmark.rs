@@ -0,0 +1,371 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An in-process benchmark of narwhal. A cluster of primaries and workers runs in the current
+//! process, transactions are submitted to the workers at a target rate, and the latency of every
+//! transaction is measured from its submission to its execution in the `ConsensusOutput` of the
+//! first authority. Unlike the benchmark client, which relies on a deployment and on parsing its
+//! logs, the benchmark returns a structured report, to compare consensus protocols and
+//! `Parameters` on a single machine.
+
+use crate::{
+    cluster::{Cluster, ConsensusProtocol},
+    CommitteeFixture,
+};
+use bytes::{BufMut as _, BytesMut};
+use config::{Parameters, WorkerId};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    sync::broadcast::error::RecvError,
+    time::{interval, Instant, MissedTickBehavior},
+};
+use tracing::{info, warn};
+use types::{Round, TransactionProto, TransactionsClient};
+
+#[cfg(test)]
+#[path = "tests/benchmark_tests.rs"]
+pub mod benchmark_tests;
+
+/// The number of bursts the transactions of every second are submitted in.
+const BURSTS_PER_SECOND: u64 = 20;
+
+/// The size of the identifier every transaction starts with.
+const TRANSACTION_ID_SIZE: usize = 8;
+
+/// The setup and the load of a benchmark run.
+#[derive(Clone, Debug)]
+pub struct BenchmarkConfig {
+    /// The number of authorities of the cluster.
+    pub committee_size: NonZeroUsize,
+    /// The number of workers of every authority. Transactions are spread among all of them.
+    pub workers_per_authority: NonZeroUsize,
+    /// The rate transactions are submitted at to the whole cluster, in transactions per second.
+    pub rate: u64,
+    /// The size of every transaction, in bytes.
+    pub transaction_size: usize,
+    /// How long transactions are submitted for, warm up included.
+    pub duration: Duration,
+    /// How long after the start the transactions submitted are not measured.
+    pub warmup: Duration,
+    /// How long to wait for the transactions submitted to execute once the submission stops.
+    pub drain_timeout: Duration,
+    /// The protocol the consensus of every authority runs.
+    pub consensus_protocol: ConsensusProtocol,
+    /// The parameters of every authority.
+    pub parameters: Parameters,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            committee_size: NonZeroUsize::new(4).unwrap(),
+            workers_per_authority: NonZeroUsize::new(1).unwrap(),
+            rate: 1_000,
+            transaction_size: 512,
+            duration: Duration::from_secs(30),
+            warmup: Duration::from_secs(5),
+            drain_timeout: Duration::from_secs(10),
+            consensus_protocol: ConsensusProtocol::Bullshark,
+            parameters: Parameters::default(),
+        }
+    }
+}
+
+/// Latency percentiles over a set of transactions, in milliseconds.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    fn new(latencies: &mut [Duration]) -> Self {
+        if latencies.is_empty() {
+            return Self::default();
+        }
+        latencies.sort();
+        let percentile = |p: f64| {
+            // The nearest rank: the smallest latency that at least p of the samples are below.
+            let rank = ((p * latencies.len() as f64).ceil() as usize).clamp(1, latencies.len());
+            latencies[rank - 1].as_secs_f64() * 1_000.0
+        };
+        let total: Duration = latencies.iter().sum();
+        Self {
+            samples: latencies.len(),
+            mean_ms: total.as_secs_f64() * 1_000.0 / latencies.len() as f64,
+            p50_ms: percentile(0.5),
+            p99_ms: percentile(0.99),
+            max_ms: percentile(1.0),
+        }
+    }
+}
+
+/// What the measured transactions of the certificates of a round went through.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RoundStats {
+    pub round: Round,
+    /// The number of certificates of the round carrying measured transactions.
+    pub certificates: usize,
+    pub transactions: usize,
+    pub latency: LatencyStats,
+}
+
+/// The outcome of a benchmark run. Only the transactions submitted after the warm up are
+/// measured.
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchmarkReport {
+    pub consensus_protocol: ConsensusProtocol,
+    pub committee_size: usize,
+    pub workers_per_authority: usize,
+    pub target_rate: u64,
+    pub transaction_size: usize,
+    /// How long the measured transactions were submitted for.
+    pub measured_duration_ms: u64,
+    pub submitted: usize,
+    /// The number of measured transactions executed before the drain timeout.
+    pub executed: usize,
+    /// The measured transactions executed per second of submission.
+    pub tps: f64,
+    /// The bytes of measured transactions executed per second of submission.
+    pub bps: f64,
+    pub latency: LatencyStats,
+    /// The statistics of every round with measured transactions, by round of the certificate
+    /// that sequenced them.
+    pub rounds: Vec<RoundStats>,
+    /// The transactions executed whose confirmation was missed because the benchmark lagged
+    /// behind; they are not measured.
+    pub missed_confirmations: u64,
+    pub parameters: Parameters,
+}
+
+/// The execution times of the transactions of the run, by transaction identifier.
+#[derive(Default)]
+struct Executions {
+    times: HashMap<u64, Instant>,
+    missed: u64,
+}
+
+/// Run the benchmark: start a cluster, submit transactions for the duration of the run, wait for
+/// them to execute, stop the cluster and report.
+pub async fn run(config: BenchmarkConfig) -> BenchmarkReport {
+    assert!(
+        config.transaction_size >= TRANSACTION_ID_SIZE,
+        "Transactions must be at least {TRANSACTION_ID_SIZE} bytes"
+    );
+    assert!(
+        config.rate >= BURSTS_PER_SECOND,
+        "The rate must be at least {BURSTS_PER_SECOND} tx/s"
+    );
+    assert!(
+        config.warmup < config.duration,
+        "The warm up must be shorter than the duration of the run"
+    );
+
+    let fixture = CommitteeFixture::builder()
+        .committee_size(config.committee_size)
+        .number_of_workers(config.workers_per_authority)
+        .randomize_ports(true)
+        .build();
+    let mut cluster = Cluster::new_with_fixture(
+        fixture,
+        Some(config.parameters.clone()),
+        true,
+        config.consensus_protocol,
+    );
+    cluster.start(None, None, None).await;
+    info!(
+        "Benchmarking {} authorities running {:?} at {} tx/s",
+        config.committee_size, config.consensus_protocol, config.rate
+    );
+
+    // Executions are observed at the first authority.
+    let observed = cluster.authority(0);
+    let executions = Arc::new(Mutex::new(Executions::default()));
+    let mut rx_executed = observed
+        .primary()
+        .await
+        .tx_transaction_confirmation
+        .subscribe();
+    let recorder = {
+        let executions = executions.clone();
+        tokio::spawn(async move {
+            loop {
+                match rx_executed.recv().await {
+                    Ok(transaction) => {
+                        let executed_at = Instant::now();
+                        if let Some(id) = transaction_id(&transaction) {
+                            executions
+                                .lock()
+                                .unwrap()
+                                .times
+                                .entry(id)
+                                .or_insert(executed_at);
+                        }
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        executions.lock().unwrap().missed += missed;
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    };
+
+    let mut clients = Vec::new();
+    for authority in cluster.authorities().await {
+        for worker_id in 0..config.workers_per_authority.get() as WorkerId {
+            clients.push(authority.new_transactions_client(&worker_id).await);
+        }
+    }
+    let submissions = submit(&config, clients).await;
+
+    // Wait for the measured transactions to execute.
+    let deadline = Instant::now() + config.drain_timeout;
+    loop {
+        let pending = {
+            let executions = executions.lock().unwrap();
+            submissions
+                .measured
+                .keys()
+                .any(|id| !executions.times.contains_key(id))
+        };
+        if !pending || Instant::now() >= deadline {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let log = observed.primary().await.commit_log().transactions();
+    for id in 0..config.committee_size.get() {
+        cluster.stop_node(id).await;
+    }
+    recorder.abort();
+
+    let executions = executions.lock().unwrap();
+    let rounds_by_id: HashMap<u64, _> = log
+        .iter()
+        .filter_map(|committed| {
+            transaction_id(&committed.transaction)
+                .map(|id| (id, (committed.round, committed.certificate)))
+        })
+        .collect();
+
+    let mut latencies = Vec::new();
+    let mut rounds: BTreeMap<Round, (HashSet<_>, Vec<Duration>)> = BTreeMap::new();
+    for (id, submitted_at) in &submissions.measured {
+        let Some(executed_at) = executions.times.get(id) else {
+            continue;
+        };
+        let latency = executed_at.saturating_duration_since(*submitted_at);
+        latencies.push(latency);
+        if let Some((round, certificate)) = rounds_by_id.get(id) {
+            let (certificates, latencies) = rounds.entry(*round).or_default();
+            certificates.insert(*certificate);
+            latencies.push(latency);
+        }
+    }
+
+    let measured_duration = config.duration - config.warmup;
+    let executed = latencies.len();
+    let tps = executed as f64 / measured_duration.as_secs_f64();
+    let report = BenchmarkReport {
+        consensus_protocol: config.consensus_protocol,
+        committee_size: config.committee_size.get(),
+        workers_per_authority: config.workers_per_authority.get(),
+        target_rate: config.rate,
+        transaction_size: config.transaction_size,
+        measured_duration_ms: measured_duration.as_millis() as u64,
+        submitted: submissions.measured.len(),
+        executed,
+        tps,
+        bps: tps * config.transaction_size as f64,
+        latency: LatencyStats::new(&mut latencies),
+        rounds: rounds
+            .into_iter()
+            .map(|(round, (certificates, mut latencies))| RoundStats {
+                round,
+                certificates: certificates.len(),
+                transactions: latencies.len(),
+                latency: LatencyStats::new(&mut latencies),
+            })
+            .collect(),
+        missed_confirmations: executions.missed,
+        parameters: config.parameters,
+    };
+    info!(
+        "Executed {} of {} measured transactions: {:.0} tx/s, p50 {:.0} ms, p99 {:.0} ms",
+        report.executed, report.submitted, report.tps, report.latency.p50_ms, report.latency.p99_ms
+    );
+    report
+}
+
+/// The submission times of the transactions submitted after the warm up.
+struct Submissions {
+    measured: HashMap<u64, Instant>,
+}
+
+/// Submit transactions to the workers in turn, in bursts, at the rate of the benchmark.
+async fn submit(
+    config: &BenchmarkConfig,
+    mut clients: Vec<TransactionsClient<tonic::transport::Channel>>,
+) -> Submissions {
+    let burst = config.rate / BURSTS_PER_SECOND;
+    let burst_duration = Duration::from_millis(1_000 / BURSTS_PER_SECOND);
+    let mut ticks = interval(burst_duration);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Burst);
+
+    let started = Instant::now();
+    let mut measured = HashMap::new();
+    let mut next_id = 0u64;
+    for bursts in 0.. {
+        if started.elapsed() >= config.duration {
+            break;
+        }
+        ticks.tick().await;
+        let burst_started = Instant::now();
+        let ids = next_id..next_id + burst;
+        next_id += burst;
+
+        let transactions: Vec<_> = ids
+            .clone()
+            .map(|id| {
+                let mut transaction = BytesMut::with_capacity(config.transaction_size);
+                transaction.put_u64(id);
+                transaction.resize(config.transaction_size, 0u8);
+                TransactionProto {
+                    transaction: transaction.freeze(),
+                }
+            })
+            .collect();
+        let client = &mut clients[bursts % clients.len()];
+        match client
+            .submit_transaction_stream(futures::stream::iter(transactions))
+            .await
+        {
+            Ok(_) if burst_started.duration_since(started) >= config.warmup => {
+                measured.extend(ids.map(|id| (id, burst_started)));
+            }
+            Ok(_) => (),
+            Err(e) => warn!("Failed to submit transactions: {e}"),
+        }
+        if burst_started.elapsed() > burst_duration {
+            warn!("Transaction rate too high for the benchmark to keep up");
+        }
+    }
+    Submissions { measured }
+}
+
+/// The identifier a transaction of the benchmark starts with.
+fn transaction_id(transaction: &[u8]) -> Option<u64> {
+    let id = transaction.get(..TRANSACTION_ID_SIZE)?;
+    Some(u64::from_be_bytes(id.try_into().ok()?))
+}
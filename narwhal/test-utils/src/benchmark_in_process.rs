@@ -0,0 +1,105 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use clap::{crate_name, crate_version, App, AppSettings};
+use config::{Export, Import, Parameters};
+use eyre::{eyre, Context};
+use narwhal_test_utils as test_utils;
+use std::{num::NonZeroUsize, time::Duration};
+use test_utils::{
+    benchmark::{run, BenchmarkConfig},
+    cluster::{setup_tracing, ConsensusProtocol},
+    ensure_test_environment,
+};
+
+#[tokio::main]
+async fn main() -> Result<(), eyre::Report> {
+    let matches = App::new(crate_name!())
+        .version(crate_version!())
+        .about("In-process benchmark for Narwhal, with Bullshark or Tusk.")
+        .long_about("Runs a cluster of primaries and workers in this process, submits transactions to the workers at\n\
+        the --rate parameter, and measures the latency from the submission of every transaction to its execution\n\
+        by the first authority. The transactions submitted during the first --warmup seconds are not measured.\n\
+        \n\
+        The report is printed as json, with the throughput, the latency percentiles and the statistics of every\n\
+        round, and also written to the --output file if provided.")
+        .args_from_usage("--authorities=[INT] 'The number of authorities (default 4)'")
+        .args_from_usage("--workers=[INT] 'The number of workers per authority (default 1)'")
+        .args_from_usage("--rate=<INT> 'The rate (txs/s) at which to send the transactions'")
+        .args_from_usage("--size=<INT> 'The size of each transaction in bytes'")
+        .args_from_usage("--duration=[INT] 'How long to send transactions for, in seconds (default 30)'")
+        .args_from_usage("--warmup=[INT] 'How long to send transactions for before measuring, in seconds (default 5)'")
+        .args_from_usage("--parameters=[FILE] 'The file containing the node parameters'")
+        .args_from_usage("--protocol=[PROTOCOL] 'The consensus protocol, bullshark (default) or tusk'")
+        .args_from_usage("--output=[FILE] 'The file to write the report to'")
+        .args_from_usage("-v... 'Sets the level of verbosity'")
+        .setting(AppSettings::ArgRequiredElseHelp)
+        .get_matches();
+
+    let _guard = (matches.occurrences_of("v") > 0).then(setup_tracing);
+    ensure_test_environment();
+
+    let parse = |name: &str| -> Result<Option<u64>, eyre::Report> {
+        matches
+            .value_of(name)
+            .map(|value| value.parse::<u64>())
+            .transpose()
+            .with_context(|| format!("The {name} must be a non-negative integer"))
+    };
+    let non_zero = |name: &str| -> Result<Option<NonZeroUsize>, eyre::Report> {
+        parse(name)?
+            .map(|value| {
+                NonZeroUsize::new(value as usize)
+                    .ok_or_else(|| eyre!("The {name} must be positive"))
+            })
+            .transpose()
+    };
+
+    let parameters = match matches.value_of("parameters") {
+        Some(filename) => {
+            Parameters::import(filename).context("Failed to load the node's parameters")?
+        }
+        None => Parameters::default(),
+    };
+    let consensus_protocol = match matches.value_of("protocol") {
+        Some("bullshark") | None => ConsensusProtocol::Bullshark,
+        Some("tusk") => ConsensusProtocol::Tusk,
+        Some(protocol) => return Err(eyre!("Unknown consensus protocol {protocol}")),
+    };
+
+    let default = BenchmarkConfig::default();
+    let config = BenchmarkConfig {
+        committee_size: non_zero("authorities")?.unwrap_or(default.committee_size),
+        workers_per_authority: non_zero("workers")?.unwrap_or(default.workers_per_authority),
+        rate: parse("rate")?.unwrap(),
+        transaction_size: parse("size")?.unwrap() as usize,
+        duration: parse("duration")?
+            .map(Duration::from_secs)
+            .unwrap_or(default.duration),
+        warmup: parse("warmup")?
+            .map(Duration::from_secs)
+            .unwrap_or(default.warmup),
+        consensus_protocol,
+        parameters,
+        ..default
+    };
+    if config.transaction_size < 8 {
+        return Err(eyre!("Transaction size must be at least 8 bytes"));
+    }
+    if config.rate < 20 {
+        return Err(eyre!(
+            "Transaction rate is too low, should be at least 20 tx/s"
+        ));
+    }
+    if config.warmup >= config.duration {
+        return Err(eyre!("The warm up must be shorter than the duration"));
+    }
+
+    let report = run(config).await;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    if let Some(filename) = matches.value_of("output") {
+        report
+            .export(filename)
+            .context("Failed to write the report")?;
+    }
+    Ok(())
+}
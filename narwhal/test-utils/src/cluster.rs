@@ -1,19 +1,24 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::{temp_dir, CommitteeFixture};
+use crate::{
+    simulator::{CommitLog, RecordingExecutionState},
+    temp_dir, CommitteeFixture,
+};
+use anemo::PeerId;
 use arc_swap::ArcSwap;
 use config::{Parameters, SharedCommittee, SharedWorkerCache, WorkerId};
+use consensus::tusk::Tusk;
 use crypto::{KeyPair, NetworkKeyPair, PublicKey};
 use executor::SerializedTransaction;
 use fastcrypto::traits::KeyPair as _;
 use itertools::Itertools;
 use multiaddr::Multiaddr;
 use node::{
-    execution_state::SimpleExecutionState,
     metrics::{primary_metrics_registry, worker_metrics_registry},
     Node,
 };
 use prometheus::{proto::Metric, Registry};
+use serde::Serialize;
 use std::{cell::RefCell, collections::HashMap, path::PathBuf, rc::Rc, sync::Arc, time::Duration};
 use storage::NodeStorage;
 use telemetry_subscribers::TelemetryGuards;
@@ -24,11 +29,24 @@ use tokio::{
 use tonic::transport::Channel;
 use tracing::info;
 use types::{ConfigurationClient, ProposerClient, TransactionsClient};
+use worker::FifoBatchPolicy;
 
 #[cfg(test)]
 #[path = "tests/cluster_tests.rs"]
 pub mod cluster_tests;
 
+/// The protocol the consensus of every authority of a cluster orders the certificates with.
+/// Validators always run Bullshark: Tusk only runs in clusters, to compare the protocols.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsensusProtocol {
+    /// Commits a leader every two rounds, once `f + 1` certificates of the next round support it.
+    #[default]
+    Bullshark,
+    /// Commits a leader every four rounds, elected once the next two rounds are certified.
+    Tusk,
+}
+
 pub struct Cluster {
     #[allow(unused)]
     fixture: CommitteeFixture,
@@ -50,6 +68,24 @@ impl Cluster {
     /// DAG externally.
     pub fn new(parameters: Option<Parameters>, internal_consensus_enabled: bool) -> Self {
         let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+        Self::new_with_fixture(
+            fixture,
+            parameters,
+            internal_consensus_enabled,
+            ConsensusProtocol::Bullshark,
+        )
+    }
+
+    /// Initialises a new cluster of the authorities of the provided fixture, as `new` does,
+    /// with the internal consensus of every authority running `consensus_protocol`. The fixture
+    /// should be built with randomized ports, so that the cluster does not conflict with other
+    /// processes.
+    pub fn new_with_fixture(
+        fixture: CommitteeFixture,
+        parameters: Option<Parameters>,
+        internal_consensus_enabled: bool,
+        consensus_protocol: ConsensusProtocol,
+    ) -> Self {
         let c = fixture.committee();
         let shared_worker_cache = fixture.shared_worker_cache();
         let shared_committee = Arc::new(ArcSwap::from_pointee(c));
@@ -71,6 +107,7 @@ impl Cluster {
                 shared_committee.clone(),
                 shared_worker_cache.clone(),
                 internal_consensus_enabled,
+                consensus_protocol,
             );
             nodes.insert(id, authority);
         }
@@ -275,12 +312,14 @@ pub struct PrimaryNodeDetails {
     pub network_key_pair: Arc<NetworkKeyPair>,
     pub tx_transaction_confirmation: Sender<SerializedTransaction>,
     registry: Registry,
+    commit_log: Arc<CommitLog>,
     store_path: PathBuf,
     committee: SharedCommittee,
     worker_cache: SharedWorkerCache,
     parameters: Parameters,
     handlers: Rc<RefCell<Vec<JoinHandle<()>>>>,
     internal_consensus_enabled: bool,
+    consensus_protocol: ConsensusProtocol,
 }
 
 impl PrimaryNodeDetails {
@@ -292,6 +331,7 @@ impl PrimaryNodeDetails {
         committee: SharedCommittee,
         worker_cache: SharedWorkerCache,
         internal_consensus_enabled: bool,
+        consensus_protocol: ConsensusProtocol,
     ) -> Self {
         // used just to initialise the struct value
         let (tx, _) = tokio::sync::broadcast::channel(1);
@@ -303,11 +343,13 @@ impl PrimaryNodeDetails {
             registry: Registry::new(),
             store_path: temp_dir(),
             tx_transaction_confirmation: tx,
+            commit_log: Arc::new(CommitLog::default()),
             committee,
             worker_cache,
             parameters,
             handlers: Rc::new(RefCell::new(Vec::new())),
             internal_consensus_enabled,
+            consensus_protocol,
         }
     }
 
@@ -320,6 +362,11 @@ impl PrimaryNodeDetails {
         metric.map(|m| m.get_metric().first().unwrap().clone())
     }
 
+    /// Returns the transactions the node executed, in order.
+    pub fn commit_log(&self) -> Arc<CommitLog> {
+        self.commit_log.clone()
+    }
+
     async fn start(&mut self, preserve_store: bool) {
         if self.is_running() {
             panic!("Tried to start a node that is already running");
@@ -331,6 +378,8 @@ impl PrimaryNodeDetails {
         let store_path = if preserve_store {
             self.store_path.clone()
         } else {
+            // A node with an empty store executes everything again.
+            self.commit_log.clear();
             temp_dir()
         };
 
@@ -346,19 +395,41 @@ impl PrimaryNodeDetails {
 
         // Primary node
         let primary_store: NodeStorage = NodeStorage::reopen(store_path.clone());
-        let mut primary_handlers = Node::spawn_primary(
-            self.key_pair.copy(),
-            self.network_key_pair.copy(),
-            self.committee.clone(),
-            self.worker_cache.clone(),
-            &primary_store,
-            self.parameters.clone(),
-            /* consensus */ self.internal_consensus_enabled,
-            /* execution_state */
-            Arc::new(SimpleExecutionState::new(tx_transaction_confirmation)),
-            &registry,
-        )
-        .await
+        let execution_state = Arc::new(RecordingExecutionState::new(
+            self.commit_log.clone(),
+            tx_transaction_confirmation,
+        ));
+        let mut primary_handlers = match self.consensus_protocol {
+            ConsensusProtocol::Bullshark => {
+                Node::spawn_primary(
+                    self.key_pair.copy(),
+                    self.network_key_pair.copy(),
+                    self.committee.clone(),
+                    self.worker_cache.clone(),
+                    &primary_store,
+                    self.parameters.clone(),
+                    /* consensus */ self.internal_consensus_enabled,
+                    execution_state,
+                    &registry,
+                )
+                .await
+            }
+            ConsensusProtocol::Tusk => {
+                Node::spawn_primary_with_protocol(
+                    self.key_pair.copy(),
+                    self.network_key_pair.copy(),
+                    self.committee.clone(),
+                    self.worker_cache.clone(),
+                    &primary_store,
+                    self.parameters.clone(),
+                    /* consensus */ self.internal_consensus_enabled,
+                    execution_state,
+                    &registry,
+                    |committee, store, gc_depth, _metrics| Tusk::new(committee, store, gc_depth),
+                )
+                .await
+            }
+        }
         .unwrap();
 
         let (tx, _) = tokio::sync::broadcast::channel(primary::CHANNEL_CAPACITY);
@@ -461,6 +532,7 @@ impl WorkerNodeDetails {
             self.worker_cache.clone(),
             &worker_store,
             self.parameters.clone(),
+            Arc::new(FifoBatchPolicy),
             &registry,
         );
 
@@ -514,6 +586,7 @@ impl AuthorityDetails {
         committee: SharedCommittee,
         worker_cache: SharedWorkerCache,
         internal_consensus_enabled: bool,
+        consensus_protocol: ConsensusProtocol,
     ) -> Self {
         // Create all the nodes we have in the committee
         let name = key_pair.public().clone();
@@ -525,6 +598,7 @@ impl AuthorityDetails {
             committee.clone(),
             worker_cache.clone(),
             internal_consensus_enabled,
+            consensus_protocol,
         );
 
         // Create all the workers - even if we don't intend to start them all. Those
@@ -680,6 +754,16 @@ impl AuthorityDetails {
             .clone()
     }
 
+    /// Returns the peer ids of the primary and of all the workers of the authority.
+    pub async fn peer_ids(&self) -> Vec<PeerId> {
+        let internal = self.internal.read().await;
+
+        std::iter::once(internal.primary.network_key_pair.public())
+            .chain(internal.worker_keypairs.iter().map(|kp| kp.public()))
+            .map(|public_key| PeerId(public_key.0.to_bytes()))
+            .collect()
+    }
+
     /// Helper method to return transaction addresses of
     /// all the worker nodes.
     /// Important: only the addresses of the running workers will
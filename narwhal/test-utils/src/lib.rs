@@ -28,17 +28,20 @@ use store::{reopen, rocks, rocks::DBMap, Store};
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tracing::info;
 use types::{
-    Batch, BatchDigest, Certificate, CertificateDigest, CommittedSubDagShell, ConsensusStore,
-    FetchCertificatesRequest, FetchCertificatesResponse, GetCertificatesRequest,
+    Batch, BatchChunk, BatchDigest, Certificate, CertificateDigest, CommittedSubDagShell,
+    ConsensusStore, FetchCertificatesRequest, FetchCertificatesResponse, GetCertificatesRequest,
     GetCertificatesResponse, Header, HeaderBuilder, LatestHeaderRequest, LatestHeaderResponse,
     PayloadAvailabilityRequest, PayloadAvailabilityResponse, PrimaryMessage, PrimaryToPrimary,
-    PrimaryToPrimaryServer, PrimaryToWorker, PrimaryToWorkerServer, RequestBatchRequest,
-    RequestBatchResponse, Round, SequenceNumber, Transaction, Vote, WorkerBatchMessage,
+    PrimaryToPrimaryServer, PrimaryToWorker, PrimaryToWorkerServer, ReputationScores,
+    RequestBatchChunkRequest, RequestBatchChunkResponse, RequestBatchRequest, RequestBatchResponse,
+    Round, SequenceNumber, Transaction, Vote, WorkerBatchChunkMessage, WorkerBatchMessage,
     WorkerDeleteBatchesMessage, WorkerReconfigureMessage, WorkerSynchronizeMessage, WorkerToWorker,
     WorkerToWorkerServer,
 };
 
+pub mod benchmark;
 pub mod cluster;
+pub mod simulator;
 
 pub const VOTES_CF: &str = "votes";
 pub const HEADERS_CF: &str = "headers";
@@ -125,24 +128,32 @@ pub fn make_consensus_store(store_path: &std::path::Path) -> Arc<ConsensusStore>
     const LAST_COMMITTED_CF: &str = "last_committed";
     const SEQUENCE_CF: &str = "sequence";
     const SUB_DAG_CF: &str = "sub_dag";
+    const REPUTATION_SCORES_CF: &str = "reputation_scores";
 
     let rocksdb = rocks::open_cf(
         store_path,
         None,
-        &[LAST_COMMITTED_CF, SEQUENCE_CF, SUB_DAG_CF],
+        &[
+            LAST_COMMITTED_CF,
+            SEQUENCE_CF,
+            SUB_DAG_CF,
+            REPUTATION_SCORES_CF,
+        ],
     )
     .expect("Failed creating database");
 
-    let (last_committed_map, sequence_map, sub_dag_map) = reopen!(&rocksdb,
+    let (last_committed_map, sequence_map, sub_dag_map, reputation_scores_map) = reopen!(&rocksdb,
         LAST_COMMITTED_CF;<PublicKey, Round>,
         SEQUENCE_CF;<SequenceNumber, CertificateDigest>,
-        SUB_DAG_CF;<Round, CommittedSubDagShell>
+        SUB_DAG_CF;<Round, CommittedSubDagShell>,
+        REPUTATION_SCORES_CF;<Round, ReputationScores>
     );
 
     Arc::new(ConsensusStore::new(
         last_committed_map,
         sequence_map,
         sub_dag_map,
+        reputation_scores_map,
     ))
 }
 
@@ -345,6 +356,20 @@ impl WorkerToWorker for WorkerToWorkerMockServer {
         tracing::error!("Not implemented WorkerToWorkerMockServer::request_batch");
         Err(anemo::rpc::Status::internal("Unimplemented"))
     }
+    async fn report_batch_chunk(
+        &self,
+        _request: anemo::Request<WorkerBatchChunkMessage>,
+    ) -> Result<anemo::Response<()>, anemo::rpc::Status> {
+        tracing::error!("Not implemented WorkerToWorkerMockServer::report_batch_chunk");
+        Err(anemo::rpc::Status::internal("Unimplemented"))
+    }
+    async fn request_batch_chunk(
+        &self,
+        _request: anemo::Request<RequestBatchChunkRequest>,
+    ) -> Result<anemo::Response<RequestBatchChunkResponse>, anemo::rpc::Status> {
+        tracing::error!("Not implemented WorkerToWorkerMockServer::request_batch_chunk");
+        Err(anemo::rpc::Status::internal("Unimplemented"))
+    }
 }
 
 ////////////////////////////////////////////////////////////////
@@ -385,6 +410,14 @@ pub fn open_batch_store() -> Store<BatchDigest, Batch> {
     Store::new(db)
 }
 
+const BATCH_CHUNKS_CF: &str = "batch_chunks";
+
+pub fn open_batch_chunk_store() -> Store<BatchDigest, BatchChunk> {
+    let db =
+        DBMap::<BatchDigest, BatchChunk>::open(temp_dir(), None, Some(BATCH_CHUNKS_CF)).unwrap();
+    Store::new(db)
+}
+
 // Creates one certificate per authority starting and finishing at the specified rounds (inclusive).
 // Outputs a VecDeque of certificates (the certificate with higher round is on the front) and a set
 // of digests to be used as parents for the certificates of the next round.
@@ -0,0 +1,324 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A cluster of narwhal authorities for network simulation tests. The tests inject delays, drops
+//! and reordering in the messages between specific authorities, crash and restart authorities,
+//! and check that the authorities still commit the same sequence. Faults and transactions are
+//! sampled from the seed of the cluster, and the network faults are only injected when running
+//! in the simulator (`cargo simtest`), which also makes the scheduling and the network
+//! deterministic: a failing seed fails the same way every time.
+
+use crate::cluster::Cluster;
+use anemo::{async_trait, PeerId};
+use bytes::Bytes;
+use config::Parameters;
+use executor::{ExecutionIndices, ExecutionState, SerializedTransaction};
+use fastcrypto::hash::Hash;
+use network::fault_injection::{LinkFaults, NetworkFaults};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::mpsc::Sender;
+use tracing::info;
+use types::{
+    CertificateDigest, ConsensusOutput, Round, SequenceNumber, Transaction, TransactionProto,
+};
+
+#[cfg(test)]
+#[path = "tests/simulator_tests.rs"]
+pub mod simulator_tests;
+
+/// A transaction an authority executed, and where consensus sequenced it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommittedTransaction {
+    pub execution_indices: ExecutionIndices,
+    pub certificate: CertificateDigest,
+    pub round: Round,
+    pub consensus_index: SequenceNumber,
+    pub transaction: Transaction,
+}
+
+/// The transactions an authority executed, in order. The log outlives the restarts of the
+/// authority, as the execution state of a real node would.
+#[derive(Default)]
+pub struct CommitLog {
+    transactions: Mutex<Vec<CommittedTransaction>>,
+}
+
+impl CommitLog {
+    pub fn transactions(&self) -> Vec<CommittedTransaction> {
+        self.transactions.lock().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.transactions.lock().unwrap().clear();
+    }
+}
+
+/// Records the transactions consensus outputs in a `CommitLog`, skipping those it executed
+/// before a restart, and forwards them to the transaction confirmation channel.
+pub struct RecordingExecutionState {
+    log: Arc<CommitLog>,
+    tx_transaction_confirmation: Sender<SerializedTransaction>,
+}
+
+impl RecordingExecutionState {
+    pub fn new(
+        log: Arc<CommitLog>,
+        tx_transaction_confirmation: Sender<SerializedTransaction>,
+    ) -> Self {
+        Self {
+            log,
+            tx_transaction_confirmation,
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutionState for RecordingExecutionState {
+    async fn handle_consensus_transaction(
+        &self,
+        consensus_output: &Arc<ConsensusOutput>,
+        execution_indices: ExecutionIndices,
+        transaction: Vec<u8>,
+    ) {
+        {
+            let mut transactions = self.log.transactions.lock().unwrap();
+            if transactions
+                .last()
+                .map_or(false, |last| execution_indices <= last.execution_indices)
+            {
+                return;
+            }
+            transactions.push(CommittedTransaction {
+                execution_indices,
+                certificate: consensus_output.certificate.digest(),
+                round: consensus_output.certificate.round(),
+                consensus_index: consensus_output.consensus_index,
+                transaction: transaction.clone(),
+            });
+        }
+        let _ = self.tx_transaction_confirmation.send(transaction).await;
+    }
+
+    async fn load_execution_indices(&self) -> ExecutionIndices {
+        self.log
+            .transactions
+            .lock()
+            .unwrap()
+            .last()
+            .map(|last| last.execution_indices.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// A cluster of all the authorities of a committee, running with internal consensus, whose
+/// network faults are installed for the lifetime of the cluster.
+pub struct SimulatedCluster {
+    cluster: Cluster,
+    faults: Arc<NetworkFaults>,
+    rng: StdRng,
+    crashed: HashSet<usize>,
+    submitted: HashSet<Transaction>,
+}
+
+impl SimulatedCluster {
+    pub fn new(seed: u64, parameters: Option<Parameters>) -> Self {
+        info!("Simulating a cluster with seed {seed}");
+        let faults = Arc::new(NetworkFaults::new(seed));
+        faults.install();
+        Self {
+            cluster: Cluster::new(parameters, true),
+            faults,
+            rng: StdRng::seed_from_u64(seed),
+            crashed: HashSet::new(),
+            submitted: HashSet::new(),
+        }
+    }
+
+    pub fn cluster(&self) -> &Cluster {
+        &self.cluster
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.faults.seed()
+    }
+
+    /// The number of authorities of the cluster.
+    pub fn size(&self) -> usize {
+        self.cluster.committee_shared.load().authorities.len()
+    }
+
+    /// Start every authority, with `workers_per_authority` workers or all of their workers.
+    pub async fn start(&mut self, workers_per_authority: Option<usize>) {
+        self.cluster.start(None, workers_per_authority, None).await;
+    }
+
+    /// Inject faults in the messages the primary and workers of authority `from` send to those
+    /// of authority `to`.
+    pub async fn set_link_faults(&self, from: usize, to: usize, faults: LinkFaults) {
+        for (from, to) in self.links(from, to).await {
+            self.faults.set_link(from, to, faults.clone());
+        }
+    }
+
+    /// Stop injecting faults in the messages authority `from` sends to authority `to`.
+    pub async fn clear_link_faults(&self, from: usize, to: usize) {
+        for (from, to) in self.links(from, to).await {
+            self.faults.clear_link(from, to);
+        }
+    }
+
+    /// Drop every message between authorities of different groups.
+    pub async fn partition(&self, groups: &[&[usize]]) {
+        for (i, group) in groups.iter().enumerate() {
+            for other in groups.iter().skip(i + 1) {
+                for from in group.iter() {
+                    for to in other.iter() {
+                        self.set_link_faults(*from, *to, LinkFaults::partition())
+                            .await;
+                        self.set_link_faults(*to, *from, LinkFaults::partition())
+                            .await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stop injecting faults in any message.
+    pub fn heal(&self) {
+        self.faults.clear();
+    }
+
+    /// Stop the primary and workers of the authority.
+    pub async fn crash(&mut self, id: usize) {
+        info!("Crashing authority {id}");
+        self.cluster.stop_node(id).await;
+        self.crashed.insert(id);
+    }
+
+    /// Start the primary and workers of a crashed authority again, from their stores.
+    pub async fn restart(&mut self, id: usize, workers_per_authority: Option<usize>) {
+        info!("Restarting authority {id}");
+        self.cluster
+            .start_node(id, /* preserve_store */ true, workers_per_authority)
+            .await;
+        self.crashed.remove(&id);
+    }
+
+    /// Submit `count` new transactions to the first worker of authorities sampled among
+    /// `authorities`, or among all the authorities that are not crashed.
+    pub async fn submit_transactions(&mut self, count: usize, authorities: Option<&[usize]>) {
+        let candidates: Vec<usize> = match authorities {
+            Some(authorities) => authorities.to_vec(),
+            None => (0..self.size())
+                .filter(|id| !self.crashed.contains(id))
+                .collect(),
+        };
+        for _ in 0..count {
+            let id = *candidates.choose(&mut self.rng).unwrap();
+            let transaction = format!("transaction {}", self.submitted.len()).into_bytes();
+            let mut client = self.cluster.authority(id).new_transactions_client(&0).await;
+            client
+                .submit_transaction(TransactionProto {
+                    transaction: Bytes::from(transaction.clone()),
+                })
+                .await
+                .unwrap();
+            self.submitted.insert(transaction);
+        }
+    }
+
+    /// Wait until every authority that is not crashed executed at least `count` transactions,
+    /// or until `timeout`. Returns whether they did.
+    pub async fn wait_for_commits(&self, count: usize, timeout: Duration) -> bool {
+        let wait = async {
+            loop {
+                let mut done = true;
+                for id in (0..self.size()).filter(|id| !self.crashed.contains(id)) {
+                    done &= self.commit_log(id).await.len() >= count;
+                }
+                if done {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        };
+        tokio::time::timeout(timeout, wait).await.is_ok()
+    }
+
+    /// The transactions the authority executed, in order.
+    pub async fn commit_log(&self, id: usize) -> Arc<CommitLog> {
+        self.cluster.authority(id).primary().await.commit_log()
+    }
+
+    /// Check that every authority executed only transactions that were submitted, each at most
+    /// once and in the order of their execution indices, and that the authorities executed the
+    /// same sequence: the sequence of each authority is a prefix of the longest one.
+    pub async fn assert_safety(&self) {
+        let mut logs = Vec::new();
+        for id in 0..self.size() {
+            logs.push((id, self.commit_log(id).await.transactions()));
+        }
+
+        for (id, log) in &logs {
+            let mut executed = HashSet::new();
+            for committed in log {
+                assert!(
+                    self.submitted.contains(&committed.transaction),
+                    "Authority {id} executed a transaction that was never submitted (seed {})",
+                    self.seed()
+                );
+                assert!(
+                    executed.insert(&committed.transaction),
+                    "Authority {id} executed a transaction twice (seed {})",
+                    self.seed()
+                );
+            }
+            assert!(
+                log.windows(2)
+                    .all(|w| w[0].execution_indices < w[1].execution_indices),
+                "Authority {id} executed transactions out of order (seed {})",
+                self.seed()
+            );
+        }
+
+        let (longest_id, longest) = logs.iter().max_by_key(|(_, log)| log.len()).unwrap();
+        for (id, log) in &logs {
+            for (i, (committed, expected)) in log.iter().zip(longest.iter()).enumerate() {
+                assert_eq!(
+                    committed,
+                    expected,
+                    "Authorities {id} and {longest_id} diverge at transaction {i} (seed {})",
+                    self.seed()
+                );
+            }
+        }
+    }
+
+    // The links from the nodes of authority `from` to the nodes of authority `to`.
+    async fn links(&self, from: usize, to: usize) -> Vec<(PeerId, PeerId)> {
+        let from = self.cluster.authority(from).peer_ids().await;
+        let to = self.cluster.authority(to).peer_ids().await;
+        from.iter()
+            .flat_map(|from| to.iter().map(move |to| (*from, *to)))
+            .collect()
+    }
+}
+
+impl Drop for SimulatedCluster {
+    fn drop(&mut self) {
+        NetworkFaults::uninstall();
+    }
+}
@@ -0,0 +1,49 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use crate::ensure_test_environment;
+
+#[test]
+fn latency_percentiles() {
+    let mut latencies: Vec<_> = (1..=100).rev().map(Duration::from_millis).collect();
+    let stats = LatencyStats::new(&mut latencies);
+    assert_eq!(stats.samples, 100);
+    assert_eq!(stats.p50_ms, 50.0);
+    assert_eq!(stats.p99_ms, 99.0);
+    assert_eq!(stats.max_ms, 100.0);
+    assert_eq!(stats.mean_ms, 50.5);
+
+    assert_eq!(LatencyStats::new(&mut []), LatencyStats::default());
+}
+
+#[tokio::test]
+async fn benchmark_reports_both_protocols() {
+    ensure_test_environment();
+
+    for protocol in [ConsensusProtocol::Bullshark, ConsensusProtocol::Tusk] {
+        let report = run(BenchmarkConfig {
+            rate: 100,
+            transaction_size: 32,
+            duration: Duration::from_secs(6),
+            warmup: Duration::from_secs(2),
+            drain_timeout: Duration::from_secs(20),
+            consensus_protocol: protocol,
+            ..BenchmarkConfig::default()
+        })
+        .await;
+
+        assert_eq!(report.consensus_protocol, protocol);
+        assert!(report.submitted > 0);
+        assert!(report.executed > 0, "Nothing executed with {protocol:?}");
+        assert!(report.latency.p50_ms <= report.latency.p99_ms);
+        assert!(report.latency.p99_ms <= report.latency.max_ms);
+        assert_eq!(
+            report
+                .rounds
+                .iter()
+                .map(|round| round.transactions)
+                .sum::<usize>(),
+            report.executed
+        );
+    }
+}
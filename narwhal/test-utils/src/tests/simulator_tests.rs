@@ -0,0 +1,69 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use sui_macros::sim_test;
+
+const NUM_TRANSACTIONS: usize = 40;
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+#[sim_test]
+async fn commits_despite_delayed_and_dropped_messages() {
+    let mut cluster = SimulatedCluster::new(1, None);
+    cluster.start(Some(1)).await;
+
+    // messages between the first two authorities are delayed and reordered, and a fifth of
+    // the messages from the third authority to the fourth are dropped
+    let delay = LinkFaults::delay(Duration::from_millis(10), Duration::from_millis(800));
+    cluster.set_link_faults(0, 1, delay.clone()).await;
+    cluster.set_link_faults(1, 0, delay).await;
+    let lossy = LinkFaults {
+        drop_probability: 0.2,
+        ..Default::default()
+    };
+    cluster.set_link_faults(2, 3, lossy).await;
+
+    cluster.submit_transactions(NUM_TRANSACTIONS, None).await;
+    assert!(cluster.wait_for_commits(NUM_TRANSACTIONS, TIMEOUT).await);
+    cluster.assert_safety().await;
+}
+
+#[sim_test]
+async fn partitioned_authority_catches_up() {
+    let mut cluster = SimulatedCluster::new(2, None);
+    cluster.start(Some(1)).await;
+
+    // the three other authorities are a quorum, and keep committing
+    cluster.partition(&[&[0], &[1, 2, 3]]).await;
+    cluster
+        .submit_transactions(NUM_TRANSACTIONS, Some(&[1, 2, 3]))
+        .await;
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert!(cluster.commit_log(0).await.is_empty());
+    cluster.assert_safety().await;
+
+    cluster.heal();
+    assert!(cluster.wait_for_commits(NUM_TRANSACTIONS, TIMEOUT).await);
+    cluster.assert_safety().await;
+}
+
+#[sim_test]
+async fn crashed_authority_restarts_from_its_store() {
+    let mut cluster = SimulatedCluster::new(3, None);
+    cluster.start(Some(1)).await;
+
+    cluster.submit_transactions(NUM_TRANSACTIONS, None).await;
+    assert!(cluster.wait_for_commits(NUM_TRANSACTIONS, TIMEOUT).await);
+
+    cluster.crash(3).await;
+    cluster.submit_transactions(NUM_TRANSACTIONS, None).await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    cluster.restart(3, Some(1)).await;
+
+    // the restarted authority executes what it missed, and nothing twice
+    assert!(
+        cluster
+            .wait_for_commits(2 * NUM_TRANSACTIONS, TIMEOUT)
+            .await
+    );
+    cluster.assert_safety().await;
+}
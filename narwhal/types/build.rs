@@ -185,6 +185,24 @@ fn build_anemo_services(out_dir: &Path) {
                 .codec_path("anemo::rpc::codec::BincodeCodec")
                 .build(),
         )
+        .method(
+            anemo_build::manual::Method::builder()
+                .name("report_batch_chunk")
+                .route_name("ReportBatchChunk")
+                .request_type("crate::WorkerBatchChunkMessage")
+                .response_type("()")
+                .codec_path("anemo::rpc::codec::BincodeCodec")
+                .build(),
+        )
+        .method(
+            anemo_build::manual::Method::builder()
+                .name("request_batch_chunk")
+                .route_name("RequestBatchChunk")
+                .request_type("crate::RequestBatchChunkRequest")
+                .response_type("crate::RequestBatchChunkResponse")
+                .codec_path("anemo::rpc::codec::BincodeCodec")
+                .build(),
+        )
         .build();
 
     anemo_build::manual::Builder::new()
@@ -6,7 +6,7 @@ use crate::{Certificate, CertificateDigest, Round};
 use crypto::PublicKey;
 use fastcrypto::hash::Hash;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use store::{
     rocks::{DBMap, TypedStoreError},
     traits::Map,
@@ -82,6 +82,43 @@ impl CommittedSubDagShell {
     }
 }
 
+/// The reputation of the authorities, scored on the leaders committed since the leader schedule
+/// last changed. An authority scores a point for every committed leader that one of its
+/// certificates of the following round votes for, as seen in the committed sub-dags.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReputationScores {
+    /// The score of each authority of the committee
+    pub scores_per_authority: BTreeMap<PublicKey, u64>,
+    /// The leader committed last, and its round
+    pub last_leader: Option<(Round, CertificateDigest)>,
+    /// The number of leaders committed since the leader schedule last changed
+    pub num_commits: u64,
+    /// True when these are the scores the leader schedule changed with
+    pub final_of_schedule: bool,
+}
+
+impl ReputationScores {
+    /// Return scores of zero for the authorities in `authorities`, carrying over the last leader
+    /// committed.
+    pub fn new<'a>(
+        authorities: impl Iterator<Item = &'a PublicKey>,
+        last_leader: Option<(Round, CertificateDigest)>,
+    ) -> Self {
+        Self {
+            scores_per_authority: authorities.map(|name| (name.clone(), 0)).collect(),
+            last_leader,
+            num_commits: 0,
+            final_of_schedule: false,
+        }
+    }
+
+    pub fn add_score(&mut self, authority: &PublicKey, score: u64) {
+        if let Some(total) = self.scores_per_authority.get_mut(authority) {
+            *total += score;
+        }
+    }
+}
+
 /// Shutdown token dropped when a task is properly shut down.
 pub type ShutdownToken = mpsc::Sender<()>;
 
@@ -96,6 +133,9 @@ pub struct ConsensusStore {
     sequence: DBMap<SequenceNumber, CertificateDigest>,
     /// All committed sub-dags, indexed by the round number of the leader committing it.
     committed_sub_dags: DBMap<Round, CommittedSubDagShell>,
+    /// The reputation scores after each committed sub-dag since the leader schedule last changed,
+    /// indexed by the round number of the leader committing it.
+    reputation_scores: DBMap<Round, ReputationScores>,
 }
 
 impl ConsensusStore {
@@ -104,11 +144,13 @@ impl ConsensusStore {
         last_committed: DBMap<PublicKey, Round>,
         sequence: DBMap<SequenceNumber, CertificateDigest>,
         committed_sub_dags: DBMap<Round, CommittedSubDagShell>,
+        reputation_scores: DBMap<Round, ReputationScores>,
     ) -> Self {
         Self {
             last_committed,
             sequence,
             committed_sub_dags,
+            reputation_scores,
         }
     }
 
@@ -116,6 +158,7 @@ impl ConsensusStore {
     pub fn clear(&self) -> StoreResult<()> {
         self.last_committed.clear()?;
         self.sequence.clear()?;
+        self.reputation_scores.clear()?;
         Ok(())
     }
 
@@ -141,20 +184,53 @@ impl ConsensusStore {
         &self,
         last_committed: &HashMap<PublicKey, Round>,
         sub_dag: &CommittedSubDag,
+        reputation_scores: &ReputationScores,
     ) -> Result<(), TypedStoreError> {
         // Compress the sub-dag to not write the entire certificates.
         let shell = CommittedSubDagShell::from_sub_dag(sub_dag);
 
-        // Atomically persist the sub-dag and the last committed certificates.
+        // Atomically persist the sub-dag, the reputation scores it leads to and the last
+        // committed certificates.
         let mut write_batch = self.last_committed.batch();
         write_batch = write_batch.insert_batch(&self.last_committed, last_committed.iter())?;
         write_batch = write_batch.insert_batch(
             &self.committed_sub_dags,
             std::iter::once((sub_dag.leader.round(), shell)),
         )?;
+        write_batch = write_batch.insert_batch(
+            &self.reputation_scores,
+            std::iter::once((sub_dag.leader.round(), reputation_scores)),
+        )?;
+        // The scores the leader schedule changes with replace all the scores before them, so
+        // that the store keeps at most one scoring window.
+        if reputation_scores.final_of_schedule {
+            write_batch = write_batch.delete_range(
+                &self.reputation_scores,
+                &Round::MIN,
+                &sub_dag.leader.round(),
+            )?;
+        }
         write_batch.write()
     }
 
+    /// Load the reputation scores after the last committed sub-dag, and the scores the leader
+    /// schedule last changed with, if it ever did.
+    pub fn read_reputation_scores(
+        &self,
+    ) -> StoreResult<(Option<ReputationScores>, Option<ReputationScores>)> {
+        let mut scores = self.reputation_scores.iter().skip_to_last().reverse();
+        let Some((_, latest)) = scores.next() else {
+            return Ok((None, None));
+        };
+        if latest.final_of_schedule {
+            return Ok((Some(latest.clone()), Some(latest)));
+        }
+        let last_final = scores
+            .map(|(_, scores)| scores)
+            .find(|scores| scores.final_of_schedule);
+        Ok((Some(latest), last_final))
+    }
+
     /// Load the last committed round of each validator.
     pub fn read_last_committed(&self) -> HashMap<PublicKey, Round> {
         self.last_committed.iter().collect()
@@ -181,6 +257,14 @@ impl ConsensusStore {
         Ok(self.sequence.iter().skip_to(from)?.collect())
     }
 
+    /// Load the certificate digest sequenced at the specified sequence number, if any.
+    pub fn read_sequenced_certificate(
+        &self,
+        index: &SequenceNumber,
+    ) -> StoreResult<Option<CertificateDigest>> {
+        self.sequence.get(index)
+    }
+
     /// Load the last (ie. the highest) consensus index associated to a certificate.
     pub fn read_last_consensus_index(&self) -> StoreResult<SequenceNumber> {
         Ok(self
@@ -204,3 +288,97 @@ impl ConsensusStore {
             .collect())
     }
 }
+
+/// A certificate of an exported DAG.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DagVertex {
+    /// The digest of the certificate, in base64.
+    pub digest: String,
+    pub round: Round,
+    /// The public key of the author of the certificate, in base64.
+    pub author: String,
+    /// The digests of the parents of the certificate.
+    pub parents: Vec<String>,
+    /// The parents above the GC round that are not in the certificate store.
+    pub missing_parents: Vec<String>,
+    /// Whether the certificate is the one of the leader of its round.
+    pub leader: bool,
+    /// Whether the certificate is a leader that committed a sub-dag.
+    pub committed_anchor: bool,
+    /// Whether the certificate is committed.
+    pub committed: bool,
+}
+
+/// The recent DAG of a primary, for inspection.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DagExport {
+    pub last_committed_round: Round,
+    /// Certificates at or below this round are garbage collected by consensus.
+    pub gc_round: Round,
+    /// The certificates, ordered by round.
+    pub vertices: Vec<DagVertex>,
+}
+
+impl DagExport {
+    /// Render the DAG in the Graphviz DOT language, one rank per round. Leaders are drawn as
+    /// boxes, committed anchors filled gold, other committed certificates filled grey, garbage
+    /// collected certificates dotted and missing parents as red dashed nodes.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from(
+            "digraph dag {\n    rankdir=BT;\n    node [style=filled, fillcolor=white];\n",
+        );
+        dot.push_str(&format!(
+            "    label=\"last committed round {}, gc round {}\";\n",
+            self.last_committed_round, self.gc_round
+        ));
+
+        let mut rounds: BTreeMap<Round, Vec<&DagVertex>> = BTreeMap::new();
+        for vertex in &self.vertices {
+            rounds.entry(vertex.round).or_default().push(vertex);
+        }
+        for (round, vertices) in &rounds {
+            dot.push_str(&format!(
+                "    subgraph round_{round} {{\n        rank=same;\n"
+            ));
+            for vertex in vertices {
+                let fill = if vertex.committed_anchor {
+                    "gold"
+                } else if vertex.committed {
+                    "lightgrey"
+                } else {
+                    "white"
+                };
+                let shape = if vertex.leader { "box" } else { "ellipse" };
+                let style = if vertex.round <= self.gc_round {
+                    "\"filled,dotted\""
+                } else {
+                    "filled"
+                };
+                dot.push_str(&format!(
+                    "        \"{}\" [label=\"{} {}\", shape={shape}, style={style}, fillcolor={fill}];\n",
+                    vertex.digest,
+                    round,
+                    vertex.author.get(0..8).unwrap_or(&vertex.author)
+                ));
+            }
+            dot.push_str("    }\n");
+        }
+
+        let exported: HashSet<&String> = self.vertices.iter().map(|v| &v.digest).collect();
+        for vertex in &self.vertices {
+            for parent in &vertex.missing_parents {
+                dot.push_str(&format!(
+                    "    \"{parent}\" [label=\"missing\", style=dashed, color=red];\n"
+                ));
+            }
+            // Only the edges to exported or missing parents, the others are below the export.
+            for parent in &vertex.parents {
+                if exported.contains(parent) || vertex.missing_parents.contains(parent) {
+                    dot.push_str(&format!("    \"{}\" -> \"{parent}\";\n", vertex.digest));
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
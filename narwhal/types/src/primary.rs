@@ -7,7 +7,9 @@ use crate::{
     CertificateDigestProto,
 };
 use bytes::Bytes;
-use config::{Committee, Epoch, SharedWorkerCache, Stake, WorkerId, WorkerInfo};
+use config::{
+    BatchCompression, Committee, Epoch, SharedWorkerCache, Stake, WorkerCache, WorkerId, WorkerInfo,
+};
 use crypto::{AggregateSignature, PublicKey, Signature};
 use dag::node_dag::Affiliated;
 use derive_builder::Builder;
@@ -21,12 +23,18 @@ use mysten_util_mem::MallocSizeOf;
 use once_cell::sync::OnceCell;
 use proptest_derive::Arbitrary;
 use roaring::RoaringBitmap;
-use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::{SerializeSeq, SerializeStruct},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_with::{de::DeserializeAsWrap, ser::SerializeAsWrap, serde_as};
 use std::time::{Duration, SystemTime};
 use std::{
+    borrow::Cow,
     collections::{BTreeMap, BTreeSet, VecDeque},
     fmt,
+    io::{self, Read},
 };
 use tracing::warn;
 
@@ -80,10 +88,34 @@ impl Default for Metadata {
 }
 
 pub type Transaction = Vec<u8>;
-#[derive(Clone, Serialize, Deserialize, Default, Debug, PartialEq, Eq, Arbitrary)]
+
+/// The largest size the transactions of a batch decompress to. Batches come from other workers,
+/// so their decompression is bounded rather than trusting the sizes they claim.
+pub const MAX_DECOMPRESSED_BATCH_SIZE: usize = 64 << 20;
+
+/// Written in place of the number of transactions of a batch to mark its transactions as
+/// compressed. No batch holds that many transactions, so batches that are not compressed keep the
+/// encoding of the versions that do not compress batches, and those versions reject compressed
+/// batches instead of misreading them. Later formats of compressed batches take the values below.
+pub const COMPRESSED_TRANSACTIONS_MARKER: u64 = u32::MAX as u64;
+
+/// A batch of transactions.
+///
+/// A batch that is not compressed is stored and sent as a sequence of transactions. A compressed
+/// batch is stored and sent with `COMPRESSED_TRANSACTIONS_MARKER` in place of the length of the
+/// sequence, followed by its compression and its transactions serialized as a whole and compressed.
+#[derive(Clone, Default, Arbitrary)]
 pub struct Batch {
-    pub transactions: Vec<Transaction>,
+    transactions: Vec<Transaction>,
     pub metadata: Metadata,
+    /// The compression of the transactions when the batch is stored or sent. It does not
+    /// change the digest of the batch.
+    #[proptest(
+        strategy = "proptest::sample::select(vec![BatchCompression::None, BatchCompression::Zstd, BatchCompression::Lz4])"
+    )]
+    compression: BatchCompression,
+    #[proptest(value = "OnceCell::new()")]
+    serialized_transactions: OnceCell<Vec<u8>>,
 }
 
 impl Batch {
@@ -91,6 +123,193 @@ impl Batch {
         Batch {
             transactions,
             metadata: Metadata::default(),
+            compression: BatchCompression::None,
+            serialized_transactions: OnceCell::new(),
+        }
+    }
+
+    /// Returns the batch with its transactions compressed with `compression` when it is stored
+    /// or sent.
+    pub fn with_compression(mut self, compression: BatchCompression) -> Self {
+        if compression != self.compression {
+            self.compression = compression;
+            self.serialized_transactions = OnceCell::new();
+        }
+        self
+    }
+
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    pub fn into_transactions(self) -> Vec<Transaction> {
+        self.transactions
+    }
+
+    pub fn compression(&self) -> BatchCompression {
+        self.compression
+    }
+
+    /// Returns the transactions serialized and compressed as they are when the batch is
+    /// serialized. They are computed once: the transactions and the compression of a batch can
+    /// only be set when it is created.
+    pub fn serialized_transactions(&self) -> &[u8] {
+        self.serialized_transactions.get_or_init(|| {
+            let bytes =
+                bincode::serialize(&self.transactions).expect("Failed to serialize transactions");
+            compress(self.compression, bytes)
+        })
+    }
+}
+
+impl fmt::Debug for Batch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Batch")
+            .field("transactions", &self.transactions)
+            .field("metadata", &self.metadata)
+            .field("compression", &self.compression)
+            .finish()
+    }
+}
+
+impl PartialEq for Batch {
+    fn eq(&self, other: &Self) -> bool {
+        self.transactions == other.transactions
+            && self.metadata == other.metadata
+            && self.compression == other.compression
+    }
+}
+
+impl Eq for Batch {}
+
+impl Serialize for Batch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Batch", 2)?;
+        state.serialize_field("transactions", &SerializedTransactions(self))?;
+        state.serialize_field("metadata", &self.metadata)?;
+        state.end()
+    }
+}
+
+/// The transactions of a batch as they are stored and sent.
+struct SerializedTransactions<'a>(&'a Batch);
+
+impl Serialize for SerializedTransactions<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let batch = self.0;
+        if batch.compression == BatchCompression::None {
+            return batch.transactions.serialize(serializer);
+        }
+        let mut seq = serializer.serialize_seq(Some(COMPRESSED_TRANSACTIONS_MARKER as usize))?;
+        seq.serialize_element(&batch.compression)?;
+        seq.serialize_element(&SerializeAsWrap::<_, serde_with::Bytes>::new(
+            &batch.serialized_transactions(),
+        ))?;
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Batch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Batch")]
+        struct SerializedBatch {
+            #[serde(deserialize_with = "deserialize_transactions")]
+            transactions: (Vec<Transaction>, BatchCompression, OnceCell<Vec<u8>>),
+            metadata: Metadata,
+        }
+
+        let batch = SerializedBatch::deserialize(deserializer)?;
+        let (transactions, compression, serialized_transactions) = batch.transactions;
+        Ok(Batch {
+            transactions,
+            metadata: batch.metadata,
+            compression,
+            serialized_transactions,
+        })
+    }
+}
+
+/// Deserializes the transactions of a batch along with their compression and, if they are
+/// compressed, the compressed transactions, to store or send the batch again as received.
+fn deserialize_transactions<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<(Vec<Transaction>, BatchCompression, OnceCell<Vec<u8>>), D::Error> {
+    struct TransactionsVisitor;
+
+    impl<'de> Visitor<'de> for TransactionsVisitor {
+        type Value = (Vec<Transaction>, BatchCompression, OnceCell<Vec<u8>>);
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("the transactions of a batch")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            if seq.size_hint() != Some(COMPRESSED_TRANSACTIONS_MARKER as usize) {
+                let mut transactions = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(transaction) = seq.next_element()? {
+                    transactions.push(transaction);
+                }
+                return Ok((transactions, BatchCompression::None, OnceCell::new()));
+            }
+
+            let compression: BatchCompression = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            if compression == BatchCompression::None {
+                return Err(de::Error::custom("Compressed batch without compression"));
+            }
+            let compressed = seq
+                .next_element::<DeserializeAsWrap<Vec<u8>, serde_with::Bytes>>()?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?
+                .into_inner();
+            let transactions = decompress(compression, &compressed).map_err(de::Error::custom)?;
+            let transactions = bincode::deserialize(&transactions).map_err(de::Error::custom)?;
+            Ok((transactions, compression, OnceCell::with_value(compressed)))
+        }
+    }
+
+    deserializer.deserialize_seq(TransactionsVisitor)
+}
+
+fn compress(compression: BatchCompression, bytes: Vec<u8>) -> Vec<u8> {
+    match compression {
+        BatchCompression::None => bytes,
+        BatchCompression::Zstd => zstd::bulk::compress(&bytes, zstd::DEFAULT_COMPRESSION_LEVEL)
+            .expect("Failed to compress"),
+        BatchCompression::Lz4 => lz4_flex::compress_prepend_size(&bytes),
+    }
+}
+
+/// Decompress the serialized transactions of a batch, up to `MAX_DECOMPRESSED_BATCH_SIZE`.
+pub(crate) fn decompress(
+    compression: BatchCompression,
+    bytes: &[u8],
+) -> Result<Cow<'_, [u8]>, io::Error> {
+    let too_large = || io::Error::new(io::ErrorKind::InvalidData, "Batch too large");
+    match compression {
+        BatchCompression::None => Ok(Cow::Borrowed(bytes)),
+        BatchCompression::Zstd => {
+            let mut decompressed = Vec::new();
+            zstd::Decoder::new(bytes)?
+                .take(MAX_DECOMPRESSED_BATCH_SIZE as u64 + 1)
+                .read_to_end(&mut decompressed)?;
+            if decompressed.len() > MAX_DECOMPRESSED_BATCH_SIZE {
+                return Err(too_large());
+            }
+            Ok(Cow::Owned(decompressed))
+        }
+        BatchCompression::Lz4 => {
+            let size = bytes
+                .get(..4)
+                .map(|size| u32::from_le_bytes(size.try_into().unwrap()) as usize)
+                .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+            if size > MAX_DECOMPRESSED_BATCH_SIZE {
+                return Err(too_large());
+            }
+            lz4_flex::decompress_size_prepended(bytes)
+                .map(Cow::Owned)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
         }
     }
 }
@@ -896,8 +1115,21 @@ pub enum ReconfigureNotification {
     UpdateCommittee(Committee),
     /// Indicate a shutdown.
     Shutdown,
+    /// Move to the committee and workers of the next epoch in place, without restarting the
+    /// node. Consensus stops committing once it commits a leader at or above `round`, and the
+    /// new committee is adopted right after, with a `NewEpoch` notification. The round must be
+    /// agreed by the whole committee and above the rounds already committed.
+    ScheduleEpoch {
+        round: Round,
+        committee: Committee,
+        worker_cache: WorkerCache,
+    },
 }
 
+/// A reconfiguration request to the primary, along with the channel it answers on whether the
+/// request was accepted.
+pub type ReconfigureRequest = (ReconfigureNotification, tokio::sync::oneshot::Sender<bool>);
+
 /// Used by the primary to reconfigure the worker.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WorkerReconfigureMessage {
@@ -1013,6 +1245,7 @@ mod tests {
             metadata: Metadata {
                 created_at: 2999309726980, // something in the future - Fri Jan 16 2065 05:35:26
             },
+            ..Batch::default()
         };
 
         assert_eq!(batch.metadata.created_at.elapsed().as_secs_f64(), 0.0);
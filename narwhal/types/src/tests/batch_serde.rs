@@ -1,23 +1,34 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{serialized_batch_digest, Batch, Metadata, WorkerBatchMessage};
+use crate::{
+    serialized_batch_digest, Batch, Metadata, Transaction, WorkerBatchMessage,
+    COMPRESSED_TRANSACTIONS_MARKER, MAX_DECOMPRESSED_BATCH_SIZE,
+};
+use config::BatchCompression;
 use fastcrypto::{
     encoding::{Encoding, Hex},
     hash::Hash,
 };
 use proptest::arbitrary::Arbitrary;
+use serde::Deserialize;
 use serde_test::{assert_tokens, Token};
 
+/// A batch as versions without compression read it.
+#[allow(dead_code)]
+#[derive(Deserialize)]
+struct LegacyBatch {
+    transactions: Vec<Transaction>,
+    metadata: Metadata,
+}
+
 #[test]
 fn test_serde_batch() {
     let tx = || vec![1; 5];
 
-    let batch = Batch {
-        transactions: (0..2).map(|_| tx()).collect(),
-        metadata: Metadata {
-            created_at: 1666205365890,
-        },
+    let mut batch = Batch::new((0..2).map(|_| tx()).collect());
+    batch.metadata = Metadata {
+        created_at: 1666205365890,
     };
 
     assert_tokens(
@@ -61,11 +72,9 @@ fn test_serde_batch() {
 fn test_bincode_serde_batch() {
     let tx = || vec![1; 5];
 
-    let txes = Batch {
-        transactions: (0..2).map(|_| tx()).collect(),
-        metadata: Metadata {
-            created_at: 1666205365890,
-        },
+    let mut txes = Batch::new((0..2).map(|_| tx()).collect());
+    txes.metadata = Metadata {
+        created_at: 1666205365890,
     };
 
     let txes_bytes = bincode::serialize(&txes).unwrap();
@@ -86,20 +95,21 @@ fn test_bincode_serde_batch() {
         "received {}",
         Hex::encode(txes_bytes)
     );
+    assert_eq!(
+        bincode::deserialize::<Batch>(&expected_bytes).unwrap(),
+        txes
+    );
 }
 
 #[test]
 fn test_bincode_serde_batch_message() {
     let tx = || vec![1; 5];
 
-    let txes = WorkerBatchMessage {
-        batch: Batch {
-            transactions: (0..2).map(|_| tx()).collect(),
-            metadata: Metadata {
-                created_at: 1666205365890,
-            },
-        },
+    let mut batch = Batch::new((0..2).map(|_| tx()).collect());
+    batch.metadata = Metadata {
+        created_at: 1666205365890,
     };
+    let txes = WorkerBatchMessage { batch };
 
     let txes_bytes = bincode::serialize(&txes).unwrap();
 
@@ -118,6 +128,72 @@ fn test_bincode_serde_batch_message() {
     );
 }
 
+#[test]
+fn test_compressed_batch_roundtrip() {
+    // compressible transactions, like the JSON-like payloads clients send
+    let tx = |i: u8| format!("{{\"sender\": \"0x{i:040}\", \"amount\": 100}}").into_bytes();
+    let batch = Batch::new((0..100).map(tx).collect());
+    let uncompressed_size = bincode::serialized_size(&batch).unwrap();
+
+    for compression in [BatchCompression::Zstd, BatchCompression::Lz4] {
+        // the batch is already serialized uncompressed, which must not be reused
+        let compressed = batch.clone().with_compression(compression);
+        let bytes = bincode::serialize(&compressed).unwrap();
+        assert!((bytes.len() as u64) < uncompressed_size / 2);
+
+        // the marker in place of the number of transactions, which versions without compression
+        // reject
+        assert_eq!(bytes[..8], COMPRESSED_TRANSACTIONS_MARKER.to_le_bytes());
+        assert!(bincode::deserialize::<LegacyBatch>(&bytes).is_err());
+
+        let deserialized: Batch = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(deserialized, compressed);
+        assert_eq!(deserialized.digest(), batch.digest());
+        // a received batch is sent again as it was received
+        assert_eq!(bincode::serialize(&deserialized).unwrap(), bytes);
+    }
+}
+
+#[test]
+fn test_compressed_batch_size_is_bounded() {
+    let batch = Batch::new(vec![vec![0; MAX_DECOMPRESSED_BATCH_SIZE + 1]]);
+    for compression in [BatchCompression::Zstd, BatchCompression::Lz4] {
+        let compressed = batch.clone().with_compression(compression);
+        let bytes = bincode::serialize(&compressed).unwrap();
+        assert!(bincode::deserialize::<Batch>(&bytes).is_err());
+    }
+}
+
+#[test]
+fn test_serialized_batch_digest_of_truncated_message() {
+    let batch = Batch::new(vec![vec![1u8; 5], vec![2u8; 5]]);
+    for compression in [
+        BatchCompression::None,
+        BatchCompression::Zstd,
+        BatchCompression::Lz4,
+    ] {
+        let batch = batch.clone().with_compression(compression);
+        let end = if compression == BatchCompression::None {
+            batch.serialized_transactions().len()
+        } else {
+            // the marker, the compression, the length of the compressed transactions, and the
+            // transactions
+            20 + batch.serialized_transactions().len()
+        };
+        let serialized = bincode::serialize(&WorkerBatchMessage { batch }).unwrap();
+        for length in 0..end {
+            assert!(serialized_batch_digest(&serialized[..length]).is_err());
+        }
+        assert!(serialized_batch_digest(&serialized[..end]).is_ok());
+    }
+
+    // a length of the compressed transactions past the end of the message
+    let batch = batch.with_compression(BatchCompression::Zstd);
+    let mut serialized = bincode::serialize(&WorkerBatchMessage { batch }).unwrap();
+    serialized[12..20].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(serialized_batch_digest(&serialized).is_err());
+}
+
 proptest::proptest! {
 
     #[test]
@@ -1,8 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{Batch, BatchDigest};
+use crate::{primary::decompress, Batch, BatchDigest, COMPRESSED_TRANSACTIONS_MARKER};
 
+use config::BatchCompression;
 use fastcrypto::hash::HashFunction;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -28,6 +29,44 @@ pub struct RequestBatchResponse {
     pub batch: Option<Batch>,
 }
 
+/// A chunk of an erasure coded batch: the batch is serialized, split into `data_shards` data
+/// chunks, and extended with `parity_shards` parity chunks, of which any `data_shards` rebuild it.
+/// The chunks of a batch are the leaves of a Merkle tree, so that each chunk is authenticated
+/// against the root of the tree before the batch is rebuilt.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BatchChunk {
+    /// The index of the chunk, which is the index of the authority it is sent to in the
+    /// committee.
+    pub index: u32,
+    pub data_shards: u32,
+    pub parity_shards: u32,
+    /// The length of the serialized batch, the last data chunk being padded.
+    pub batch_length: u64,
+    /// The root of the Merkle tree of the chunks of the batch.
+    pub root: [u8; crypto::DIGEST_LENGTH],
+    /// The hashes of the siblings of the chunk on its path to the root, from the leaves up.
+    pub proof: Vec<[u8; crypto::DIGEST_LENGTH]>,
+    pub data: Vec<u8>,
+}
+
+/// Used by workers to send the chunk of a new batch to the worker of an authority.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkerBatchChunkMessage {
+    pub digest: BatchDigest,
+    pub chunk: BatchChunk,
+}
+
+/// Used by workers to ask another worker for its chunk of a batch.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RequestBatchChunkRequest {
+    pub batch: BatchDigest,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RequestBatchChunkResponse {
+    pub chunk: Option<BatchChunk>,
+}
+
 pub type TxResponse = tokio::sync::oneshot::Sender<BatchDigest>;
 pub type PrimaryResponse = Option<tokio::sync::oneshot::Sender<()>>;
 
@@ -43,16 +82,27 @@ pub type PrimaryResponse = Option<tokio::sync::oneshot::Sender<()>>;
 /// TODO: update batch hashing to reflect hashing fixed sequences of transactions, see #87.
 pub fn serialized_batch_digest<K: AsRef<[u8]>>(sbm: K) -> Result<BatchDigest, DigestError> {
     let sbm = sbm.as_ref();
-    let mut offset = 0;
-    let num_transactions = u64::from_le_bytes(
-        sbm[offset..offset + 8]
-            .try_into()
-            .map_err(|_| DigestError::InvalidArgumentError(offset))?,
-    );
-    offset += 8;
+    // A compressed batch starts with a marker in place of the number of its transactions, then
+    // the compression and the length of the compressed transactions.
+    let decompressed;
+    let sbm = if read_u64(sbm, 0)? == COMPRESSED_TRANSACTIONS_MARKER {
+        let compression: BatchCompression = sbm
+            .get(8..12)
+            .and_then(|bytes| bincode::deserialize(bytes).ok())
+            .ok_or(DigestError::InvalidArgumentError(8))?;
+        let (compressed, _) = read_bytes(sbm, 12)?;
+        decompressed = decompress(compression, compressed)
+            .map_err(|e| DigestError::DecompressionError(e.to_string()))?;
+        decompressed.as_ref()
+    } else {
+        sbm
+    };
+
+    let num_transactions = read_u64(sbm, 0)?;
+    let mut offset = 8;
     let mut transactions = Vec::new();
     for _i in 0..num_transactions {
-        let (tx_ref, new_offset) = read_one_transaction(sbm, offset)?;
+        let (tx_ref, new_offset) = read_bytes(sbm, offset)?;
         transactions.push(tx_ref);
         offset = new_offset;
     }
@@ -66,15 +116,30 @@ pub fn serialized_batch_digest<K: AsRef<[u8]>>(sbm: K) -> Result<BatchDigest, Di
 pub enum DigestError {
     #[error("Invalid argument: invalid byte at {0}")]
     InvalidArgumentError(usize),
+    #[error("Failed to decompress the transactions: {0}")]
+    DecompressionError(String),
+}
+
+/// Reads the little-endian `u64` at `offset` of `sbm`.
+fn read_u64(sbm: &[u8], offset: usize) -> Result<u64, DigestError> {
+    let bytes = offset
+        .checked_add(8)
+        .and_then(|end| sbm.get(offset..end))
+        .ok_or(DigestError::InvalidArgumentError(offset))?;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
 }
 
-fn read_one_transaction(sbm: &[u8], offset: usize) -> Result<(&[u8], usize), DigestError> {
-    let length = u64::from_le_bytes(
-        sbm[offset..offset + 8]
-            .try_into()
-            .map_err(|_| DigestError::InvalidArgumentError(offset))?,
-    );
-    let length = usize::try_from(length).map_err(|_| DigestError::InvalidArgumentError(offset))?;
-    let end = offset + 8 + length;
-    Ok((&sbm[offset + 8..end], end))
+/// Reads the bytes at `offset` of `sbm`, prefixed with their length as a `u64`, and returns
+/// them along with the offset right after them.
+fn read_bytes(sbm: &[u8], offset: usize) -> Result<(&[u8], usize), DigestError> {
+    let length = usize::try_from(read_u64(sbm, offset)?)
+        .map_err(|_| DigestError::InvalidArgumentError(offset))?;
+    let start = offset + 8;
+    let end = start
+        .checked_add(length)
+        .ok_or(DigestError::InvalidArgumentError(offset))?;
+    let bytes = sbm
+        .get(start..end)
+        .ok_or(DigestError::InvalidArgumentError(offset))?;
+    Ok((bytes, end))
 }
@@ -1,10 +1,10 @@
 // Copyright (c) 2021, Facebook, Inc. and its affiliates
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::metrics::WorkerMetrics;
+use crate::{batch_policy::PendingTransactions, metrics::WorkerMetrics};
 #[cfg(feature = "trace_transaction")]
 use byteorder::{BigEndian, ReadBytesExt};
-use config::Committee;
+use config::{BatchCompression, Committee};
 use fastcrypto::hash::Hash;
 use futures::stream::FuturesOrdered;
 use store::Store;
@@ -48,10 +48,14 @@ pub struct BatchMaker {
     batch_size: usize,
     /// The maximum delay after which to seal the batch.
     max_batch_delay: Duration,
+    /// The compression of the sealed batches.
+    batch_compression: BatchCompression,
     /// Receive reconfiguration updates.
     rx_reconfigure: watch::Receiver<ReconfigureNotification>,
     /// Channel to receive transactions from the network.
     rx_batch_maker: Receiver<(Transaction, TxResponse)>,
+    /// The received transactions waiting to be sealed.
+    pending: PendingTransactions,
     /// Output channel to deliver sealed batches to the `QuorumWaiter`.
     tx_message: Sender<(Batch, Option<tokio::sync::oneshot::Sender<()>>)>,
     /// Metrics handler
@@ -72,8 +76,10 @@ impl BatchMaker {
         committee: Committee,
         batch_size: usize,
         max_batch_delay: Duration,
+        batch_compression: BatchCompression,
         rx_reconfigure: watch::Receiver<ReconfigureNotification>,
         rx_batch_maker: Receiver<(Transaction, TxResponse)>,
+        pending: PendingTransactions,
         tx_message: Sender<(Batch, Option<tokio::sync::oneshot::Sender<()>>)>,
         node_metrics: Arc<WorkerMetrics>,
         store: Store<BatchDigest, Batch>,
@@ -85,8 +91,10 @@ impl BatchMaker {
                 committee,
                 batch_size,
                 max_batch_delay,
+                batch_compression,
                 rx_reconfigure,
                 rx_batch_maker,
+                pending,
                 tx_message,
                 batch_start_timestamp: Instant::now(),
                 node_metrics,
@@ -103,55 +111,32 @@ impl BatchMaker {
         let timer = sleep(self.max_batch_delay);
         tokio::pin!(timer);
 
-        let mut current_batch = Batch::default();
-        let mut current_responses = Vec::new();
-        let mut current_batch_size = 0;
-
         let mut batch_pipeline = FuturesOrdered::new();
 
         loop {
             tokio::select! {
-                // Assemble client transactions into batches of preset size.
-                // Note that transactions are only consumed when the number of batches
-                // 'in-flight' are below a certain number (MAX_PARALLEL_BATCH). This
-                // condition will be met eventually if the store and network are functioning.
-                Some((transaction, response_sender)) = self.rx_batch_maker.recv(), if batch_pipeline.len() < MAX_PARALLEL_BATCH => {
-
-                    if current_batch.transactions.is_empty() {
+                // Queue client transactions, in the order of the batch policy. Transactions are
+                // consumed even when the number of batches 'in-flight' reaches MAX_PARALLEL_BATCH,
+                // so that the pending transactions of highest priority are sealed first once the
+                // pipeline makes progress.
+                Some((transaction, response_sender)) = self.rx_batch_maker.recv() => {
+                    if self.pending.is_empty() {
                         // We are interested to measure the time to seal a batch
                         // only when we do have transactions to include. Thus we reset
                         // the timer on the first transaction we receive to include on
                         // an empty batch.
                         self.batch_start_timestamp = Instant::now();
                     }
-
-                    current_batch_size += transaction.len();
-                    current_batch.transactions.push(transaction);
-                    current_responses.push(response_sender);
-                    if current_batch_size >= self.batch_size {
-                        if let Some(seal) = self.seal(false, current_batch, current_batch_size, current_responses).await{
-                            batch_pipeline.push_back(seal);
-                        }
-                        self.node_metrics.parallel_worker_batches.set(batch_pipeline.len() as i64);
-
-                        timer.as_mut().reset(Instant::now() + self.max_batch_delay);
-                        current_batch = Batch::default();
-                        current_responses = Vec::new();
-                        current_batch_size = 0;
-                    }
+                    self.pending.push(transaction, response_sender);
                 },
 
                 // If the timer triggers, seal the batch even if it contains few transactions.
                 () = &mut timer => {
-                    if !current_batch.transactions.is_empty() {
-                        if let Some(seal) = self.seal(true, current_batch, current_batch_size, current_responses).await {
+                    if !self.pending.is_empty() && batch_pipeline.len() < MAX_PARALLEL_BATCH {
+                        if let Some(seal) = self.seal(true).await {
                             batch_pipeline.push_back(seal);
                         }
                         self.node_metrics.parallel_worker_batches.set(batch_pipeline.len() as i64);
-
-                        current_batch = Batch::default();
-                        current_responses = Vec::new();
-                        current_batch_size = 0;
                     }
                     timer.as_mut().reset(Instant::now() + self.max_batch_delay);
                 }
@@ -169,7 +154,8 @@ impl BatchMaker {
                             self.committee = new_committee;
 
                         },
-                        ReconfigureNotification::Shutdown => return
+                        ReconfigureNotification::Shutdown => return,
+                        ReconfigureNotification::ScheduleEpoch { .. } => continue,
                     }
                     tracing::debug!("Committee updated to {}", self.committee);
                 },
@@ -183,26 +169,39 @@ impl BatchMaker {
 
             }
 
+            // Seal the batches of preset size the pending transactions fill. Note that batches
+            // are only sealed when the number of batches 'in-flight' are below a certain number
+            // (MAX_PARALLEL_BATCH). This condition will be met eventually if the store and
+            // network are functioning.
+            while self.pending.size() >= self.batch_size
+                && batch_pipeline.len() < MAX_PARALLEL_BATCH
+            {
+                if let Some(seal) = self.seal(false).await {
+                    batch_pipeline.push_back(seal);
+                }
+                self.node_metrics
+                    .parallel_worker_batches
+                    .set(batch_pipeline.len() as i64);
+                timer.as_mut().reset(Instant::now() + self.max_batch_delay);
+            }
+
             // Give the change to schedule other tasks.
             tokio::task::yield_now().await;
         }
     }
 
-    /// Seal and broadcast the current batch.
-    async fn seal(
-        &self,
-        timeout: bool,
-        batch: Batch,
-        size: usize,
-        responses: Vec<TxResponse>,
-    ) -> Option<impl Future<Output = ()>> {
+    /// Seal and broadcast a batch of the pending transactions of highest priority.
+    async fn seal(&mut self, timeout: bool) -> Option<impl Future<Output = ()>> {
+        let (transactions, responses, size) = self.pending.take(self.batch_size);
+        // Compress the transactions once, for the store and for every worker the batch is sent to.
+        let batch = Batch::new(transactions).with_compression(self.batch_compression);
         #[cfg(feature = "benchmark")]
         {
             let digest = batch.digest();
 
             // Look for sample txs (they all start with 0) and gather their txs id (the next 8 bytes).
             let tx_ids: Vec<_> = batch
-                .transactions
+                .transactions()
                 .iter()
                 .filter(|tx| tx[0] == 0u8 && tx.len() > 8)
                 .filter_map(|tx| tx[1..9].try_into().ok())
@@ -223,7 +222,7 @@ impl BatchMaker {
                 // that's useful for debugging and tracking the lifetime of messages between
                 // Narwhal and clients.
                 let tracking_ids: Vec<_> = batch
-                    .transactions
+                    .transactions()
                     .iter()
                     .map(|tx| {
                         let len = tx.len();
@@ -252,6 +251,17 @@ impl BatchMaker {
             .with_label_values(&[self.committee.epoch.to_string().as_str(), reason])
             .observe(size as f64);
 
+        if self.batch_compression != BatchCompression::None && size > 0 {
+            let compressed_size = batch.serialized_transactions().len();
+            self.node_metrics
+                .created_batch_compression_ratio
+                .with_label_values(&[
+                    self.committee.epoch.to_string().as_str(),
+                    &format!("{:?}", self.batch_compression),
+                ])
+                .observe(size as f64 / compressed_size as f64);
+        }
+
         // Send the batch through the deliver channel for further processing.
         let (notify_done, done_sending) = tokio::sync::oneshot::channel();
         if self
@@ -271,6 +281,8 @@ impl BatchMaker {
             .created_batch_latency
             .with_label_values(&[self.committee.epoch.to_string().as_str(), reason])
             .observe(self.batch_start_timestamp.elapsed().as_secs_f64());
+        // The transactions left pending start the next batch.
+        self.batch_start_timestamp = Instant::now();
 
         // Clone things to not capture self
         let store = self.store.clone();
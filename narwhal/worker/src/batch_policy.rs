@@ -0,0 +1,174 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::metrics::WorkerMetrics;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+use types::{Transaction, TxResponse};
+
+#[cfg(test)]
+#[path = "tests/batch_policy_tests.rs"]
+pub mod batch_policy_tests;
+
+/// The priority of a transaction, and the sender it counts against for fairness.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionPriority {
+    /// Transactions of higher priority are sealed first.
+    pub priority: u64,
+    /// The sender of the transaction, if the fairness limits apply to it.
+    pub sender: Option<Vec<u8>>,
+}
+
+/// Decides the order in which the worker seals the transactions it receives into batches.
+/// Received transactions wait in a queue bounded in size, and are sealed in decreasing priority,
+/// first-in first-out among transactions of the same priority. When the queue is full, a
+/// transaction is only admitted by evicting a pending transaction of lower priority.
+pub trait BatchPolicy: Send + Sync + 'static {
+    fn prioritize(&self, transaction: &Transaction) -> TransactionPriority;
+}
+
+/// Seals transactions in the order they are received.
+#[derive(Clone, Debug, Default)]
+pub struct FifoBatchPolicy;
+
+impl BatchPolicy for FifoBatchPolicy {
+    fn prioritize(&self, _transaction: &Transaction) -> TransactionPriority {
+        TransactionPriority::default()
+    }
+}
+
+struct PendingTransaction {
+    transaction: Transaction,
+    response: TxResponse,
+    sender: Option<Vec<u8>>,
+}
+
+/// The transactions waiting to be sealed, ordered by a `BatchPolicy`.
+pub(crate) struct PendingTransactions {
+    policy: Arc<dyn BatchPolicy>,
+    /// The maximum size in bytes of the pending transactions.
+    max_size: usize,
+    /// The maximum number of pending transactions of a sender.
+    max_per_sender: usize,
+    /// The pending transactions, the last one to be sealed first.
+    queue: BTreeMap<(u64, Reverse<u64>), PendingTransaction>,
+    /// The number of pending transactions of each sender.
+    per_sender: HashMap<Vec<u8>, usize>,
+    /// The size in bytes of the pending transactions.
+    size: usize,
+    /// The number of transactions received, to order transactions of the same priority.
+    received: u64,
+    metrics: Arc<WorkerMetrics>,
+}
+
+impl PendingTransactions {
+    pub fn new(
+        policy: Arc<dyn BatchPolicy>,
+        max_size: usize,
+        max_per_sender: usize,
+        metrics: Arc<WorkerMetrics>,
+    ) -> Self {
+        Self {
+            policy,
+            max_size,
+            max_per_sender,
+            queue: BTreeMap::new(),
+            per_sender: HashMap::new(),
+            size: 0,
+            received: 0,
+            metrics,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Queue the transaction, unless its sender has too many pending transactions or the queue
+    /// is full of transactions of higher priority. The response of a transaction that is not
+    /// sealed is dropped.
+    pub fn push(&mut self, transaction: Transaction, response: TxResponse) {
+        let TransactionPriority { priority, sender } = self.policy.prioritize(&transaction);
+        if let Some(sender) = &sender {
+            if self.per_sender.get(sender).copied().unwrap_or_default() >= self.max_per_sender {
+                self.drop_transaction("sender_limit");
+                return;
+            }
+        }
+
+        // Make room by evicting transactions of lower priority. The queue takes a transaction
+        // larger than it when it is empty, so that every transaction can be sealed.
+        while !self.queue.is_empty() && self.size + transaction.len() > self.max_size {
+            let lowest = *self.queue.keys().next().unwrap();
+            if lowest.0 >= priority {
+                self.drop_transaction("queue_full");
+                return;
+            }
+            self.remove(&lowest);
+            self.drop_transaction("evicted");
+        }
+
+        if let Some(sender) = &sender {
+            *self.per_sender.entry(sender.clone()).or_default() += 1;
+        }
+        self.size += transaction.len();
+        self.queue.insert(
+            (priority, Reverse(self.received)),
+            PendingTransaction {
+                transaction,
+                response,
+                sender,
+            },
+        );
+        self.received += 1;
+        self.metrics.pending_transactions_size.set(self.size as i64);
+    }
+
+    /// Take the transactions of highest priority, up to `batch_size` bytes, along with their
+    /// responses and their size.
+    pub fn take(&mut self, batch_size: usize) -> (Vec<Transaction>, Vec<TxResponse>, usize) {
+        let mut transactions = Vec::new();
+        let mut responses = Vec::new();
+        let mut size = 0;
+        while size < batch_size {
+            let Some(key) = self.queue.keys().next_back().copied() else {
+                break;
+            };
+            let pending = self.remove(&key);
+            size += pending.transaction.len();
+            transactions.push(pending.transaction);
+            responses.push(pending.response);
+        }
+        self.metrics.pending_transactions_size.set(self.size as i64);
+        (transactions, responses, size)
+    }
+
+    fn remove(&mut self, key: &(u64, Reverse<u64>)) -> PendingTransaction {
+        let pending = self
+            .queue
+            .remove(key)
+            .expect("Pending transaction not found");
+        self.size -= pending.transaction.len();
+        if let Some(sender) = &pending.sender {
+            let count = self.per_sender.get_mut(sender).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.per_sender.remove(sender);
+            }
+        }
+        pending
+    }
+
+    fn drop_transaction(&self, reason: &str) {
+        self.metrics
+            .dropped_transactions
+            .with_label_values(&[reason])
+            .inc();
+    }
+}
@@ -0,0 +1,385 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::metrics::WorkerMetrics;
+use anyhow::{anyhow, ensure, Context, Result};
+use config::{BatchDissemination, Committee};
+use crypto::{NetworkPublicKey, PublicKey, DIGEST_LENGTH};
+use fastcrypto::hash::{Hash, HashFunction};
+use futures::{stream::FuturesUnordered, StreamExt};
+use reed_solomon_erasure::galois_8::ReedSolomon;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    time::Duration,
+};
+use sui_metrics::monitored_future;
+use tokio::time::{sleep, Instant};
+use tracing::{debug, warn};
+use types::{Batch, BatchChunk, BatchDigest, RequestBatchChunkRequest, WorkerToWorkerClient};
+
+#[cfg(test)]
+#[path = "tests/erasure_coding_tests.rs"]
+pub mod erasure_coding_tests;
+
+/// The delay before asking a worker again for a chunk it does not have yet, doubled on every
+/// attempt up to `CHUNK_RETRY_MAX_DELAY`.
+const CHUNK_RETRY_INITIAL_DELAY: Duration = Duration::from_millis(100);
+const CHUNK_RETRY_MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// How a batch is erasure coded for a committee: one chunk per authority, of which the chunks
+/// of any `f + 1` authorities rebuild the batch, `f` being the number of faulty authorities the
+/// committee tolerates by count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkLayout {
+    pub data_shards: usize,
+    pub parity_shards: usize,
+}
+
+impl ChunkLayout {
+    /// The layout for a committee, or `None` if the committee is too small to tolerate a faulty
+    /// authority, in which case batches are broadcast.
+    pub fn new(committee: &Committee) -> Option<Self> {
+        let size = committee.size();
+        let f = size.saturating_sub(1) / 3;
+        (f > 0).then_some(Self {
+            data_shards: f + 1,
+            parity_shards: size - f - 1,
+        })
+    }
+
+    /// The layout a chunk claims to be coded with.
+    pub fn of(chunk: &BatchChunk) -> Self {
+        Self {
+            data_shards: chunk.data_shards as usize,
+            parity_shards: chunk.parity_shards as usize,
+        }
+    }
+
+    /// The number of authorities that must store their chunk of a batch before it is considered
+    /// delivered, so that the chunks of the honest ones among them rebuild it.
+    pub fn min_acknowledgements(&self) -> usize {
+        2 * self.data_shards - 1
+    }
+
+    fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+}
+
+/// The index of the chunk of an authority, which is its index in the committee.
+pub fn chunk_index(committee: &Committee, name: &PublicKey) -> Option<u32> {
+    committee
+        .keys()
+        .into_iter()
+        .position(|key| key == name)
+        .map(|index| index as u32)
+}
+
+/// The label of a dissemination mode in the metrics.
+pub fn mode_label(mode: BatchDissemination) -> String {
+    format!("{mode:?}")
+}
+
+/// Erasure code a batch into one chunk per authority of the layout.
+pub fn encode(batch: &Batch, layout: ChunkLayout) -> Result<Vec<BatchChunk>> {
+    let bytes = bincode::serialize(batch)?;
+    let shard_length = ((bytes.len() + layout.data_shards - 1) / layout.data_shards).max(1);
+    let mut shards: Vec<Vec<u8>> = bytes
+        .chunks(shard_length)
+        .map(|data| {
+            let mut shard = data.to_vec();
+            shard.resize(shard_length, 0);
+            shard
+        })
+        .collect();
+    shards.resize(layout.total_shards(), vec![0; shard_length]);
+    ReedSolomon::new(layout.data_shards, layout.parity_shards)
+        .and_then(|coder| coder.encode(&mut shards))
+        .map_err(|e| anyhow!("Failed to erasure code the batch: {e:?}"))?;
+
+    let batch_length = bytes.len() as u64;
+    let levels = merkle_levels(leaves(&shards, layout, batch_length));
+    let root = levels.last().unwrap()[0];
+    Ok(shards
+        .into_iter()
+        .enumerate()
+        .map(|(index, data)| BatchChunk {
+            index: index as u32,
+            data_shards: layout.data_shards as u32,
+            parity_shards: layout.parity_shards as u32,
+            batch_length,
+            root,
+            proof: merkle_proof(&levels, index),
+            data,
+        })
+        .collect())
+}
+
+type MerkleHash = [u8; DIGEST_LENGTH];
+
+/// The hash of a chunk in the Merkle tree of its batch. It covers the layout and the length of
+/// the batch, so that a chunk cannot claim other ones than the chunks it is coded with.
+fn leaf_hash(index: u32, layout: ChunkLayout, batch_length: u64, data: &[u8]) -> MerkleHash {
+    let mut hasher = crypto::DefaultHashFunction::new();
+    hasher.update([0u8]);
+    hasher.update(index.to_le_bytes());
+    hasher.update((layout.data_shards as u64).to_le_bytes());
+    hasher.update((layout.parity_shards as u64).to_le_bytes());
+    hasher.update(batch_length.to_le_bytes());
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+fn leaves(shards: &[Vec<u8>], layout: ChunkLayout, batch_length: u64) -> Vec<MerkleHash> {
+    shards
+        .iter()
+        .enumerate()
+        .map(|(index, data)| leaf_hash(index as u32, layout, batch_length, data))
+        .collect()
+}
+
+fn node_hash(left: &MerkleHash, right: &MerkleHash) -> MerkleHash {
+    let mut hasher = crypto::DefaultHashFunction::new();
+    hasher.update([1u8]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// The levels of the Merkle tree of `leaves`, from the leaves up to the root. The last node of a
+/// level of odd width is carried up to the next level as it is.
+fn merkle_levels(leaves: Vec<MerkleHash>) -> Vec<Vec<MerkleHash>> {
+    let mut levels = vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let level = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => node_hash(left, right),
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(level);
+    }
+    levels
+}
+
+fn merkle_proof(levels: &[Vec<MerkleHash>], mut index: usize) -> Vec<MerkleHash> {
+    let mut proof = Vec::new();
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        index /= 2;
+    }
+    proof
+}
+
+/// Check that a chunk is the chunk at its index in the Merkle tree of its root.
+pub fn verify(chunk: &BatchChunk) -> bool {
+    let layout = ChunkLayout::of(chunk);
+    let mut width = layout.total_shards();
+    let mut index = chunk.index as usize;
+    if index >= width {
+        return false;
+    }
+    let mut hash = leaf_hash(chunk.index, layout, chunk.batch_length, &chunk.data);
+    let mut proof = chunk.proof.iter();
+    while width > 1 {
+        if index ^ 1 < width {
+            let Some(sibling) = proof.next() else {
+                return false;
+            };
+            hash = if index % 2 == 0 {
+                node_hash(&hash, sibling)
+            } else {
+                node_hash(sibling, &hash)
+            };
+        }
+        index /= 2;
+        width = (width + 1) / 2;
+    }
+    proof.next().is_none() && hash == chunk.root
+}
+
+/// The chunks of a batch are not the Reed-Solomon codeword their Merkle root commits to. Their
+/// author is faulty, and no subset of them rebuilds the batch.
+#[derive(Debug)]
+pub struct InconsistentChunks;
+
+impl fmt::Display for InconsistentChunks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The chunks are not the codeword of their Merkle root")
+    }
+}
+
+impl std::error::Error for InconsistentChunks {}
+
+/// Rebuild a batch from chunks verified against a same Merkle root. The codeword decoded from
+/// the chunks is checked against the root, so that the chunks of a root rebuild the batch on
+/// every honest worker or on none of them: an `InconsistentChunks` error means the root is bad.
+pub fn reconstruct(digest: BatchDigest, chunks: &[BatchChunk]) -> Result<Batch> {
+    let first = chunks
+        .first()
+        .context("No chunk to rebuild the batch from")?;
+    let layout = ChunkLayout::of(first);
+    let mut shards: Vec<Option<Vec<u8>>> = vec![None; layout.total_shards()];
+    for chunk in chunks {
+        ensure!(
+            chunk.root == first.root,
+            "Chunk {} does not have the root of the other chunks",
+            chunk.index
+        );
+        if ChunkLayout::of(chunk) != layout
+            || chunk.batch_length != first.batch_length
+            || chunk.data.len() != first.data.len()
+        {
+            return Err(InconsistentChunks.into());
+        }
+        let shard = shards
+            .get_mut(chunk.index as usize)
+            .with_context(|| format!("Chunk index {} out of range", chunk.index))?;
+        *shard = Some(chunk.data.clone());
+    }
+    ReedSolomon::new(layout.data_shards, layout.parity_shards)
+        .and_then(|coder| coder.reconstruct(&mut shards))
+        .map_err(|e| anyhow!("Failed to rebuild the batch: {e:?}"))?;
+    let shards: Vec<Vec<u8>> = shards.into_iter().flatten().collect();
+
+    // The standard AVID consistency check: the chunks of the other authorities, coded again from
+    // the decoded ones, must be the chunks the root commits to.
+    let levels = merkle_levels(leaves(&shards, layout, first.batch_length));
+    if levels.last().unwrap()[0] != first.root {
+        return Err(InconsistentChunks.into());
+    }
+
+    let mut bytes: Vec<u8> = shards
+        .into_iter()
+        .take(layout.data_shards)
+        .flatten()
+        .collect();
+    let length = usize::try_from(first.batch_length)?;
+    ensure!(
+        bytes.len() >= length,
+        "The chunks are shorter than the batch"
+    );
+    bytes.truncate(length);
+    let batch: Batch = bincode::deserialize(&bytes)?;
+    ensure!(
+        batch.digest() == digest,
+        "The rebuilt batch does not match digest {digest}"
+    );
+    Ok(batch)
+}
+
+/// Rebuild a batch from the chunks of the other workers, all asked at once, as soon as enough of
+/// them answer. `peers` are the workers to ask, along with the index of the chunk of their
+/// authority. A worker that does not have its chunk yet, or fails to answer, is asked again with
+/// a backoff until `deadline` has elapsed. Returns `None` if the batch could not be rebuilt from
+/// the chunks received.
+///
+/// The chunks are grouped by the Merkle root they are verified against: a faulty worker can only
+/// answer with a chunk of its own index, under a root that the chunks of the honest workers do
+/// not share, so that it can neither prevent nor corrupt the rebuild of the batch.
+pub async fn fetch_batch(
+    network: &anemo::Network,
+    peers: Vec<(u32, NetworkPublicKey)>,
+    digest: BatchDigest,
+    layout: ChunkLayout,
+    own_chunk: Option<BatchChunk>,
+    timeout: Duration,
+    deadline: Duration,
+    metrics: &WorkerMetrics,
+) -> Option<Batch> {
+    let started = Instant::now();
+    let deadline = started + deadline;
+    let mode = mode_label(BatchDissemination::ErasureCoded);
+    let mut requests: FuturesUnordered<_> = peers
+        .into_iter()
+        .filter_map(|(index, name)| {
+            let peer = network.peer(anemo::PeerId(name.0.to_bytes()))?;
+            Some(monitored_future!(async move {
+                let mut delay = CHUNK_RETRY_INITIAL_DELAY;
+                loop {
+                    let request = anemo::Request::new(RequestBatchChunkRequest { batch: digest })
+                        .with_timeout(timeout);
+                    match WorkerToWorkerClient::new(peer.clone())
+                        .request_batch_chunk(request)
+                        .await
+                    {
+                        Ok(response) => {
+                            if let Some(chunk) = response.into_body().chunk {
+                                return (index, Some(chunk));
+                            }
+                        }
+                        Err(e) => debug!("RequestBatchChunk for batch {digest} failed: {e:?}"),
+                    }
+                    // The chunks of a batch are sent to all the workers at once: the worker may
+                    // not have received its own yet.
+                    if Instant::now() + delay > deadline {
+                        return (index, None);
+                    }
+                    sleep(delay).await;
+                    delay = (delay * 2).min(CHUNK_RETRY_MAX_DELAY);
+                }
+            }))
+        })
+        .collect();
+
+    let mut chunks_by_root: HashMap<MerkleHash, Vec<BatchChunk>> = HashMap::new();
+    let mut bad_roots: HashSet<MerkleHash> = HashSet::new();
+    let mut received: Vec<BatchChunk> = own_chunk.into_iter().collect();
+    loop {
+        for chunk in received.drain(..) {
+            let root = chunk.root;
+            if bad_roots.contains(&root) {
+                continue;
+            }
+            let chunks = chunks_by_root.entry(root).or_default();
+            chunks.push(chunk);
+            if chunks.len() < layout.data_shards {
+                continue;
+            }
+            match reconstruct(digest, chunks) {
+                Ok(batch) => {
+                    metrics
+                        .batch_fetch_latency
+                        .with_label_values(&[&mode])
+                        .observe(started.elapsed().as_secs_f64());
+                    return Some(batch);
+                }
+                // The author of the batch is faulty: more chunks of this root would not help,
+                // but the chunks of a faulty worker under its own root may not be the only ones.
+                Err(e) if e.is::<InconsistentChunks>() => {
+                    warn!("Unable to rebuild batch {digest}: {e}");
+                    chunks_by_root.remove(&root);
+                    bad_roots.insert(root);
+                }
+                // Retried with every other chunk of this root received.
+                Err(e) => warn!("Unable to rebuild batch {digest}: {e}"),
+            }
+        }
+
+        match requests.next().await {
+            Some((index, Some(chunk))) => {
+                metrics
+                    .batch_fetch_bytes
+                    .with_label_values(&[&mode])
+                    .inc_by(chunk.data.len() as u64);
+                if chunk.index == index && ChunkLayout::of(&chunk) == layout && verify(&chunk) {
+                    received.push(chunk);
+                } else {
+                    warn!("Invalid chunk {} of batch {digest}", chunk.index);
+                }
+            }
+            Some((index, None)) => debug!("No chunk {index} of batch {digest} before the deadline"),
+            None => break,
+        }
+    }
+
+    warn!("Unable to rebuild batch {digest} from the chunks of the other workers");
+    metrics.batch_reconstruction_failures.inc();
+    None
+}
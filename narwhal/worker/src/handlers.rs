@@ -1,42 +1,70 @@
 // Copyright (c) 2021, Facebook, Inc. and its affiliates
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use crate::{
+    batch_maker::MAX_PARALLEL_BATCH,
+    erasure_coding::{self, ChunkLayout},
+    metrics::WorkerMetrics,
+};
 use anyhow::Result;
 use async_trait::async_trait;
-use config::{Committee, SharedCommittee, SharedWorkerCache, WorkerCache, WorkerId, WorkerIndex};
-use crypto::PublicKey;
+use config::{
+    BatchDissemination, Committee, SharedCommittee, SharedWorkerCache, WorkerCache, WorkerId,
+    WorkerIndex,
+};
+use crypto::{NetworkPublicKey, PublicKey};
 use fastcrypto::hash::Hash;
 use futures::{stream::FuturesUnordered, StreamExt};
 
 use rand::seq::SliceRandom;
 use std::{
-    collections::{BTreeMap, HashSet},
-    sync::Arc,
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Mutex},
     time::Duration,
 };
 use store::Store;
 use tap::TapOptional;
-use tokio::{sync::watch, time::sleep};
+use tokio::{
+    sync::watch,
+    time::{sleep, Instant},
+};
 use tracing::{debug, error, info, trace, warn};
 use types::{
-    metered_channel::Sender, Batch, BatchDigest, PrimaryToWorker, ReconfigureNotification,
-    RequestBatchRequest, RequestBatchResponse, WorkerBatchMessage, WorkerDeleteBatchesMessage,
-    WorkerOthersBatchMessage, WorkerReconfigureMessage, WorkerSynchronizeMessage, WorkerToWorker,
-    WorkerToWorkerClient,
+    metered_channel::Sender, Batch, BatchChunk, BatchDigest, PrimaryToWorker,
+    ReconfigureNotification, RequestBatchChunkRequest, RequestBatchChunkResponse,
+    RequestBatchRequest, RequestBatchResponse, WorkerBatchChunkMessage, WorkerBatchMessage,
+    WorkerDeleteBatchesMessage, WorkerOthersBatchMessage, WorkerReconfigureMessage,
+    WorkerSynchronizeMessage, WorkerToWorker, WorkerToWorkerClient,
 };
 
-use sui_metrics::monitored_future;
+use sui_metrics::{monitored_future, spawn_monitored_task};
 
 #[cfg(test)]
 #[path = "tests/handlers_tests.rs"]
 pub mod handlers_tests;
 
+/// How long the other workers are asked for their chunks of a batch being rebuilt.
+const BATCH_REBUILD_DEADLINE: Duration = Duration::from_secs(30);
+
 /// Defines how the network receiver handles incoming workers messages.
 #[derive(Clone)]
 pub struct WorkerReceiverHandler {
+    // The public key of this authority.
+    pub name: PublicKey,
     pub id: WorkerId,
+    // The committee information.
+    pub committee: SharedCommittee,
+    // The worker information cache.
+    pub worker_cache: SharedWorkerCache,
     pub tx_others_batch: Sender<WorkerOthersBatchMessage>,
     pub store: Store<BatchDigest, Batch>,
+    // The chunks of the batches of other workers, until the batches are deleted.
+    pub chunk_store: Store<BatchDigest, BatchChunk>,
+    // The batches being rebuilt, along with the worker that authored them.
+    pub rebuilding: Arc<Mutex<HashMap<BatchDigest, anemo::PeerId>>>,
+    // Timeout on RequestBatchChunk RPC.
+    pub request_batch_timeout: Duration,
+    pub metrics: Arc<WorkerMetrics>,
 }
 
 #[async_trait]
@@ -72,6 +100,206 @@ impl WorkerToWorker for WorkerReceiverHandler {
 
         Ok(anemo::Response::new(RequestBatchResponse { batch }))
     }
+
+    async fn report_batch_chunk(
+        &self,
+        request: anemo::Request<WorkerBatchChunkMessage>,
+    ) -> Result<anemo::Response<()>, anemo::rpc::Status> {
+        let network = request
+            .extensions()
+            .get::<anemo::NetworkRef>()
+            .and_then(anemo::NetworkRef::upgrade)
+            .ok_or_else(|| {
+                anemo::rpc::Status::internal("Unable to access network to send child RPCs")
+            })?;
+        let author = request.peer_id().copied();
+        let WorkerBatchChunkMessage { digest, chunk } = request.into_body();
+
+        // Workers only send the chunks of their own batches, each to the authority it is for.
+        let author = author
+            .filter(|author| {
+                self.worker_cache
+                    .load()
+                    .others_workers_by_id(&self.name, &self.id)
+                    .iter()
+                    .any(|(_, info)| anemo::PeerId(info.name.0.to_bytes()) == *author)
+            })
+            .ok_or_else(|| anemo::rpc::Status::internal("Chunk not sent by a known worker"))?;
+        let valid = {
+            let committee = self.committee.load();
+            ChunkLayout::new(&committee) == Some(ChunkLayout::of(&chunk))
+                && erasure_coding::chunk_index(&committee, &self.name) == Some(chunk.index)
+                && erasure_coding::verify(&chunk)
+        };
+        if !valid {
+            return Err(anemo::rpc::Status::internal(format!(
+                "Invalid chunk {} of batch {digest}",
+                chunk.index
+            )));
+        }
+
+        let stored = self
+            .store
+            .read(digest)
+            .await
+            .map_err(|e| anemo::rpc::Status::from_error(Box::new(e)))?;
+        if stored.is_some() {
+            return Ok(anemo::Response::new(()));
+        }
+
+        // The first chunk received for a batch is kept until the batch is deleted, and an author
+        // only rebuilds as many batches at once as it seals.
+        let in_flight = {
+            let mut rebuilding = self.rebuilding.lock().unwrap();
+            if rebuilding.contains_key(&digest) {
+                true
+            } else if rebuilding.values().filter(|a| **a == author).count() >= MAX_PARALLEL_BATCH {
+                return Err(anemo::rpc::Status::internal(
+                    "Too many batches of this worker being rebuilt",
+                ));
+            } else {
+                rebuilding.insert(digest, author);
+                false
+            }
+        };
+        if in_flight {
+            let stored = self
+                .chunk_store
+                .read(digest)
+                .await
+                .map_err(|e| anemo::rpc::Status::from_error(Box::new(e)))?;
+            return match stored {
+                Some(stored) if stored.root == chunk.root => Ok(anemo::Response::new(())),
+                _ => Err(anemo::rpc::Status::internal(format!(
+                    "Batch {digest} is being rebuilt from another chunk"
+                ))),
+            };
+        }
+
+        // The chunk is acknowledged once stored, and the batch is rebuilt in the background. The
+        // chunk kept from a rebuild that failed is only sent again by the author to retry it.
+        let stored = match self.chunk_store.read(digest).await {
+            Ok(stored) => stored,
+            Err(e) => {
+                self.rebuilding.lock().unwrap().remove(&digest);
+                return Err(anemo::rpc::Status::from_error(Box::new(e)));
+            }
+        };
+        match stored {
+            Some(stored) if stored.root != chunk.root => {
+                self.rebuilding.lock().unwrap().remove(&digest);
+                return Err(anemo::rpc::Status::internal(format!(
+                    "Batch {digest} was received with another chunk"
+                )));
+            }
+            Some(_) => (),
+            None => {
+                if let Err(e) = self.chunk_store.sync_write(digest, chunk.clone()).await {
+                    self.rebuilding.lock().unwrap().remove(&digest);
+                    return Err(anemo::rpc::Status::from_error(Box::new(e)));
+                }
+            }
+        }
+        let handler = self.clone();
+        spawn_monitored_task!(handler.rebuild_batch(network, digest, chunk));
+        Ok(anemo::Response::new(()))
+    }
+
+    async fn request_batch_chunk(
+        &self,
+        request: anemo::Request<RequestBatchChunkRequest>,
+    ) -> Result<anemo::Response<RequestBatchChunkResponse>, anemo::rpc::Status> {
+        let digest = request.into_body().batch;
+        let chunk = self
+            .chunk_store
+            .read(digest)
+            .await
+            .map_err(|e| anemo::rpc::Status::from_error(Box::new(e)))?;
+        if chunk.is_some() {
+            return Ok(anemo::Response::new(RequestBatchChunkResponse { chunk }));
+        }
+
+        // Once the batch is rebuilt, or if it is ours, our chunk is coded again from it.
+        let batch = self
+            .store
+            .read(digest)
+            .await
+            .map_err(|e| anemo::rpc::Status::from_error(Box::new(e)))?;
+        let committee = self.committee.load();
+        let chunk = match (
+            batch,
+            ChunkLayout::new(&committee),
+            erasure_coding::chunk_index(&committee, &self.name),
+        ) {
+            (Some(batch), Some(layout), Some(index)) => erasure_coding::encode(&batch, layout)
+                .map_err(|e| anemo::rpc::Status::internal(e.to_string()))?
+                .into_iter()
+                .nth(index as usize),
+            _ => None,
+        };
+        Ok(anemo::Response::new(RequestBatchChunkResponse { chunk }))
+    }
+}
+
+impl WorkerReceiverHandler {
+    /// Rebuild a batch of another worker from our chunk and the chunks of the other workers, then
+    /// report it to our primary as if it was received whole.
+    async fn rebuild_batch(self, network: anemo::Network, digest: BatchDigest, chunk: BatchChunk) {
+        let layout = ChunkLayout::of(&chunk);
+        let peers = indexed_peers(
+            &self.committee.load(),
+            &self.worker_cache.load(),
+            &self.name,
+            &self.id,
+        );
+        let batch = erasure_coding::fetch_batch(
+            &network,
+            peers,
+            digest,
+            layout,
+            Some(chunk),
+            self.request_batch_timeout,
+            BATCH_REBUILD_DEADLINE,
+            &self.metrics,
+        )
+        .await;
+
+        // Whether or not the batch was rebuilt, its chunk is kept until the batch is deleted: it
+        // was acknowledged to the author, and the other workers may still need it.
+        if let Some(batch) = &batch {
+            self.store.async_write(digest, batch.clone()).await;
+        }
+        self.rebuilding.lock().unwrap().remove(&digest);
+        if batch.is_none() {
+            return;
+        }
+        if let Err(e) = self
+            .tx_others_batch
+            .send(WorkerOthersBatchMessage {
+                digest,
+                worker_id: self.id,
+            })
+            .await
+        {
+            warn!("Failed to report rebuilt batch {digest}: {e}");
+        }
+    }
+}
+
+/// The other workers of our id, along with the index of the chunk of their authority.
+fn indexed_peers(
+    committee: &Committee,
+    worker_cache: &WorkerCache,
+    name: &PublicKey,
+    id: &WorkerId,
+) -> Vec<(u32, NetworkPublicKey)> {
+    worker_cache
+        .others_workers_by_id(name, id)
+        .into_iter()
+        .filter_map(|(authority, info)| {
+            erasure_coding::chunk_index(committee, &authority).map(|index| (index, info.name))
+        })
+        .collect()
 }
 
 /// Defines how the network receiver handles incoming primary messages.
@@ -86,12 +314,17 @@ pub struct PrimaryReceiverHandler {
     pub worker_cache: SharedWorkerCache,
     // The batch store
     pub store: Store<BatchDigest, Batch>,
+    // The chunks of the batches of other workers, deleted along with the batches.
+    pub chunk_store: Store<BatchDigest, BatchChunk>,
     // Timeout on RequestBatch RPC.
     pub request_batch_timeout: Duration,
     // Number of random nodes to query when retrying batch requests.
     pub request_batch_retry_nodes: usize,
     /// Send reconfiguration update to other tasks.
     pub tx_reconfigure: watch::Sender<ReconfigureNotification>,
+    // How the batches are disseminated, and missing batches fetched.
+    pub batch_dissemination: BatchDissemination,
+    pub metrics: Arc<WorkerMetrics>,
 }
 
 #[async_trait]
@@ -103,8 +336,22 @@ impl PrimaryToWorker for PrimaryReceiverHandler {
         let message = request.into_body().message;
         match &message {
             ReconfigureNotification::NewEpoch(new_committee) => {
+                // Adopt the workers of an epoch change scheduled in place, if any.
+                let scheduled_worker_cache = match &*self.tx_reconfigure.borrow() {
+                    ReconfigureNotification::ScheduleEpoch {
+                        committee,
+                        worker_cache,
+                        ..
+                    } if committee == new_committee => Some(worker_cache.clone()),
+                    _ => None,
+                };
                 self.committee.swap(Arc::new(new_committee.clone()));
-                self.update_worker_cache(new_committee);
+                match scheduled_worker_cache {
+                    Some(worker_cache) => {
+                        self.worker_cache.swap(Arc::new(worker_cache));
+                    }
+                    None => self.update_worker_cache(new_committee),
+                }
                 tracing::debug!("Committee updated to {}", self.committee);
             }
             ReconfigureNotification::UpdateCommittee(new_committee) => {
@@ -113,6 +360,7 @@ impl PrimaryToWorker for PrimaryReceiverHandler {
                 tracing::debug!("Committee updated to {}", self.committee);
             }
             ReconfigureNotification::Shutdown => (), // no-op
+            ReconfigureNotification::ScheduleEpoch { .. } => (), // adopted on `NewEpoch`
         };
 
         // Notify all other tasks.
@@ -147,11 +395,66 @@ impl PrimaryToWorker for PrimaryReceiverHandler {
             };
         }
 
+        let started = Instant::now();
+        let fetching = !missing.is_empty();
+
+        // In the erasure coded mode, the missing batches are first rebuilt from the chunks of all
+        // the other workers, so that a slow worker does not delay them.
+        let layout = ChunkLayout::new(&self.committee.load())
+            .filter(|_| fetching && self.batch_dissemination == BatchDissemination::ErasureCoded);
+        if let Some(layout) = layout {
+            let network = request
+                .extensions()
+                .get::<anemo::NetworkRef>()
+                .and_then(anemo::NetworkRef::upgrade)
+                .ok_or_else(|| {
+                    anemo::rpc::Status::internal("Unable to access network to send child RPCs")
+                })?;
+            let peers = indexed_peers(
+                &self.committee.load(),
+                &self.worker_cache.load(),
+                &self.name,
+                &self.id,
+            );
+            let mut fetches: FuturesUnordered<_> = missing
+                .iter()
+                .map(|digest| {
+                    erasure_coding::fetch_batch(
+                        &network,
+                        peers.clone(),
+                        *digest,
+                        layout,
+                        None,
+                        self.request_batch_timeout,
+                        self.request_batch_timeout,
+                        &self.metrics,
+                    )
+                })
+                .collect();
+            while let Some(batch) = fetches.next().await {
+                if let Some(batch) = batch {
+                    let digest = batch.digest();
+                    missing.remove(&digest);
+                    self.store.sync_write(digest, batch).await.map_err(|e| {
+                        anemo::rpc::Status::internal(format!(
+                            "failed to write to batch store: {e:?}"
+                        ))
+                    })?;
+                }
+            }
+        }
+
         // Keep attempting to retrieve missing batches until we get them all or the client
         // abandons the RPC.
         let mut first_attempt = true;
         loop {
             if missing.is_empty() {
+                if fetching {
+                    self.metrics
+                        .batch_fetch_latency
+                        .with_label_values(&[&erasure_coding::mode_label(self.batch_dissemination)])
+                        .observe(started.elapsed().as_secs_f64());
+                }
                 return Ok(anemo::Response::new(()));
             }
 
@@ -241,6 +544,14 @@ impl PrimaryToWorker for PrimaryReceiverHandler {
                     Ok(response) => {
                         if let Some(batch) = response.into_body().batch {
                             let digest = batch.digest();
+                            if let Ok(size) = bincode::serialized_size(&batch) {
+                                self.metrics
+                                    .batch_fetch_bytes
+                                    .with_label_values(&[&erasure_coding::mode_label(
+                                        BatchDissemination::Broadcast,
+                                    )])
+                                    .inc_by(size);
+                            }
                             if missing.remove(&digest) {
                                 self.store.sync_write(digest, batch).await.map_err(|e| {
                                     anemo::rpc::Status::internal(format!(
@@ -273,6 +584,10 @@ impl PrimaryToWorker for PrimaryReceiverHandler {
         request: anemo::Request<WorkerDeleteBatchesMessage>,
     ) -> Result<anemo::Response<()>, anemo::rpc::Status> {
         let digests = request.into_body().digests;
+        self.chunk_store
+            .remove_all(digests.clone())
+            .await
+            .map_err(|e| anemo::rpc::Status::from_error(Box::new(e)))?;
         self.store
             .remove_all(digests)
             .await
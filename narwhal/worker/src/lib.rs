@@ -9,10 +9,13 @@
 )]
 
 mod batch_maker;
+mod batch_policy;
+mod erasure_coding;
 mod handlers;
 pub mod metrics;
 mod primary_connector;
 mod quorum_waiter;
 mod worker;
 
+pub use crate::batch_policy::{BatchPolicy, FifoBatchPolicy, TransactionPriority};
 pub use crate::worker::Worker;
@@ -59,8 +59,28 @@ pub struct WorkerMetrics {
     pub created_batch_size: HistogramVec,
     /// Time taken to create a batch
     pub created_batch_latency: HistogramVec,
+    /// The size of the transactions of the created batches, over their size once compressed
+    pub created_batch_compression_ratio: HistogramVec,
     /// The number of parallel worker batches currently processed by the worker
     pub parallel_worker_batches: IntGauge,
+    /// The size in bytes of the transactions waiting to be sealed into batches
+    pub pending_transactions_size: IntGauge,
+    /// The number of transactions dropped by the batch maker before being sealed, by reason
+    pub dropped_transactions: IntCounterVec,
+    /// The bytes of our batches, or of their chunks, sent to the other workers, by dissemination
+    /// mode
+    pub batch_dissemination_bytes: IntCounterVec,
+    /// The time from sending one of our batches to the other workers to a quorum of
+    /// acknowledgements, by dissemination mode
+    pub batch_dissemination_latency: HistogramVec,
+    /// The bytes of the batches, or of their chunks, received when fetching the batches of the
+    /// other workers, by dissemination mode
+    pub batch_fetch_bytes: IntCounterVec,
+    /// The time to fetch the batches missing from a header, or to rebuild a batch from its
+    /// chunks, by dissemination mode
+    pub batch_fetch_latency: HistogramVec,
+    /// The number of batches that could not be rebuilt from the chunks received
+    pub batch_reconstruction_failures: IntCounter,
 }
 
 impl WorkerMetrics {
@@ -96,12 +116,69 @@ impl WorkerMetrics {
                 registry
             )
             .unwrap(),
+            created_batch_compression_ratio: register_histogram_vec_with_registry!(
+                "created_batch_compression_ratio",
+                "The size of the transactions of the created batches, over their size once compressed",
+                &["epoch", "compression"],
+                vec![1.0, 1.25, 1.5, 2.0, 3.0, 4.0, 6.0, 8.0, 12.0, 16.0, 32.0],
+                registry
+            )
+            .unwrap(),
             parallel_worker_batches: register_int_gauge_with_registry!(
                 "parallel_worker_batches",
                 "The number of parallel worker batches currently processed by the worker",
                 registry
             )
             .unwrap(),
+            pending_transactions_size: register_int_gauge_with_registry!(
+                "pending_transactions_size",
+                "The size in bytes of the transactions waiting to be sealed into batches",
+                registry
+            )
+            .unwrap(),
+            dropped_transactions: register_int_counter_vec_with_registry!(
+                "dropped_transactions",
+                "The number of transactions dropped by the batch maker before being sealed, by reason",
+                &["reason"],
+                registry
+            )
+            .unwrap(),
+            batch_dissemination_bytes: register_int_counter_vec_with_registry!(
+                "batch_dissemination_bytes",
+                "The bytes of our batches, or of their chunks, sent to the other workers",
+                &["mode"],
+                registry
+            )
+            .unwrap(),
+            batch_dissemination_latency: register_histogram_vec_with_registry!(
+                "batch_dissemination_latency",
+                "The time from sending one of our batches to the other workers to a quorum of acknowledgements",
+                &["mode"],
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry
+            )
+            .unwrap(),
+            batch_fetch_bytes: register_int_counter_vec_with_registry!(
+                "batch_fetch_bytes",
+                "The bytes of the batches, or of their chunks, received when fetching the batches of the other workers",
+                &["mode"],
+                registry
+            )
+            .unwrap(),
+            batch_fetch_latency: register_histogram_vec_with_registry!(
+                "batch_fetch_latency",
+                "The time to fetch the batches missing from a header, or to rebuild a batch from its chunks",
+                &["mode"],
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry
+            )
+            .unwrap(),
+            batch_reconstruction_failures: register_int_counter_with_registry!(
+                "batch_reconstruction_failures",
+                "The number of batches that could not be rebuilt from the chunks received",
+                registry
+            )
+            .unwrap(),
         }
     }
 }
@@ -2,17 +2,28 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch_maker::MAX_PARALLEL_BATCH;
-use config::{Committee, SharedWorkerCache, Stake, WorkerId};
-use crypto::PublicKey;
+use crate::{
+    batch_maker::MAX_PARALLEL_BATCH,
+    erasure_coding::{self, ChunkLayout},
+    metrics::WorkerMetrics,
+};
+use config::{BatchDissemination, Committee, SharedWorkerCache, Stake, WorkerId};
+use crypto::{NetworkPublicKey, PublicKey};
 use fastcrypto::hash::Hash;
 use futures::stream::{futures_unordered::FuturesUnordered, FuturesOrdered, StreamExt as _};
 use network::{CancelOnDropHandler, P2pNetwork, ReliableNetwork};
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use sui_metrics::{monitored_future, spawn_monitored_task};
-use tokio::{sync::watch, task::JoinHandle, time::timeout};
+use tokio::{
+    sync::watch,
+    task::JoinHandle,
+    time::{timeout, Instant},
+};
 use tracing::{error, trace};
-use types::{metered_channel::Receiver, Batch, ReconfigureNotification, WorkerBatchMessage};
+use types::{
+    metered_channel::Receiver, Batch, ReconfigureNotification, WorkerBatchChunkMessage,
+    WorkerBatchMessage,
+};
 
 #[cfg(test)]
 #[path = "tests/quorum_waiter_tests.rs"]
@@ -34,6 +45,10 @@ pub struct QuorumWaiter {
     rx_message: Receiver<(Batch, Option<tokio::sync::oneshot::Sender<()>>)>,
     /// A network sender to broadcast the batches to the other workers.
     network: P2pNetwork,
+    /// How the batches are disseminated to the other workers.
+    batch_dissemination: BatchDissemination,
+    /// The metrics of the worker.
+    metrics: Arc<WorkerMetrics>,
 }
 
 impl QuorumWaiter {
@@ -47,6 +62,8 @@ impl QuorumWaiter {
         rx_reconfigure: watch::Receiver<ReconfigureNotification>,
         rx_message: Receiver<(Batch, Option<tokio::sync::oneshot::Sender<()>>)>,
         network: P2pNetwork,
+        batch_dissemination: BatchDissemination,
+        metrics: Arc<WorkerMetrics>,
     ) -> JoinHandle<()> {
         spawn_monitored_task!(async move {
             Self {
@@ -57,6 +74,8 @@ impl QuorumWaiter {
                 rx_reconfigure,
                 rx_message,
                 network,
+                batch_dissemination,
+                metrics,
             }
             .run()
             .await;
@@ -72,6 +91,73 @@ impl QuorumWaiter {
         deliver
     }
 
+    /// Send a batch to the other workers, whole or as the chunk of their authority. Returns the
+    /// handlers to receive their acknowledgements, the mode used, and the minimum number of
+    /// authorities, including ours, that must acknowledge the batch.
+    async fn disseminate(
+        &mut self,
+        batch: &Batch,
+        worker_names: Vec<NetworkPublicKey>,
+        primary_names: &[PublicKey],
+    ) -> (
+        Vec<CancelOnDropHandler<anemo::Result<anemo::Response<()>>>>,
+        BatchDissemination,
+        usize,
+    ) {
+        // Every other authority is sent its own chunk, unless the committee is too small to
+        // tolerate a fault or an authority is unknown to it.
+        let chunks = match self.batch_dissemination {
+            BatchDissemination::ErasureCoded => {
+                ChunkLayout::new(&self.committee).and_then(|layout| {
+                    let indices = primary_names
+                        .iter()
+                        .map(|name| erasure_coding::chunk_index(&self.committee, name))
+                        .collect::<Option<Vec<_>>>()?;
+                    erasure_coding::encode(batch, layout)
+                        .map_err(|e| error!("Broadcasting batch {}: {e}", batch.digest()))
+                        .ok()
+                        .map(|chunks| (layout, indices, chunks))
+                })
+            }
+            BatchDissemination::Broadcast => None,
+        };
+        if let Some((layout, indices, chunks)) = chunks {
+            let digest = batch.digest();
+            let mut handlers = Vec::new();
+            let mut size = 0;
+            for (worker_name, index) in worker_names.into_iter().zip(indices) {
+                let chunk = chunks[index as usize].clone();
+                size += chunk.data.len() as u64;
+                let message = WorkerBatchChunkMessage { digest, chunk };
+                handlers
+                    .push(ReliableNetwork::send(&mut self.network, worker_name, &message).await);
+            }
+            self.metrics
+                .batch_dissemination_bytes
+                .with_label_values(&[&erasure_coding::mode_label(
+                    BatchDissemination::ErasureCoded,
+                )])
+                .inc_by(size);
+            return (
+                handlers,
+                BatchDissemination::ErasureCoded,
+                layout.min_acknowledgements(),
+            );
+        }
+
+        if let Ok(size) = bincode::serialized_size(batch) {
+            self.metrics
+                .batch_dissemination_bytes
+                .with_label_values(&[&erasure_coding::mode_label(BatchDissemination::Broadcast)])
+                .inc_by(size * worker_names.len() as u64);
+        }
+        let message = WorkerBatchMessage {
+            batch: batch.clone(),
+        };
+        let handlers = self.network.broadcast(worker_names, &message).await;
+        (handlers, BatchDissemination::Broadcast, 0)
+    }
+
     /// Main loop.
     async fn run(&mut self) {
         //
@@ -95,8 +181,13 @@ impl QuorumWaiter {
                         .map(|(name, info)| (name, info.name))
                         .collect();
                     let (primary_names, worker_names): (Vec<_>, _) = workers.into_iter().unzip();
-                    let message  = WorkerBatchMessage{batch: batch.clone()};
-                    let handlers = self.network.broadcast(worker_names, &message).await;
+                    let started = Instant::now();
+                    let (handlers, mode, min_acknowledgements) =
+                        self.disseminate(&batch, worker_names, &primary_names).await;
+                    let dissemination_latency = self
+                        .metrics
+                        .batch_dissemination_latency
+                        .with_label_values(&[&erasure_coding::mode_label(mode)]);
 
                     // Collect all the handlers to receive acknowledgements.
                     let mut wait_for_quorum: FuturesUnordered<_> = primary_names
@@ -110,9 +201,12 @@ impl QuorumWaiter {
 
                     // Wait for the first 2f nodes to send back an Ack. Then we consider the batch
                     // delivered and we send its digest to the primary (that will include it into
-                    // the dag). This should reduce the amount of synching.
+                    // the dag). This should reduce the amount of synching. Chunks of an erasure
+                    // coded batch must also be acknowledged by enough authorities by count, for
+                    // the honest ones among them to rebuild it.
                     let threshold = self.committee.quorum_threshold();
                     let mut total_stake = self.committee.stake(&self.name);
+                    let mut acknowledgements = 1;
 
                     pipeline.push_back(async move {
                         // A future that sends to 2/3 stake then returns. Also prints an error
@@ -121,7 +215,9 @@ impl QuorumWaiter {
                         loop{
                             if let Some(stake) = wait_for_quorum.next().await {
                                 total_stake += stake;
-                                if total_stake >= threshold {
+                                acknowledgements += 1;
+                                if total_stake >= threshold && acknowledgements >= min_acknowledgements {
+                                    dissemination_latency.observe(started.elapsed().as_secs_f64());
 
                                     // Notify anyone waiting for this.
                                     if let Some(channel) = opt_channel {
@@ -187,7 +283,8 @@ impl QuorumWaiter {
                             best_effort_with_timeout = FuturesUnordered::new()
 
                         },
-                        ReconfigureNotification::Shutdown => return
+                        ReconfigureNotification::Shutdown => return,
+                        ReconfigureNotification::ScheduleEpoch { .. } => continue,
                     }
                     tracing::debug!("Committee updated to {}", self.committee);
                 }
@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 use super::*;
 
+use crate::FifoBatchPolicy;
+
 use prometheus::Registry;
 use store::rocks;
 use test_utils::{temp_dir, transaction, CommitteeFixture};
@@ -12,6 +14,10 @@ fn create_batches_store() -> Store<BatchDigest, Batch> {
     Store::new(db)
 }
 
+fn pending_transactions(node_metrics: Arc<WorkerMetrics>) -> PendingTransactions {
+    PendingTransactions::new(Arc::new(FifoBatchPolicy), 1_000_000, 1_000, node_metrics)
+}
+
 #[tokio::test]
 async fn make_batch() {
     let fixture = CommitteeFixture::builder().build();
@@ -22,7 +28,7 @@ async fn make_batch() {
     let (tx_batch_maker, rx_batch_maker) = test_utils::test_channel!(1);
     let (tx_message, mut rx_message) = test_utils::test_channel!(1);
     let (tx_digest, mut rx_digest) = test_utils::test_channel!(1);
-    let node_metrics = WorkerMetrics::new(&Registry::new());
+    let node_metrics = Arc::new(WorkerMetrics::new(&Registry::new()));
 
     // Spawn a `BatchMaker` instance.
     let id = 0;
@@ -32,10 +38,12 @@ async fn make_batch() {
         /* max_batch_size */ 200,
         /* max_batch_delay */
         Duration::from_millis(1_000_000), // Ensure the timer is not triggered.
+        BatchCompression::None,
         rx_reconfiguration,
         rx_batch_maker,
+        pending_transactions(node_metrics.clone()),
         tx_message,
-        Arc::new(node_metrics),
+        node_metrics,
         store.clone(),
         tx_digest,
     );
@@ -51,7 +59,7 @@ async fn make_batch() {
     let expected_batch = Batch::new(vec![tx.clone(), tx.clone()]);
     let (batch, overall_response) = rx_message.recv().await.unwrap();
 
-    assert_eq!(batch.transactions, expected_batch.transactions);
+    assert_eq!(batch.transactions(), expected_batch.transactions());
 
     // Eventually deliver message
     if let Some(resp) = overall_response {
@@ -82,7 +90,7 @@ async fn batch_timeout() {
         watch::channel(ReconfigureNotification::NewEpoch(committee.clone()));
     let (tx_batch_maker, rx_batch_maker) = test_utils::test_channel!(1);
     let (tx_message, mut rx_message) = test_utils::test_channel!(1);
-    let node_metrics = WorkerMetrics::new(&Registry::new());
+    let node_metrics = Arc::new(WorkerMetrics::new(&Registry::new()));
     let (tx_digest, mut rx_digest) = test_utils::test_channel!(1);
 
     // Spawn a `BatchMaker` instance.
@@ -93,10 +101,12 @@ async fn batch_timeout() {
         /* max_batch_size */ 200,
         /* max_batch_delay */
         Duration::from_millis(50), // Ensure the timer is triggered.
+        BatchCompression::None,
         rx_reconfiguration,
         rx_batch_maker,
+        pending_transactions(node_metrics.clone()),
         tx_message,
-        Arc::new(node_metrics),
+        node_metrics,
         store.clone(),
         tx_digest,
     );
@@ -109,7 +119,7 @@ async fn batch_timeout() {
     // Ensure the batch is as expected.
     let (batch, overall_response) = rx_message.recv().await.unwrap();
     let expected_batch = Batch::new(vec![tx.clone()]);
-    assert_eq!(batch.transactions, expected_batch.transactions);
+    assert_eq!(batch.transactions(), expected_batch.transactions());
 
     // Eventually deliver message
     if let Some(resp) = overall_response {
@@ -125,3 +135,68 @@ async fn batch_timeout() {
     // Ensure the batch is stored
     assert!(store.notify_read(batch.digest()).await.unwrap().is_some());
 }
+
+#[tokio::test]
+async fn make_compressed_batch() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let store = create_batches_store();
+    let (_tx_reconfiguration, rx_reconfiguration) =
+        watch::channel(ReconfigureNotification::NewEpoch(committee.clone()));
+    let (tx_batch_maker, rx_batch_maker) = test_utils::test_channel!(1);
+    let (tx_message, mut rx_message) = test_utils::test_channel!(1);
+    let (tx_digest, mut rx_digest) = test_utils::test_channel!(1);
+    let node_metrics = Arc::new(WorkerMetrics::new(&Registry::new()));
+
+    // Spawn a `BatchMaker` instance.
+    let id = 0;
+    let _batch_maker_handle = BatchMaker::spawn(
+        id,
+        committee,
+        /* max_batch_size */ 200,
+        /* max_batch_delay */
+        Duration::from_millis(1_000_000), // Ensure the timer is not triggered.
+        BatchCompression::Zstd,
+        rx_reconfiguration,
+        rx_batch_maker,
+        pending_transactions(node_metrics.clone()),
+        tx_message,
+        node_metrics.clone(),
+        store.clone(),
+        tx_digest,
+    );
+
+    // Send enough transactions to seal a batch.
+    let tx = transaction();
+    let (s0, _r0) = tokio::sync::oneshot::channel();
+    let (s1, _r1) = tokio::sync::oneshot::channel();
+    tx_batch_maker.send((tx.clone(), s0)).await.unwrap();
+    tx_batch_maker.send((tx.clone(), s1)).await.unwrap();
+
+    // Ensure the batch is compressed, without changing its digest.
+    let expected_batch = Batch::new(vec![tx.clone(), tx.clone()]);
+    let (batch, overall_response) = rx_message.recv().await.unwrap();
+    assert_eq!(batch.compression(), BatchCompression::Zstd);
+    assert_eq!(batch.digest(), expected_batch.digest());
+    assert_eq!(
+        node_metrics
+            .created_batch_compression_ratio
+            .with_label_values(&["0", "Zstd"])
+            .get_sample_count(),
+        1
+    );
+
+    if let Some(resp) = overall_response {
+        assert!(resp.send(()).is_ok());
+    }
+    let (_message, respond) = rx_digest.recv().await.unwrap();
+    assert!(respond.unwrap().send(()).is_ok());
+
+    // Ensure the batch is stored compressed, and read back as it was made.
+    let stored = store
+        .notify_read(expected_batch.digest())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored, batch);
+}
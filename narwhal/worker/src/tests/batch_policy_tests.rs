@@ -0,0 +1,115 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use prometheus::Registry;
+use types::BatchDigest;
+
+// Transactions are a priority byte, a sender byte, then padding.
+struct TestPolicy;
+
+impl BatchPolicy for TestPolicy {
+    fn prioritize(&self, transaction: &Transaction) -> TransactionPriority {
+        TransactionPriority {
+            priority: transaction[0] as u64,
+            sender: Some(vec![transaction[1]]),
+        }
+    }
+}
+
+fn pending(max_size: usize, max_per_sender: usize) -> PendingTransactions {
+    PendingTransactions::new(
+        Arc::new(TestPolicy),
+        max_size,
+        max_per_sender,
+        Arc::new(WorkerMetrics::new(&Registry::new())),
+    )
+}
+
+fn push(pending: &mut PendingTransactions, priority: u8, sender: u8) {
+    let (response, _) = tokio::sync::oneshot::channel();
+    pending.push(vec![priority, sender, 0, 0], response);
+}
+
+#[test]
+fn seals_by_priority_then_arrival() {
+    let mut pending = pending(1_000, 10);
+    for (priority, sender) in [(1, 0), (5, 1), (1, 2), (9, 3)] {
+        push(&mut pending, priority, sender);
+    }
+    assert_eq!(pending.size(), 16);
+
+    let (transactions, responses, size) = pending.take(8);
+    assert_eq!(transactions, vec![vec![9, 3, 0, 0], vec![5, 1, 0, 0]]);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(size, 8);
+
+    let (transactions, _, _) = pending.take(100);
+    assert_eq!(transactions, vec![vec![1, 0, 0, 0], vec![1, 2, 0, 0]]);
+    assert!(pending.is_empty());
+}
+
+#[test]
+fn fifo_policy_keeps_arrival_order() {
+    let mut pending = PendingTransactions::new(
+        Arc::new(FifoBatchPolicy),
+        1_000,
+        1,
+        Arc::new(WorkerMetrics::new(&Registry::new())),
+    );
+    for i in 0..5u8 {
+        let (response, _) = tokio::sync::oneshot::channel();
+        pending.push(vec![i], response);
+    }
+    let (transactions, _, _) = pending.take(100);
+    assert_eq!(transactions, (0..5u8).map(|i| vec![i]).collect::<Vec<_>>());
+}
+
+#[test]
+fn senders_are_limited() {
+    let mut pending = pending(1_000, 2);
+    for _ in 0..3 {
+        push(&mut pending, 1, 7);
+    }
+    push(&mut pending, 1, 8);
+    assert_eq!(pending.size(), 12);
+    assert_eq!(
+        pending
+            .metrics
+            .dropped_transactions
+            .with_label_values(&["sender_limit"])
+            .get(),
+        1
+    );
+
+    // sealing frees the sender's slots
+    pending.take(4);
+    push(&mut pending, 1, 7);
+    assert_eq!(pending.size(), 12);
+}
+
+#[tokio::test]
+async fn full_queue_evicts_lower_priority() {
+    let mut pending = pending(8, 10);
+    let (low, rx_low) = tokio::sync::oneshot::channel();
+    pending.push(vec![1, 0, 0, 0], low);
+    let (high, rx_high) = tokio::sync::oneshot::channel();
+    pending.push(vec![5, 1, 0, 0], high);
+
+    // not admitted: the queue is full of transactions of higher or equal priority
+    let (same, rx_same) = tokio::sync::oneshot::channel();
+    pending.push(vec![1, 2, 0, 0], same);
+    assert!(rx_same.await.is_err());
+
+    // admitted in place of the lowest priority transaction
+    let (higher, _rx_higher) = tokio::sync::oneshot::channel();
+    pending.push(vec![9, 3, 0, 0], higher);
+    assert!(rx_low.await.is_err());
+    assert_eq!(pending.size(), 8);
+
+    let (transactions, responses, _) = pending.take(100);
+    assert_eq!(transactions, vec![vec![9, 3, 0, 0], vec![5, 1, 0, 0]]);
+    for response in responses {
+        response.send(BatchDigest::default()).unwrap();
+    }
+    assert!(rx_high.await.is_ok());
+}
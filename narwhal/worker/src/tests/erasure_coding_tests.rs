@@ -0,0 +1,113 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use test_utils::CommitteeFixture;
+
+#[test]
+fn layout_tolerates_f_faults() {
+    let committee = CommitteeFixture::builder()
+        .committee_size(7.try_into().unwrap())
+        .build()
+        .committee();
+    let layout = ChunkLayout::new(&committee).unwrap();
+    assert_eq!(layout.data_shards, 3);
+    assert_eq!(layout.parity_shards, 4);
+    assert_eq!(layout.min_acknowledgements(), 5);
+
+    // A committee that tolerates no fault broadcasts its batches.
+    let committee = CommitteeFixture::builder()
+        .committee_size(3.try_into().unwrap())
+        .build()
+        .committee();
+    assert!(ChunkLayout::new(&committee).is_none());
+}
+
+#[test]
+fn reconstruct_from_any_f_plus_one_chunks() {
+    let committee = CommitteeFixture::builder().build().committee();
+    let layout = ChunkLayout::new(&committee).unwrap();
+    let batch = test_utils::batch_with_transactions(10);
+    let digest = batch.digest();
+
+    let chunks = encode(&batch, layout).unwrap();
+    assert_eq!(chunks.len(), committee.size());
+    for first in 0..chunks.len() {
+        for second in first + 1..chunks.len() {
+            let subset = [chunks[second].clone(), chunks[first].clone()];
+            assert_eq!(reconstruct(digest, &subset).unwrap(), batch);
+        }
+    }
+
+    // Not enough chunks.
+    assert!(reconstruct(digest, &chunks[..1]).is_err());
+}
+
+#[test]
+fn reconstruct_detects_faulty_chunks() {
+    let committee = CommitteeFixture::builder().build().committee();
+    let layout = ChunkLayout::new(&committee).unwrap();
+    let batch = test_utils::batch_with_transactions(10);
+
+    let mut chunks = encode(&batch, layout).unwrap();
+    chunks[0].data[0] ^= 0xff;
+    assert!(reconstruct(batch.digest(), &chunks[..2]).is_err());
+    assert_eq!(reconstruct(batch.digest(), &chunks[1..3]).unwrap(), batch);
+}
+
+#[test]
+fn verify_authenticates_chunks() {
+    // 7 chunks, so that the Merkle tree has a level of odd width.
+    let committee = CommitteeFixture::builder()
+        .committee_size(7.try_into().unwrap())
+        .build()
+        .committee();
+    let layout = ChunkLayout::new(&committee).unwrap();
+    let batch = test_utils::batch_with_transactions(10);
+
+    let chunks = encode(&batch, layout).unwrap();
+    assert!(chunks.iter().all(verify));
+    assert!(chunks.iter().all(|chunk| chunk.root == chunks[0].root));
+
+    let mut chunk = chunks[3].clone();
+    chunk.data[0] ^= 0xff;
+    assert!(!verify(&chunk));
+
+    let mut chunk = chunks[3].clone();
+    chunk.index = 2;
+    assert!(!verify(&chunk));
+
+    let mut chunk = chunks[3].clone();
+    chunk.batch_length += 1;
+    assert!(!verify(&chunk));
+
+    let mut chunk = chunks[6].clone();
+    chunk.proof.push(chunks[0].root);
+    assert!(!verify(&chunk));
+}
+
+#[test]
+fn reconstruct_rejects_inconsistent_codewords() {
+    let committee = CommitteeFixture::builder()
+        .committee_size(7.try_into().unwrap())
+        .build()
+        .committee();
+    let layout = ChunkLayout::new(&committee).unwrap();
+    let batch = test_utils::batch_with_transactions(10);
+
+    // A faulty author commits to chunks that are not a Reed-Solomon codeword.
+    let mut chunks = encode(&batch, layout).unwrap();
+    chunks[6].data[0] ^= 0xff;
+    let shards: Vec<_> = chunks.iter().map(|chunk| chunk.data.clone()).collect();
+    let levels = merkle_levels(leaves(&shards, layout, chunks[0].batch_length));
+    for chunk in chunks.iter_mut() {
+        chunk.root = levels.last().unwrap()[0];
+        chunk.proof = merkle_proof(&levels, chunk.index as usize);
+    }
+    assert!(chunks.iter().all(verify));
+
+    // No subset of the chunks rebuilds the batch, whether it decodes to the batch or not.
+    for subset in [&chunks[..3], &chunks[4..]] {
+        let error = reconstruct(batch.digest(), subset).unwrap_err();
+        assert!(error.is::<InconsistentChunks>());
+    }
+}
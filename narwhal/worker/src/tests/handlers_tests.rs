@@ -4,6 +4,7 @@
 use super::*;
 
 use fastcrypto::hash::Hash;
+use prometheus::Registry;
 use test_utils::CommitteeFixture;
 use types::{MockWorkerToWorker, WorkerToWorkerServer};
 
@@ -28,9 +29,12 @@ async fn synchronize() {
         committee: committee.into(),
         worker_cache,
         store: store.clone(),
+        chunk_store: test_utils::open_batch_chunk_store(),
         request_batch_timeout: Duration::from_secs(999),
         request_batch_retry_nodes: 3, // Not used in this test.
         tx_reconfigure,
+        batch_dissemination: BatchDissemination::Broadcast,
+        metrics: Arc::new(WorkerMetrics::new(&Registry::new())),
     };
 
     // Set up mock behavior for child RequestBatches RPC.
@@ -79,6 +83,289 @@ async fn synchronize() {
     assert!(store.notify_read(digest).await.unwrap().is_some())
 }
 
+#[tokio::test]
+async fn synchronize_erasure_coded() {
+    telemetry_subscribers::init_for_testing();
+
+    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let committee = fixture.committee();
+    let worker_cache = fixture.shared_worker_cache();
+    let name = fixture.authorities().next().unwrap().public_key();
+    let id = 0;
+    let (tx_reconfigure, _rx_reconfigure) =
+        watch::channel(ReconfigureNotification::NewEpoch(committee.clone()));
+
+    // Create a new test store.
+    let store = test_utils::open_batch_store();
+
+    let handler = PrimaryReceiverHandler {
+        name,
+        id,
+        committee: committee.clone().into(),
+        worker_cache,
+        store: store.clone(),
+        chunk_store: test_utils::open_batch_chunk_store(),
+        request_batch_timeout: Duration::from_secs(999),
+        request_batch_retry_nodes: 3, // Not used in this test.
+        tx_reconfigure,
+        batch_dissemination: BatchDissemination::ErasureCoded,
+        metrics: Arc::new(WorkerMetrics::new(&Registry::new())),
+    };
+
+    // The other workers answer with their chunk of the batch, except the one of the target,
+    // which does not have it: the batch is rebuilt from the chunks of the two others.
+    let batch = test_utils::batch();
+    let digest = batch.digest();
+    let layout = ChunkLayout::new(&committee).unwrap();
+    let chunks = erasure_coding::encode(&batch, layout).unwrap();
+    let target_primary = fixture.authorities().nth(1).unwrap();
+    let send_network = test_utils::random_network();
+    let mut recv_networks = Vec::new();
+    for authority in fixture.authorities().skip(1) {
+        let chunk = (authority.public_key() != target_primary.public_key()).then(|| {
+            let index = erasure_coding::chunk_index(&committee, &authority.public_key()).unwrap();
+            chunks[index as usize].clone()
+        });
+        let mut mock_server = MockWorkerToWorker::new();
+        mock_server
+            .expect_request_batch_chunk()
+            .withf(move |request| request.body().batch == digest)
+            .return_once(move |_| Ok(anemo::Response::new(RequestBatchChunkResponse { chunk })));
+        let routes = anemo::Router::new().add_rpc_service(WorkerToWorkerServer::new(mock_server));
+        let worker = authority.worker(id);
+        recv_networks.push(worker.new_network(routes));
+        send_network
+            .connect_with_peer_id(
+                network::multiaddr_to_address(&worker.info().worker_address).unwrap(),
+                anemo::PeerId(worker.info().name.0.to_bytes()),
+            )
+            .await
+            .unwrap();
+    }
+
+    // Send a sync request.
+    let message = WorkerSynchronizeMessage {
+        digests: vec![digest],
+        target: target_primary.public_key(),
+    };
+    let mut request = anemo::Request::new(message);
+    assert!(request
+        .extensions_mut()
+        .insert(send_network.downgrade())
+        .is_none());
+    handler.synchronize(request).await.unwrap();
+
+    // Check its now stored
+    assert_eq!(store.read(digest).await.unwrap(), Some(batch));
+}
+
+#[tokio::test]
+async fn request_batch_chunk() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let name = fixture.authorities().nth(2).unwrap().public_key();
+    let (tx_others_batch, _rx_others_batch) = test_utils::test_channel!(1);
+    let store = test_utils::open_batch_store();
+    let chunk_store = test_utils::open_batch_chunk_store();
+    let handler = WorkerReceiverHandler {
+        name: name.clone(),
+        id: 0,
+        committee: committee.clone().into(),
+        worker_cache: fixture.shared_worker_cache(),
+        tx_others_batch,
+        store: store.clone(),
+        chunk_store: chunk_store.clone(),
+        rebuilding: Default::default(),
+        request_batch_timeout: Duration::from_secs(999),
+        metrics: Arc::new(WorkerMetrics::new(&Registry::new())),
+    };
+
+    // No chunk of an unknown batch.
+    let batch = test_utils::batch();
+    let digest = batch.digest();
+    let request = || anemo::Request::new(RequestBatchChunkRequest { batch: digest });
+    let response = handler.request_batch_chunk(request()).await.unwrap();
+    assert!(response.into_body().chunk.is_none());
+
+    // The chunk of our authority is coded from the batch once stored.
+    store.async_write(digest, batch.clone()).await;
+    let chunks = erasure_coding::encode(&batch, ChunkLayout::new(&committee).unwrap()).unwrap();
+    let index = erasure_coding::chunk_index(&committee, &name).unwrap() as usize;
+    let response = handler.request_batch_chunk(request()).await.unwrap();
+    assert_eq!(response.into_body().chunk, Some(chunks[index].clone()));
+}
+
+#[tokio::test]
+async fn report_batch_chunk_rejects_other_chunks() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let name = fixture.authorities().nth(2).unwrap().public_key();
+    let (tx_others_batch, _rx_others_batch) = test_utils::test_channel!(1);
+    let chunk_store = test_utils::open_batch_chunk_store();
+    let handler = WorkerReceiverHandler {
+        name: name.clone(),
+        id: 0,
+        committee: committee.clone().into(),
+        worker_cache: fixture.shared_worker_cache(),
+        tx_others_batch,
+        store: test_utils::open_batch_store(),
+        chunk_store: chunk_store.clone(),
+        rebuilding: Default::default(),
+        request_batch_timeout: Duration::from_secs(999),
+        metrics: Arc::new(WorkerMetrics::new(&Registry::new())),
+    };
+
+    let batch = test_utils::batch();
+    let digest = batch.digest();
+    let chunks = erasure_coding::encode(&batch, ChunkLayout::new(&committee).unwrap()).unwrap();
+    let index = erasure_coding::chunk_index(&committee, &name).unwrap() as usize;
+    let author = anemo::PeerId(
+        fixture
+            .authorities()
+            .next()
+            .unwrap()
+            .worker(0)
+            .info()
+            .name
+            .0
+            .to_bytes(),
+    );
+    let network = test_utils::random_network();
+    let request = |peer_id: anemo::PeerId, chunk: BatchChunk| {
+        let mut request = anemo::Request::new(WorkerBatchChunkMessage { digest, chunk });
+        request.extensions_mut().insert(network.downgrade());
+        request.extensions_mut().insert(peer_id);
+        request
+    };
+
+    // The chunk of another authority.
+    let other = chunks[(index + 1) % chunks.len()].clone();
+    assert!(handler
+        .report_batch_chunk(request(author, other))
+        .await
+        .is_err());
+
+    // Our chunk, tampered with.
+    let mut tampered = chunks[index].clone();
+    tampered.data[0] ^= 1;
+    assert!(handler
+        .report_batch_chunk(request(author, tampered))
+        .await
+        .is_err());
+
+    // Our chunk, from a peer that is not a worker.
+    let stranger = network.peer_id();
+    let chunk = chunks[index].clone();
+    assert!(handler
+        .report_batch_chunk(request(stranger, chunk))
+        .await
+        .is_err());
+
+    assert!(chunk_store.read(digest).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn report_batch_chunk_rebuilds_batch() {
+    telemetry_subscribers::init_for_testing();
+
+    // 7 authorities, so that the chunks of 3 of them are needed to rebuild a batch.
+    let fixture = CommitteeFixture::builder()
+        .committee_size(7.try_into().unwrap())
+        .randomize_ports(true)
+        .build();
+    let committee = fixture.committee();
+    let name = fixture.authorities().nth(2).unwrap().public_key();
+    let id = 0;
+    let (tx_others_batch, mut rx_others_batch) = test_utils::test_channel!(1);
+    let store = test_utils::open_batch_store();
+    let chunk_store = test_utils::open_batch_chunk_store();
+    let handler = WorkerReceiverHandler {
+        name: name.clone(),
+        id,
+        committee: committee.clone().into(),
+        worker_cache: fixture.shared_worker_cache(),
+        tx_others_batch,
+        store: store.clone(),
+        chunk_store: chunk_store.clone(),
+        rebuilding: Default::default(),
+        request_batch_timeout: Duration::from_secs(999),
+        metrics: Arc::new(WorkerMetrics::new(&Registry::new())),
+    };
+
+    let batch = test_utils::batch();
+    let digest = batch.digest();
+    let layout = ChunkLayout::new(&committee).unwrap();
+    assert_eq!(layout.data_shards, 3);
+    let chunks = erasure_coding::encode(&batch, layout).unwrap();
+    let index = erasure_coding::chunk_index(&committee, &name).unwrap() as usize;
+
+    // Only two other workers ever get their chunk, and they get it after being first asked for
+    // it, as all the chunks are sent at once.
+    let network = test_utils::random_network();
+    let mut recv_networks = Vec::new();
+    for (i, authority) in fixture
+        .authorities()
+        .filter(|authority| authority.public_key() != name)
+        .enumerate()
+    {
+        let index = erasure_coding::chunk_index(&committee, &authority.public_key()).unwrap();
+        let chunk = (i < 2).then(|| chunks[index as usize].clone());
+        let mut requests = 0;
+        let mut mock_server = MockWorkerToWorker::new();
+        mock_server
+            .expect_request_batch_chunk()
+            .withf(move |request| request.body().batch == digest)
+            .returning(move |_| {
+                requests += 1;
+                let chunk = chunk.clone().filter(|_| requests > 1);
+                Ok(anemo::Response::new(RequestBatchChunkResponse { chunk }))
+            });
+        let routes = anemo::Router::new().add_rpc_service(WorkerToWorkerServer::new(mock_server));
+        let worker = authority.worker(id);
+        recv_networks.push(worker.new_network(routes));
+        network
+            .connect_with_peer_id(
+                network::multiaddr_to_address(&worker.info().worker_address).unwrap(),
+                anemo::PeerId(worker.info().name.0.to_bytes()),
+            )
+            .await
+            .unwrap();
+    }
+
+    let author = anemo::PeerId(
+        fixture
+            .authorities()
+            .next()
+            .unwrap()
+            .worker(id)
+            .info()
+            .name
+            .0
+            .to_bytes(),
+    );
+    let mut request = anemo::Request::new(WorkerBatchChunkMessage {
+        digest,
+        chunk: chunks[index].clone(),
+    });
+    request.extensions_mut().insert(network.downgrade());
+    request.extensions_mut().insert(author);
+    handler.report_batch_chunk(request).await.unwrap();
+
+    // The batch is rebuilt and reported to our primary.
+    let message = tokio::time::timeout(Duration::from_secs(10), rx_others_batch.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(message.digest, digest);
+    assert_eq!(store.read(digest).await.unwrap(), Some(batch));
+
+    // Our chunk is kept until the batch is deleted.
+    assert_eq!(
+        chunk_store.read(digest).await.unwrap(),
+        Some(chunks[index].clone())
+    );
+}
+
 #[tokio::test]
 async fn synchronize_when_batch_exists() {
     telemetry_subscribers::init_for_testing();
@@ -100,9 +387,12 @@ async fn synchronize_when_batch_exists() {
         committee: committee.into(),
         worker_cache,
         store: store.clone(),
+        chunk_store: test_utils::open_batch_chunk_store(),
         request_batch_timeout: Duration::from_secs(999),
         request_batch_retry_nodes: 3, // Not used in this test.
         tx_reconfigure,
+        batch_dissemination: BatchDissemination::Broadcast,
+        metrics: Arc::new(WorkerMetrics::new(&Registry::new())),
     };
 
     // Store the batch.
@@ -143,6 +433,9 @@ async fn delete_batches() {
     let batch = test_utils::batch();
     let digest = batch.digest();
     store.async_write(digest, batch.clone()).await;
+    let chunk_store = test_utils::open_batch_chunk_store();
+    let chunks = erasure_coding::encode(&batch, ChunkLayout::new(&committee).unwrap()).unwrap();
+    chunk_store.async_write(digest, chunks[0].clone()).await;
 
     // Send a delete request.
     let handler = PrimaryReceiverHandler {
@@ -151,9 +444,12 @@ async fn delete_batches() {
         committee: committee.into(),
         worker_cache,
         store: store.clone(),
+        chunk_store: chunk_store.clone(),
         request_batch_timeout: Duration::from_secs(999),
         request_batch_retry_nodes: 3, // Not used in this test.
         tx_reconfigure,
+        batch_dissemination: BatchDissemination::Broadcast,
+        metrics: Arc::new(WorkerMetrics::new(&Registry::new())),
     };
     let message = WorkerDeleteBatchesMessage {
         digests: vec![digest],
@@ -164,4 +460,5 @@ async fn delete_batches() {
         .unwrap();
 
     assert!(store.read(digest).await.unwrap().is_none());
+    assert!(chunk_store.read(digest).await.unwrap().is_none());
 }
@@ -2,6 +2,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use super::*;
+use prometheus::Registry;
 use test_utils::{batch, test_network, CommitteeFixture, WorkerToWorkerMockServer};
 
 #[tokio::test]
@@ -27,6 +28,8 @@ async fn wait_for_quorum() {
         rx_reconfiguration,
         rx_message,
         P2pNetwork::new(network.clone()),
+        BatchDissemination::Broadcast,
+        Arc::new(WorkerMetrics::new(&Registry::new())),
     );
 
     // Make a batch.
@@ -85,6 +88,8 @@ async fn pipeline_for_quorum() {
         rx_reconfiguration,
         rx_message,
         P2pNetwork::new(network.clone()),
+        BatchDissemination::Broadcast,
+        Arc::new(WorkerMetrics::new(&Registry::new())),
     );
 
     // Make a batch.
@@ -2,7 +2,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use super::*;
-use crate::metrics::initialise_metrics;
+use crate::{metrics::initialise_metrics, FifoBatchPolicy};
 use arc_swap::ArcSwap;
 use bytes::Bytes;
 use consensus::{dag::Dag, metrics::ConsensusMetrics};
@@ -51,6 +51,8 @@ async fn handle_clients_transactions() {
         worker_cache.clone(),
         parameters,
         store,
+        test_utils::open_batch_chunk_store(),
+        Arc::new(FifoBatchPolicy),
         metrics,
     );
 
@@ -103,7 +105,7 @@ async fn handle_clients_transactions() {
 
     let join_handle = tokio::task::spawn(async move {
         let mut fut_list = FuturesOrdered::new();
-        for tx in batch.transactions {
+        for tx in batch.into_transactions() {
             let txn = TransactionProto {
                 transaction: Bytes::from(tx.clone()),
             };
@@ -174,6 +176,7 @@ async fn get_network_peers_from_admin_server() {
             Dag::new(&committee, rx_new_certificates, consensus_metrics).1,
         )),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure,
         tx_feedback,
         &Registry::new(),
@@ -200,6 +203,8 @@ async fn get_network_peers_from_admin_server() {
         worker_cache.clone(),
         worker_1_parameters.clone(),
         store.batch_store.clone(),
+        store.batch_chunk_store.clone(),
+        Arc::new(FifoBatchPolicy),
         metrics_1.clone(),
     );
 
@@ -285,6 +290,7 @@ async fn get_network_peers_from_admin_server() {
             Dag::new(&committee, rx_new_certificates_2, consensus_metrics).1,
         )),
         NetworkModel::Asynchronous,
+        /* consensus_store */ None,
         tx_reconfigure_2,
         tx_feedback_2,
         &Registry::new(),
@@ -311,6 +317,8 @@ async fn get_network_peers_from_admin_server() {
         worker_cache.clone(),
         worker_2_parameters.clone(),
         store.batch_store,
+        store.batch_chunk_store,
+        Arc::new(FifoBatchPolicy),
         metrics_2.clone(),
     );
 
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     batch_maker::BatchMaker,
+    batch_policy::{BatchPolicy, PendingTransactions},
     handlers::{PrimaryReceiverHandler, WorkerReceiverHandler},
     metrics::WorkerChannelMetrics,
     primary_connector::PrimaryConnector,
@@ -28,13 +29,13 @@ use sui_metrics::spawn_monitored_task;
 use tokio::{sync::watch, task::JoinHandle};
 use tonic::{Request, Response, Status};
 use tower::ServiceBuilder;
-use tracing::info;
+use tracing::{info, warn};
 use types::{
     error::DagError,
     metered_channel::{channel_with_total, Sender},
-    Batch, BatchDigest, Empty, PrimaryToWorkerServer, ReconfigureNotification, Transaction,
-    TransactionProto, Transactions, TransactionsServer, TxResponse, WorkerOurBatchMessage,
-    WorkerToWorkerServer,
+    Batch, BatchChunk, BatchDigest, Empty, PrimaryToWorkerServer, ReconfigureNotification,
+    Transaction, TransactionProto, Transactions, TransactionsServer, TxResponse,
+    WorkerOurBatchMessage, WorkerToWorkerServer,
 };
 
 #[cfg(test)]
@@ -64,6 +65,10 @@ pub struct Worker {
     parameters: Parameters,
     /// The persistent storage.
     store: Store<BatchDigest, Batch>,
+    /// The chunks of the erasure coded batches of other workers, until the batches are deleted.
+    chunk_store: Store<BatchDigest, BatchChunk>,
+    /// The order in which client transactions are sealed into batches.
+    batch_policy: Arc<dyn BatchPolicy>,
 }
 
 impl Worker {
@@ -75,6 +80,8 @@ impl Worker {
         worker_cache: SharedWorkerCache,
         parameters: Parameters,
         store: Store<BatchDigest, Batch>,
+        chunk_store: Store<BatchDigest, BatchChunk>,
+        batch_policy: Arc<dyn BatchPolicy>,
         metrics: Metrics,
     ) -> Vec<JoinHandle<()>> {
         info!(
@@ -92,6 +99,8 @@ impl Worker {
             worker_cache,
             parameters: parameters.clone(),
             store,
+            chunk_store,
+            batch_policy,
         };
 
         let node_metrics = Arc::new(metrics.worker_metrics.unwrap());
@@ -118,9 +127,16 @@ impl Worker {
             watch::channel(ReconfigureNotification::NewEpoch(initial_committee));
 
         let worker_service = WorkerToWorkerServer::new(WorkerReceiverHandler {
+            name: worker.primary_name.clone(),
             id: worker.id,
+            committee: worker.committee.clone(),
+            worker_cache: worker.worker_cache.clone(),
             tx_others_batch,
             store: worker.store.clone(),
+            chunk_store: worker.chunk_store.clone(),
+            rebuilding: Default::default(),
+            request_batch_timeout: worker.parameters.sync_retry_delay,
+            metrics: node_metrics.clone(),
         });
         let primary_service = PrimaryToWorkerServer::new(PrimaryReceiverHandler {
             name: worker.primary_name.clone(),
@@ -128,9 +144,12 @@ impl Worker {
             committee: worker.committee.clone(),
             worker_cache: worker.worker_cache.clone(),
             store: worker.store.clone(),
+            chunk_store: worker.chunk_store.clone(),
             request_batch_timeout: worker.parameters.sync_retry_delay,
             request_batch_retry_nodes: worker.parameters.sync_retry_nodes,
             tx_reconfigure,
+            batch_dissemination: worker.parameters.batch_dissemination,
+            metrics: node_metrics.clone(),
         });
 
         // Receive incoming messages from other workers.
@@ -172,8 +191,14 @@ impl Worker {
             .layer(TraceLayer::new_for_client_and_server_errors())
             .layer(CallbackLayer::new(MetricsMakeCallbackHandler::new(
                 outbound_network_metrics,
-            )))
-            .into_inner();
+            )));
+        // In the simulator, the requests go through the network faults the tests inject.
+        #[cfg(msim)]
+        let outbound_layer =
+            outbound_layer.layer(network::fault_injection::FaultInjectionLayer::new(PeerId(
+                worker.keypair.public().0.to_bytes(),
+            )));
+        let outbound_layer = outbound_layer.into_inner();
 
         let anemo_config = {
             let mut quic_config = anemo::QuicConfig::default();
@@ -250,8 +275,12 @@ impl Worker {
             network.clone(),
             rx_reconfigure.clone(),
             None,
+            None,
         );
 
+        let peers_handle =
+            worker.update_peers_on_new_epoch(network.clone(), rx_reconfigure.clone());
+
         let primary_connector_handle = PrimaryConnector::spawn(
             primary_network_key,
             rx_reconfigure.clone(),
@@ -280,12 +309,54 @@ impl Worker {
                 .transactions
         );
 
-        let mut handles = vec![primary_connector_handle, connection_monitor_handle];
+        let mut handles = vec![
+            primary_connector_handle,
+            connection_monitor_handle,
+            peers_handle,
+        ];
         handles.extend(admin_handles);
         handles.extend(client_flow_handles);
         handles
     }
 
+    /// Keep the known peers of the network in line with the committee and the workers of each
+    /// new epoch, reusing the connections to the peers that remain.
+    fn update_peers_on_new_epoch(
+        &self,
+        network: Network,
+        mut rx_reconfigure: watch::Receiver<ReconfigureNotification>,
+    ) -> JoinHandle<()> {
+        let primary_name = self.primary_name.clone();
+        let id = self.id;
+        let worker_cache = self.worker_cache.clone();
+        spawn_monitored_task!(async move {
+            while rx_reconfigure.changed().await.is_ok() {
+                let message = rx_reconfigure.borrow().clone();
+                match message {
+                    ReconfigureNotification::NewEpoch(committee) => {
+                        // The worker cache of the epoch is updated before the notification.
+                        let mut peers: Vec<_> = worker_cache
+                            .load()
+                            .others_workers_by_id(&primary_name, &id)
+                            .into_iter()
+                            .map(|(_, info)| (info.name, info.worker_address))
+                            .collect();
+                        match (
+                            committee.network_key(&primary_name),
+                            committee.primary(&primary_name),
+                        ) {
+                            (Ok(network_key), Ok(address)) => peers.push((network_key, address)),
+                            _ => warn!("Our primary is not in the committee of the new epoch"),
+                        }
+                        network::update_known_peers(&network, peers);
+                    }
+                    ReconfigureNotification::Shutdown => return,
+                    _ => (),
+                }
+            }
+        })
+    }
+
     fn add_peer_in_network(
         network: &Network,
         peer_name: NetworkPublicKey,
@@ -351,10 +422,17 @@ impl Worker {
             (*(*(*self.committee).load()).clone()).clone(),
             self.parameters.batch_size,
             self.parameters.max_batch_delay,
+            self.parameters.batch_compression,
             rx_reconfigure.clone(),
             rx_batch_maker,
+            PendingTransactions::new(
+                self.batch_policy.clone(),
+                self.parameters.max_pending_transactions_size,
+                self.parameters.max_pending_transactions_per_sender,
+                node_metrics.clone(),
+            ),
             tx_quorum_waiter,
-            node_metrics,
+            node_metrics.clone(),
             self.store.clone(),
             tx_our_batch,
         );
@@ -369,6 +447,8 @@ impl Worker {
             rx_reconfigure,
             /* rx_message */ rx_quorum_waiter,
             P2pNetwork::new(network),
+            self.parameters.batch_dissemination,
+            node_metrics,
         );
 
         info!(
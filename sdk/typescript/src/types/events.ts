@@ -104,6 +104,8 @@ export type EventId = {
 export type PaginatedEvents = {
   data: SuiEvents;
   nextCursor: EventId | null;
+  /** Events before this one may have been pruned by the node */
+  earliestAvailable?: EventId | null;
 };
 
 export type EventType =
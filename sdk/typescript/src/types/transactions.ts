@@ -2,7 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 import { ObjectOwner, SuiAddress, TransactionDigest } from './common';
-import { ObjectId, SuiMovePackage, SuiObject, SuiObjectRef } from './objects';
+import {
+  MovePackageContent,
+  ObjectId,
+  SuiMovePackage,
+  SuiObject,
+  SuiObjectRef,
+} from './objects';
 
 export type TransferObject = {
   recipient: SuiAddress;
@@ -37,6 +43,15 @@ export type PayAllSui = {
   recipient: SuiAddress;
 };
 
+export type SuiMovePackageUpgrade = {
+  /** The latest version of the package being upgraded */
+  package: ObjectId;
+  /** The capability granting the upgrade of the package */
+  upgradeCap: SuiObjectRef;
+  /** A mapping from module name to disassembled Move bytecode */
+  disassembled: MovePackageContent;
+};
+
 export type ExecuteTransactionRequestType =
   | 'ImmediateReturn'
   | 'WaitForTxCert'
@@ -51,7 +66,8 @@ export type TransactionKindName =
   | 'ChangeEpoch'
   | 'Pay'
   | 'PaySui'
-  | 'PayAllSui';
+  | 'PayAllSui'
+  | 'Upgrade';
 
 export type SuiTransactionKind =
   | { TransferObject: TransferObject }
@@ -61,7 +77,8 @@ export type SuiTransactionKind =
   | { ChangeEpoch: SuiChangeEpoch }
   | { Pay: Pay }
   | { PaySui: PaySui }
-  | { PayAllSui: PayAllSui };
+  | { PayAllSui: PayAllSui }
+  | { Upgrade: SuiMovePackageUpgrade };
 export type SuiTransactionData = {
   transactions: SuiTransactionKind[];
   sender: SuiAddress;